@@ -0,0 +1,270 @@
+// declare cargo crates
+use std::io;
+
+// declare local code
+use super::tools::args::separate_args;
+use super::tools::json::Json;
+use super::{compare, compress, dedupe, list, move_task, organize, purge, restore, split, verify};
+use super::{Logger, RunTask, TaskReport};
+
+/// options shared by all tasks, main.rs removes them before the task is configured and every one is followed by a value
+const GLOBAL_OPTIONS: &[&str] = &["--log-file", "--report-json", "--threads"];
+
+/// every task Config::new() accepts along with its options that are followed by a value and those that are not, taken
+/// from the lists each task parses its own arguments with
+const TASKS: &[(&str, &[&str], &[&str])] = &[
+    ("organize", organize::VALUE_OPTIONS, organize::FLAG_OPTIONS),
+    ("list", list::VALUE_OPTIONS, list::FLAG_OPTIONS),
+    ("split", split::VALUE_OPTIONS, split::FLAG_OPTIONS),
+    ("compress", compress::VALUE_OPTIONS, compress::FLAG_OPTIONS),
+    ("dedupe", dedupe::VALUE_OPTIONS, dedupe::FLAG_OPTIONS),
+    ("move", move_task::VALUE_OPTIONS, move_task::FLAG_OPTIONS),
+    ("purge", purge::VALUE_OPTIONS, purge::FLAG_OPTIONS),
+    ("restore", restore::VALUE_OPTIONS, restore::FLAG_OPTIONS),
+    ("verify", verify::VALUE_OPTIONS, verify::FLAG_OPTIONS),
+    ("compare", compare::VALUE_OPTIONS, compare::FLAG_OPTIONS),
+    ("capabilities", &[], &[]),
+];
+
+/// remarks listed under `notes` for tasks that lack an option most tasks share, so wrappers can explain why it is
+/// missing rather than assume an outdated binary
+const TASK_NOTES: &[(&str, &[&str])] = &[(
+    "purge",
+    &["has no '--follow' or '--no-follow' as symbolic links are never followed: a link counts as content of its \
+       directory, which is therefore kept, so nothing is ever removed through a link"],
+)];
+
+/// CapabilitiesTask struct: prints what this build of fileman_rs supports so wrappers such as a GUI can adapt to the
+/// installed binary, takes no arguments
+#[derive(Debug, PartialEq, Eq)]
+pub struct CapabilitiesTask;
+
+/// RunTask trait implementation for Capabilities variant of Config enum
+impl RunTask for CapabilitiesTask {
+    /// run_task() driver function for Capabilities variant of Config, prints capabilities() on a single line to stdout,
+    /// nothing is processed so the returned TaskReport is empty
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Config enum
+    /// `logger` a Logger, unused as nothing is processed
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn run_task(&self, _logger: &mut Logger) -> Result<TaskReport, io::Error> {
+        println!("{}", capabilities());
+
+        Ok(TaskReport::default())
+    }
+}
+
+impl CapabilitiesTask {
+    /// CapabilitiesTask struct constructor
+    ///
+    /// # Arguments
+    ///
+    /// `args` an iterator containing Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - any path or option is provided
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
+        let (paths, options) = separate_args(args, &[]);
+        if !paths.is_empty() || !options.is_empty() {
+            return Err("capabilities does not accept any arguments");
+        }
+
+        Ok(Self)
+    }
+}
+
+/// capabilities() returns the version of this build, the platform it was built for, its features, the options shared
+/// by all tasks, and every task with the options it accepts, each option marked with whether it is followed by a
+/// value and listed in alphabetical order, along with the remarks of TASK_NOTES
+///
+/// # Arguments
+///
+/// None
+///
+/// # Errors
+///
+/// None
+fn capabilities() -> Json {
+    let options = |value_options: &[&str], flag_options: &[&str]| {
+        let mut options: Vec<(&str, bool)> = value_options
+            .iter()
+            .map(|option| (*option, true))
+            .chain(flag_options.iter().map(|option| (*option, false)))
+            .collect();
+        options.sort();
+        Json::Array(
+            options
+                .into_iter()
+                .map(|(name, value)| {
+                    Json::object([("name", Json::from(name)), ("value", Json::from(value))])
+                })
+                .collect(),
+        )
+    };
+
+    Json::object([
+        ("name", Json::from(env!("CARGO_PKG_NAME"))),
+        ("version", Json::from(env!("CARGO_PKG_VERSION"))),
+        ("platform", Json::from(std::env::consts::OS)),
+        (
+            "features",
+            Json::Array(features().into_iter().map(Json::from).collect()),
+        ),
+        ("global_options", options(GLOBAL_OPTIONS, &[])),
+        (
+            "tasks",
+            Json::Array(
+                TASKS
+                    .iter()
+                    .map(|(name, value_options, flag_options)| {
+                        let notes = TASK_NOTES
+                            .iter()
+                            .filter(|(task, _)| task == name)
+                            .flat_map(|(_, notes)| notes.iter().map(|note| Json::from(*note)));
+                        Json::object([
+                            ("name", Json::from(*name)),
+                            ("options", options(value_options, flag_options)),
+                            ("notes", Json::Array(notes.collect())),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+/// features() returns the features of this build. EXIF and SHA-256 support are always built in, while grouping by
+/// owner and `--progress-fd` are only available on Unix
+///
+/// # Arguments
+///
+/// None
+///
+/// # Errors
+///
+/// None
+fn features() -> Vec<&'static str> {
+    let mut features = vec!["exif", "sha256"];
+    if cfg!(unix) {
+        features.extend(["owner", "progress-fd"]);
+    }
+    if cfg!(feature = "blake3") {
+        features.push("blake3");
+    }
+
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Config;
+    use super::*;
+
+    /// verifies capabilities() lists the organize task with its options, marking those followed by a value
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - capabilities() does not write valid JSON
+    /// - capabilities() omits organize or one of its options, or marks an option wrongly
+    /// - capabilities() does not explain why purge has no symlink policy
+    #[test]
+    fn capabilities_lists_organize() {
+        let test_result = Json::parse(&capabilities().to_string()).unwrap();
+
+        let Some(Json::Array(tasks)) = test_result.get("tasks") else {
+            panic!("no tasks listed");
+        };
+        let organize = tasks
+            .iter()
+            .find(|task| task.get("name") == Some(&Json::from("organize")))
+            .unwrap();
+        let Some(Json::Array(options)) = organize.get("options") else {
+            panic!("no organize options listed");
+        };
+        let option = |name: &str| {
+            options
+                .iter()
+                .find(|option| option.get("name") == Some(&Json::from(name)))
+                .and_then(|option| option.get("value"))
+                .cloned()
+        };
+        assert_eq!(option("--date"), Some(Json::from(true)));
+        assert_eq!(option("--keep-going"), Some(Json::from(false)));
+        assert_eq!(organize.get("notes"), Some(&Json::Array(Vec::new())));
+        let purge = tasks
+            .iter()
+            .find(|task| task.get("name") == Some(&Json::from("purge")))
+            .unwrap();
+        let Some(Json::Array(notes)) = purge.get("notes") else {
+            panic!("no purge notes listed");
+        };
+        assert!(matches!(&notes[..], [Json::String(note)] if note.contains("'--follow'")));
+        assert_eq!(
+            test_result.get("version"),
+            Some(&Json::from(env!("CARGO_PKG_VERSION")))
+        );
+    }
+
+    /// verifies every listed task is one Config::new() accepts, and that every option matched by the argument parser
+    /// of a task is listed for it so the capabilities cannot drift from the tasks
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Config::new() does not recognize a listed task
+    /// - an option a task parses is missing from its VALUE_OPTIONS and FLAG_OPTIONS
+    #[test]
+    fn capabilities_match_tasks() {
+        for (name, _, _) in TASKS {
+            let args = ["foo", name].map(String::from).into_iter();
+            assert_ne!(
+                Config::new(args).err(),
+                Some("provided task did not match any defined tasks")
+            );
+        }
+
+        // options are matched by arms such as `"--follow" | "--no-follow" =>` before the tests of each module
+        let sources = [
+            ("organize", include_str!("../organize/mod.rs")),
+            ("list", include_str!("../list/mod.rs")),
+            ("split", include_str!("../split/mod.rs")),
+            ("compress", include_str!("../compress/mod.rs")),
+            ("dedupe", include_str!("../dedupe/mod.rs")),
+            ("move", include_str!("../move_task/mod.rs")),
+            ("purge", include_str!("../purge/mod.rs")),
+            ("restore", include_str!("../restore/mod.rs")),
+            ("verify", include_str!("../verify/mod.rs")),
+            ("compare", include_str!("../compare/mod.rs")),
+        ];
+        for (name, source) in sources {
+            let (_, value_options, flag_options) =
+                TASKS.iter().find(|(task, _, _)| *task == name).unwrap();
+            let code = source.split("#[cfg(test)]").next().unwrap();
+            for line in code.lines().map(str::trim) {
+                let Some((arm, _)) = line.split_once(" => ").filter(|_| line.starts_with("\"-"))
+                else {
+                    continue;
+                };
+                for option in arm.split(" | ").map(|option| option.trim_matches('"')) {
+                    assert!(
+                        value_options.contains(&option) || flag_options.contains(&option),
+                        "{name} does not list {option}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,561 @@
+// declare cargo crates
+use std::collections::{BTreeMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+// declare local code
+use super::dedupe::hash_files;
+use super::tools::args::separate_args;
+use super::tools::collect_files::collect_files;
+use super::tools::digest::DigestAlgorithm;
+use super::tools::error::PathContext;
+use super::tools::file_walker::FileWalker;
+use super::tools::paths::expand_paths;
+use super::tools::progress::ProgressCounter;
+use super::tools::symlinks::SymlinkPolicy;
+use super::tools::threads::threads;
+use super::{Logger, RunTask, TaskReport};
+
+/// compare options that are followed by a value, see separate_args()
+pub(crate) const VALUE_OPTIONS: &[&str] = &["--by", "--exclude-dir"];
+
+/// compare options that are not followed by a value, listed along with VALUE_OPTIONS by the capabilities task
+pub(crate) const FLAG_OPTIONS: &[&str] = &["--follow", "--no-follow"];
+
+/// CompareBy enum: how the files of the two trees are matched up
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CompareBy {
+    /// files are matched by their path relative to their tree and must have the same contents
+    #[default]
+    Name,
+
+    /// a file is matched by any file of the other tree with the same contents, wherever it lies, so a tree that was
+    /// organized under new names still counts as contained
+    Content,
+}
+
+/// Difference enum: how a file of one tree differs from the other tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Difference {
+    /// the file has no match in the second tree
+    OnlyInA,
+
+    /// the file has no match in the first tree
+    OnlyInB,
+
+    /// both trees hold a file at the same relative path but with different contents
+    Differs,
+}
+
+impl Difference {
+    /// reason() returns the reason a file with this Difference is listed as failed
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Difference
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn reason(&self) -> &'static str {
+        match self {
+            Difference::OnlyInA => "only in a",
+            Difference::OnlyInB => "only in b",
+            Difference::Differs => "content differs",
+        }
+    }
+}
+
+/// Comparison struct: outcome of comparing the two trees, both lists sorted by path
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Comparison {
+    /// files of the first tree with a match in the second
+    matched: Vec<PathBuf>,
+
+    /// every difference between the trees, a file differing from its counterpart is listed by its path in the first
+    differences: Vec<(PathBuf, Difference)>,
+}
+
+/// CompareTask struct: PathBufs correspond to the two directory trees that are compared
+#[derive(Debug, PartialEq, Eq)]
+pub struct CompareTask {
+    /// PathBuf to the first directory, such as the source of an organize run
+    a: PathBuf,
+
+    /// PathBuf to the second directory, such as the target of an organize run
+    b: PathBuf,
+
+    /// FileWalker used to collect files from both directories
+    walker: FileWalker,
+
+    /// how the files of the two trees are matched up
+    by: CompareBy,
+}
+
+/// RunTask trait implementation for Compare variant of Config enum
+impl RunTask for CompareTask {
+    /// run_task() driver function for Compare variant of Config, prints the number of files in each category followed
+    /// by the returned TaskReport, in which every file with a match is counted as processed and every difference is
+    /// listed as failed, so the exit code tells scripts whether the trees differ, the summary calls them identical and
+    /// different
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Config enum
+    /// `logger` a Logger that records every matched and differing file
+    ///
+    /// # Errors
+    ///
+    /// - compare() call fails
+    /// - Logger::log() call fails
+    fn run_task(&self, logger: &mut Logger) -> Result<TaskReport, io::Error> {
+        let Comparison {
+            matched,
+            differences,
+        } = self.compare(threads())?;
+        let count = |difference: Difference| {
+            differences
+                .iter()
+                .filter(|(_, kind)| *kind == difference)
+                .count()
+        };
+        println!(
+            "{} matching, {} only in a, {} only in b, {} differing",
+            matched.len(),
+            count(Difference::OnlyInA),
+            count(Difference::OnlyInB),
+            count(Difference::Differs)
+        );
+
+        let mut report = TaskReport::worded("identical", "different");
+        for file in &matched {
+            logger.log("compare", file, None)?;
+            report.moved += 1;
+        }
+        for (file, difference) in differences {
+            logger.log("fail", &file, None)?;
+            report
+                .failed
+                .push((file, String::from(difference.reason())));
+        }
+
+        Ok(report)
+    }
+}
+
+impl CompareTask {
+    /// CompareTask struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// `args` - an iterator containing Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - a path refers to an undefined environment variable, see expand_path()
+    /// - `./a/` or `./b/` path not provided
+    /// - `./a/` or `./b/` does not correspond to valid directory
+    /// - more than two paths are provided
+    /// - `--by` is not followed by `name` or `content`
+    /// - `--exclude-dir` is not followed by a name or pattern
+    /// - an unknown option is provided
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
+        // separate options from the paths so they may be given in any position
+        let (paths, options) = separate_args(args, VALUE_OPTIONS);
+        let paths = expand_paths(paths)?;
+        let mut paths = paths.into_iter();
+
+        // ensures both paths are provided and correspond to valid directories
+        let a = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => return Err("no 'a' path provided"),
+        };
+        if !a.is_dir() {
+            return Err("'a' path does not correspond to a valid directory");
+        }
+        let b = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => return Err("no 'b' path provided"),
+        };
+        if !b.is_dir() {
+            return Err("'b' path does not correspond to a valid directory");
+        }
+
+        // errors if extra paths are provided
+        if paths.next().is_some() {
+            return Err("compare accepts exactly one 'a' and one 'b' path");
+        }
+
+        // default options
+        let mut walker = FileWalker::new();
+        let mut by = CompareBy::default();
+
+        // parse options
+        let mut args = options.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--by" => {
+                    by = match args.next().as_deref() {
+                        Some("name") => CompareBy::Name,
+                        Some("content") => CompareBy::Content,
+                        _ => return Err("'--by' must be followed by 'name' or 'content'"),
+                    }
+                }
+                "--follow" => walker = walker.symlinks(SymlinkPolicy::Follow),
+                "--no-follow" => walker = walker.symlinks(SymlinkPolicy::NoFollow),
+                "--exclude-dir" => {
+                    walker = match args.next() {
+                        Some(pattern) => walker.exclude_dir(pattern),
+                        None => {
+                            return Err("'--exclude-dir' must be followed by a name or pattern")
+                        }
+                    }
+                }
+                _ => return Err("provided option did not match any compare options"),
+            }
+        }
+
+        Ok(Self { a, b, walker, by })
+    }
+
+    /// compare() returns every file of &self.a with a match in &self.b along with every difference between the trees.
+    /// Files are compared by size first so only files that could be equal are hashed
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to CompareTask
+    /// `threads` number of worker threads candidate files are hashed on
+    ///
+    /// # Errors
+    ///
+    /// - collect_files() call fails
+    /// - metadata is not retrievable for a given file
+    /// - hash_files() call fails
+    fn compare(&self, threads: usize) -> io::Result<Comparison> {
+        let a = sized_files(&self.walker, &self.a)?;
+        let b = sized_files(&self.walker, &self.b)?;
+
+        let mut differences: Vec<(PathBuf, Difference)> = Vec::new();
+        let mut matched: Vec<PathBuf> = Vec::new();
+        match self.by {
+            CompareBy::Name => {
+                // only files present in both trees with the same size need hashing
+                let same_size: Vec<PathBuf> = a
+                    .iter()
+                    .filter(|(relative, size)| b.get(*relative) == Some(size))
+                    .flat_map(|(relative, _)| [self.a.join(relative), self.b.join(relative)])
+                    .collect();
+                let digests = hash_files(
+                    same_size.iter(),
+                    threads,
+                    DigestAlgorithm::Sha256,
+                    false,
+                    &ProgressCounter::default(),
+                )?;
+
+                for (relative, size) in &a {
+                    let difference = match b.get(relative) {
+                        None => Some(Difference::OnlyInA),
+                        Some(other) if other != size => Some(Difference::Differs),
+                        Some(_) => (digests[&self.a.join(relative)]
+                            != digests[&self.b.join(relative)])
+                            .then_some(Difference::Differs),
+                    };
+                    match difference {
+                        Some(difference) => differences.push((self.a.join(relative), difference)),
+                        None => matched.push(self.a.join(relative)),
+                    }
+                }
+                for relative in b.keys().filter(|relative| !a.contains_key(*relative)) {
+                    differences.push((self.b.join(relative), Difference::OnlyInB));
+                }
+            }
+            CompareBy::Content => {
+                // only files sharing their size with a file of the other tree could be contained in it
+                let sizes_a: HashSet<u64> = a.values().copied().collect();
+                let sizes_b: HashSet<u64> = b.values().copied().collect();
+                let candidates: Vec<PathBuf> = a
+                    .iter()
+                    .filter(|(_, size)| sizes_b.contains(size))
+                    .map(|(relative, _)| self.a.join(relative))
+                    .chain(
+                        b.iter()
+                            .filter(|(_, size)| sizes_a.contains(size))
+                            .map(|(relative, _)| self.b.join(relative)),
+                    )
+                    .collect();
+                let digests = hash_files(
+                    candidates.iter(),
+                    threads,
+                    DigestAlgorithm::Sha256,
+                    false,
+                    &ProgressCounter::default(),
+                )?;
+                let contents = |root: &Path, files: &BTreeMap<PathBuf, u64>| -> HashSet<[u8; 32]> {
+                    files
+                        .keys()
+                        .filter_map(|relative| digests.get(&root.join(relative)).copied())
+                        .collect()
+                };
+                let contents_a = contents(&self.a, &a);
+                let contents_b = contents(&self.b, &b);
+                let contained = |root: &Path, relative: &Path, other: &HashSet<[u8; 32]>| {
+                    digests
+                        .get(&root.join(relative))
+                        .is_some_and(|digest| other.contains(digest))
+                };
+
+                // a file without a match is only reported as differing, once, if the other tree holds its path
+                for relative in a.keys() {
+                    if contained(&self.a, relative, &contents_b) {
+                        matched.push(self.a.join(relative));
+                    } else if b.contains_key(relative) {
+                        differences.push((self.a.join(relative), Difference::Differs));
+                    } else {
+                        differences.push((self.a.join(relative), Difference::OnlyInA));
+                    }
+                }
+                for relative in b.keys() {
+                    if !contained(&self.b, relative, &contents_a) && !a.contains_key(relative) {
+                        differences.push((self.b.join(relative), Difference::OnlyInB));
+                    }
+                }
+            }
+        }
+        differences.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+        Ok(Comparison {
+            matched,
+            differences,
+        })
+    }
+}
+
+/// sized_files() returns the path relative to `dir` and the size of every file found in `dir`
+///
+/// # Arguments
+///
+/// `walker` the FileWalker the files are collected with
+/// `dir` a Path that corresponds to a directory
+///
+/// # Errors
+///
+/// - collect_files() call fails
+/// - metadata is not retrievable for a given file
+fn sized_files(walker: &FileWalker, dir: &Path) -> io::Result<BTreeMap<PathBuf, u64>> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    collect_files(walker, dir, &mut files)?;
+
+    files
+        .into_iter()
+        .map(|file| {
+            let size = file.metadata().path_context(&file)?.len();
+            // collected files always start with the directory they were collected from
+            Ok((file.strip_prefix(dir).unwrap().to_path_buf(), size))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EXIT_PARTIAL_FAILURE;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+
+    /// verifies CompareTask::new() parses both paths and the comparison mode
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - CompareTask::new() does not parse valid arguments
+    /// - CompareTask::new() does not error on a missing path, an unknown mode, or an unknown option
+    #[test]
+    fn compare_task_new() {
+        // args iterator builder
+        let args = |values: &[&str]| {
+            values
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+
+        assert_eq!(
+            CompareTask::new(args(&["./src", "--by", "content", "./src/compare"])),
+            Ok(CompareTask {
+                a: PathBuf::from("./src"),
+                b: PathBuf::from("./src/compare"),
+                walker: FileWalker::new(),
+                by: CompareBy::Content,
+            })
+        );
+        assert_eq!(
+            CompareTask::new(args(&["./src", "./src/compare"])).map(|task| task.by),
+            Ok(CompareBy::Name)
+        );
+        assert!(CompareTask::new(args(&["./src"])).is_err());
+        assert!(CompareTask::new(args(&["./src", "./missing"])).is_err());
+        assert!(CompareTask::new(args(&["./src", "./src", "./src"])).is_err());
+        assert!(CompareTask::new(args(&["./src", "./src", "--by", "size"])).is_err());
+        assert!(CompareTask::new(args(&["./src", "./src", "--quick"])).is_err());
+    }
+
+    /// verifies both modes report files only in the first tree, only in the second, and in both with other contents,
+    /// including contents of the same size, and that comparing by content matches files under new names
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - CompareTask::compare() misses a difference or reports a match as one
+    /// - CompareTask::run_task() does not list every difference as failed
+    #[test]
+    fn compare_task_differences() {
+        // create inputs, `renamed.txt` holds the contents of `a/organized.txt` under another name
+        let path_buf = PathBuf::from("./compare_task_differences_test");
+        let a = path_buf.join("a");
+        let b = path_buf.join("b");
+
+        // create mock directories
+        create_dir_all(a.join("nested")).unwrap();
+        create_dir_all(b.join("nested")).unwrap();
+        for (file, contents) in [
+            ("a/same.txt", "same"),
+            ("b/same.txt", "same"),
+            ("a/nested/resized.txt", "short"),
+            ("b/nested/resized.txt", "longer"),
+            ("a/edited.txt", "1111"),
+            ("b/edited.txt", "2222"),
+            ("a/organized.txt", "organized"),
+            ("b/nested/renamed.txt", "organized"),
+            ("a/lost.txt", "lost"),
+            ("b/extra.txt", "extra"),
+        ] {
+            write(path_buf.join(file), contents).unwrap();
+        }
+
+        // run test
+        let task = |by: CompareBy| CompareTask {
+            a: a.clone(),
+            b: b.clone(),
+            walker: FileWalker::new(),
+            by,
+        };
+        let by_name = task(CompareBy::Name).compare(4);
+        let by_content = task(CompareBy::Content).compare(1);
+        let report = task(CompareBy::Name).run_task(&mut Logger::disabled());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        let Comparison {
+            matched,
+            differences,
+        } = by_name.unwrap();
+        assert_eq!(matched, [a.join("same.txt")]);
+        assert_eq!(
+            differences,
+            [
+                (a.join("edited.txt"), Difference::Differs),
+                (a.join("lost.txt"), Difference::OnlyInA),
+                (a.join("nested/resized.txt"), Difference::Differs),
+                (a.join("organized.txt"), Difference::OnlyInA),
+                (b.join("extra.txt"), Difference::OnlyInB),
+                (b.join("nested/renamed.txt"), Difference::OnlyInB),
+            ]
+        );
+        let Comparison {
+            matched,
+            differences,
+        } = by_content.unwrap();
+        assert_eq!(matched, [a.join("organized.txt"), a.join("same.txt")]);
+        assert_eq!(
+            differences,
+            [
+                (a.join("edited.txt"), Difference::Differs),
+                (a.join("lost.txt"), Difference::OnlyInA),
+                (a.join("nested/resized.txt"), Difference::Differs),
+                (b.join("extra.txt"), Difference::OnlyInB),
+            ]
+        );
+        let report = report.unwrap();
+        assert_eq!(report.moved, 1);
+        assert_eq!(report.failed.len(), 6);
+        assert!(report
+            .to_string()
+            .starts_with("1 identical, 0 skipped, 6 different,"));
+        assert_eq!(
+            report.failed[0],
+            (a.join("edited.txt"), String::from("content differs"))
+        );
+        assert_eq!(report.exit_code(), EXIT_PARTIAL_FAILURE);
+    }
+
+    /// verifies a symbolic link to a directory is left out by default, compared as an entry of its own with
+    /// `--no-follow`, and has the files it leads to compared with `--follow`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - CompareTask::compare() does not honor the symlink policy given on the command line
+    #[cfg(unix)]
+    #[test]
+    fn compare_task_symlink_policy() {
+        use std::os::unix::fs::symlink;
+
+        // create inputs
+        let path_buf = PathBuf::from("./compare_task_symlink_policy_test");
+        let a = path_buf.join("a");
+        let b = path_buf.join("b");
+
+        // create mock directories, b holds the linked folder of a as a real one
+        create_dir_all(path_buf.join("outside")).unwrap();
+        create_dir_all(&a).unwrap();
+        create_dir_all(b.join("linked")).unwrap();
+        for dir in [&path_buf.join("outside"), &b.join("linked")] {
+            write(dir.join("b.txt"), "b").unwrap();
+        }
+        write(a.join("a.txt"), "a").unwrap();
+        write(b.join("a.txt"), "a").unwrap();
+        symlink("../outside", a.join("linked")).unwrap();
+
+        // run test
+        let compare = |policy: Option<&str>| {
+            let mut args = vec![a.display().to_string(), b.display().to_string()];
+            args.extend(policy.map(String::from));
+            CompareTask::new(args.into_iter())
+                .unwrap()
+                .compare(1)
+                .map(|comparison| (comparison.matched.len(), comparison.differences))
+        };
+        let test_result = [None, Some("--no-follow"), Some("--follow")].map(compare);
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        let [skip, no_follow, follow] = test_result.map(Result::unwrap);
+        assert_eq!(
+            skip,
+            (1, vec![(b.join("linked/b.txt"), Difference::OnlyInB)])
+        );
+        assert_eq!(
+            no_follow,
+            (
+                1,
+                vec![
+                    (a.join("linked"), Difference::OnlyInA),
+                    (b.join("linked/b.txt"), Difference::OnlyInB)
+                ]
+            )
+        );
+        assert_eq!(follow, (2, Vec::new()));
+    }
+}
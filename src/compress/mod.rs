@@ -0,0 +1,764 @@
+// declare cargo crates
+use std::collections::BTreeMap;
+use std::fs::{remove_file, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+// declare local code
+use super::tools::args::separate_args;
+use super::tools::collect_files::collect_files;
+use super::tools::crc32::Crc32;
+use super::tools::dates::{date_label, file_date, parse_date_source, DateSource};
+use super::tools::error::{with_path, PathContext};
+use super::tools::file_walker::FileWalker;
+use super::tools::paths::expand_paths;
+use super::tools::symlinks::SymlinkPolicy;
+use super::tools::throttle::{parse_throttle, Throttle};
+use super::{Logger, RunTask, SkipReason, TaskReport};
+
+/// compress options that are followed by a value, see separate_args()
+pub(crate) const VALUE_OPTIONS: &[&str] = &["--format", "--throttle", "--date", "--exclude-dir"];
+
+/// compress options that are not followed by a value, listed along with VALUE_OPTIONS by the capabilities task
+pub(crate) const FLAG_OPTIONS: &[&str] = &["--delete", "--follow", "--no-follow"];
+
+// declare local modules
+mod tar_gz;
+mod zip;
+
+/// size of the buffer files are streamed into archives with
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// ArchiveFormat enum: container format archives are written in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// gzip compressed tar archive
+    #[default]
+    TarGz,
+
+    /// zip archive with deflate compressed entries
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// extension() returns the file extension of archives written in this format
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to ArchiveFormat
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::TarGz => "tar.gz",
+            Self::Zip => "zip",
+        }
+    }
+}
+
+/// ArchiveEntry struct: name, size, and checksum of a single file stored in an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    /// path of the file within the archive, relative to the source directory and separated by `/`
+    pub name: String,
+
+    /// uncompressed size in bytes
+    pub size: u64,
+
+    /// CRC-32 of the uncompressed contents
+    pub crc: u32,
+}
+
+/// ArchiveSource struct: a file to be written into an archive
+struct ArchiveSource<'a> {
+    /// Path to the file on disk
+    path: &'a Path,
+
+    /// name the file is stored under
+    name: String,
+
+    /// last modification date, stored in the archive
+    modified: OffsetDateTime,
+}
+
+/// CompressTask struct: PathBufs correspond to source and target directories along with how archives are written
+#[derive(Debug, PartialEq, Eq)]
+pub struct CompressTask {
+    /// PathBuf to directory containing files to be archived
+    source: PathBuf,
+
+    /// PathBuf to directory the monthly archives are written into
+    target: PathBuf,
+
+    /// FileWalker used to collect files from the source directory
+    walker: FileWalker,
+
+    /// which date of a file decides the month it is archived under, matching the organize task
+    date: DateSource,
+
+    /// container format archives are written in
+    format: ArchiveFormat,
+
+    /// whether to delete the original files once their archive has been verified
+    delete: bool,
+
+    /// maximum average rate in bytes per second that files are read into archives at, None for no limit
+    throttle: Option<u64>,
+}
+
+/// RunTask trait implementation for Compress variant of Config enum
+impl RunTask for CompressTask {
+    /// run_task() driver function for Compress variant of Config, bundles files from &self.source into one archive per
+    /// `YYYY-MM` date folder inside &self.target, every archive is read back and verified after it is written and the
+    /// original files are only deleted, when requested, once this succeeds, the summary counts every stored file as
+    /// archived
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Config enum
+    /// `logger` a Logger that records every archived, deleted, skipped, and failed file
+    ///
+    /// # Errors
+    ///
+    /// - collect_files() call fails
+    /// - creation or modification date is not retrievable for a given file
+    /// - an archive cannot be written or fails verification
+    /// - fs::remove_file() call fails
+    /// - Logger::log() call fails
+    fn run_task(&self, logger: &mut Logger) -> Result<TaskReport, io::Error> {
+        let mut report = TaskReport::worded("archived", "failed");
+
+        // PathBufs for all files found at the source directory
+        let mut files: Vec<PathBuf> = Vec::new();
+        collect_files(&self.walker, &self.source, &mut files)?;
+
+        // files grouped by the YYYY-MM label of their date folder, ordered so archives are written oldest first
+        let mut groups: BTreeMap<String, Vec<ArchiveSource>> = BTreeMap::new();
+        for file in &files {
+            // with `--no-follow` a link to a directory is collected as an entry of its own but holds no contents
+            if file.is_dir() {
+                logger.log("skip", file, None)?;
+                report.skip(
+                    file.clone(),
+                    SkipReason::LinkedDir,
+                    "symbolic link to a directory cannot be archived as a file",
+                );
+                continue;
+            }
+
+            // date the file is grouped by and the modification date stored alongside it
+            let dates = file_date(file, &self.date)
+                .and_then(|date| Ok((date, OffsetDateTime::from(file.metadata()?.modified()?))));
+            let (date, modified) = match dates {
+                Ok(dates) => dates,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    logger.log("skip", file, None)?;
+                    report.skip(
+                        file.clone(),
+                        SkipReason::Vanished,
+                        "file vanished before it could be archived",
+                    );
+                    continue;
+                }
+                Err(err) => {
+                    logger.log("fail", file, None)?;
+                    return Err(with_path(err, file));
+                }
+            };
+
+            groups
+                .entry(date_label(date))
+                .or_default()
+                .push(ArchiveSource {
+                    path: file,
+                    name: self.entry_name(file),
+                    modified,
+                });
+        }
+
+        // shared by every archive so the limit applies to the run as a whole
+        let mut throttle = Throttle::new(self.throttle);
+
+        for (label, sources) in groups {
+            let archive = self.archive_path(&label);
+            let entries = match self.write_archive(&archive, &sources, &mut throttle) {
+                Ok(entries) => entries,
+                Err(err) => {
+                    // do not leave a partial archive behind, the original error is more useful than a removal error
+                    let _ = remove_file(&archive);
+                    logger.log("fail", &archive, None)?;
+                    return Err(with_path(err, &archive));
+                }
+            };
+
+            // read the archive back and ensure every entry matches what was written
+            if self.read_archive(&archive).path_context(&archive)? != entries {
+                logger.log("fail", &archive, None)?;
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("archive {} failed verification", archive.display()),
+                ));
+            }
+
+            for source in &sources {
+                logger.log("archive", source.path, Some(&archive))?;
+            }
+            report.moved += sources.len();
+
+            if self.delete {
+                for source in &sources {
+                    remove_file(source.path).path_context(source.path)?;
+                    logger.log("delete", source.path, None)?;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+impl CompressTask {
+    /// CompressTask struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// `args` - an iterator containing Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - a path refers to an undefined environment variable, see expand_path()
+    /// - `./source/` path not provided
+    /// - `./source/` does not correspond to valid directory
+    /// - `./target/` path not provided
+    /// - `./target/` does not correspond to valid directory
+    /// - more than two paths are provided
+    /// - `--format` is not followed by `tar.gz` or `zip`
+    /// - `--exclude-dir` is not followed by a name or pattern
+    /// - `--date` is not followed by `created`, `modified`, `sidecar`, `sidecar=FIELD`, `name`, or `name:PATTERN`
+    /// - `--throttle` is not followed by a positive number of bytes per second
+    /// - an unknown option is provided
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
+        // separate options from the source and target paths so they may be given in any position
+        let (paths, options) = separate_args(args, VALUE_OPTIONS);
+        let paths = expand_paths(paths)?;
+        let mut paths = paths.into_iter();
+
+        // ensures source path is provided and corresponds to a valid directory
+        let source = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => return Err("no 'source' path provided"),
+        };
+        if !source.is_dir() {
+            return Err("'source' path does not correspond to a valid directory");
+        }
+
+        // ensures target path is provided and corresponds to a valid directory
+        let target = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => return Err("no 'target' path provided"),
+        };
+        if !target.is_dir() {
+            return Err("'target' path does not correspond to a valid directory");
+        }
+
+        // errors if extra paths are provided
+        if paths.next().is_some() {
+            return Err("compress accepts exactly one 'source' and one 'target' path");
+        }
+
+        // default options
+        let mut walker = FileWalker::new();
+        let mut date = DateSource::default();
+        let mut format = ArchiveFormat::default();
+        let mut delete = false;
+        let mut throttle: Option<u64> = None;
+
+        // parse options
+        let mut args = options.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--format" => {
+                    format = match args.next().as_deref() {
+                        Some("tar.gz") => ArchiveFormat::TarGz,
+                        Some("zip") => ArchiveFormat::Zip,
+                        _ => return Err("'--format' must be followed by 'tar.gz' or 'zip'"),
+                    }
+                }
+                "--delete" => delete = true,
+                "--throttle" => throttle = Some(parse_throttle(args.next())?),
+                "--date" => date = parse_date_source(args.next())?,
+                "--follow" => walker = walker.symlinks(SymlinkPolicy::Follow),
+                "--no-follow" => walker = walker.symlinks(SymlinkPolicy::NoFollow),
+                "--exclude-dir" => {
+                    walker = match args.next() {
+                        Some(pattern) => walker.exclude_dir(pattern),
+                        None => {
+                            return Err("'--exclude-dir' must be followed by a name or pattern")
+                        }
+                    }
+                }
+                _ => return Err("provided option did not match any compress options"),
+            }
+        }
+
+        Ok(Self {
+            source,
+            target,
+            walker,
+            date,
+            format,
+            delete,
+            throttle,
+        })
+    }
+
+    /// entry_name() returns the name `file` is stored under, its path relative to &self.source separated by `/`
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to CompressTask
+    /// `file` a Path that corresponds to a file inside &self.source
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn entry_name(&self, file: &Path) -> String {
+        // collected files always live inside the source directory
+        let relative = file.strip_prefix(&self.source).unwrap_or(file);
+
+        relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// archive_path() returns the path of the archive for `label`, suffixed with `_1`, `_2`, ... if an archive with
+    /// that name already exists so existing archives are never overwritten
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to CompressTask
+    /// `label` the `YYYY-MM` label of the files being archived
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn archive_path(&self, label: &str) -> PathBuf {
+        let extension = self.format.extension();
+        let path = self.target.join(format!("{label}.{extension}"));
+        if !path.exists() {
+            return path;
+        }
+
+        (1..)
+            .map(|suffix| self.target.join(format!("{label}_{suffix}.{extension}")))
+            .find(|path| !path.exists())
+            .unwrap() // the range is unbounded so a free path is always found
+    }
+
+    /// write_archive() streams `sources` into a new archive at `archive` and returns the entries written
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to CompressTask
+    /// `archive` a Path that corresponds to the archive to create
+    /// `sources` the files to store in the archive
+    /// `throttle` a Throttle limiting the rate files are read at
+    ///
+    /// # Errors
+    ///
+    /// - the archive cannot be created or written to
+    /// - a file cannot be read or changes size while being archived
+    /// - a file exceeds the limits of the archive format
+    fn write_archive(
+        &self,
+        archive: &Path,
+        sources: &[ArchiveSource],
+        throttle: &mut Throttle,
+    ) -> io::Result<Vec<ArchiveEntry>> {
+        // create_new ensures an archive created by another process in the meantime is not overwritten
+        let file = File::create_new(archive)?;
+
+        match self.format {
+            ArchiveFormat::TarGz => tar_gz::write(file, sources, throttle),
+            ArchiveFormat::Zip => zip::write(file, sources, throttle),
+        }
+    }
+
+    /// read_archive() reads the archive at `archive` back and returns the entries it contains
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to CompressTask
+    /// `archive` a Path that corresponds to the archive to read
+    ///
+    /// # Errors
+    ///
+    /// - the archive cannot be opened or read
+    /// - the archive is malformed or a stored checksum does not match its contents
+    fn read_archive(&self, archive: &Path) -> io::Result<Vec<ArchiveEntry>> {
+        let file = File::open(archive)?;
+
+        match self.format {
+            ArchiveFormat::TarGz => tar_gz::read(file),
+            ArchiveFormat::Zip => zip::read(file),
+        }
+    }
+}
+
+/// copy_exact() copies exactly `size` bytes from `reader` into `writer` and returns their CRC-32
+///
+/// # Arguments
+///
+/// `reader` source of the data
+/// `writer` destination of the data
+/// `size` number of bytes to copy
+/// `throttle` a Throttle limiting the rate data is copied at
+///
+/// # Errors
+///
+/// - reading from `reader` or writing to `writer` fails
+/// - `reader` holds fewer or more than `size` bytes, which happens when a file changes while being archived
+fn copy_exact(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    size: u64,
+    throttle: &mut Throttle,
+) -> io::Result<u32> {
+    let mut crc = Crc32::new();
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut remaining = size;
+
+    while remaining > 0 {
+        let wanted = remaining.min(BUFFER_SIZE as u64) as usize;
+        let read = reader.read(&mut buffer[..wanted])?;
+        if read == 0 {
+            return Err(changed_while_archived());
+        }
+
+        crc.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        remaining -= read as u64;
+        throttle.consume(read as u64);
+    }
+
+    // the file grew after its size was read
+    if reader.read(&mut [0u8; 1])? != 0 {
+        return Err(changed_while_archived());
+    }
+
+    Ok(crc.value())
+}
+
+/// changed_while_archived() creates the error returned when a file changes size while it is being archived
+///
+/// # Arguments
+///
+/// None
+///
+/// # Errors
+///
+/// None
+fn changed_while_archived() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "file changed size while being archived",
+    )
+}
+
+/// CrcWriter struct: Write implementation that discards data while recording its size and CRC-32, used when reading
+/// archives back for verification
+#[derive(Debug, Default)]
+struct CrcWriter {
+    /// CRC-32 of all data written so far
+    crc: Crc32,
+
+    /// number of bytes written so far
+    size: u64,
+}
+
+/// Write trait implementation for CrcWriter
+impl Write for CrcWriter {
+    /// write() records `buf` in the checksum and size
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to CrcWriter
+    /// `buf` the data written
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.crc.update(buf);
+        self.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    /// flush() does nothing as no data is stored
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to CrcWriter
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, read_dir, remove_dir_all, write};
+    use std::time::{Duration, SystemTime};
+
+    /// verifies CompressTask::new() parses paths and options and rejects invalid formats
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - CompressTask::new() does not parse valid arguments
+    /// - CompressTask::new() does not error on an unknown format
+    #[test]
+    fn compress_task_new() {
+        // args iterator builder
+        let args = |options: &[&str]| {
+            ["./src", "./src/compress"]
+                .iter()
+                .chain(options)
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+
+        assert_eq!(
+            CompressTask::new(args(&["--format", "zip", "--delete"])),
+            Ok(CompressTask {
+                source: PathBuf::from("./src"),
+                target: PathBuf::from("./src/compress"),
+                walker: FileWalker::new(),
+                date: DateSource::default(),
+                format: ArchiveFormat::Zip,
+                delete: true,
+                throttle: None,
+            })
+        );
+        assert_eq!(
+            CompressTask::new(args(&[])).map(|task| task.format),
+            Ok(ArchiveFormat::TarGz)
+        );
+        assert!(CompressTask::new(args(&["--format", "rar"])).is_err());
+        assert!(CompressTask::new(args(&["--format"])).is_err());
+    }
+
+    /// verifies CompressTask::run_task() writes one archive per month that reads back to the original files, and
+    /// deletes originals only when requested
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - CompressTask::run_task() does not group files by month
+    /// - archives do not read back to the original names, sizes, and checksums
+    /// - originals are not deleted with `--delete` or are deleted without it
+    #[test]
+    fn compress_task_round_trip() {
+        for (format, delete) in [(ArchiveFormat::TarGz, false), (ArchiveFormat::Zip, true)] {
+            // create inputs
+            let path_buf = PathBuf::from(format!("./compress_task_{}_test", format.extension()));
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+
+            // create mock directories with files from two months, one with a name too long for a plain tar header
+            let long_name = format!("{}.txt", "n".repeat(120));
+            let contents: Vec<(String, Vec<u8>, u64)> = vec![
+                (String::from("a.txt"), b"first".to_vec(), 1_000_000_000),
+                (
+                    String::from("nested/b.bin"),
+                    (0..=255).cycle().take(200_000).collect(),
+                    1_000_000_000,
+                ),
+                (String::from("empty"), Vec::new(), 1_000_000_000),
+                (
+                    format!("nested/{long_name}"),
+                    b"later".repeat(1000),
+                    1_010_000_000,
+                ),
+            ];
+            create_dir_all(source.join("nested")).unwrap();
+            create_dir_all(&target).unwrap();
+            for (name, data, seconds) in &contents {
+                write(source.join(name), data).unwrap();
+                File::options()
+                    .write(true)
+                    .open(source.join(name))
+                    .unwrap()
+                    .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(*seconds))
+                    .unwrap();
+            }
+
+            // run test
+            let task = CompressTask {
+                source: source.clone(),
+                target: target.clone(),
+                walker: FileWalker::new(),
+                date: DateSource::Modified,
+                format,
+                delete,
+                throttle: None,
+            };
+            let report = task.run_task(&mut Logger::disabled()).unwrap();
+
+            let mut archives: Vec<String> = read_dir(&target)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+                .collect();
+            archives.sort();
+
+            let first = task.read_archive(&target.join(&archives[0])).unwrap();
+            let second = task.read_archive(&target.join(&archives[1])).unwrap();
+            let remaining = source.join("a.txt").exists();
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            // expected entries of an archive
+            let expected = |range: std::ops::Range<usize>| -> Vec<ArchiveEntry> {
+                let mut entries: Vec<ArchiveEntry> = contents[range]
+                    .iter()
+                    .map(|(name, data, _)| {
+                        let mut crc = Crc32::new();
+                        crc.update(data);
+                        ArchiveEntry {
+                            name: name.clone(),
+                            size: data.len() as u64,
+                            crc: crc.value(),
+                        }
+                    })
+                    .collect();
+                entries.sort_by(|a, b| a.name.cmp(&b.name));
+                entries
+            };
+            let sorted = |mut entries: Vec<ArchiveEntry>| {
+                entries.sort_by(|a, b| a.name.cmp(&b.name));
+                entries
+            };
+
+            let extension = format.extension();
+            assert_eq!(
+                archives,
+                vec![
+                    format!("2001-09.{extension}"),
+                    format!("2002-01.{extension}")
+                ]
+            );
+            assert_eq!(sorted(first), expected(0..3));
+            assert_eq!(sorted(second), expected(3..4));
+            assert_eq!(report.moved, 4);
+            assert!(report.to_string().starts_with("4 archived,"));
+            assert_eq!(remaining, !delete);
+        }
+    }
+
+    /// verifies copy_exact() errors when the data does not match the expected size
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - copy_exact() does not error on data that is shorter or longer than expected
+    #[test]
+    fn copy_exact_size_mismatch() {
+        let mut output: Vec<u8> = Vec::new();
+        let mut throttle = Throttle::default();
+
+        assert!(copy_exact(&mut b"abc".as_slice(), &mut output, 3, &mut throttle).is_ok());
+        assert!(copy_exact(&mut b"ab".as_slice(), &mut output, 3, &mut throttle).is_err());
+        assert!(copy_exact(&mut b"abcd".as_slice(), &mut output, 3, &mut throttle).is_err());
+    }
+
+    /// verifies a symbolic link to a directory is left out of the archive by default, skipped as a linked directory
+    /// with `--no-follow`, and has the files it leads to archived with `--follow`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - CompressTask::run_task() does not honor the symlink policy given on the command line
+    #[cfg(unix)]
+    #[test]
+    fn compress_task_symlink_policy() {
+        use std::os::unix::fs::symlink;
+
+        for policy in [None, Some("--no-follow"), Some("--follow")] {
+            // create inputs, 1_000_000_000 seconds after the epoch falls in 2001-09
+            let path_buf = PathBuf::from("./compress_task_symlink_policy_test");
+            let outside = path_buf.join("outside");
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+
+            // create mock directories, the linked folder lives next to the source
+            for dir in [&outside, &source, &target] {
+                create_dir_all(dir).unwrap();
+            }
+            for file in [outside.join("b.txt"), source.join("a.txt")] {
+                write(&file, "contents").unwrap();
+                File::options()
+                    .write(true)
+                    .open(&file)
+                    .unwrap()
+                    .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000))
+                    .unwrap();
+            }
+            symlink("../outside", source.join("linked")).unwrap();
+
+            // run test
+            let mut args = vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+            ];
+            args.extend(policy.map(String::from));
+            let task = CompressTask::new(args.into_iter()).unwrap();
+            let report = task.run_task(&mut Logger::disabled());
+            let names = task
+                .read_archive(&target.join("2001-09.tar.gz"))
+                .map(|entries| {
+                    entries
+                        .into_iter()
+                        .map(|entry| entry.name)
+                        .collect::<Vec<_>>()
+                });
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            let report = report.unwrap();
+            let (moved, linked, expected) = match policy {
+                None => (1, Vec::new(), vec!["a.txt"]),
+                Some("--no-follow") => (1, vec![(SkipReason::LinkedDir, 1)], vec!["a.txt"]),
+                _ => (2, Vec::new(), vec!["a.txt", "linked/b.txt"]),
+            };
+            assert_eq!(report.moved, moved);
+            assert_eq!(report.skip_counts(), linked);
+            assert_eq!(names.unwrap(), expected);
+        }
+    }
+}
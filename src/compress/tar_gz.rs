@@ -0,0 +1,556 @@
+// declare cargo crates
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+// declare local code
+use super::super::tools::crc32::Crc32;
+use super::super::tools::deflate::{inflate, DeflateEncoder};
+use super::super::tools::throttle::Throttle;
+use super::{copy_exact, ArchiveEntry, ArchiveSource, CrcWriter};
+
+/// size of a tar header and the unit tar data is padded to
+const BLOCK: usize = 512;
+
+/// longest name that fits in the name field of a tar header, longer names are stored in a GNU long name entry
+const NAME_SIZE: usize = 100;
+
+/// longest GNU long name accepted when reading, keeps memory bounded for malformed archives
+const MAX_LONG_NAME: usize = 64 * 1024;
+
+/// gzip header: magic, deflate method, no flags, no modification time, no extra flags, unknown operating system
+const GZIP_HEADER: [u8; 10] = [0x1F, 0x8B, 0x08, 0x00, 0, 0, 0, 0, 0x00, 0xFF];
+
+/// write() streams `sources` into a gzip compressed tar archive written to `file` and returns the entries written
+///
+/// # Arguments
+///
+/// `file` the newly created archive
+/// `sources` the files to store in the archive
+/// `throttle` a Throttle limiting the rate files are read at
+///
+/// # Errors
+///
+/// - writing to `file` fails
+/// - a file cannot be opened or read, or changes size while being archived
+pub(super) fn write(
+    file: File,
+    sources: &[ArchiveSource],
+    throttle: &mut Throttle,
+) -> io::Result<Vec<ArchiveEntry>> {
+    let mut gzip = GzipWriter::new(BufWriter::new(file))?;
+    let mut entries: Vec<ArchiveEntry> = Vec::with_capacity(sources.len());
+
+    for source in sources {
+        let mut input = File::open(source.path)?;
+        let size = input.metadata()?.len();
+        let mtime = source.modified.unix_timestamp().max(0) as u64;
+        let name = source.name.as_bytes();
+
+        // names that do not fit in the header are stored NUL terminated in a preceding GNU long name entry
+        if name.len() > NAME_SIZE {
+            gzip.write_all(&header(b"././@LongLink", name.len() as u64 + 1, 0, b'L'))?;
+            gzip.write_all(name)?;
+            gzip.write_all(&[0])?;
+            gzip.write_all(&padding(name.len() as u64 + 1))?;
+        }
+
+        gzip.write_all(&header(
+            &name[..name.len().min(NAME_SIZE)],
+            size,
+            mtime,
+            b'0',
+        ))?;
+        let crc = copy_exact(&mut input, &mut gzip, size, throttle)?;
+        gzip.write_all(&padding(size))?;
+
+        entries.push(ArchiveEntry {
+            name: source.name.clone(),
+            size,
+            crc,
+        });
+    }
+
+    // two zero blocks mark the end of the archive
+    gzip.write_all(&[0u8; BLOCK * 2])?;
+    gzip.finish()?.flush()?;
+
+    Ok(entries)
+}
+
+/// read() reads the gzip compressed tar archive in `file` and returns the entries it contains, file contents are
+/// checksummed as they are decompressed rather than held in memory
+///
+/// # Arguments
+///
+/// `file` the archive to read
+///
+/// # Errors
+///
+/// - reading from `file` fails
+/// - the gzip or tar data is malformed, truncated, or fails a checksum
+pub(super) fn read(file: File) -> io::Result<Vec<ArchiveEntry>> {
+    let mut reader = BufReader::new(file);
+
+    // only the header written by GzipWriter is supported
+    let mut gzip_header = [0u8; 10];
+    reader.read_exact(&mut gzip_header)?;
+    if gzip_header[..4] != GZIP_HEADER[..4] {
+        return Err(invalid_data("unsupported gzip header"));
+    }
+
+    let mut parser = TarParser::default();
+    inflate(&mut reader, &mut parser)?;
+
+    // trailer holds the CRC-32 and size modulo 2^32 of the uncompressed data
+    let mut trailer = [0u8; 8];
+    reader.read_exact(&mut trailer)?;
+    if u32::from_le_bytes(trailer[..4].try_into().unwrap()) != parser.stream.crc.value()
+        || u32::from_le_bytes(trailer[4..].try_into().unwrap()) != parser.stream.size as u32
+    {
+        return Err(invalid_data(
+            "gzip checksum does not match archive contents",
+        ));
+    }
+
+    parser.finish()
+}
+
+/// header() builds a tar header in GNU format for an entry of type `kind`
+///
+/// # Arguments
+///
+/// `name` name of the entry, at most NAME_SIZE bytes
+/// `size` size of the entry's data in bytes
+/// `mtime` modification time of the entry in seconds since the Unix epoch
+/// `kind` the type flag of the entry, `0` for regular files and `L` for long names
+///
+/// # Errors
+///
+/// None
+fn header(name: &[u8], size: u64, mtime: u64, kind: u8) -> [u8; BLOCK] {
+    let mut header = [0u8; BLOCK];
+
+    header[..name.len()].copy_from_slice(name);
+    octal(&mut header[100..108], 0o644);
+    octal(&mut header[108..116], 0);
+    octal(&mut header[116..124], 0);
+    octal(&mut header[124..136], size);
+    octal(&mut header[136..148], mtime);
+    header[156] = kind;
+    header[257..265].copy_from_slice(b"ustar  \0");
+
+    // the checksum is calculated with its own field filled with spaces
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|byte| *byte as u32).sum();
+    header[148..155].copy_from_slice(format!("{checksum:06o}\0").as_bytes());
+
+    header
+}
+
+/// octal() writes `value` into a numeric header field as NUL terminated octal, values too large for octal are written
+/// in the GNU base-256 encoding
+///
+/// # Arguments
+///
+/// `field` the header field
+/// `value` the value to store
+///
+/// # Errors
+///
+/// None
+fn octal(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    let text = format!("{value:0digits$o}");
+
+    if text.len() <= digits {
+        field[..digits].copy_from_slice(text.as_bytes());
+        field[digits] = 0;
+    } else {
+        field.fill(0);
+        let bytes = value.to_be_bytes();
+        let length = field.len();
+        field[length - bytes.len()..].copy_from_slice(&bytes);
+        field[0] |= 0x80;
+    }
+}
+
+/// parse_number() reads a numeric header field written in octal or base-256
+///
+/// # Arguments
+///
+/// `field` the header field
+///
+/// # Errors
+///
+/// - the field does not hold a valid number
+fn parse_number(field: &[u8]) -> io::Result<u64> {
+    if field[0] & 0x80 != 0 {
+        return Ok(field[1..]
+            .iter()
+            .fold((field[0] & 0x7F) as u64, |value, byte| {
+                value << 8 | *byte as u64
+            }));
+    }
+
+    let text = String::from_utf8_lossy(field);
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if text.is_empty() {
+        return Ok(0);
+    }
+
+    u64::from_str_radix(text, 8).map_err(|_| invalid_data("invalid number in tar header"))
+}
+
+/// padding() returns the zeros that follow `size` bytes of data to fill the last block
+///
+/// # Arguments
+///
+/// `size` size of the data in bytes
+///
+/// # Errors
+///
+/// None
+fn padding(size: u64) -> Vec<u8> {
+    vec![0u8; (BLOCK - (size % BLOCK as u64) as usize) % BLOCK]
+}
+
+/// invalid_data() creates an InvalidData error with `message`
+///
+/// # Arguments
+///
+/// `message` description of the problem
+///
+/// # Errors
+///
+/// None
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// GzipWriter struct: wraps a DeflateEncoder in gzip framing
+struct GzipWriter<W: Write> {
+    /// compressor of the uncompressed data
+    encoder: DeflateEncoder<W>,
+
+    /// CRC-32 of the uncompressed data
+    crc: Crc32,
+
+    /// size of the uncompressed data in bytes
+    size: u64,
+}
+
+impl<W: Write> GzipWriter<W> {
+    /// GzipWriter struct initializer, writes the gzip header to `inner`
+    ///
+    /// # Arguments
+    ///
+    /// `inner` destination of the gzip stream
+    ///
+    /// # Errors
+    ///
+    /// - writing to `inner` fails
+    fn new(mut inner: W) -> io::Result<Self> {
+        inner.write_all(&GZIP_HEADER)?;
+
+        Ok(Self {
+            encoder: DeflateEncoder::new(inner),
+            crc: Crc32::new(),
+            size: 0,
+        })
+    }
+
+    /// finish() terminates the compressed data, writes the gzip trailer, and returns the destination
+    ///
+    /// # Arguments
+    ///
+    /// `self` the GzipWriter to finish
+    ///
+    /// # Errors
+    ///
+    /// - writing to the destination fails
+    fn finish(self) -> io::Result<W> {
+        let mut inner = self.encoder.finish()?;
+        inner.write_all(&self.crc.value().to_le_bytes())?;
+        inner.write_all(&(self.size as u32).to_le_bytes())?;
+
+        Ok(inner)
+    }
+}
+
+/// Write trait implementation for GzipWriter
+impl<W: Write> Write for GzipWriter<W> {
+    /// write() compresses `buf` while recording its checksum and size
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to GzipWriter
+    /// `buf` the uncompressed data
+    ///
+    /// # Errors
+    ///
+    /// - writing to the destination fails
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.encoder.write(buf)?;
+        self.crc.update(&buf[..written]);
+        self.size += written as u64;
+
+        Ok(written)
+    }
+
+    /// flush() flushes the destination
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to GzipWriter
+    ///
+    /// # Errors
+    ///
+    /// - flushing the destination fails
+    fn flush(&mut self) -> io::Result<()> {
+        self.encoder.flush()
+    }
+}
+
+/// Body enum: what the data following a tar header is used for
+enum Body {
+    /// contents of a regular file
+    File { name: String, contents: CrcWriter },
+
+    /// GNU long name of the next entry
+    LongName(Vec<u8>),
+
+    /// data of an entry that is not recorded, such as a directory or link
+    Skip,
+}
+
+/// ParserState enum: position of a TarParser within the archive
+#[derive(Default)]
+enum ParserState {
+    /// reading a header
+    #[default]
+    Header,
+
+    /// reading the data of an entry followed by its padding
+    Body {
+        body: Body,
+        remaining: u64,
+        padding: u64,
+    },
+
+    /// the end of archive marker has been read
+    End,
+}
+
+/// TarParser struct: Write implementation that parses a tar stream as it is decompressed
+#[derive(Default)]
+struct TarParser {
+    /// checksum and size of the whole stream, compared against the gzip trailer
+    stream: CrcWriter,
+
+    /// header bytes read so far
+    block: Vec<u8>,
+
+    /// current position within the archive
+    state: ParserState,
+
+    /// GNU long name that replaces the name of the next entry
+    long_name: Option<String>,
+
+    /// every regular file read so far
+    entries: Vec<ArchiveEntry>,
+}
+
+impl TarParser {
+    /// parse_header() interprets the complete header held in &self.block
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to TarParser
+    ///
+    /// # Errors
+    ///
+    /// - the header checksum does not match
+    /// - a numeric field is invalid
+    /// - a long name is larger than MAX_LONG_NAME
+    fn parse_header(&mut self) -> io::Result<()> {
+        let block = std::mem::take(&mut self.block);
+
+        // a zero block marks the end of the archive
+        if block.iter().all(|byte| *byte == 0) {
+            self.state = ParserState::End;
+            return Ok(());
+        }
+
+        let stored = parse_number(&block[148..156])?;
+        let checksum: u64 = block
+            .iter()
+            .enumerate()
+            .map(|(index, byte)| if (148..156).contains(&index) { b' ' } else { *byte } as u64)
+            .sum();
+        if stored != checksum {
+            return Err(invalid_data("tar header checksum does not match"));
+        }
+
+        let size = parse_number(&block[124..136])?;
+        let field = |range: std::ops::Range<usize>| {
+            let field = &block[range];
+            let end = field
+                .iter()
+                .position(|byte| *byte == 0)
+                .unwrap_or(field.len());
+            String::from_utf8_lossy(&field[..end]).to_string()
+        };
+
+        let body = match block[156] {
+            b'0' | 0 => {
+                // names may be split across the ustar prefix and name fields
+                let name = self.long_name.take().unwrap_or_else(|| {
+                    let prefix = field(345..500);
+                    if &block[257..263] == b"ustar\0" && !prefix.is_empty() {
+                        format!("{prefix}/{}", field(0..100))
+                    } else {
+                        field(0..100)
+                    }
+                });
+                Body::File {
+                    name,
+                    contents: CrcWriter::default(),
+                }
+            }
+            b'L' if size as usize <= MAX_LONG_NAME => Body::LongName(Vec::new()),
+            b'L' => return Err(invalid_data("tar long name is too large")),
+            _ => Body::Skip,
+        };
+
+        self.state = ParserState::Body {
+            body,
+            remaining: size,
+            padding: padding(size).len() as u64,
+        };
+
+        Ok(())
+    }
+
+    /// finish_body() records the entry whose data has been completely read
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to TarParser
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn finish_body(&mut self) {
+        if let ParserState::Body { body, .. } = std::mem::take(&mut self.state) {
+            match body {
+                Body::File { name, contents } => self.entries.push(ArchiveEntry {
+                    name,
+                    size: contents.size,
+                    crc: contents.crc.value(),
+                }),
+                Body::LongName(mut name) => {
+                    while name.last() == Some(&0) {
+                        name.pop();
+                    }
+                    self.long_name = Some(String::from_utf8_lossy(&name).to_string());
+                }
+                Body::Skip => {}
+            }
+        }
+    }
+
+    /// finish() returns every regular file in the archive
+    ///
+    /// # Arguments
+    ///
+    /// `self` the TarParser to finish
+    ///
+    /// # Errors
+    ///
+    /// - the stream ended before the end of archive marker
+    fn finish(self) -> io::Result<Vec<ArchiveEntry>> {
+        match self.state {
+            ParserState::End => Ok(self.entries),
+            _ => Err(invalid_data("tar archive ended unexpectedly")),
+        }
+    }
+}
+
+/// Write trait implementation for TarParser
+impl Write for TarParser {
+    /// write() advances the parser through `buf`
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to TarParser
+    /// `buf` the next chunk of the tar stream
+    ///
+    /// # Errors
+    ///
+    /// - see TarParser::parse_header()
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.write_all(buf)?;
+        let mut rest = buf;
+
+        loop {
+            match &mut self.state {
+                ParserState::Header => {
+                    if rest.is_empty() {
+                        break;
+                    }
+                    let taken = rest.len().min(BLOCK - self.block.len());
+                    self.block.extend_from_slice(&rest[..taken]);
+                    rest = &rest[taken..];
+                    if self.block.len() == BLOCK {
+                        self.parse_header()?;
+                    }
+                }
+                ParserState::Body {
+                    body,
+                    remaining,
+                    padding,
+                } => {
+                    if *remaining > 0 {
+                        if rest.is_empty() {
+                            break;
+                        }
+                        let taken = rest.len().min(*remaining as usize);
+                        match body {
+                            Body::File { contents, .. } => contents.write_all(&rest[..taken])?,
+                            Body::LongName(name) => name.extend_from_slice(&rest[..taken]),
+                            Body::Skip => {}
+                        }
+                        *remaining -= taken as u64;
+                        rest = &rest[taken..];
+                    } else if *padding > 0 {
+                        if rest.is_empty() {
+                            break;
+                        }
+                        let taken = rest.len().min(*padding as usize);
+                        *padding -= taken as u64;
+                        rest = &rest[taken..];
+                    } else {
+                        self.finish_body();
+                    }
+                }
+                // anything after the end of archive marker is padding
+                ParserState::End => break,
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    /// flush() does nothing as no data is stored
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to TarParser
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
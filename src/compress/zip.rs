@@ -0,0 +1,340 @@
+// declare cargo crates
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use time::OffsetDateTime;
+
+// declare local code
+use super::super::tools::deflate::{inflate, DeflateEncoder};
+use super::super::tools::throttle::Throttle;
+use super::{copy_exact, ArchiveEntry, ArchiveSource, CrcWriter};
+
+/// signatures of the zip records written
+const LOCAL_SIGNATURE: u32 = 0x0403_4B50;
+const DESCRIPTOR_SIGNATURE: u32 = 0x0807_4B50;
+const CENTRAL_SIGNATURE: u32 = 0x0201_4B50;
+const END_SIGNATURE: u32 = 0x0605_4B50;
+
+/// version 2.0 of the zip specification, the first to support deflate
+const VERSION: u16 = 20;
+
+/// general purpose flags, sizes and checksum follow the data (bit 3) and names are UTF-8 (bit 11)
+const FLAGS: u16 = 0x0808;
+
+/// compression methods
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+
+/// write() streams `sources` into a zip archive written to `file` and returns the entries written, sizes and checksums
+/// are written after each file's data so no seeking is needed
+///
+/// # Arguments
+///
+/// `file` the newly created archive
+/// `sources` the files to store in the archive
+/// `throttle` a Throttle limiting the rate files are read at
+///
+/// # Errors
+///
+/// - writing to `file` fails
+/// - a file cannot be opened or read, or changes size while being archived
+/// - the archive exceeds the 4 GiB or 65535 entry limits of zip archives without ZIP64
+pub(super) fn write(
+    file: File,
+    sources: &[ArchiveSource],
+    throttle: &mut Throttle,
+) -> io::Result<Vec<ArchiveEntry>> {
+    let mut writer = CountingWriter {
+        inner: BufWriter::new(file),
+        count: 0,
+    };
+    let mut entries: Vec<ArchiveEntry> = Vec::with_capacity(sources.len());
+    let mut central: Vec<u8> = Vec::new();
+
+    let entry_count = u16::try_from(sources.len()).map_err(|_| too_large())?;
+
+    for source in sources {
+        let mut input = File::open(source.path)?;
+        let size = input.metadata()?.len();
+        let offset = writer.count;
+        let (time, date) = dos_date_time(source.modified);
+        let name = source.name.as_bytes();
+        let name_length = u16::try_from(name.len()).map_err(|_| too_large())?;
+
+        // local header, checksum and sizes are left zero as they follow in the data descriptor
+        let mut local: Vec<u8> = Vec::with_capacity(30 + name.len());
+        put32(&mut local, LOCAL_SIGNATURE);
+        for value in [VERSION, FLAGS, DEFLATED, time, date] {
+            put16(&mut local, value);
+        }
+        for _ in 0..3 {
+            put32(&mut local, 0);
+        }
+        put16(&mut local, name_length);
+        put16(&mut local, 0);
+        local.extend_from_slice(name);
+        writer.write_all(&local)?;
+
+        // compressed data
+        let start = writer.count;
+        let mut encoder = DeflateEncoder::new(&mut writer);
+        let crc = copy_exact(&mut input, &mut encoder, size, throttle)?;
+        encoder.finish()?;
+        let compressed = writer.count - start;
+
+        let size32 = u32::try_from(size).map_err(|_| too_large())?;
+        let compressed32 = u32::try_from(compressed).map_err(|_| too_large())?;
+        let offset32 = u32::try_from(offset).map_err(|_| too_large())?;
+
+        // data descriptor
+        let mut descriptor: Vec<u8> = Vec::with_capacity(16);
+        for value in [DESCRIPTOR_SIGNATURE, crc, compressed32, size32] {
+            put32(&mut descriptor, value);
+        }
+        writer.write_all(&descriptor)?;
+
+        // central directory record, version made by is MS-DOS so extractors apply their default permissions
+        put32(&mut central, CENTRAL_SIGNATURE);
+        for value in [VERSION, VERSION, FLAGS, DEFLATED, time, date] {
+            put16(&mut central, value);
+        }
+        for value in [crc, compressed32, size32] {
+            put32(&mut central, value);
+        }
+        for value in [name_length, 0, 0, 0, 0] {
+            put16(&mut central, value);
+        }
+        put32(&mut central, 0);
+        put32(&mut central, offset32);
+        central.extend_from_slice(name);
+
+        entries.push(ArchiveEntry {
+            name: source.name.clone(),
+            size,
+            crc,
+        });
+    }
+
+    // central directory followed by the end of central directory record
+    let central_offset = u32::try_from(writer.count).map_err(|_| too_large())?;
+    writer.write_all(&central)?;
+
+    let mut end: Vec<u8> = Vec::with_capacity(22);
+    put32(&mut end, END_SIGNATURE);
+    for value in [0, 0, entry_count, entry_count] {
+        put16(&mut end, value);
+    }
+    put32(&mut end, central.len() as u32);
+    put32(&mut end, central_offset);
+    put16(&mut end, 0);
+    writer.write_all(&end)?;
+    writer.flush()?;
+
+    Ok(entries)
+}
+
+/// read() reads the entries of the zip archive in `file` from their local headers, file contents are checksummed as
+/// they are decompressed rather than held in memory
+///
+/// # Arguments
+///
+/// `file` the archive to read
+///
+/// # Errors
+///
+/// - reading from `file` fails
+/// - the archive is malformed, truncated, uses an unsupported compression method, or fails a checksum
+pub(super) fn read(file: File) -> io::Result<Vec<ArchiveEntry>> {
+    let mut reader = BufReader::new(file);
+    let mut entries: Vec<ArchiveEntry> = Vec::new();
+
+    loop {
+        // the central directory begins once every entry has been read
+        match read32(&mut reader)? {
+            LOCAL_SIGNATURE => {}
+            CENTRAL_SIGNATURE | END_SIGNATURE => return Ok(entries),
+            _ => return Err(invalid_data("invalid zip record signature")),
+        }
+
+        let mut header = [0u8; 26];
+        reader.read_exact(&mut header)?;
+        let field16 = |at: usize| u16::from_le_bytes([header[at], header[at + 1]]);
+        let field32 = |at: usize| u32::from_le_bytes(header[at..at + 4].try_into().unwrap());
+        let (flags, method) = (field16(2), field16(4));
+        let (mut crc, compressed, mut size) = (field32(10), field32(14), field32(18));
+
+        let mut name = vec![0u8; field16(22) as usize];
+        reader.read_exact(&mut name)?;
+        io::copy(&mut (&mut reader).take(field16(24) as u64), &mut io::sink())?;
+
+        let mut contents = CrcWriter::default();
+        match method {
+            DEFLATED => {
+                inflate(&mut reader, &mut contents)?;
+            }
+            STORED if flags & 0x08 == 0 => {
+                io::copy(&mut (&mut reader).take(compressed as u64), &mut contents)?;
+            }
+            _ => return Err(invalid_data("unsupported zip compression method")),
+        }
+
+        // data descriptor, its signature is optional
+        if flags & 0x08 != 0 {
+            crc = read32(&mut reader)?;
+            if crc == DESCRIPTOR_SIGNATURE {
+                crc = read32(&mut reader)?;
+            }
+            // the compressed size is not needed once the data has been read
+            read32(&mut reader)?;
+            size = read32(&mut reader)?;
+        }
+
+        if crc != contents.crc.value() || size as u64 != contents.size {
+            return Err(invalid_data("zip checksum does not match entry contents"));
+        }
+
+        entries.push(ArchiveEntry {
+            name: String::from_utf8_lossy(&name).to_string(),
+            size: contents.size,
+            crc,
+        });
+    }
+}
+
+/// dos_date_time() converts `date` into the MS-DOS time and date fields used by zip, dates before 1980 are clamped
+///
+/// # Arguments
+///
+/// `date` the date to convert
+///
+/// # Errors
+///
+/// None
+fn dos_date_time(date: OffsetDateTime) -> (u16, u16) {
+    if date.year() < 1980 {
+        return (0, 1 << 5 | 1);
+    }
+
+    let time =
+        (date.hour() as u16) << 11 | (date.minute() as u16) << 5 | (date.second() as u16 / 2);
+    let day = ((date.year() - 1980).min(127) as u16) << 9
+        | (u8::from(date.month()) as u16) << 5
+        | date.day() as u16;
+
+    (time, day)
+}
+
+/// put16() appends `value` to `buffer` in little endian order
+///
+/// # Arguments
+///
+/// `buffer` the record being built
+/// `value` the value to append
+///
+/// # Errors
+///
+/// None
+fn put16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+/// put32() appends `value` to `buffer` in little endian order
+///
+/// # Arguments
+///
+/// `buffer` the record being built
+/// `value` the value to append
+///
+/// # Errors
+///
+/// None
+fn put32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+/// read32() reads a little endian u32 from `reader`
+///
+/// # Arguments
+///
+/// `reader` the archive being read
+///
+/// # Errors
+///
+/// - reading from `reader` fails or it ends
+fn read32(reader: &mut dyn Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// too_large() creates the error returned when an archive exceeds the limits of zip without ZIP64
+///
+/// # Arguments
+///
+/// None
+///
+/// # Errors
+///
+/// None
+fn too_large() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "zip archives are limited to 4 GiB and 65535 files, use '--format tar.gz' instead",
+    )
+}
+
+/// invalid_data() creates an InvalidData error with `message`
+///
+/// # Arguments
+///
+/// `message` description of the problem
+///
+/// # Errors
+///
+/// None
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// CountingWriter struct: Write implementation that records how many bytes have been written, used to find the
+/// offsets and compressed sizes of entries
+struct CountingWriter<W: Write> {
+    /// destination of the data
+    inner: W,
+
+    /// number of bytes written so far
+    count: u64,
+}
+
+/// Write trait implementation for CountingWriter
+impl<W: Write> Write for CountingWriter<W> {
+    /// write() forwards `buf` to the destination and counts the bytes written
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to CountingWriter
+    /// `buf` the data to write
+    ///
+    /// # Errors
+    ///
+    /// - writing to the destination fails
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written as u64;
+
+        Ok(written)
+    }
+
+    /// flush() flushes the destination
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to CountingWriter
+    ///
+    /// # Errors
+    ///
+    /// - flushing the destination fails
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,1224 @@
+// declare cargo crates
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{hard_link, remove_file, rename};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::sync_channel;
+use std::sync::Mutex;
+use std::thread;
+use std::time::SystemTime;
+
+// declare local code
+use super::tools::args::separate_args;
+use super::tools::collect_files::collect_files;
+use super::tools::digest::{digest_file, parse_digest_algorithm, DigestAlgorithm};
+use super::tools::error::PathContext;
+use super::tools::file_walker::FileWalker;
+use super::tools::json::Json;
+use super::tools::parse_size::format_size_human;
+use super::tools::paths::expand_paths;
+use super::tools::progress::{Progress, ProgressCounter, ProgressUpdate};
+use super::tools::symlinks::SymlinkPolicy;
+use super::tools::threads::threads;
+use super::{Logger, RunTask, TaskReport};
+
+/// dedupe options that are followed by a value, see separate_args()
+pub(crate) const VALUE_OPTIONS: &[&str] =
+    &["--report", "--keep", "--action", "--hash", "--exclude-dir"];
+
+/// dedupe options that are not followed by a value, listed along with VALUE_OPTIONS by the capabilities task
+pub(crate) const FLAG_OPTIONS: &[&str] = &["--mmap", "--follow", "--no-follow"];
+
+/// ReportFormat enum: how duplicate sets are printed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// human readable grouping
+    #[default]
+    Text,
+
+    /// one JSON object per line, one per duplicate set followed by a summary
+    Json,
+}
+
+/// DedupeAction enum: what is done with the duplicates of every set once they are found
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DedupeAction {
+    /// nothing, duplicates are only reported
+    #[default]
+    Report,
+
+    /// every duplicate is removed, leaving only the canonical copy
+    Remove,
+
+    /// every duplicate is replaced with a hard link to the canonical copy so each path still opens the same contents
+    Hardlink,
+}
+
+/// KeepPolicy enum: which member of a duplicate set is kept as the canonical copy, ties are always broken by the
+/// lexicographically smallest path so repeated runs agree
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum KeepPolicy {
+    /// the lexicographically smallest path
+    #[default]
+    First,
+
+    /// the file with the earliest modification date, usually the original
+    Oldest,
+
+    /// the file with the latest modification date
+    Newest,
+
+    /// the file with the shortest path, usually the one nested least deeply
+    ShortestPath,
+}
+
+impl KeepPolicy {
+    /// select() removes and returns the file of `files` this policy keeps, `files` must be sorted
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to KeepPolicy
+    /// `files` the sorted members of a duplicate set, left holding the other members
+    ///
+    /// # Errors
+    ///
+    /// - the modification date is not retrievable for a given file
+    fn select(&self, files: &mut Vec<PathBuf>) -> io::Result<PathBuf> {
+        // min_by_key() returns the first of equal keys, which is the smallest path as `files` is sorted
+        let index = match self {
+            Self::First => Some(0),
+            Self::ShortestPath => {
+                (0..files.len()).min_by_key(|index| files[*index].as_os_str().len())
+            }
+            Self::Oldest | Self::Newest => {
+                let dates = files
+                    .iter()
+                    .map(|file| file.metadata()?.modified().path_context(file))
+                    .collect::<io::Result<Vec<SystemTime>>>()?;
+                if *self == Self::Oldest {
+                    (0..files.len()).min_by_key(|index| dates[*index])
+                } else {
+                    (0..files.len()).min_by_key(|index| Reverse(dates[*index]))
+                }
+            }
+        };
+
+        // duplicate sets always have at least two members
+        Ok(files.remove(index.unwrap_or_default()))
+    }
+}
+
+/// parse_keep_policy() parses the value of `--keep`
+///
+/// # Arguments
+///
+/// `value` the value following `--keep`
+///
+/// # Errors
+///
+/// - `value` is not `first`, `oldest`, `newest`, or `shortest-path`
+pub fn parse_keep_policy(value: Option<String>) -> Result<KeepPolicy, &'static str> {
+    match value.as_deref() {
+        Some("first") => Ok(KeepPolicy::First),
+        Some("oldest") => Ok(KeepPolicy::Oldest),
+        Some("newest") => Ok(KeepPolicy::Newest),
+        Some("shortest-path") => Ok(KeepPolicy::ShortestPath),
+        _ => Err("'--keep' must be followed by 'first', 'oldest', 'newest', or 'shortest-path'"),
+    }
+}
+
+/// DuplicateSet struct: files with identical contents, one of which is kept as the canonical copy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateSet {
+    /// the file that is kept, chosen by the KeepPolicy of the task
+    pub canonical: PathBuf,
+
+    /// every other file in the set, sorted
+    pub duplicates: Vec<PathBuf>,
+
+    /// size in bytes of every file in the set
+    pub size: u64,
+}
+
+impl DuplicateSet {
+    /// reclaimable() returns the number of bytes freed if every duplicate were removed
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to DuplicateSet
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn reclaimable(&self) -> u64 {
+        self.size * self.duplicates.len() as u64
+    }
+}
+
+/// DedupeTask struct: PathBuf corresponds to the directory searched for duplicate files along with report options
+#[derive(Debug, PartialEq, Eq)]
+pub struct DedupeTask {
+    /// PathBuf to directory searched for duplicate files
+    dir: PathBuf,
+
+    /// FileWalker used to collect files from the directory
+    walker: FileWalker,
+
+    /// how duplicate sets are printed
+    report: ReportFormat,
+
+    /// which member of each duplicate set is kept
+    keep: KeepPolicy,
+
+    /// what is done with the duplicates
+    action: DedupeAction,
+
+    /// digest the candidate files are compared by
+    hash: DigestAlgorithm,
+
+    /// whether candidate files are memory mapped rather than streamed while hashing
+    mmap: bool,
+}
+
+/// RunTask trait implementation for Dedupe variant of Config enum
+impl RunTask for DedupeTask {
+    /// run_task() driver function for Dedupe variant of Config, finds every set of files in &self.dir with identical
+    /// contents, applies &self.action to their duplicates, and prints them along with the bytes reclaimed, the
+    /// returned TaskReport counts the duplicates handled
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Config enum
+    /// `logger` a Logger that records every removed or linked duplicate
+    ///
+    /// # Errors
+    ///
+    /// - run_with() call fails
+    fn run_task(&self, logger: &mut Logger) -> Result<TaskReport, io::Error> {
+        self.run_with(logger, Progress::new(true, None))
+    }
+}
+
+impl DedupeTask {
+    /// run_with_progress() finds and handles duplicates exactly as run_task() does, calling `on_file` from the thread
+    /// reporting on the hashing workers whenever the number of files and bytes hashed has grown, along with the
+    /// current rate and the estimated time remaining, so library users can render their own progress. The path given
+    /// to `on_file` is empty as files are hashed out of order, and no progress line is drawn on stderr
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to DedupeTask
+    /// `logger` a Logger that records every removed or linked duplicate
+    /// `on_file` callback given the ProgressUpdate of the hashing so far
+    ///
+    /// # Errors
+    ///
+    /// - see run_with()
+    pub fn run_with_progress(
+        &self,
+        logger: &mut Logger,
+        on_file: &mut dyn FnMut(&Path, &ProgressUpdate),
+    ) -> io::Result<TaskReport> {
+        self.run_with(logger, Progress::new(false, Some(on_file)))
+    }
+
+    /// run_with() body of run_task() reporting the hashing of candidate files to `progress`
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to DedupeTask
+    /// `logger` a Logger that records every removed or linked duplicate
+    /// `progress` the Progress the hashed files are reported to
+    ///
+    /// # Errors
+    ///
+    /// - find_duplicates() call fails
+    /// - apply() call fails
+    fn run_with(&self, logger: &mut Logger, progress: Progress) -> io::Result<TaskReport> {
+        let sets = self.find_duplicates(threads(), progress)?;
+        let reclaimed = self.apply(&sets, logger)?;
+        for line in self.render(&sets, reclaimed) {
+            println!("{line}");
+        }
+
+        Ok(self.summary(&sets, reclaimed))
+    }
+
+    /// DedupeTask struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// `args` - an iterator containing Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - a path refers to an undefined environment variable, see expand_path()
+    /// - `./dir/` path not provided
+    /// - `./dir/` does not correspond to valid directory
+    /// - more than one path is provided
+    /// - `--report` is not followed by `text` or `json`
+    /// - `--keep` is not followed by `first`, `oldest`, `newest`, or `shortest-path`
+    /// - `--action` is not followed by `report`, `remove`, or `hardlink`
+    /// - `--hash` is not followed by a digest this build supports
+    /// - `--exclude-dir` is not followed by a name or pattern
+    /// - an unknown option is provided
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
+        // separate options from the dir path so they may be given in any position
+        let (paths, options) = separate_args(args, VALUE_OPTIONS);
+        let paths = expand_paths(paths)?;
+        let mut paths = paths.into_iter();
+
+        // ensures dir path is provided
+        let dir = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => return Err("no 'dir' path provided"),
+        };
+
+        // ensures the dir path corresponds to a valid directory
+        if !dir.is_dir() {
+            return Err("'dir' path does not correspond to a valid directory");
+        }
+
+        // errors if extra paths are provided
+        if paths.next().is_some() {
+            return Err("dedupe accepts exactly one 'dir' path");
+        }
+
+        // default options
+        let mut walker = FileWalker::new();
+        let mut report = ReportFormat::default();
+        let mut keep = KeepPolicy::default();
+        let mut action = DedupeAction::default();
+        let mut hash = DigestAlgorithm::default();
+        let mut mmap = false;
+
+        // parse options
+        let mut args = options.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--report" => {
+                    report = match args.next().as_deref() {
+                        Some("text") => ReportFormat::Text,
+                        Some("json") => ReportFormat::Json,
+                        _ => return Err("'--report' must be followed by 'text' or 'json'"),
+                    }
+                }
+                "--keep" => keep = parse_keep_policy(args.next())?,
+                "--action" => {
+                    action =
+                        match args.next().as_deref() {
+                            Some("report") => DedupeAction::Report,
+                            Some("remove") => DedupeAction::Remove,
+                            Some("hardlink") => DedupeAction::Hardlink,
+                            _ => return Err(
+                                "'--action' must be followed by 'report', 'remove', or 'hardlink'",
+                            ),
+                        }
+                }
+                "--hash" => hash = parse_digest_algorithm(args.next())?,
+                "--mmap" => mmap = true,
+                "--follow" => walker = walker.symlinks(SymlinkPolicy::Follow),
+                "--no-follow" => walker = walker.symlinks(SymlinkPolicy::NoFollow),
+                "--exclude-dir" => {
+                    walker = match args.next() {
+                        Some(pattern) => walker.exclude_dir(pattern),
+                        None => {
+                            return Err("'--exclude-dir' must be followed by a name or pattern")
+                        }
+                    }
+                }
+                _ => return Err("provided option did not match any dedupe options"),
+            }
+        }
+
+        Ok(Self {
+            dir,
+            walker,
+            report,
+            keep,
+            action,
+            hash,
+            mmap,
+        })
+    }
+
+    /// find_duplicates() returns every set of two or more files in &self.dir with identical contents, sorted by their
+    /// canonical path. Files are first grouped by size so only files that could be equal are hashed, empty files are
+    /// ignored as removing them reclaims nothing, and so are members that already are hard links to the canonical copy
+    /// as they take up no space of their own
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to DedupeTask
+    /// `threads` number of worker threads the candidate files are hashed on
+    /// `progress` the Progress the hashed files are reported to
+    ///
+    /// # Errors
+    ///
+    /// - collect_files() call fails
+    /// - metadata is not retrievable for a given file
+    /// - hash_files() call fails
+    /// - KeepPolicy::select() call fails
+    /// - same_file() call fails
+    fn find_duplicates(
+        &self,
+        threads: usize,
+        mut progress: Progress,
+    ) -> io::Result<Vec<DuplicateSet>> {
+        // PathBufs for all files found in the directory
+        let mut files: Vec<PathBuf> = Vec::new();
+        collect_files(&self.walker, &self.dir, &mut files)?;
+
+        // a file is never a duplicate of itself, so only one path to each file is kept when links are followed and a
+        // link visited as an entry of its own is left out as its contents are those of the file it points to
+        if self.walker.symlink_policy() == SymlinkPolicy::Follow {
+            let mut real_files: HashSet<PathBuf> = HashSet::new();
+            files.retain(|file| {
+                file.canonicalize()
+                    .map_or(true, |real| real_files.insert(real))
+            });
+        } else {
+            files.retain(|file| !file.is_symlink());
+        }
+
+        let mut by_size: BTreeMap<u64, Vec<PathBuf>> = BTreeMap::new();
+        for file in files {
+            let size = file.metadata().path_context(&file)?.len();
+            if size > 0 {
+                by_size.entry(size).or_default().push(file);
+            }
+        }
+
+        // files of a unique size cannot have a duplicate and are never hashed, the workers hashing the others are
+        // reported on through `progress`
+        by_size.retain(|_, files| files.len() >= 2);
+        let counter = ProgressCounter::default();
+        progress.start(
+            by_size.values().map(Vec::len).sum(),
+            by_size
+                .iter()
+                .map(|(size, files)| size * files.len() as u64)
+                .sum(),
+        );
+        let digests = progress.run_parallel(&counter, || {
+            hash_files(
+                by_size.values().flatten(),
+                threads,
+                self.hash,
+                self.mmap,
+                &counter,
+            )
+        });
+        progress.finish();
+        let digests = digests?;
+
+        let mut sets: Vec<DuplicateSet> = Vec::new();
+        for (size, files) in by_size {
+            // files of equal size are only duplicates if their contents hash the same
+            let mut by_hash: BTreeMap<[u8; 32], Vec<PathBuf>> = BTreeMap::new();
+            for file in files {
+                by_hash.entry(digests[&file]).or_default().push(file);
+            }
+
+            for mut files in by_hash.into_values() {
+                if files.len() < 2 {
+                    continue;
+                }
+                files.sort();
+                let canonical = self.keep.select(&mut files)?;
+
+                // a link to the canonical copy is the canonical copy, linking it again would leave its temporary behind
+                let mut duplicates: Vec<PathBuf> = Vec::new();
+                for file in files {
+                    if !same_file(&canonical, &file).path_context(&file)? {
+                        duplicates.push(file);
+                    }
+                }
+                if duplicates.is_empty() {
+                    continue;
+                }
+                sets.push(DuplicateSet {
+                    canonical,
+                    duplicates,
+                    size,
+                });
+            }
+        }
+        sets.sort_by(|a, b| a.canonical.cmp(&b.canonical));
+
+        Ok(sets)
+    }
+
+    /// apply() removes or hard links the duplicates of every set as &self.action says and returns the combined size of
+    /// the duplicates handled, nothing is done and nothing is reclaimed with DedupeAction::Report. A hard link is
+    /// created next to the duplicate and renamed over it so the duplicate is never missing
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to DedupeTask
+    /// `sets` the duplicate sets found by find_duplicates()
+    /// `logger` a Logger that records every removed or linked duplicate
+    ///
+    /// # Errors
+    ///
+    /// - a duplicate cannot be removed
+    /// - a hard link to the canonical copy cannot be created or renamed over a duplicate
+    /// - Logger::log() call fails
+    fn apply(&self, sets: &[DuplicateSet], logger: &mut Logger) -> io::Result<u64> {
+        if self.action == DedupeAction::Report {
+            return Ok(0);
+        }
+
+        let mut reclaimed: u64 = 0;
+        for set in sets {
+            for duplicate in &set.duplicates {
+                if self.action == DedupeAction::Remove {
+                    remove_file(duplicate).path_context(duplicate)?;
+                    logger.log("remove", duplicate, None)?;
+                } else {
+                    link_over(&set.canonical, duplicate).path_context(duplicate)?;
+                    logger.log("link", duplicate, Some(&set.canonical))?;
+                }
+                reclaimed += set.size;
+            }
+        }
+
+        Ok(reclaimed)
+    }
+
+    /// summary() returns the TaskReport of a run that handled `sets`, counting their duplicates as removed, linked, or
+    /// only found as &self.action says, along with the `reclaimed` bytes of removed duplicates, the space of a linked
+    /// copy is only given back once nothing else links to it so it is left to the deduplicated total of render()
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to DedupeTask
+    /// `sets` the duplicate sets found by find_duplicates()
+    /// `reclaimed` the bytes returned by apply()
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn summary(&self, sets: &[DuplicateSet], reclaimed: u64) -> TaskReport {
+        let processed = match self.action {
+            DedupeAction::Report => "duplicates found",
+            DedupeAction::Remove => "removed",
+            DedupeAction::Hardlink => "linked",
+        };
+
+        TaskReport {
+            moved: sets.iter().map(|set| set.duplicates.len()).sum(),
+            reclaimed: if self.action == DedupeAction::Remove {
+                reclaimed
+            } else {
+                0
+            },
+            ..TaskReport::worded(processed, "failed")
+        }
+    }
+
+    /// render() builds the lines to be printed for `sets` in &self.report format, both end with the total number of
+    /// reclaimable bytes, followed by the bytes reclaimed by removing duplicates or deduplicated by linking them
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to DedupeTask
+    /// `sets` the duplicate sets to print
+    /// `reclaimed` the bytes returned by apply()
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn render(&self, sets: &[DuplicateSet], reclaimed: u64) -> Vec<String> {
+        let total: u64 = sets.iter().map(|set| set.reclaimable()).sum();
+        let mut lines: Vec<String> = Vec::new();
+
+        match self.report {
+            ReportFormat::Text => {
+                for (index, set) in sets.iter().enumerate() {
+                    lines.push(format!(
+                        "duplicate set {} ({} files, {} bytes each, {} bytes reclaimable)",
+                        index + 1,
+                        set.duplicates.len() + 1,
+                        set.size,
+                        set.reclaimable()
+                    ));
+                    lines.push(format!("  keep      {}", set.canonical.display()));
+                    for duplicate in &set.duplicates {
+                        lines.push(format!("  duplicate {}", duplicate.display()));
+                    }
+                }
+                lines.push(format!(
+                    "{} duplicate sets, {total} bytes reclaimable",
+                    sets.len()
+                ));
+                if let Some(key) = self.reclaimed_key() {
+                    lines.push(format!(
+                        "{key} {reclaimed} bytes ({})",
+                        format_size_human(reclaimed)
+                    ));
+                }
+            }
+            ReportFormat::Json => {
+                // file entries carry their own size so consumers need not look it up on the set
+                let file = |path: &PathBuf, size: u64| {
+                    Json::object([
+                        ("path", Json::from(path.display().to_string())),
+                        ("size", Json::from(size)),
+                    ])
+                };
+                for set in sets {
+                    lines.push(
+                        Json::object([
+                            ("type", Json::from("set")),
+                            ("canonical", file(&set.canonical, set.size)),
+                            (
+                                "duplicates",
+                                Json::Array(
+                                    set.duplicates
+                                        .iter()
+                                        .map(|duplicate| file(duplicate, set.size))
+                                        .collect(),
+                                ),
+                            ),
+                            ("reclaimable", Json::from(set.reclaimable())),
+                        ])
+                        .to_string(),
+                    );
+                }
+                let mut summary = vec![
+                    (String::from("type"), Json::from("summary")),
+                    (String::from("sets"), Json::from(sets.len())),
+                    (String::from("reclaimable"), Json::from(total)),
+                ];
+                if let Some(key) = self.reclaimed_key() {
+                    summary.push((key.to_string(), Json::from(reclaimed)));
+                }
+                lines.push(Json::Object(summary).to_string());
+            }
+        }
+
+        lines
+    }
+
+    /// reclaimed_key() returns how the bytes returned by apply() are labelled, `reclaimed` when duplicates are removed
+    /// and `deduplicated` when they are linked as the space is only freed once every other link is gone, None when
+    /// nothing was done
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to DedupeTask
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn reclaimed_key(&self) -> Option<&'static str> {
+        match self.action {
+            DedupeAction::Report => None,
+            DedupeAction::Remove => Some("reclaimed"),
+            DedupeAction::Hardlink => Some("deduplicated"),
+        }
+    }
+}
+
+/// link_over() replaces `duplicate` with a hard link to `canonical`, the link is created under a temporary name in the
+/// same directory and renamed over `duplicate` so a failure leaves `duplicate` untouched, the temporary name never
+/// outlives the call
+///
+/// # Arguments
+///
+/// `canonical` a Path that corresponds to the file linked to
+/// `duplicate` a Path that corresponds to the file replaced with the link
+///
+/// # Errors
+///
+/// - the hard link cannot be created, such as across file systems
+/// - the hard link cannot be renamed over `duplicate`
+fn link_over(canonical: &Path, duplicate: &Path) -> io::Result<()> {
+    let mut name = duplicate.file_name().unwrap_or_default().to_os_string();
+    name.push(".fileman_rs_link");
+    let temporary = duplicate.with_file_name(name);
+
+    hard_link(canonical, &temporary)?;
+    let renamed = rename(&temporary, duplicate);
+
+    // the link is only a second name for the canonical copy, so removing it loses nothing, a rename between two links
+    // to one file does nothing at all and leaves it behind even when it succeeds
+    if temporary.symlink_metadata().is_ok() {
+        let _ = remove_file(&temporary);
+    }
+    renamed
+}
+
+/// same_file() returns whether `a` and `b` are hard links to one file, always false on platforms where this cannot be
+/// told
+///
+/// # Arguments
+///
+/// `a` a Path that corresponds to the first file
+/// `b` a Path that corresponds to the second file
+///
+/// # Errors
+///
+/// - metadata is not retrievable for `a` or `b`
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let (a, b) = (a.metadata()?, b.metadata()?);
+    Ok(a.dev() == b.dev() && a.ino() == b.ino())
+}
+
+#[cfg(not(unix))]
+fn same_file(_a: &Path, _b: &Path) -> io::Result<bool> {
+    Ok(false)
+}
+
+/// hash_files() returns the `algorithm` digest of every one of `files`, hashed on `threads` worker threads. Files are
+/// handed to the workers through a queue holding at most two files per worker, so memory stays bounded however many
+/// files there are, and once any file fails no further files are queued. Every hashed file is added to `counter`
+///
+/// # Arguments
+///
+/// `files` PathBufs of the files to hash
+/// `threads` number of worker threads, files are hashed on the calling thread when it is one or less
+/// `algorithm` the digest to compute
+/// `mmap` whether the files are memory mapped rather than streamed
+/// `counter` the ProgressCounter every hashed file is added to along with its size
+///
+/// # Errors
+///
+/// - digest_file() call fails, the error of the first failing file is returned
+pub(crate) fn hash_files<'a>(
+    files: impl Iterator<Item = &'a PathBuf>,
+    threads: usize,
+    algorithm: DigestAlgorithm,
+    mmap: bool,
+    counter: &ProgressCounter,
+) -> io::Result<HashMap<PathBuf, [u8; 32]>> {
+    // the size is only needed for the progress, so a file whose size cannot be read still counts
+    let hash = |file: &PathBuf| {
+        let digest = digest_file(file, algorithm, mmap).path_context(file)?;
+        counter.add(file.metadata().map_or(0, |metadata| metadata.len()));
+        Ok(digest)
+    };
+    if threads <= 1 {
+        return files.map(|file| Ok((file.clone(), hash(file)?))).collect();
+    }
+
+    let digests: Mutex<HashMap<PathBuf, [u8; 32]>> = Mutex::new(HashMap::new());
+    let error: Mutex<Option<io::Error>> = Mutex::new(None);
+    let (sender, receiver) = sync_channel::<&PathBuf>(threads * 2);
+    let receiver = Mutex::new(receiver);
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                loop {
+                    // the lock is released as soon as a file is received so the others can receive while this one
+                    // hashes, an error means the queue is closed and drained
+                    let received = receiver.lock().unwrap().recv();
+                    let Ok(file) = received else {
+                        break;
+                    };
+                    match hash(file) {
+                        Ok(digest) => {
+                            digests.lock().unwrap().insert(file.clone(), digest);
+                        }
+                        Err(err) => {
+                            error.lock().unwrap().get_or_insert(err);
+                        }
+                    }
+                }
+            });
+        }
+
+        for file in files {
+            if error.lock().unwrap().is_some() || sender.send(file).is_err() {
+                break;
+            }
+        }
+
+        // closing the queue lets the workers exit once it is drained
+        drop(sender);
+    });
+
+    match error.into_inner().unwrap() {
+        Some(err) => Err(err),
+        None => Ok(digests.into_inner().unwrap()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, write, File};
+    use std::time::Duration;
+
+    /// verifies DedupeTask::new() parses the directory and report format
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - DedupeTask::new() does not parse valid arguments
+    /// - DedupeTask::new() does not error on an unknown report format
+    #[test]
+    fn dedupe_task_new() {
+        // args iterator builder
+        let args = |options: &[&str]| {
+            ["./src"]
+                .iter()
+                .chain(options)
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+
+        assert_eq!(
+            DedupeTask::new(args(&["--report", "json"])),
+            Ok(DedupeTask {
+                dir: PathBuf::from("./src"),
+                walker: FileWalker::new(),
+                report: ReportFormat::Json,
+                keep: KeepPolicy::First,
+                action: DedupeAction::Report,
+                hash: DigestAlgorithm::Sha256,
+                mmap: false,
+            })
+        );
+        assert_eq!(
+            DedupeTask::new(args(&["--keep", "shortest-path"])).map(|task| task.keep),
+            Ok(KeepPolicy::ShortestPath)
+        );
+        assert_eq!(
+            DedupeTask::new(args(&["--hash", "sha256", "--mmap"]))
+                .map(|task| (task.hash, task.mmap)),
+            Ok((DigestAlgorithm::Sha256, true))
+        );
+        assert!(DedupeTask::new(args(&["--hash", "md5"])).is_err());
+        assert!(DedupeTask::new(args(&["--keep", "largest"])).is_err());
+        assert!(DedupeTask::new(args(&["--report", "xml"])).is_err());
+        assert!(DedupeTask::new(args(&["--report"])).is_err());
+    }
+
+    /// verifies DedupeTask::find_duplicates() groups three identical files under the smallest path, ignores two unique
+    /// files (one of equal size), and renders both report formats
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - DedupeTask::find_duplicates() groups unique files or picks a different canonical file
+    /// - DedupeTask::render() does not report the reclaimable byte total
+    #[test]
+    fn dedupe_task_find_duplicates() {
+        // create inputs
+        let path_buf = PathBuf::from("./dedupe_task_find_duplicates_test");
+
+        // create mock directories, the identical files are spread over nested folders
+        create_dir_all(path_buf.join("b/nested")).unwrap();
+        write(path_buf.join("b/copy.txt"), "same contents").unwrap();
+        write(path_buf.join("b/nested/copy.txt"), "same contents").unwrap();
+        write(path_buf.join("a.txt"), "same contents").unwrap();
+        write(path_buf.join("unique.txt"), "diff contents").unwrap();
+        write(path_buf.join("other.txt"), "other").unwrap();
+
+        // run test
+        let mut task = DedupeTask {
+            dir: path_buf.clone(),
+            walker: FileWalker::new(),
+            report: ReportFormat::Text,
+            keep: KeepPolicy::default(),
+            action: DedupeAction::Report,
+            hash: DigestAlgorithm::Sha256,
+            mmap: false,
+        };
+        let test_result = task.find_duplicates(1, Progress::hidden());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        let sets = test_result.unwrap();
+        assert_eq!(
+            sets,
+            [DuplicateSet {
+                canonical: path_buf.join("a.txt"),
+                duplicates: vec![
+                    path_buf.join("b/copy.txt"),
+                    path_buf.join("b/nested/copy.txt")
+                ],
+                size: 13,
+            }]
+        );
+
+        let text = task.render(&sets, 0);
+        assert_eq!(text.len(), 5);
+        assert_eq!(text[4], "1 duplicate sets, 26 bytes reclaimable");
+
+        task.report = ReportFormat::Json;
+        let json = task.render(&sets, 0);
+        let canonical = Json::from(path_buf.join("a.txt").display().to_string());
+        assert_eq!(json.len(), 2);
+        assert!(json[0].starts_with(&format!(
+            r#"{{"type":"set","canonical":{{"path":{canonical},"size":13}},"duplicates":[{{"path":"#
+        )));
+        assert!(json[0].ends_with(r#""reclaimable":26}"#));
+        assert_eq!(json[1], r#"{"type":"summary","sets":1,"reclaimable":26}"#);
+    }
+
+    /// verifies DedupeTask::apply() removes or links every duplicate of sets with known sizes and that the reported
+    /// total is the combined size of the duplicates handled, labelled by the action
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - DedupeTask::apply() does not return the combined size of the duplicates
+    /// - DedupeTask::apply() keeps a removed duplicate, or loses the contents of a linked one or the canonical copy
+    /// - DedupeTask::render() does not report the reclaimed or deduplicated total in either report format
+    /// - DedupeTask::summary() does not count the duplicates handled and the bytes reclaimed
+    #[test]
+    fn dedupe_task_apply_reclaimed() {
+        // create inputs, three copies of 10 bytes and two of 1536 bytes free 2 * 10 + 1536 bytes
+        let path_buf = PathBuf::from("./dedupe_task_apply_reclaimed_test");
+        let big = "x".repeat(1536);
+        let task = |action: DedupeAction, report: ReportFormat| DedupeTask {
+            dir: path_buf.clone(),
+            walker: FileWalker::new(),
+            report,
+            keep: KeepPolicy::default(),
+            action,
+            hash: DigestAlgorithm::Sha256,
+            mmap: false,
+        };
+        let fixture = || {
+            create_dir_all(&path_buf).unwrap();
+            for name in ["a.txt", "b.txt", "c.txt"] {
+                write(path_buf.join(name), "0123456789").unwrap();
+            }
+            for name in ["big1.bin", "big2.bin"] {
+                write(path_buf.join(name), &big).unwrap();
+            }
+        };
+
+        // run test, removing then linking the duplicates of fresh copies of the fixture
+        let remove = task(DedupeAction::Remove, ReportFormat::Text);
+        fixture();
+        let sets = remove.find_duplicates(1, Progress::hidden()).unwrap();
+        let removed = remove.apply(&sets, &mut Logger::disabled());
+        let removed_lines = remove.render(&sets, *removed.as_ref().unwrap());
+        let removed_summary = remove.summary(&sets, *removed.as_ref().unwrap());
+        let remaining = ["a.txt", "b.txt", "c.txt", "big1.bin", "big2.bin"]
+            .map(|name| path_buf.join(name).is_file());
+        remove_dir_all(&path_buf).unwrap();
+
+        let link = task(DedupeAction::Hardlink, ReportFormat::Json);
+        fixture();
+        let sets = link.find_duplicates(1, Progress::hidden()).unwrap();
+        let linked = link.apply(&sets, &mut Logger::disabled());
+        let linked_lines = link.render(&sets, *linked.as_ref().unwrap());
+        let linked_summary = link.summary(&sets, *linked.as_ref().unwrap());
+        let contents = ["a.txt", "c.txt", "big2.bin"]
+            .map(|name| std::fs::read_to_string(path_buf.join(name)).unwrap().len());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(removed.unwrap(), 1556);
+        assert_eq!(remaining, [true, false, false, true, false]);
+        assert_eq!(
+            removed_lines.last().unwrap(),
+            "reclaimed 1556 bytes (1.5 KiB)"
+        );
+        assert_eq!(
+            removed_summary.to_string(),
+            "3 removed, 0 skipped, 0 failed, 0 directories created, 1556 bytes reclaimed"
+        );
+        assert_eq!(linked.unwrap(), 1556);
+        assert_eq!(contents, [10, 10, 1536]);
+        assert_eq!(
+            linked_lines.last().unwrap(),
+            r#"{"type":"summary","sets":2,"reclaimable":1556,"deduplicated":1556}"#
+        );
+        assert_eq!(
+            linked_summary.to_string(),
+            "3 linked, 0 skipped, 0 failed, 0 directories created"
+        );
+        assert_eq!(
+            task(DedupeAction::Report, ReportFormat::Text)
+                .apply(&sets, &mut Logger::disabled())
+                .unwrap(),
+            0
+        );
+    }
+
+    /// verifies a duplicate that already is a hard link to the canonical copy is left out of its set and of the totals,
+    /// and that linking twice leaves no temporary link behind
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - DedupeTask::find_duplicates() reports a hard link to the canonical copy as a duplicate
+    /// - DedupeTask::apply() counts such a link as reclaimed or leaves a temporary link behind
+    /// - a second run fails or finds duplicates after every copy was linked
+    #[cfg(unix)]
+    #[test]
+    fn dedupe_task_apply_existing_links() {
+        // create inputs
+        let path_buf = PathBuf::from("./dedupe_task_apply_existing_links_test");
+        let task = DedupeTask {
+            dir: path_buf.clone(),
+            walker: FileWalker::new(),
+            report: ReportFormat::Text,
+            keep: KeepPolicy::default(),
+            action: DedupeAction::Hardlink,
+            hash: DigestAlgorithm::Sha256,
+            mmap: false,
+        };
+
+        // create mock directory, b.txt is already a link to a.txt while c.txt is a copy
+        create_dir_all(&path_buf).unwrap();
+        write(path_buf.join("a.txt"), "0123456789").unwrap();
+        hard_link(path_buf.join("a.txt"), path_buf.join("b.txt")).unwrap();
+        write(path_buf.join("c.txt"), "0123456789").unwrap();
+
+        // run test, then run again once every copy is a link
+        let sets = task.find_duplicates(1, Progress::hidden()).unwrap();
+        let linked = task.apply(&sets, &mut Logger::disabled());
+        let rerun = task
+            .find_duplicates(1, Progress::hidden())
+            .and_then(|sets| task.apply(&sets, &mut Logger::disabled()));
+        let mut names: Vec<String> = path_buf
+            .read_dir()
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].duplicates, [path_buf.join("c.txt")]);
+        assert_eq!(sets[0].reclaimable(), 10);
+        assert_eq!(linked.unwrap(), 10);
+        assert_eq!(rerun.unwrap(), 0);
+        assert_eq!(names, ["a.txt", "b.txt", "c.txt"]);
+    }
+
+    /// verifies a symbolic link to a file is never reported as a duplicate of the file it points to, whatever the
+    /// symlink policy, while a link to a directory of copies is followed with `--follow`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - DedupeTask::find_duplicates() groups a link with the file it points to
+    /// - DedupeTask::find_duplicates() reports a file twice when it is reached through a followed link
+    #[cfg(unix)]
+    #[test]
+    fn dedupe_task_find_duplicates_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        // create inputs
+        let path_buf = PathBuf::from("./dedupe_task_find_duplicates_symlinks_test");
+
+        // create mock directory, link.txt points at a.txt and link_dir at the folder holding its only copy
+        create_dir_all(path_buf.join("d")).unwrap();
+        write(path_buf.join("a.txt"), "same contents").unwrap();
+        write(path_buf.join("d/copy.txt"), "same contents").unwrap();
+        symlink("a.txt", path_buf.join("link.txt")).unwrap();
+        symlink("d", path_buf.join("link_dir")).unwrap();
+
+        // run test
+        let find = |symlinks: SymlinkPolicy| {
+            DedupeTask {
+                dir: path_buf.clone(),
+                walker: FileWalker::new().symlinks(symlinks),
+                report: ReportFormat::Text,
+                keep: KeepPolicy::default(),
+                action: DedupeAction::Report,
+                hash: DigestAlgorithm::Sha256,
+                mmap: false,
+            }
+            .find_duplicates(1, Progress::hidden())
+            .unwrap()
+        };
+        let test_result = [
+            SymlinkPolicy::Skip,
+            SymlinkPolicy::NoFollow,
+            SymlinkPolicy::Follow,
+        ]
+        .map(find);
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        let expected = [DuplicateSet {
+            canonical: path_buf.join("a.txt"),
+            duplicates: vec![path_buf.join("d/copy.txt")],
+            size: 13,
+        }];
+        for sets in test_result {
+            assert_eq!(sets, expected);
+        }
+    }
+
+    /// verifies hashing on several threads finds exactly the duplicate sets found by hashing serially, with more
+    /// candidate files than the queue holds at once
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - DedupeTask::find_duplicates() groups files differently on several threads than on one
+    /// - hash_files() does not fail on a missing file when run on several threads
+    /// - hash_files() does not count every hashed file and its size when run on several threads
+    /// - the progress callback does not end at the number of files and bytes hashed
+    #[test]
+    fn dedupe_task_find_duplicates_parallel() {
+        // create inputs
+        let path_buf = PathBuf::from("./dedupe_task_find_duplicates_parallel_test");
+        let task = DedupeTask {
+            dir: path_buf.clone(),
+            walker: FileWalker::new(),
+            report: ReportFormat::Text,
+            keep: KeepPolicy::default(),
+            action: DedupeAction::Report,
+            hash: DigestAlgorithm::Sha256,
+            mmap: false,
+        };
+
+        // create mock directory, sets of two to four copies of contents that share their size with each other
+        create_dir_all(&path_buf).unwrap();
+        for index in 0..40 {
+            write(
+                path_buf.join(format!("{index:02}.txt")),
+                format!("contents {}", index % 12),
+            )
+            .unwrap();
+        }
+
+        // run test
+        let serial = task.find_duplicates(1, Progress::hidden());
+        let mut observed: Option<(usize, u64)> = None;
+        let mut on_file = |_: &Path, update: &ProgressUpdate| {
+            observed = Some((update.files_done, update.bytes_done));
+        };
+        let parallel = task.find_duplicates(4, Progress::new(false, Some(&mut on_file)));
+        let files: Vec<PathBuf> = (0..40)
+            .map(|index| path_buf.join(format!("{index:02}.txt")))
+            .collect();
+        let counter = ProgressCounter::default();
+        let hashed = hash_files(files.iter(), 4, DigestAlgorithm::Sha256, false, &counter)
+            .map(|digests| digests.len());
+        let missing = hash_files(
+            [path_buf.join("missing.txt")].iter(),
+            4,
+            DigestAlgorithm::Sha256,
+            false,
+            &ProgressCounter::default(),
+        );
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        let serial = serial.unwrap();
+        assert_eq!(serial.len(), 12);
+        assert_eq!(parallel.unwrap(), serial);
+        assert_eq!(hashed.ok(), Some(40));
+        let bytes: usize = (0..40)
+            .map(|index| format!("contents {}", index % 12).len())
+            .sum();
+        assert_eq!(counter.totals(), (40, bytes as u64));
+        assert_eq!(observed, Some((40, bytes as u64)));
+        assert!(missing.is_err());
+    }
+
+    /// verifies KeepPolicy::select() keeps the expected member of a crafted duplicate set under every policy and
+    /// leaves the others in order
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - KeepPolicy::select() keeps a different file than the policy describes
+    /// - KeepPolicy::select() does not break ties by the smallest path
+    #[test]
+    fn keep_policy_select() {
+        // create inputs
+        let path_buf = PathBuf::from("./keep_policy_select_test");
+        let members = [
+            ("a/deep/copy.txt", 1_010_000_000),
+            ("b/newest.txt", 1_020_000_000),
+            ("b/tie.txt", 1_020_000_000),
+            ("oldest.txt", 1_000_000_000),
+        ];
+
+        // create mock directories
+        create_dir_all(path_buf.join("a/deep")).unwrap();
+        create_dir_all(path_buf.join("b")).unwrap();
+        for (name, seconds) in members {
+            let file = path_buf.join(name);
+            write(&file, "same contents").unwrap();
+            File::options()
+                .write(true)
+                .open(&file)
+                .unwrap()
+                .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+                .unwrap();
+        }
+
+        // run test
+        let files: Vec<PathBuf> = members
+            .iter()
+            .map(|(name, _)| path_buf.join(name))
+            .collect();
+        let test_result = [
+            KeepPolicy::First,
+            KeepPolicy::Oldest,
+            KeepPolicy::Newest,
+            KeepPolicy::ShortestPath,
+        ]
+        .map(|policy| {
+            let mut files = files.clone();
+            let kept = policy.select(&mut files).unwrap();
+            (kept, files.len())
+        });
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            test_result,
+            [
+                (path_buf.join("a/deep/copy.txt"), 3),
+                (path_buf.join("oldest.txt"), 3),
+                (path_buf.join("b/newest.txt"), 3),
+                (path_buf.join("b/tie.txt"), 3),
+            ]
+        );
+        assert_eq!(
+            parse_keep_policy(Some(String::from("oldest"))),
+            Ok(KeepPolicy::Oldest)
+        );
+        assert!(parse_keep_policy(None).is_err());
+    }
+}
@@ -3,10 +3,36 @@
 
 // declare cargo crates
 use std::io;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 // declare local modules
+mod capabilities;
+mod compare;
+mod compress;
+mod dedupe;
+mod list;
+mod move_task;
 mod organize;
+mod purge;
+mod report;
+mod restore;
+mod split;
 mod tools;
+mod verify;
+pub mod walk;
+
+// expose the logger and report so that they can be used from main.rs, and tasks that can be driven programmatically
+pub use dedupe::DedupeTask;
+pub use organize::{DateStrategy, ExtensionStrategy, GroupStrategy, OrganizeTask, SizeStrategy};
+pub use report::{
+    report_json_from_args, SkipReason, TaskReport, EXIT_CONFIG_ERROR, EXIT_FAILURE,
+    EXIT_PARTIAL_FAILURE, EXIT_SUCCESS,
+};
+pub use tools::cancel::cancel_on_interrupt;
+pub use tools::logger::Logger;
+pub use tools::progress::ProgressUpdate;
+pub use tools::threads::threads_from_args;
 
 pub trait RunTask {
     /// task definition that allows Config to run a task outlined in a task module
@@ -14,14 +40,35 @@ pub trait RunTask {
     /// # Arguments
     ///
     /// `&self` - a reference to Config enum
-    fn run_task(&self) -> Result<(), io::Error>;
+    /// `logger` - a Logger shared by all tasks that records every operation performed
+    fn run_task(&self, logger: &mut Logger) -> Result<TaskReport, io::Error>;
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Config {
     /// configuration enum, all tasks are given their own variant
-    // variant to run the organize task
-    Organize(organize::OrganizeTask),
+    // variant to run the organize task, boxed as it carries far more options than any other task
+    Organize(Box<organize::OrganizeTask>),
+    // variant to run the list task
+    List(list::ListTask),
+    // variant to run the split task
+    Split(split::SplitTask),
+    // variant to run the compress task
+    Compress(compress::CompressTask),
+    // variant to run the dedupe task
+    Dedupe(dedupe::DedupeTask),
+    // variant to run the move task
+    Move(move_task::MoveTask),
+    // variant to run the purge task
+    Purge(purge::PurgeTask),
+    // variant to run the restore task
+    Restore(restore::RestoreTask),
+    // variant to run the verify task
+    Verify(verify::VerifyTask),
+    // variant to run the compare task
+    Compare(compare::CompareTask),
+    // variant to print the tasks and options this build supports
+    Capabilities(capabilities::CapabilitiesTask),
 }
 
 impl Config {
@@ -52,10 +99,88 @@ impl Config {
                 // ensures OrganizeTask created successfully, otherwise propagates error
                 let organize_task = organize::OrganizeTask::new(args)?;
 
-                Ok(Self::Organize(organize_task))
+                Ok(Self::Organize(Box::new(organize_task)))
+            }
+            "list" => {
+                // ensures ListTask created successfully, otherwise propagates error
+                let list_task = list::ListTask::new(args)?;
+
+                Ok(Self::List(list_task))
+            }
+            "split" => {
+                // ensures SplitTask created successfully, otherwise propagates error
+                let split_task = split::SplitTask::new(args)?;
+
+                Ok(Self::Split(split_task))
+            }
+            "compress" => {
+                // ensures CompressTask created successfully, otherwise propagates error
+                let compress_task = compress::CompressTask::new(args)?;
+
+                Ok(Self::Compress(compress_task))
+            }
+            "dedupe" => {
+                // ensures DedupeTask created successfully, otherwise propagates error
+                let dedupe_task = dedupe::DedupeTask::new(args)?;
+
+                Ok(Self::Dedupe(dedupe_task))
+            }
+            "move" => {
+                // ensures MoveTask created successfully, otherwise propagates error
+                let move_task = move_task::MoveTask::new(args)?;
+
+                Ok(Self::Move(move_task))
+            }
+            "purge" => {
+                // ensures PurgeTask created successfully, otherwise propagates error
+                let purge_task = purge::PurgeTask::new(args)?;
+
+                Ok(Self::Purge(purge_task))
+            }
+            "restore" => {
+                // ensures RestoreTask created successfully, otherwise propagates error
+                let restore_task = restore::RestoreTask::new(args)?;
+
+                Ok(Self::Restore(restore_task))
+            }
+            "verify" => {
+                // ensures VerifyTask created successfully, otherwise propagates error
+                let verify_task = verify::VerifyTask::new(args)?;
+
+                Ok(Self::Verify(verify_task))
+            }
+            "compare" => {
+                // ensures CompareTask created successfully, otherwise propagates error
+                let compare_task = compare::CompareTask::new(args)?;
+
+                Ok(Self::Compare(compare_task))
+            }
+            "capabilities" => {
+                // ensures CapabilitiesTask created successfully, otherwise propagates error
+                let capabilities_task = capabilities::CapabilitiesTask::new(args)?;
+
+                Ok(Self::Capabilities(capabilities_task))
             }
             // errors if desired task is not defined
-            _ => return Err("provided task did not match any defined tasks"),
+            _ => Err("provided task did not match any defined tasks"),
+        }
+    }
+
+    /// with_cancel() makes a task that can be cancelled stop early once `flag` is set, see OrganizeTask::with_cancel(),
+    /// other tasks are returned unchanged
+    ///
+    /// # Arguments
+    ///
+    /// `self` the Config to make cancellable
+    /// `flag` the flag that asks the task to stop
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn with_cancel(self, flag: Arc<AtomicBool>) -> Self {
+        match self {
+            Config::Organize(task) => Config::Organize(Box::new(task.with_cancel(flag))),
+            config => config,
         }
     }
 }
@@ -63,17 +188,28 @@ impl Config {
 /// RunTask trait allows for all tasks to be run from main.rs
 impl RunTask for Config {
     /// run_task() matches against Config and runs the corresponding method Variant(task) => task.run_task()
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// `&self` reference to Config enum
-    /// 
+    /// `logger` a Logger that records every operation performed
+    ///
     /// # Errors
-    /// 
+    ///
     /// - if Variant(task) => task.run_task() errors
-    fn run_task(&self) -> Result<(), io::Error> {
+    fn run_task(&self, logger: &mut Logger) -> Result<TaskReport, io::Error> {
         match self {
-            Config::Organize(task) => task.run_task(),
+            Config::Organize(task) => task.run_task(logger),
+            Config::List(task) => task.run_task(logger),
+            Config::Split(task) => task.run_task(logger),
+            Config::Compress(task) => task.run_task(logger),
+            Config::Dedupe(task) => task.run_task(logger),
+            Config::Move(task) => task.run_task(logger),
+            Config::Purge(task) => task.run_task(logger),
+            Config::Restore(task) => task.run_task(logger),
+            Config::Verify(task) => task.run_task(logger),
+            Config::Compare(task) => task.run_task(logger),
+            Config::Capabilities(task) => task.run_task(logger),
         }
     }
 }
@@ -107,7 +243,47 @@ mod tests {
         ]
         .into_iter();
 
-        assert_eq!(Config::new(args_2), Ok(Config::Organize(organize_task)))
+        assert_eq!(
+            Config::new(args_2),
+            Ok(Config::Organize(Box::new(organize_task)))
+        )
+    }
+
+    /// verifies Config::new() passes options placed before, between, or after the paths on to every task
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Config::new() does not configure a task whose options precede or separate its paths
+    #[test]
+    fn config_new_options_any_position() {
+        // args iterator builder
+        let args = |args: &[&str]| {
+            ["foo"]
+                .iter()
+                .chain(args)
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+
+        for args in [
+            args(&["organize", "--keep-going", "./src", "./src/organize"]),
+            args(&["organize", "./src", "--date", "modified", "./src/organize"]),
+            args(&["list", "--depth", "1", "./src"]),
+            args(&["compress", "--format", "zip", "./src", "./src/compress"]),
+            args(&["dedupe", "--report", "json", "./src"]),
+            args(&["move", "./src", "--flatten-depth", "0", "./src/move_task"]),
+            args(&["split", "./src", "--parts", "2", "./src/split"]),
+            args(&["restore", "--skip-missing", "./Cargo.toml"]),
+            args(&["verify", "./Cargo.toml", "./src"]),
+            args(&["compare", "--by", "content", "./src", "./src/compare"]),
+        ] {
+            assert!(Config::new(args).is_ok());
+        }
     }
 
     /// verifies Config::new() errors if args does contain a task
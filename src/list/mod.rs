@@ -0,0 +1,577 @@
+// declare cargo crates
+use std::io;
+use std::path::{Path, PathBuf};
+
+// declare local code
+use super::tools::args::separate_args;
+use super::tools::file_walker::FileWalker;
+use super::tools::path_list::write_paths;
+use super::tools::paths::expand_paths;
+use super::tools::symlinks::SymlinkPolicy;
+use super::{Logger, RunTask, TaskReport};
+
+/// list options that are followed by a value, see separate_args()
+pub(crate) const VALUE_OPTIONS: &[&str] =
+    &["--depth", "--max-depth", "--min-depth", "--exclude-dir"];
+
+/// list options that are not followed by a value, listed along with VALUE_OPTIONS by the capabilities task
+pub(crate) const FLAG_OPTIONS: &[&str] = &[
+    "--follow",
+    "--no-follow",
+    "--counts",
+    "--files-only",
+    "-0",
+    "--null",
+];
+
+/// ListTask struct: PathBuf corresponds to the directory to be listed along with display options
+#[derive(Debug, PartialEq, Eq)]
+pub struct ListTask {
+    /// PathBuf to directory to be listed
+    dir: PathBuf,
+
+    /// FileWalker used to visit the tree, limits the depths displayed and skips excluded directories
+    walker: FileWalker,
+
+    /// whether to display the number of files directly inside each directory
+    counts: bool,
+
+    /// whether to display a flat sorted list of file paths instead of a tree
+    files_only: bool,
+
+    /// whether the paths listed with &self.files_only are terminated by NUL bytes rather than newlines
+    null: bool,
+}
+
+/// RunTask trait implementation for List variant of Config enum
+impl RunTask for ListTask {
+    /// run_task() driver function for List variant of Config, prints the contents of &self.dir without modifying it,
+    /// the returned TaskReport counts the listed items below &self.dir
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Config enum
+    /// `_logger` unused as listing does not perform any operations
+    ///
+    /// # Errors
+    ///
+    /// - files() call fails when &self.null is set
+    /// - stdout cannot be written to when &self.null is set
+    /// - render() call fails
+    fn run_task(&self, _logger: &mut Logger) -> Result<TaskReport, io::Error> {
+        // paths are written as raw bytes so names containing newlines reach NUL aware tools such as `xargs -0` intact
+        let listed = if self.null {
+            let files = self.files()?;
+            write_paths(&mut io::stdout().lock(), &files, true)?;
+            files.len()
+        } else {
+            let lines = self.render()?;
+            for line in &lines {
+                println!("{line}");
+            }
+            // the tree view starts with &self.dir itself
+            lines.len() - usize::from(!self.files_only)
+        };
+
+        Ok(TaskReport {
+            moved: listed,
+            ..TaskReport::worded("listed", "failed")
+        })
+    }
+}
+
+impl ListTask {
+    /// ListTask struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// `args` - an iterator containing Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - a path refers to an undefined environment variable, see expand_path()
+    /// - `./dir/` path not provided
+    /// - `./dir/` does not correspond to valid directory
+    /// - more than one path is provided
+    /// - `--depth`, `--max-depth`, or `--min-depth` is not followed by a non-negative integer
+    /// - `--min-depth` is greater than `--max-depth`
+    /// - `--exclude-dir` is not followed by a name or pattern
+    /// - `--null` is provided without `--files-only`
+    /// - an unknown option is provided
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
+        // separate options from the dir path so they may be given in any position
+        let (paths, options) = separate_args(args, VALUE_OPTIONS);
+        let paths = expand_paths(paths)?;
+        let mut paths = paths.into_iter();
+
+        // ensures dir path is provided
+        let dir = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => return Err("no 'dir' path provided"),
+        };
+
+        // ensures the dir path corresponds to a valid directory
+        if !dir.is_dir() {
+            return Err("'dir' path does not correspond to a valid directory");
+        }
+
+        // errors if extra paths are provided
+        if paths.next().is_some() {
+            return Err("list accepts exactly one 'dir' path");
+        }
+
+        // default options
+        let mut walker = FileWalker::new();
+        let mut counts = false;
+        let mut files_only = false;
+        let mut null = false;
+
+        // parse options
+        let mut args = options.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--depth" | "--max-depth" => walker = match args
+                    .next()
+                    .map(|value| value.parse::<usize>())
+                {
+                    Some(Ok(value)) => walker.max_depth(Some(value)),
+                    _ => return Err(
+                        "'--depth' and '--max-depth' must be followed by a non-negative integer",
+                    ),
+                },
+                "--min-depth" => {
+                    walker = match args.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(value)) => walker.min_depth(value),
+                        _ => {
+                            return Err("'--min-depth' must be followed by a non-negative integer")
+                        }
+                    }
+                }
+                "--follow" => walker = walker.symlinks(SymlinkPolicy::Follow),
+                "--no-follow" => walker = walker.symlinks(SymlinkPolicy::NoFollow),
+                "--exclude-dir" => {
+                    walker = match args.next() {
+                        Some(pattern) => walker.exclude_dir(pattern),
+                        None => {
+                            return Err("'--exclude-dir' must be followed by a name or pattern")
+                        }
+                    }
+                }
+                "--counts" => counts = true,
+                "--files-only" => files_only = true,
+                "-0" | "--null" => null = true,
+                _ => return Err("provided option did not match any list options"),
+            }
+        }
+
+        // an empty range would list nothing at all
+        if let (min_depth, Some(max_depth)) = walker.depth_range() {
+            if min_depth > max_depth {
+                return Err("'--min-depth' cannot be greater than '--max-depth'");
+            }
+        }
+
+        // only a flat list of paths can be NUL delimited
+        if null && !files_only {
+            return Err("'--null' requires '--files-only'");
+        }
+
+        Ok(Self {
+            dir,
+            walker,
+            counts,
+            files_only,
+            null,
+        })
+    }
+
+    /// files() returns every file below &self.dir sorted by path
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to ListTask
+    ///
+    /// # Errors
+    ///
+    /// - FileWalker::walk() call fails
+    fn files(&self) -> io::Result<Vec<PathBuf>> {
+        // the walker already visits items in sorted order
+        let mut files: Vec<PathBuf> = Vec::new();
+        self.walker.walk(&self.dir, &mut |entry| {
+            if !entry.is_dir {
+                files.push(entry.path.clone());
+            }
+            Ok(())
+        })?;
+        files.sort();
+
+        Ok(files)
+    }
+
+    /// render() builds the lines to be printed for &self.dir, either an indented tree or a flat sorted list of files
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to ListTask
+    ///
+    /// # Errors
+    ///
+    /// - files() call fails
+    /// - FileWalker::walk() call fails
+    fn render(&self) -> io::Result<Vec<String>> {
+        // flat sorted list of files
+        if self.files_only {
+            return Ok(self
+                .files()?
+                .iter()
+                .map(|file| file.display().to_string())
+                .collect());
+        }
+
+        // tree view, the root is displayed as provided and every level below it is indented by four spaces
+        let root = self.dir.display().to_string();
+        let mut lines: Vec<String> =
+            vec![self.label(&self.dir, root.trim_end_matches(['/', '\\']).to_string(), 0)?];
+        self.walker.walk(&self.dir, &mut |entry| {
+            let name = entry
+                .path
+                .file_name()
+                .unwrap_or(entry.path.as_os_str())
+                .to_string_lossy()
+                .to_string();
+
+            let label = if entry.is_dir {
+                self.label(&entry.path, name, entry.depth)?
+            } else {
+                name
+            };
+
+            lines.push(format!("{}{}", "    ".repeat(entry.depth), label));
+            Ok(())
+        })?;
+
+        Ok(lines)
+    }
+
+    /// label() formats the displayed name of a directory, appending the number of files directly inside it if requested,
+    /// counted by &self.walker so symbolic links are counted as the walk treats them and files outside its depth range
+    /// are not counted
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to ListTask
+    /// `dir` a Path that corresponds to a directory
+    /// `name` the name to display for `dir`
+    /// `depth` the number of levels `dir` lies below &self.dir
+    ///
+    /// # Errors
+    ///
+    /// - FileWalker::walk() call fails
+    fn label(&self, dir: &Path, name: String, depth: usize) -> io::Result<String> {
+        if self.counts {
+            let mut files: usize = 0;
+            let (min_depth, max_depth) = self.walker.depth_range();
+            if max_depth.is_none_or(|max_depth| depth < max_depth) {
+                self.walker
+                    .clone()
+                    .min_depth(min_depth.saturating_sub(depth))
+                    .max_depth(Some(1))
+                    .walk(dir, &mut |entry| {
+                        files += usize::from(!entry.is_dir);
+                        Ok(())
+                    })?;
+            }
+            Ok(format!("{name}/ ({files} files)"))
+        } else {
+            Ok(format!("{name}/"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, File};
+
+    /// verifies ListTask::new() works correctly with valid arguments passed in
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - ListTask::new() does not parse the dir path and all options
+    #[test]
+    fn list_task_new_with_valid_args() {
+        // args iterator
+        let args = [
+            String::from("./src"),
+            String::from("--depth"),
+            String::from("2"),
+            String::from("--counts"),
+            String::from("--files-only"),
+        ]
+        .into_iter();
+
+        assert_eq!(
+            ListTask::new(args),
+            Ok(ListTask {
+                dir: PathBuf::from("./src"),
+                walker: FileWalker::new().max_depth(Some(2)),
+                counts: true,
+                files_only: true,
+                null: false,
+            })
+        );
+    }
+
+    /// verifies ListTask::new() errors if dir path provided is not a real directory or an option is invalid
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - ListTask::new() does not error if the dir path is missing or invalid
+    /// - ListTask::new() does not error if `--depth` is not followed by an integer
+    #[test]
+    fn list_task_new_invalid_args() {
+        assert!(ListTask::new([].into_iter()).is_err());
+        assert!(ListTask::new([String::from("not_a_dir")].into_iter()).is_err());
+        assert!(
+            ListTask::new([String::from("./src"), String::from("--depth")].into_iter()).is_err()
+        );
+        assert!(ListTask::new([String::from("./src"), String::from("--foo")].into_iter()).is_err());
+        assert!(
+            ListTask::new([String::from("./src"), String::from("--null")].into_iter()).is_err()
+        );
+    }
+
+    /// verifies ListTask::render() produces an indented tree with file counts and respects the depth limit
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - ListTask::render() does not produce the expected tree
+    #[test]
+    fn list_task_render_tree() {
+        // create inputs
+        let path_buf = PathBuf::from("./list_task_render_tree_test");
+        let nested_path_buf = path_buf.join("nested/deeper");
+
+        // create mock directory
+        create_dir_all(&nested_path_buf).unwrap();
+        File::create(path_buf.join("1.txt")).unwrap();
+        File::create(path_buf.join("nested/2.txt")).unwrap();
+        File::create(nested_path_buf.join("3.txt")).unwrap();
+
+        // run test
+        let mut task = ListTask {
+            dir: path_buf.clone(),
+            walker: FileWalker::new(),
+            counts: true,
+            files_only: false,
+            null: false,
+        };
+        let full_tree = task.render().unwrap();
+
+        task.walker = FileWalker::new().max_depth(Some(1));
+        task.counts = false;
+        let shallow_tree = task.render().unwrap();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            full_tree,
+            vec![
+                "./list_task_render_tree_test/ (1 files)",
+                "    1.txt",
+                "    nested/ (1 files)",
+                "        2.txt",
+                "        deeper/ (1 files)",
+                "            3.txt",
+            ]
+        );
+        assert_eq!(
+            shallow_tree,
+            vec!["./list_task_render_tree_test/", "    1.txt", "    nested/"]
+        );
+    }
+
+    /// verifies `--min-depth` and `--max-depth` leave out files outside the range from the listed files and the
+    /// per-directory counts as the bounds are moved
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - ListTask::files() lists a file outside the depth range or misses one inside it
+    /// - ListTask::render() counts a file outside the depth range
+    /// - ListTask::new() accepts a minimum depth greater than the maximum
+    #[test]
+    fn list_task_depth_range() {
+        // create inputs
+        let path_buf = PathBuf::from("./list_task_depth_range_test");
+
+        // create mock directory, one file at depth 1, two at depth 2, and three at depth 3
+        create_dir_all(path_buf.join("a/b")).unwrap();
+        for file in [
+            "1.txt",
+            "a/1.txt",
+            "a/2.txt",
+            "a/b/1.txt",
+            "a/b/2.txt",
+            "a/b/3.txt",
+        ] {
+            File::create(path_buf.join(file)).unwrap();
+        }
+
+        // run test
+        let task = |range: &[&str], counts: bool| {
+            let args = [path_buf.display().to_string()]
+                .into_iter()
+                .chain(range.iter().map(|arg| arg.to_string()));
+            ListTask {
+                counts,
+                files_only: !counts,
+                ..ListTask::new(args).unwrap()
+            }
+        };
+        let counted = [
+            &[][..],
+            &["--max-depth", "2"],
+            &["--min-depth", "2"],
+            &["--min-depth", "2", "--max-depth", "2"],
+            &["--min-depth", "3"],
+        ]
+        .map(|range| task(range, false).files().unwrap().len());
+        let tree = task(&["--min-depth", "2", "--max-depth", "2"], true)
+            .render()
+            .unwrap();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(counted, [6, 3, 5, 2, 3]);
+        assert_eq!(
+            tree,
+            vec![
+                "./list_task_depth_range_test/ (0 files)",
+                "    a/ (2 files)",
+                "        1.txt",
+                "        2.txt",
+                "        b/ (0 files)",
+            ]
+        );
+        assert!(ListTask::new(
+            ["./src", "--min-depth", "3", "--max-depth", "2"]
+                .map(String::from)
+                .into_iter()
+        )
+        .is_err());
+    }
+
+    /// verifies ListTask::render() produces a flat sorted list of files when `files_only` is set
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - ListTask::render() does not list every file in sorted order
+    /// - ListTask::run_task() does not count the listed files in its summary
+    #[test]
+    fn list_task_render_files_only() {
+        // create inputs
+        let path_buf = PathBuf::from("./list_task_render_files_only_test");
+        let nested_path_buf = path_buf.join("a");
+
+        // create mock directory
+        create_dir_all(&nested_path_buf).unwrap();
+        File::create(path_buf.join("b.txt")).unwrap();
+        File::create(nested_path_buf.join("c.txt")).unwrap();
+
+        // run test
+        let task = ListTask {
+            dir: path_buf.clone(),
+            walker: FileWalker::new(),
+            counts: false,
+            files_only: true,
+            null: false,
+        };
+        let test_result = task.render().unwrap();
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            test_result,
+            vec![
+                path_buf.join("a/c.txt").display().to_string(),
+                path_buf.join("b.txt").display().to_string(),
+            ]
+        );
+        assert_eq!(
+            report.to_string(),
+            "2 listed, 0 skipped, 0 failed, 0 directories created"
+        );
+    }
+
+    /// verifies a symbolic link to a directory is left out by default, listed as an entry of its own with
+    /// `--no-follow`, and listed with the files it leads to with `--follow`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - ListTask::run_task() does not honor the symlink policy given on the command line
+    #[cfg(unix)]
+    #[test]
+    fn list_task_symlink_policy() {
+        use std::os::unix::fs::symlink;
+
+        // create inputs
+        let path_buf = PathBuf::from("./list_task_symlink_policy_test");
+        let source = path_buf.join("source");
+
+        // create mock directories, the linked folder lives next to the listed one
+        create_dir_all(path_buf.join("outside")).unwrap();
+        create_dir_all(&source).unwrap();
+        File::create(path_buf.join("outside/b.txt")).unwrap();
+        File::create(source.join("a.txt")).unwrap();
+        symlink("../outside", source.join("linked")).unwrap();
+
+        // run test
+        let list = |policy: Option<&str>| {
+            let mut args = vec![source.display().to_string(), String::from("--files-only")];
+            args.extend(policy.map(String::from));
+            ListTask::new(args.into_iter()).unwrap().render().unwrap()
+        };
+        let test_result = [None, Some("--no-follow"), Some("--follow")].map(list);
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        let paths = |names: &[&str]| -> Vec<String> {
+            names
+                .iter()
+                .map(|name| source.join(name).display().to_string())
+                .collect()
+        };
+        assert_eq!(test_result[0], paths(&["a.txt"]));
+        assert_eq!(test_result[1], paths(&["a.txt", "linked"]));
+        assert_eq!(test_result[2], paths(&["a.txt", "linked/b.txt"]));
+    }
+}
@@ -1,17 +1,90 @@
-use std::{env, process};
+use std::env;
+use std::fs::write;
+use std::process::ExitCode;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
-use fileman_rs::{Config, RunTask};
+use fileman_rs::{
+    cancel_on_interrupt, report_json_from_args, threads_from_args, Config, Logger, RunTask,
+    EXIT_CONFIG_ERROR, EXIT_FAILURE, EXIT_SUCCESS,
+};
 
-fn main() {
-    // create new config enum
-    let config = Config::new(env::args()).unwrap_or_else(|err| {
+fn main() -> ExitCode {
+    // collect arguments so that options shared by all tasks can be removed before the task is configured
+    let mut args: Vec<String> = env::args().collect();
+
+    // create logger from the optional --log-file argument
+    let mut logger = match Logger::from_args(&mut args) {
+        Ok(logger) => logger,
+        Err(err) => {
+            eprintln!("Error in configuration: {err}");
+            return ExitCode::from(EXIT_CONFIG_ERROR);
+        }
+    };
+
+    // path the JSON report of the run is written to from the optional --report-json argument
+    let report_json = match report_json_from_args(&mut args) {
+        Ok(path) => path,
+        Err(err) => {
+            eprintln!("Error in configuration: {err}");
+            return ExitCode::from(EXIT_CONFIG_ERROR);
+        }
+    };
+
+    // set the number of worker threads from the optional --threads argument
+    if let Err(err) = threads_from_args(&mut args) {
         eprintln!("Error in configuration: {err}");
-        process::exit(1);
-    });
+        return ExitCode::from(EXIT_CONFIG_ERROR);
+    }
+
+    // create new config enum
+    let config = match Config::new(args.into_iter()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Error in configuration: {err}");
+            return ExitCode::from(EXIT_CONFIG_ERROR);
+        }
+    };
+
+    // the first Ctrl-C stops the task after the file it is working on, leaving the run resumable
+    let cancel = Arc::new(AtomicBool::new(false));
+    cancel_on_interrupt(cancel.clone());
+    let config = config.with_cancel(cancel);
+
+    // capabilities are read by other programs, so nothing but their JSON is printed
+    if let Config::Capabilities(task) = &config {
+        return match task.run_task(&mut logger) {
+            Ok(_) => ExitCode::from(EXIT_SUCCESS),
+            Err(err) => {
+                eprintln!("Error running task: {err}");
+                ExitCode::from(EXIT_FAILURE)
+            }
+        };
+    }
 
-    // run desired task
-    match config.run_task() {
-        Ok(_) => println!("fileman_rs completed task successfully and is now exiting"),
-        Err(err) => eprintln!("Error running task: {err}"),
+    // run desired task, the exit code tells scripts whether any file failed
+    match config.run_task(&mut logger) {
+        Ok(report) => {
+            // the summary goes to stderr so stdout only holds what the task itself prints, which may be read by scripts
+            eprintln!("{report}");
+            if let Some(path) = &report_json {
+                if let Err(err) = write(path, format!("{}\n", report.to_json())) {
+                    eprintln!("Error writing report: {err}");
+                    return ExitCode::from(EXIT_FAILURE);
+                }
+            }
+            // like the summary the closing line is kept off stdout, which may hold NUL separated paths
+            let code = report.exit_code();
+            if code == EXIT_SUCCESS {
+                eprintln!("fileman_rs completed task successfully and is now exiting");
+            } else {
+                eprintln!("fileman_rs completed task with failures and is now exiting");
+            }
+            ExitCode::from(code)
+        }
+        Err(err) => {
+            eprintln!("Error running task: {err}");
+            ExitCode::from(EXIT_FAILURE)
+        }
     }
 }
@@ -0,0 +1,724 @@
+// declare cargo crates
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+// declare local code
+use super::tools::args::separate_args;
+use super::tools::collect_files::collect_files_in;
+use super::tools::ensured_dirs::EnsuredDirs;
+use super::tools::error::with_path;
+use super::tools::file_system::{FileSystem, RealFileSystem};
+use super::tools::file_walker::FileWalker;
+use super::tools::move_file::move_file;
+use super::tools::paths::expand_paths;
+use super::tools::shard::{parse_shard_key, Shard};
+use super::tools::symlinks::SymlinkPolicy;
+use super::tools::throttle::{parse_throttle, Throttle};
+use super::tools::unique_path::unique_path;
+use super::{Logger, RunTask, SkipReason, TaskReport};
+
+/// move options that are followed by a value, see separate_args()
+pub(crate) const VALUE_OPTIONS: &[&str] = &[
+    "--flatten-depth",
+    "--strip-prefix",
+    "--throttle",
+    "--exclude-dir",
+    "--name-from-hash-prefix",
+    "--shard-depth",
+    "--shard-width",
+];
+
+/// move options that are not followed by a value, listed along with VALUE_OPTIONS by the capabilities task
+pub(crate) const FLAG_OPTIONS: &[&str] = &["--keep-going", "--follow", "--no-follow"];
+
+/// MoveTask struct: PathBufs correspond to source and target directories along with how much of the directory
+/// structure is kept
+#[derive(Debug, PartialEq, Eq)]
+pub struct MoveTask {
+    /// PathBuf to directory containing files to be moved
+    source: PathBuf,
+
+    /// PathBuf to directory files are moved into
+    target: PathBuf,
+
+    /// FileWalker used to collect files from the source directory
+    walker: FileWalker,
+
+    /// number of leading directories of each file's path relative to &self.source that are kept, deeper directories
+    /// are flattened into the last kept one, None keeps the whole structure
+    flatten_depth: Option<usize>,
+
+    /// leading directories removed from each file's path relative to &self.source before it is joined onto
+    /// &self.target, None keeps the path as is
+    strip_prefix: Option<PathBuf>,
+
+    /// whether files that do not start with &self.strip_prefix are skipped rather than ending the run
+    keep_going: bool,
+
+    /// maximum average rate in bytes per second that files are copied across file systems at, None for no limit
+    throttle: Option<u64>,
+
+    /// nested folders named after a hash that every file is placed in below its target directory, None for none
+    shard: Option<Shard>,
+}
+
+/// RunTask trait implementation for Move variant of Config enum
+impl RunTask for MoveTask {
+    /// run_task() driver function for Move variant of Config, moves every file from &self.source into &self.target
+    /// keeping its directory structure, less &self.strip_prefix, up to &self.flatten_depth levels, files that would
+    /// collide with an existing file are suffixed `_1`, `_2`, ... rather than overwriting it
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Config enum
+    /// `logger` a Logger that records every move and failed move
+    ///
+    /// # Errors
+    ///
+    /// - see run_with()
+    fn run_task(&self, logger: &mut Logger) -> Result<TaskReport, io::Error> {
+        self.run_with(&RealFileSystem, logger)
+    }
+}
+
+impl MoveTask {
+    /// run_with() body of run_task() performing every file system operation on `fs`, so tests can exercise error
+    /// handling with a FileSystem that fails on demand
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to MoveTask
+    /// `fs` the FileSystem files are collected from and moved on
+    /// `logger` a Logger that records every move and failed move
+    ///
+    /// # Errors
+    ///
+    /// - collect_files_in() call fails
+    /// - a file does not start with &self.strip_prefix and &self.keep_going is not set, nothing is moved
+    /// - Shard::dirs() call fails, nothing is moved
+    /// - EnsuredDirs::ensure() call fails
+    /// - move_file() call fails
+    /// - Logger::log() call fails
+    fn run_with(&self, fs: &dyn FileSystem, logger: &mut Logger) -> io::Result<TaskReport> {
+        let mut report = TaskReport::default();
+
+        // PathBufs for all files found at the source directory, collected up front so files moved into a target inside
+        // the source are not visited again
+        let mut files: Vec<PathBuf> = Vec::new();
+        collect_files_in(fs, &self.walker, &self.source, &mut files)?;
+
+        // target directory of every file, computed before anything is moved so a prefix that does not fit stops the
+        // run while the source is still untouched
+        let mut planned: Vec<(&PathBuf, PathBuf)> = Vec::new();
+        for file in &files {
+            match self.target_dir(file) {
+                Some(dir) => match &self.shard {
+                    Some(shard) => planned.push((file, dir.join(shard.dirs(file)?))),
+                    None => planned.push((file, dir)),
+                },
+                None if self.keep_going => {
+                    logger.log("skip", file, None)?;
+                    report.skip(
+                        file.clone(),
+                        SkipReason::OutsidePrefix,
+                        "does not start with strip prefix",
+                    );
+                }
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "{} does not start with strip prefix {}, pass --keep-going to skip it",
+                            file.display(),
+                            // target_dir() only fails when a prefix is set
+                            self.strip_prefix
+                                .as_deref()
+                                .unwrap_or(Path::new(""))
+                                .display()
+                        ),
+                    ));
+                }
+            }
+        }
+
+        // shared by every move so the limit applies to the run as a whole
+        let mut throttle = Throttle::new(self.throttle);
+
+        // directories are created and recorded in the report the first time a file is moved into them
+        let ensured = EnsuredDirs::new();
+
+        for (file, dir) in planned {
+            if let Some(created) = ensured.ensure(fs, &dir)? {
+                report.record_dir(dir.clone(), created);
+            }
+
+            // keep the original file name, suffixing it if another file with the same name is already there
+            let target = unique_path(
+                &dir,
+                &file.file_name().unwrap().to_string_lossy(), // collected files always have a file name
+            );
+
+            if let Err(err) = move_file(fs, file, &target, &mut throttle, false) {
+                logger.log("fail", file, Some(&target))?;
+                return Err(with_path(err, file));
+            }
+            logger.log("move", file, Some(&target))?;
+            report.moved += 1;
+            report.moves.push((file.clone(), target));
+        }
+
+        Ok(report)
+    }
+
+    /// MoveTask struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// `args` - an iterator containing Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - a path refers to an undefined environment variable, see expand_path()
+    /// - `./source/` path not provided
+    /// - `./source/` does not correspond to valid directory
+    /// - `./target/` path not provided
+    /// - `./target/` does not correspond to valid directory
+    /// - more than two paths are provided
+    /// - `--flatten-depth` is not followed by a non-negative integer
+    /// - `--strip-prefix` is not followed by a relative path without `..`
+    /// - `--throttle` is not followed by a positive number of bytes per second
+    /// - `--exclude-dir` is not followed by a name or pattern
+    /// - `--name-from-hash-prefix` is not followed by `name` or `content`
+    /// - `--shard-depth` or `--shard-width` is not followed by an integer the digest has enough digits for, or is
+    ///   given without `--name-from-hash-prefix`
+    /// - an unknown option is provided
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
+        // separate options from the source and target paths so they may be given in any position
+        let (paths, options) = separate_args(args, VALUE_OPTIONS);
+        let paths = expand_paths(paths)?;
+        let mut paths = paths.into_iter();
+
+        // ensures source path is provided and corresponds to a valid directory
+        let source = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => return Err("no 'source' path provided"),
+        };
+        if !source.is_dir() {
+            return Err("'source' path does not correspond to a valid directory");
+        }
+
+        // ensures target path is provided and corresponds to a valid directory
+        let target = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => return Err("no 'target' path provided"),
+        };
+        if !target.is_dir() {
+            return Err("'target' path does not correspond to a valid directory");
+        }
+
+        // errors if extra paths are provided
+        if paths.next().is_some() {
+            return Err("move accepts exactly one 'source' and one 'target' path");
+        }
+
+        // default options
+        let mut walker = FileWalker::new();
+        let mut flatten_depth: Option<usize> = None;
+        let mut strip_prefix: Option<PathBuf> = None;
+        let mut keep_going = false;
+        let mut throttle: Option<u64> = None;
+        let mut shard: Option<Shard> = None;
+        let mut shard_depth: Option<usize> = None;
+        let mut shard_width: Option<usize> = None;
+
+        // parse options
+        let mut args = options.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--flatten-depth" => {
+                    flatten_depth = match args.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(depth)) => Some(depth),
+                        _ => {
+                            return Err(
+                                "'--flatten-depth' must be followed by a non-negative integer",
+                            )
+                        }
+                    }
+                }
+                "--strip-prefix" => strip_prefix = Some(parse_strip_prefix(args.next())?),
+                "--keep-going" => keep_going = true,
+                "--throttle" => throttle = Some(parse_throttle(args.next())?),
+                "--name-from-hash-prefix" => {
+                    shard = Some(Shard::new(parse_shard_key(args.next())?))
+                }
+                "--shard-depth" => {
+                    shard_depth = match args.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(depth)) => Some(depth),
+                        _ => return Err("'--shard-depth' must be followed by a positive integer"),
+                    }
+                }
+                "--shard-width" => {
+                    shard_width = match args.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(width)) => Some(width),
+                        _ => return Err("'--shard-width' must be followed by a positive integer"),
+                    }
+                }
+                "--follow" => walker = walker.symlinks(SymlinkPolicy::Follow),
+                "--no-follow" => walker = walker.symlinks(SymlinkPolicy::NoFollow),
+                "--exclude-dir" => {
+                    walker = match args.next() {
+                        Some(pattern) => walker.exclude_dir(pattern),
+                        None => {
+                            return Err("'--exclude-dir' must be followed by a name or pattern")
+                        }
+                    }
+                }
+                _ => return Err("provided option did not match any move options"),
+            }
+        }
+
+        // the layout is only checked once every option is known, so the options may be given in any order
+        match &mut shard {
+            Some(shard) => {
+                shard.depth = shard_depth.unwrap_or(shard.depth);
+                shard.width = shard_width.unwrap_or(shard.width);
+                shard.validate()?;
+            }
+            None if shard_depth.is_some() || shard_width.is_some() => {
+                return Err("'--shard-depth' and '--shard-width' require '--name-from-hash-prefix'")
+            }
+            None => {}
+        }
+
+        Ok(Self {
+            source,
+            target,
+            walker,
+            flatten_depth,
+            strip_prefix,
+            keep_going,
+            throttle,
+            shard,
+        })
+    }
+
+    /// target_dir() returns the directory inside &self.target that `file` is moved into, its directories relative to
+    /// &self.source without &self.strip_prefix truncated to &self.flatten_depth levels, or None when they do not start
+    /// with &self.strip_prefix
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to MoveTask
+    /// `file` a Path that corresponds to a file collected from &self.source
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn target_dir(&self, file: &Path) -> Option<PathBuf> {
+        // directories between the source and the file, collected files always start with the source path
+        let relative = file
+            .strip_prefix(&self.source)
+            .unwrap()
+            .parent()
+            .unwrap_or(Path::new(""));
+
+        // prefixes are matched by whole directories, so `archive` does not match `archives/`
+        let relative = match &self.strip_prefix {
+            Some(prefix) => relative.strip_prefix(prefix).ok()?,
+            None => relative,
+        };
+
+        Some(match self.flatten_depth {
+            Some(depth) => self
+                .target
+                .join(relative.components().take(depth).collect::<PathBuf>()),
+            None => self.target.join(relative),
+        })
+    }
+}
+
+/// parse_strip_prefix() parses the value of `--strip-prefix`, a path relative to the source directory made of plain
+/// directory names, `.` components are dropped
+///
+/// # Arguments
+///
+/// `value` the value following `--strip-prefix`
+///
+/// # Errors
+///
+/// - `value` is missing, empty, absolute, or contains `..`
+fn parse_strip_prefix(value: Option<String>) -> Result<PathBuf, &'static str> {
+    const ERROR: &str =
+        "'--strip-prefix' must be followed by a path relative to 'source' without '..', such as archive";
+
+    let mut prefix = PathBuf::new();
+    for component in Path::new(&value.ok_or(ERROR)?).components() {
+        match component {
+            Component::Normal(name) => prefix.push(name),
+            Component::CurDir => {}
+            _ => return Err(ERROR),
+        }
+    }
+
+    if prefix.as_os_str().is_empty() {
+        return Err(ERROR);
+    }
+
+    Ok(prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tools::sha256::{hash_file, to_hex, Sha256};
+    use super::super::tools::shard::ShardKey;
+    use super::*;
+    use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+
+    /// verifies MoveTask::new() parses paths and options
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - MoveTask::new() does not parse valid arguments
+    /// - MoveTask::new() accepts a negative or missing `--flatten-depth`
+    /// - MoveTask::new() accepts an empty, absolute, or escaping `--strip-prefix`
+    /// - MoveTask::new() does not apply `--shard-depth` and `--shard-width` or accepts them without a hash prefix
+    #[test]
+    fn move_task_new() {
+        // args iterator builder
+        let args = |options: &[&str]| {
+            ["./src", "./src/move_task"]
+                .iter()
+                .chain(options)
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+
+        assert_eq!(
+            MoveTask::new(args(&[
+                "--flatten-depth",
+                "2",
+                "--strip-prefix",
+                "./archive/2020/",
+                "--keep-going"
+            ])),
+            Ok(MoveTask {
+                source: PathBuf::from("./src"),
+                target: PathBuf::from("./src/move_task"),
+                walker: FileWalker::new(),
+                flatten_depth: Some(2),
+                strip_prefix: Some(PathBuf::from("archive/2020")),
+                keep_going: true,
+                throttle: None,
+                shard: None,
+            })
+        );
+        assert!(MoveTask::new(args(&["--flatten-depth", "-1"])).is_err());
+        assert!(MoveTask::new(args(&["--flatten-depth"])).is_err());
+        assert!(MoveTask::new(args(&["--flatten"])).is_err());
+        for prefix in ["", ".", "../archive", "archive/../..", "/archive"] {
+            assert!(MoveTask::new(args(&["--strip-prefix", prefix])).is_err());
+        }
+        assert_eq!(
+            MoveTask::new(args(&[
+                "--shard-width",
+                "1",
+                "--name-from-hash-prefix",
+                "content",
+                "--shard-depth",
+                "3"
+            ]))
+            .map(|task| task.shard),
+            Ok(Some(Shard {
+                key: ShardKey::Content,
+                depth: 3,
+                width: 1,
+            }))
+        );
+        assert!(MoveTask::new(args(&["--shard-depth", "2"])).is_err());
+        assert!(MoveTask::new(args(&[
+            "--name-from-hash-prefix",
+            "name",
+            "--shard-width",
+            "0"
+        ]))
+        .is_err());
+        assert!(MoveTask::new(args(&["--name-from-hash-prefix", "size"])).is_err());
+    }
+
+    /// verifies MoveTask::run_task() keeps the first `--flatten-depth` directories of every file, flattens the rest with
+    /// collision suffixes, and keeps the whole structure when the depth exceeds the tree
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - MoveTask::run_task() keeps too many or too few directories for depth 0, 1, or one deeper than the tree
+    /// - MoveTask::run_task() overwrites a file that collides once flattened
+    #[test]
+    fn move_task_run_task_flatten_depth() {
+        for (depth, expected) in [
+            (0, ["2.txt", "1.txt", "1_1.txt", "top.txt"]),
+            (1, ["a/2.txt", "a/1.txt", "a/1_1.txt", "top.txt"]),
+            (5, ["a/2.txt", "a/b/1.txt", "a/b/c/1.txt", "top.txt"]),
+        ] {
+            // create inputs
+            let path_buf = PathBuf::from("./move_task_run_task_flatten_depth_test");
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+            let files = ["a/2.txt", "a/b/1.txt", "a/b/c/1.txt", "top.txt"];
+
+            // create mock directories, every file holds its own relative path so it can be traced after moving
+            create_dir_all(source.join("a/b/c")).unwrap();
+            create_dir_all(&target).unwrap();
+            for file in files {
+                write(source.join(file), file).unwrap();
+            }
+
+            // run test
+            let task = MoveTask {
+                source: source.clone(),
+                target: target.clone(),
+                walker: FileWalker::new(),
+                flatten_depth: Some(depth),
+                strip_prefix: None,
+                keep_going: false,
+                throttle: None,
+                shard: None,
+            };
+            let report = task.run_task(&mut Logger::disabled()).unwrap();
+            let test_result: Vec<String> = expected
+                .iter()
+                .map(|file| read_to_string(target.join(file)).unwrap_or_default())
+                .collect();
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            assert_eq!(report.moved, 4);
+            assert_eq!(test_result, files);
+        }
+    }
+
+    /// verifies MoveTask::run_task() removes `--strip-prefix` from matching files, stops before moving anything when a
+    /// file does not match, and skips non-matching files under `--keep-going`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - MoveTask::run_task() does not remove the prefix from the path of a matching file
+    /// - MoveTask::run_task() moves any file or does not error when a file does not match and keep going is not set
+    /// - MoveTask::run_task() does not skip a non-matching file when keep going is set
+    #[test]
+    fn move_task_run_task_strip_prefix() {
+        for keep_going in [false, true] {
+            // create inputs
+            let path_buf = PathBuf::from("./move_task_run_task_strip_prefix_test");
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+
+            // create mock directories, `archives` shares its first letters with the prefix but not its directory
+            create_dir_all(source.join("archive/2020")).unwrap();
+            create_dir_all(source.join("archives")).unwrap();
+            create_dir_all(&target).unwrap();
+            write(source.join("archive/2020/x.jpg"), "x").unwrap();
+            write(source.join("archive/y.jpg"), "y").unwrap();
+            write(source.join("archives/z.jpg"), "z").unwrap();
+
+            // run test
+            let task = MoveTask {
+                source: source.clone(),
+                target: target.clone(),
+                walker: FileWalker::new(),
+                flatten_depth: None,
+                strip_prefix: Some(PathBuf::from("archive")),
+                keep_going,
+                throttle: None,
+                shard: None,
+            };
+            let test_result = task.run_task(&mut Logger::disabled());
+            let moved = (
+                read_to_string(target.join("2020/x.jpg")).unwrap_or_default(),
+                read_to_string(target.join("y.jpg")).unwrap_or_default(),
+                source.join("archives/z.jpg").exists(),
+            );
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            if keep_going {
+                let report = test_result.unwrap();
+                assert_eq!(report.moved, 2);
+                assert_eq!(
+                    report.skipped,
+                    [(
+                        source.join("archives/z.jpg"),
+                        SkipReason::OutsidePrefix,
+                        String::from("does not start with strip prefix")
+                    )]
+                );
+                assert_eq!(moved, (String::from("x"), String::from("y"), true));
+            } else {
+                assert_eq!(test_result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+                assert_eq!(moved, (String::new(), String::new(), true));
+            }
+        }
+    }
+
+    /// verifies `--name-from-hash-prefix` places every flattened file in the shard folders named after the leading
+    /// digits of the digest of its name or contents
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - MoveTask::run_task() places a file in other folders than those named after its digest
+    /// - MoveTask::run_task() places files sharing a name in the same folder when sharding by contents
+    #[test]
+    fn move_task_run_task_shard() {
+        for key in [ShardKey::Name, ShardKey::Content] {
+            // create inputs, two files share a name but not their contents
+            let path_buf = PathBuf::from("./move_task_run_task_shard_test");
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+            let files = ["a/1.txt", "b/1.txt", "b/2.txt"];
+
+            // create mock directories
+            create_dir_all(source.join("a")).unwrap();
+            create_dir_all(source.join("b")).unwrap();
+            create_dir_all(&target).unwrap();
+            for file in files {
+                write(source.join(file), file).unwrap();
+            }
+            let expected: Vec<PathBuf> = files
+                .iter()
+                .map(|file| {
+                    let path = source.join(file);
+                    let digest = match key {
+                        ShardKey::Name => {
+                            let mut sha256 = Sha256::new();
+                            sha256.update(path.file_name().unwrap().as_encoded_bytes());
+                            sha256.finish()
+                        }
+                        ShardKey::Content => hash_file(&path).unwrap(),
+                    };
+                    let hex = to_hex(&digest);
+                    target
+                        .join(&hex[..2])
+                        .join(&hex[2..4])
+                        .join(path.file_name().unwrap())
+                })
+                .collect();
+
+            // run test
+            let task = MoveTask {
+                source: source.clone(),
+                target: target.clone(),
+                walker: FileWalker::new(),
+                flatten_depth: Some(0),
+                strip_prefix: None,
+                keep_going: false,
+                throttle: None,
+                shard: Some(Shard::new(key)),
+            };
+            let report = task.run_task(&mut Logger::disabled()).unwrap();
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            assert_eq!(report.moved, 3);
+            if key == ShardKey::Name {
+                // both files named 1.txt share their folder, the second is suffixed
+                let mut collided = expected[1].clone();
+                collided.set_file_name("1_1.txt");
+                assert_eq!(
+                    report
+                        .moves
+                        .iter()
+                        .map(|(_, to)| to.clone())
+                        .collect::<Vec<_>>(),
+                    [expected[0].clone(), collided, expected[2].clone()]
+                );
+            } else {
+                assert_ne!(expected[0].parent(), expected[1].parent());
+                assert_eq!(
+                    report
+                        .moves
+                        .iter()
+                        .map(|(_, to)| to.clone())
+                        .collect::<Vec<_>>(),
+                    expected
+                );
+            }
+        }
+    }
+
+    /// verifies a symbolic link to a directory is left in place by default, moved as the link itself with
+    /// `--no-follow`, and has the files it leads to moved with `--follow`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - MoveTask::run_task() moves the link or the files it leads to without a symlink policy
+    /// - MoveTask::run_task() does not move the link itself with `--no-follow`
+    /// - MoveTask::run_task() does not move the files below the link with `--follow`
+    #[cfg(unix)]
+    #[test]
+    fn move_task_run_task_symlink_policy() {
+        use std::os::unix::fs::symlink;
+
+        for policy in [None, Some("--no-follow"), Some("--follow")] {
+            // create inputs
+            let path_buf = PathBuf::from("./move_task_run_task_symlink_policy_test");
+            let outside = path_buf.join("outside");
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+
+            // create mock directories, the linked folder lives next to the source
+            for dir in [&outside, &source, &target] {
+                create_dir_all(dir).unwrap();
+            }
+            write(outside.join("b.txt"), "b").unwrap();
+            write(source.join("a.txt"), "a").unwrap();
+            symlink("../outside", source.join("linked")).unwrap();
+
+            // run test
+            let mut args = vec![source.display().to_string(), target.display().to_string()];
+            args.extend(policy.map(String::from));
+            let report = MoveTask::new(args.into_iter())
+                .unwrap()
+                .run_task(&mut Logger::disabled());
+            let test_result = (
+                source.join("linked").is_symlink(),
+                target.join("linked").is_symlink(),
+                target.join("linked/b.txt").is_file(),
+                outside.join("b.txt").exists(),
+            );
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            let (moved, expected) = match policy {
+                None => (1, (true, false, false, true)),
+                Some("--no-follow") => (2, (false, true, true, true)),
+                _ => (2, (true, false, true, false)),
+            };
+            assert_eq!(report.unwrap().moved, moved);
+            assert_eq!(test_result, expected);
+        }
+    }
+}
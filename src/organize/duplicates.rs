@@ -0,0 +1,101 @@
+// declare cargo crates
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// declare local code
+use crate::dedupe::hash_files;
+use crate::tools::collect_files::collect_files_in;
+use crate::tools::digest::DigestAlgorithm;
+use crate::tools::error::PathContext;
+use crate::tools::file_system::FileSystem;
+use crate::tools::file_walker::FileWalker;
+use crate::tools::progress::ProgressCounter;
+use crate::tools::sha256::hash_file;
+use crate::tools::threads::threads;
+
+/// TargetContents struct: the files already held by the target directory, used by `--skip-duplicates` to recognize
+/// incoming files whose contents are already archived somewhere in it
+#[derive(Debug, Default)]
+pub struct TargetContents {
+    /// files of the target by size, only files of a size some incoming file shares are ever hashed
+    by_size: HashMap<u64, Vec<PathBuf>>,
+
+    /// path of a target file by content digest, for every size hashed so far
+    by_digest: HashMap<u64, HashMap<[u8; 32], PathBuf>>,
+}
+
+impl TargetContents {
+    /// new() lists every file below `target` along with its size, nothing is hashed yet
+    ///
+    /// # Arguments
+    ///
+    /// `fs` the FileSystem the target is read from
+    /// `target` a Path that corresponds to the target directory
+    ///
+    /// # Errors
+    ///
+    /// - collect_files_in() call fails
+    /// - metadata is not retrievable for a file of the target
+    pub fn new(fs: &dyn FileSystem, target: &Path) -> io::Result<Self> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        collect_files_in(fs, &FileWalker::new(), target, &mut files)?;
+
+        let mut contents = Self::default();
+        for file in files {
+            let size = fs.metadata(&file).path_context(&file)?.len();
+            contents.by_size.entry(size).or_default().push(file);
+        }
+
+        Ok(contents)
+    }
+
+    /// find() returns a file of the target other than `file` itself with the same contents as `file`, the files of the
+    /// target sharing its size are hashed the first time that size is seen and `file` itself is only hashed when there
+    /// are any
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to TargetContents
+    /// `file` a Path that corresponds to the incoming file
+    /// `size` the size of `file` in bytes
+    ///
+    /// # Errors
+    ///
+    /// - hash_files() or hash_file() call fails
+    pub fn find(&mut self, file: &Path, size: u64) -> io::Result<Option<&Path>> {
+        let Some(candidates) = self.by_size.get(&size) else {
+            return Ok(None);
+        };
+        let by_digest = match self.by_digest.entry(size) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                // of several target files with the same contents the smallest path is reported, so repeated runs agree
+                let mut by_digest: HashMap<[u8; 32], PathBuf> = HashMap::new();
+                for (path, digest) in hash_files(
+                    candidates.iter(),
+                    threads(),
+                    DigestAlgorithm::Sha256,
+                    false,
+                    &ProgressCounter::default(),
+                )? {
+                    match by_digest.get_mut(&digest) {
+                        Some(kept) if *kept <= path => {}
+                        Some(kept) => *kept = path,
+                        None => {
+                            by_digest.insert(digest, path);
+                        }
+                    }
+                }
+                entry.insert(by_digest)
+            }
+        };
+        let digest = hash_file(file).path_context(file)?;
+
+        Ok(by_digest
+            .get(&digest)
+            .map(PathBuf::as_path)
+            .filter(|original| *original != file))
+    }
+}
@@ -0,0 +1,1245 @@
+// declare cargo crates
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::Metadata;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use time::OffsetDateTime;
+
+// declare local code
+use crate::tools::compound_ext::CompoundExtensions;
+use crate::tools::dates::{date_folder, file_date, DateSource};
+use crate::tools::exif::camera_model;
+#[cfg(unix)]
+use crate::tools::owner::owner_name;
+use crate::tools::parse_size::{format_size, parse_size};
+use crate::tools::regex::Regex;
+use crate::tools::sanitize_filename::sanitize_filename;
+use crate::tools::timezone::TimeZone;
+
+// declare local code
+use super::template::DateTemplate;
+
+/// name of the folder that files without an extension are grouped into by GroupBy::Extension unless another is given
+const NO_EXTENSION: &str = "no_extension";
+
+/// name of the folder that files without a camera in their EXIF data are grouped into by GroupBy::Camera
+const UNKNOWN_CAMERA: &str = "unknown_camera";
+
+/// name of the folder that files whose name does not match the pattern of GroupBy::Regex are grouped into
+const UNGROUPED: &str = "ungrouped";
+
+/// name of the folder that files whose name starts with a digit are grouped into by GroupBy::FirstLetter
+const FIRST_DIGIT: &str = "0-9";
+
+/// name of the folder that files whose name starts with neither a letter nor a digit are grouped into by
+/// GroupBy::FirstLetter
+const FIRST_OTHER: &str = "_";
+
+/// lowercased extensions folded into the folder of another by default once aliasing is enabled, see ExtAliases
+const BUILTIN_ALIASES: &[(&str, &str)] = &[
+    ("jpeg", "jpg"),
+    ("jpe", "jpg"),
+    ("tif", "tiff"),
+    ("htm", "html"),
+    ("mpeg", "mpg"),
+    ("yml", "yaml"),
+    ("markdown", "md"),
+];
+
+/// GroupBy enum: a criterion that contributes one or more path segments to the folder a file is organized into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    /// `YYYY/YYYY-MM` folders from the date of the file, or the folders of a DateTemplate
+    Date,
+
+    /// a folder named after the lowercased extension of the file, which may be a compound extension such as `tar.gz`,
+    /// after any ExtAliases are applied, files without an extension share a folder named after GroupOptions::no_ext_label
+    Extension,
+
+    /// a folder named after the range of sizes the file falls in, see SizeBuckets
+    Size,
+
+    /// a folder named after the user owning the file, or their numeric id when the name cannot be resolved
+    #[cfg(unix)]
+    Owner,
+
+    /// a folder named after the camera make and model in the EXIF data of the file, see camera_model()
+    Camera,
+
+    /// a folder named after the text captured from the file name by GroupOptions::group_regex, see regex_segment()
+    Regex,
+
+    /// an `A` to `Z` folder named after the first letter of the file name, see first_letter_segment()
+    FirstLetter,
+}
+
+impl GroupBy {
+    /// segment() returns the relative path this criterion contributes for a file
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to GroupBy
+    /// `file` a Path that corresponds to the file being organized
+    /// `date` the date of the file
+    /// `replacement` character used in place of characters that are illegal in file names
+    /// `options` the GroupOptions of the criteria that take them
+    ///
+    /// # Errors
+    ///
+    /// - metadata is not retrievable for `file` when grouping by size or owner
+    /// - `file` cannot be read when grouping by camera
+    /// - regex_segment() call fails when grouping by regex
+    pub fn segment(
+        &self,
+        file: &Path,
+        date: OffsetDateTime,
+        replacement: char,
+        options: &GroupOptions,
+    ) -> io::Result<PathBuf> {
+        Ok(match self {
+            Self::Date => date_segment(date, options),
+            Self::Extension => extension_segment(file, replacement, options),
+            Self::Size => PathBuf::from(options.size_buckets.label(file.metadata()?.len())),
+            #[cfg(unix)]
+            Self::Owner => {
+                use std::os::unix::fs::MetadataExt;
+
+                PathBuf::from(sanitize_filename(
+                    &owner_name(file.metadata()?.uid()),
+                    replacement,
+                ))
+            }
+            Self::Camera => match camera_model(file)? {
+                Some(camera) => PathBuf::from(sanitize_filename(&camera, replacement)),
+                None => PathBuf::from(UNKNOWN_CAMERA),
+            },
+            Self::Regex => regex_segment(file, replacement, options)?,
+            Self::FirstLetter => first_letter_segment(file),
+        })
+    }
+}
+
+/// date_segment() returns the folders GroupBy::Date places a file of the given date in
+///
+/// # Arguments
+///
+/// `date` the date of the file
+/// `options` the GroupOptions holding the DateTemplate, if any
+///
+/// # Errors
+///
+/// None
+fn date_segment(date: OffsetDateTime, options: &GroupOptions) -> PathBuf {
+    match &options.date_template {
+        Some(template) => template.render(date),
+        None => date_folder(date),
+    }
+}
+
+/// extension_segment() returns the folder GroupBy::Extension places `file` in
+///
+/// # Arguments
+///
+/// `file` a Path that corresponds to the file being organized
+/// `replacement` character used in place of characters that are illegal in file names
+/// `options` the GroupOptions holding the extension settings
+///
+/// # Errors
+///
+/// None
+fn extension_segment(file: &Path, replacement: char, options: &GroupOptions) -> PathBuf {
+    match options.compound_exts.extension(file) {
+        // lowercased so `JPG` and `jpg` files share a folder
+        Some(extension) => PathBuf::from(sanitize_filename(
+            options
+                .ext_aliases
+                .resolve(&extension.to_string_lossy().to_lowercase()),
+            replacement,
+        )),
+        None => PathBuf::from(options.no_ext_label.as_deref().unwrap_or(NO_EXTENSION)),
+    }
+}
+
+/// regex_segment() returns the folder GroupBy::Regex places `file` in, the text the pattern captures from the file
+/// name, each capture group contributing a nested folder in order (the whole match for a pattern without groups), and
+/// `ungrouped` when the name does not match or every group captured nothing
+///
+/// # Arguments
+///
+/// `file` a Path that corresponds to the file being organized
+/// `replacement` character used in place of characters that are illegal in file names
+/// `options` the GroupOptions holding the pattern
+///
+/// # Errors
+///
+/// - Regex::captures() call fails
+fn regex_segment(file: &Path, replacement: char, options: &GroupOptions) -> io::Result<PathBuf> {
+    let name = file.file_name().unwrap_or_default().to_string_lossy();
+    let Some(regex) = &options.group_regex else {
+        return Ok(PathBuf::from(UNGROUPED));
+    };
+
+    // group 0 is the whole match, only used when there are no groups
+    let folder: PathBuf = regex
+        .captures(&name)?
+        .unwrap_or_default()
+        .into_iter()
+        .skip(usize::from(regex.groups() > 0))
+        .flatten()
+        .filter(|capture| !capture.is_empty())
+        .map(|capture| sanitize_filename(&capture, replacement))
+        .collect();
+    Ok(if folder.as_os_str().is_empty() {
+        PathBuf::from(UNGROUPED)
+    } else {
+        folder
+    })
+}
+
+/// first_letter_segment() returns the folder GroupBy::FirstLetter places `file` in, the uppercased first character of
+/// its stem when that is a letter from `A` to `Z`, `0-9` when it is a digit, and `_` for anything else, including
+/// letters outside of ASCII and the leading dot of hidden files
+///
+/// # Arguments
+///
+/// `file` a Path that corresponds to the file being organized
+///
+/// # Errors
+///
+/// None
+fn first_letter_segment(file: &Path) -> PathBuf {
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+
+    PathBuf::from(match stem.chars().next() {
+        Some(first) if first.is_ascii_alphabetic() => first.to_ascii_uppercase().to_string(),
+        Some(first) if first.is_ascii_digit() => String::from(FIRST_DIGIT),
+        _ => String::from(FIRST_OTHER),
+    })
+}
+
+/// parse_group_regex() parses the value of `--group-regex`, the regular expression GroupBy::Regex matches file names
+/// against, see Regex
+///
+/// # Arguments
+///
+/// `value` the value following `--group-regex`
+///
+/// # Errors
+///
+/// - `value` is missing
+/// - Regex::new() call fails
+pub fn parse_group_regex(value: Option<String>) -> Result<Regex, &'static str> {
+    match value {
+        Some(pattern) => Regex::new(&pattern),
+        None => {
+            Err("'--group-regex' must be followed by a regular expression such as '(PROJ\\d+)_'")
+        }
+    }
+}
+
+/// GroupStrategy trait: decides the folder a file is organized into, for library users that need folders the
+/// built-in criteria cannot express, such as one per project code parsed from the file name. A strategy given to
+/// OrganizeTask::with_strategy() replaces `--group`, while naming, counters, and everything else work as usual
+pub trait GroupStrategy {
+    /// target_subdir() returns the folder `path` is organized into, relative to the target directory, made of plain
+    /// folder names only, see checked_subdir()
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to the GroupStrategy
+    /// `path` a Path that corresponds to the file being organized
+    /// `meta` the Metadata of the file
+    ///
+    /// # Errors
+    ///
+    /// - the folder cannot be determined, the file is recorded as failed like any other per-file error
+    fn target_subdir(&self, path: &Path, meta: &Metadata) -> io::Result<PathBuf>;
+}
+
+/// checked_subdir() returns `subdir`, the folder a GroupStrategy placed a file in, if it lies below the target
+/// directory, so a strategy cannot organize files into an absolute path or step outside of the target with `..`
+///
+/// # Arguments
+///
+/// `subdir` the PathBuf returned by GroupStrategy::target_subdir()
+///
+/// # Errors
+///
+/// - `subdir` has a component other than a plain folder name, such as a root, a prefix, `.`, or `..`
+pub fn checked_subdir(subdir: PathBuf) -> io::Result<PathBuf> {
+    if subdir
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        Ok(subdir)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "group strategy returned {}, which is not a relative path below the target",
+                subdir.display()
+            ),
+        ))
+    }
+}
+
+/// CustomStrategy struct: a boxed GroupStrategy held by OrganizeTask, compared by identity since strategies need not
+/// be comparable
+pub struct CustomStrategy(pub Box<dyn GroupStrategy>);
+
+impl fmt::Debug for CustomStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CustomStrategy")
+    }
+}
+
+impl PartialEq for CustomStrategy {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::addr_eq(&*self.0, &*other.0)
+    }
+}
+
+impl Eq for CustomStrategy {}
+
+/// DateStrategy struct: the built-in date GroupStrategy, placing files in the `YYYY/YYYY-MM` folders of GroupBy::Date by
+/// their creation date in UTC unless configured otherwise
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DateStrategy {
+    /// which date of a file decides its folder
+    date: DateSource,
+
+    /// time zone dates are converted to before the folder is derived
+    timezone: TimeZone,
+
+    /// settings passed on to GroupBy::segment()
+    options: GroupOptions,
+}
+
+impl DateStrategy {
+    /// DateStrategy struct initializer for folders derived from `date` in UTC
+    ///
+    /// # Arguments
+    ///
+    /// `date` which date of a file decides its folder
+    pub fn new(date: DateSource) -> Self {
+        Self {
+            date,
+            ..Self::default()
+        }
+    }
+
+    /// local_time() returns the DateStrategy with dates converted to the local time zone of the machine, as with
+    /// `--timezone local`
+    ///
+    /// # Arguments
+    ///
+    /// `self` the DateStrategy
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn local_time(self) -> Self {
+        Self {
+            timezone: TimeZone::Local,
+            ..self
+        }
+    }
+}
+
+impl GroupStrategy for DateStrategy {
+    fn target_subdir(&self, path: &Path, _meta: &Metadata) -> io::Result<PathBuf> {
+        let date = self.timezone.convert(file_date(path, &self.date)?);
+        GroupBy::Date.segment(path, date, '_', &self.options)
+    }
+}
+
+/// ExtensionStrategy struct: the built-in extension GroupStrategy, placing files in the folder of GroupBy::Extension
+/// named after their lowercased extension and files without one in `no_extension`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExtensionStrategy {
+    /// settings passed on to GroupBy::segment()
+    options: GroupOptions,
+}
+
+impl ExtensionStrategy {
+    /// ExtensionStrategy struct initializer for one folder per lowercased extension
+    ///
+    /// # Arguments
+    ///
+    /// None
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// case_insensitive() returns the ExtensionStrategy with common spellings of the same type folded into one
+    /// folder, as with `--case-insensitive-ext`
+    ///
+    /// # Arguments
+    ///
+    /// `self` the ExtensionStrategy
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn case_insensitive(mut self) -> Self {
+        self.options.ext_aliases.enable();
+        self
+    }
+
+    /// alias() returns the ExtensionStrategy with files of extension `from` grouped into the folder `to`, as with
+    /// `--ext-alias FROM=TO`, which implies case_insensitive()
+    ///
+    /// # Arguments
+    ///
+    /// `self` the ExtensionStrategy
+    /// `from` the extension to alias
+    /// `to` the folder files with that extension are grouped into
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn alias(mut self, from: &str, to: &str) -> Self {
+        self.options.ext_aliases.insert(from, to);
+        self
+    }
+}
+
+impl GroupStrategy for ExtensionStrategy {
+    fn target_subdir(&self, path: &Path, _meta: &Metadata) -> io::Result<PathBuf> {
+        // the date is only used by GroupBy::Date
+        let date = OffsetDateTime::UNIX_EPOCH;
+        GroupBy::Extension.segment(path, date, '_', &self.options)
+    }
+}
+
+/// SizeStrategy struct: the built-in size GroupStrategy, placing files in the folder of GroupBy::Size named after the
+/// range of sizes they fall in, see SizeBuckets
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SizeStrategy {
+    /// settings passed on to GroupBy::segment()
+    options: GroupOptions,
+}
+
+impl SizeStrategy {
+    /// SizeStrategy struct initializer for ranges split at `boundaries` bytes, as with `--size-buckets`
+    ///
+    /// # Arguments
+    ///
+    /// `boundaries` the sizes in bytes the ranges are split at
+    ///
+    /// # Errors
+    ///
+    /// - `boundaries` is empty, contains zero, or is not strictly increasing
+    pub fn new(boundaries: Vec<u64>) -> Result<Self, &'static str> {
+        if boundaries.first().is_none_or(|&first| first == 0)
+            || boundaries.windows(2).any(|pair| pair[0] >= pair[1])
+        {
+            return Err("size boundaries must be positive and strictly increasing");
+        }
+
+        Ok(Self {
+            options: GroupOptions {
+                size_buckets: SizeBuckets(boundaries),
+                ..GroupOptions::default()
+            },
+        })
+    }
+}
+
+impl GroupStrategy for SizeStrategy {
+    fn target_subdir(&self, path: &Path, _meta: &Metadata) -> io::Result<PathBuf> {
+        // the date is only used by GroupBy::Date
+        let date = OffsetDateTime::UNIX_EPOCH;
+        GroupBy::Size.segment(path, date, '_', &self.options)
+    }
+}
+
+/// GroupOptions struct: settings of the grouping criteria that take any, shared by every criterion of a grouping
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GroupOptions {
+    /// aliases folding extensions into the folder of another, used by GroupBy::Extension
+    pub ext_aliases: ExtAliases,
+
+    /// boundaries of the size ranges, used by GroupBy::Size
+    pub size_buckets: SizeBuckets,
+
+    /// multi-part extensions treated as one by GroupBy::Extension, and kept whole at the end of organized file names
+    pub compound_exts: CompoundExtensions,
+
+    /// folders used by GroupBy::Date in place of `YYYY/YYYY-MM`, set by `--date-template` or `--granularity`, None for
+    /// the default
+    pub date_template: Option<DateTemplate>,
+
+    /// name of the folder GroupBy::Extension places files without an extension in, set by `--no-ext-label`, None for
+    /// `no_extension`
+    pub no_ext_label: Option<String>,
+
+    /// pattern GroupBy::Regex captures folder names from file names with, set by `--group-regex`
+    pub group_regex: Option<Regex>,
+}
+
+/// SizeBuckets struct: ascending boundaries splitting file sizes into ranges, each range is labelled by the sizes it
+/// spans such as `1M-100M`, a file exactly on a boundary belongs to the range starting there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeBuckets(Vec<u64>);
+
+impl Default for SizeBuckets {
+    /// default() splits sizes at 1 MiB, 100 MiB, and 1 GiB
+    ///
+    /// # Arguments
+    ///
+    /// None
+    fn default() -> Self {
+        Self(vec![1 << 20, 100 << 20, 1 << 30])
+    }
+}
+
+impl SizeBuckets {
+    /// label() returns the name of the folder files of `len` bytes are grouped into, `0-FIRST` below the first
+    /// boundary, `LOWER-UPPER` between two boundaries, and `LAST+` from the last boundary on
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to SizeBuckets
+    /// `len` size of the file in bytes
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn label(&self, len: u64) -> String {
+        // number of boundaries at or below `len`
+        let index = self.0.partition_point(|&boundary| boundary <= len);
+
+        match (
+            index.checked_sub(1).map(|lower| self.0[lower]),
+            self.0.get(index),
+        ) {
+            (None, Some(&upper)) => format!("0-{}", format_size(upper)),
+            (Some(lower), Some(&upper)) => format!("{}-{}", format_size(lower), format_size(upper)),
+            (Some(lower), None) => format!("{}+", format_size(lower)),
+            // there is always at least one boundary
+            (None, None) => String::from("0+"),
+        }
+    }
+}
+
+/// parse_size_buckets() parses the value of `--size-buckets`, a comma separated list of strictly increasing positive
+/// sizes in any form accepted by parse_size(), such as `1MB,100MB,1GB`
+///
+/// # Arguments
+///
+/// `value` the value following `--size-buckets`
+///
+/// # Errors
+///
+/// - `value` is missing or empty, contains a size that is not valid or is zero, or is not strictly increasing
+pub fn parse_size_buckets(value: Option<String>) -> Result<SizeBuckets, &'static str> {
+    const ERROR: &str =
+        "'--size-buckets' must be followed by a comma separated list of increasing sizes, such as 1MB,100MB,1GB";
+
+    let mut boundaries: Vec<u64> = Vec::new();
+    for size in value.ok_or(ERROR)?.split(',') {
+        match parse_size(size) {
+            Some(size) if size > 0 && boundaries.last().is_none_or(|&last| last < size) => {
+                boundaries.push(size)
+            }
+            _ => return Err(ERROR),
+        }
+    }
+
+    Ok(SizeBuckets(boundaries))
+}
+
+/// ExtAliases struct: folds extensions into the folder of another when grouping by extension, such as `jpeg` into `jpg`.
+/// Aliasing is off by default, once enabled the built-in table applies and entries given by the user replace or extend
+/// it, every extension is lowercased before it is looked up
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExtAliases {
+    /// whether extensions are aliased at all
+    enabled: bool,
+
+    /// lowercased extensions mapped by the user to the folder they are grouped into, taking precedence over the
+    /// built-in table
+    custom: BTreeMap<String, String>,
+}
+
+impl ExtAliases {
+    /// enable() turns aliasing on with the built-in table
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to ExtAliases
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    /// insert() turns aliasing on and groups files with the lowercased extension `from` into the folder `to`, replacing
+    /// any previous entry for `from`
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to ExtAliases
+    /// `from` the extension to alias
+    /// `to` the folder files with that extension are grouped into
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn insert(&mut self, from: &str, to: &str) {
+        self.enabled = true;
+        self.custom.insert(from.to_lowercase(), to.to_string());
+    }
+
+    /// resolve() returns the folder name files with the lowercased `extension` are grouped into, the extension itself
+    /// when it has no alias
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to ExtAliases
+    /// `extension` a lowercased extension
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn resolve<'a>(&'a self, extension: &'a str) -> &'a str {
+        if !self.enabled {
+            return extension;
+        }
+
+        match self.custom.get(extension) {
+            Some(to) => to,
+            None => BUILTIN_ALIASES
+                .iter()
+                .find(|(from, _)| *from == extension)
+                .map_or(extension, |(_, to)| to),
+        }
+    }
+}
+
+/// parse_ext_alias() parses the value of `--ext-alias`, an extension and the folder it is grouped into separated by
+/// `=` such as `jpeg=jpg`, a leading `.` on either side is ignored
+///
+/// # Arguments
+///
+/// `value` the value following `--ext-alias`
+///
+/// # Errors
+///
+/// - `value` is missing, has no `=`, or either side is empty or not legal in file names
+pub fn parse_ext_alias(value: Option<String>) -> Result<(String, String), &'static str> {
+    const ERROR: &str =
+        "'--ext-alias' must be followed by an extension and the folder it is grouped into, such as jpeg=jpg";
+
+    let value = value.ok_or(ERROR)?;
+    let (from, to) = value.split_once('=').ok_or(ERROR)?;
+    let (from, to) = (
+        from.trim_start_matches('.').to_lowercase(),
+        to.trim_start_matches('.').to_lowercase(),
+    );
+
+    // both sides name a folder so both must be usable as one
+    for side in [&from, &to] {
+        if side.is_empty() || sanitize_filename(side, '_') != *side {
+            return Err(ERROR);
+        }
+    }
+
+    Ok((from, to))
+}
+
+/// folder_for() joins the segments contributed by every criterion of `grouping` in order onto `root`
+///
+/// # Arguments
+///
+/// `grouping` the criteria in the order their segments are nested
+/// `root` a Path that corresponds to the directory the folders are placed in
+/// `file` a Path that corresponds to the file being organized
+/// `date` the date of the file
+/// `replacement` character used in place of characters that are illegal in file names
+/// `options` the GroupOptions of the criteria that take them
+///
+/// # Errors
+///
+/// - GroupBy::segment() call fails
+pub fn folder_for(
+    grouping: &[GroupBy],
+    root: &Path,
+    file: &Path,
+    date: OffsetDateTime,
+    replacement: char,
+    options: &GroupOptions,
+) -> io::Result<PathBuf> {
+    let mut folder = root.to_path_buf();
+    for group in grouping {
+        folder.push(group.segment(file, date, replacement, options)?);
+    }
+
+    Ok(folder)
+}
+
+/// parse_grouping() parses the value of `--group`, a comma separated list of `date`, `extension`, `size`, `camera`,
+/// `regex`, `first-letter`, and on Unix `owner` in the order their folders are nested, each criterion may appear once
+///
+/// # Arguments
+///
+/// `value` the value following `--group`
+///
+/// # Errors
+///
+/// - `value` is missing, empty, names an unknown criterion, or repeats one
+/// - `value` names `owner` on a platform other than Unix
+pub fn parse_grouping(value: Option<String>) -> Result<Vec<GroupBy>, &'static str> {
+    const ERROR: &str =
+        "'--group' must be followed by a comma separated list of 'date', 'extension', 'size', 'camera', 'regex', 'first-letter', and 'owner', each at most once, such as date,extension";
+
+    let value = value.ok_or(ERROR)?;
+    let mut grouping: Vec<GroupBy> = Vec::new();
+
+    for name in value.split(',') {
+        let group = match name {
+            "date" => GroupBy::Date,
+            "extension" => GroupBy::Extension,
+            "size" => GroupBy::Size,
+            "camera" => GroupBy::Camera,
+            "regex" => GroupBy::Regex,
+            "first-letter" => GroupBy::FirstLetter,
+            #[cfg(unix)]
+            "owner" => GroupBy::Owner,
+            #[cfg(not(unix))]
+            "owner" => return Err("'--group owner' is only supported on Unix"),
+            _ => return Err(ERROR),
+        };
+        if grouping.contains(&group) {
+            return Err(ERROR);
+        }
+        grouping.push(group);
+    }
+
+    Ok(grouping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+    use time::macros::datetime;
+
+    /// verifies parse_grouping() keeps the given order and folder_for() nests segments in that order
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_grouping() accepts an unknown, empty, or repeated criterion
+    /// - folder_for() does not join segments in order or does not lowercase extensions
+    #[test]
+    fn grouping_folder_for() {
+        // create inputs
+        let date = datetime!(2023-01-15 12:00 UTC);
+        let root = Path::new("target");
+        let parse = |value: &str| parse_grouping(Some(value.to_string()));
+
+        assert_eq!(parse("date"), Ok(vec![GroupBy::Date]));
+        assert_eq!(
+            parse("extension,date"),
+            Ok(vec![GroupBy::Extension, GroupBy::Date])
+        );
+        for value in ["", "date,", "colour", "date,date"] {
+            assert!(parse(value).is_err());
+        }
+        assert!(parse_grouping(None).is_err());
+
+        let composed = [GroupBy::Date, GroupBy::Extension];
+        assert_eq!(
+            folder_for(
+                &composed,
+                root,
+                Path::new("IMG_1.JPG"),
+                date,
+                '_',
+                &GroupOptions::default()
+            )
+            .unwrap(),
+            PathBuf::from("target/2023/2023-01/jpg")
+        );
+        assert_eq!(
+            folder_for(
+                &[GroupBy::Extension],
+                root,
+                Path::new("notes"),
+                date,
+                '_',
+                &GroupOptions::default()
+            )
+            .unwrap(),
+            PathBuf::from("target/no_extension")
+        );
+        assert_eq!(
+            folder_for(
+                &[GroupBy::Extension],
+                root,
+                Path::new("notes"),
+                date,
+                '_',
+                &GroupOptions {
+                    no_ext_label: Some(String::from("plain")),
+                    ..GroupOptions::default()
+                }
+            )
+            .unwrap(),
+            PathBuf::from("target/plain")
+        );
+    }
+
+    /// verifies grouping by first letter files names starting with a letter under that letter uppercased, with a digit
+    /// under `0-9`, and with anything else under `_`, nested with the other criteria in order
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - folder_for() files a name under another bucket than its first character belongs to
+    /// - parse_grouping() does not accept `first-letter`
+    #[test]
+    fn grouping_first_letter() {
+        // create inputs
+        let date = datetime!(2023-01-15 12:00 UTC);
+        let folder = |grouping: &[GroupBy], name: &str| {
+            folder_for(
+                grouping,
+                Path::new("target"),
+                &Path::new("source").join(name),
+                date,
+                '_',
+                &GroupOptions::default(),
+            )
+            .unwrap()
+        };
+
+        for (name, expected) in [
+            ("invoice.pdf", "target/I"),
+            ("Report.docx", "target/R"),
+            ("2023 taxes.pdf", "target/0-9"),
+            ("_draft.txt", "target/_"),
+            ("#notes", "target/_"),
+            ("élan.txt", "target/_"),
+            (".profile", "target/_"),
+        ] {
+            assert_eq!(
+                folder(&[GroupBy::FirstLetter], name),
+                PathBuf::from(expected),
+                "{name}"
+            );
+        }
+        assert_eq!(
+            folder(&[GroupBy::FirstLetter, GroupBy::Date], "scan.jpg"),
+            PathBuf::from("target/S/2023/2023-01")
+        );
+        assert_eq!(
+            parse_grouping(Some(String::from("first-letter,date"))),
+            Ok(vec![GroupBy::FirstLetter, GroupBy::Date])
+        );
+    }
+
+    /// verifies grouping by extension places `archive.tar.gz` in a `tar.gz` folder once compound extensions are
+    /// enabled, and in a `gz` folder otherwise
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - folder_for() does not group by the compound extension once enabled, or does so while disabled
+    #[test]
+    fn grouping_compound_extension() {
+        // create inputs
+        let date = datetime!(2023-01-15 12:00 UTC);
+        let mut options = GroupOptions::default();
+        let folder = |options: &GroupOptions, name: &str| {
+            folder_for(
+                &[GroupBy::Extension],
+                Path::new("target"),
+                Path::new(name),
+                date,
+                '_',
+                options,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            folder(&options, "archive.tar.gz"),
+            PathBuf::from("target/gz")
+        );
+        options.compound_exts.enable();
+        assert_eq!(
+            folder(&options, "archive.TAR.GZ"),
+            PathBuf::from("target/tar.gz")
+        );
+        assert_eq!(folder(&options, "notes.tar"), PathBuf::from("target/tar"));
+    }
+
+    /// verifies ExtAliases folds extensions through the built-in table once enabled and that user entries override it
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - ExtAliases aliases extensions while disabled
+    /// - ExtAliases does not fold `JPEG` into `jpg` by default once enabled
+    /// - a user entry does not replace the built-in alias or add a new one
+    /// - parse_ext_alias() accepts a malformed alias
+    #[test]
+    fn grouping_ext_aliases() {
+        // create inputs
+        let date = datetime!(2023-01-15 12:00 UTC);
+        let root = Path::new("target");
+        let mut aliases = ExtAliases::default();
+        let folder = |aliases: &ExtAliases, name: &str| {
+            let options = GroupOptions {
+                ext_aliases: aliases.clone(),
+                ..GroupOptions::default()
+            };
+            folder_for(
+                &[GroupBy::Extension],
+                root,
+                Path::new(name),
+                date,
+                '_',
+                &options,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(folder(&aliases, "a.JPEG"), PathBuf::from("target/jpeg"));
+
+        aliases.enable();
+        for name in ["a.JPG", "b.jpeg", "c.Jpe"] {
+            assert_eq!(folder(&aliases, name), PathBuf::from("target/jpg"));
+        }
+        assert_eq!(folder(&aliases, "d.png"), PathBuf::from("target/png"));
+
+        let (from, to) = parse_ext_alias(Some(String::from(".JPEG=photo"))).unwrap();
+        aliases.insert(&from, &to);
+        let (from, to) = parse_ext_alias(Some(String::from("heic=jpg"))).unwrap();
+        aliases.insert(&from, &to);
+        assert_eq!(folder(&aliases, "b.jpeg"), PathBuf::from("target/photo"));
+        assert_eq!(folder(&aliases, "e.HEIC"), PathBuf::from("target/jpg"));
+        assert_eq!(folder(&aliases, "c.jpe"), PathBuf::from("target/jpg"));
+
+        for value in ["jpeg", "=jpg", "jpeg=", "jpeg=a/b"] {
+            assert!(parse_ext_alias(Some(value.to_string())).is_err());
+        }
+        assert!(parse_ext_alias(None).is_err());
+    }
+
+    /// verifies GroupBy::Size places files in the range they fall in, files exactly on a boundary starting the next range
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - folder_for() places a file below, on, or above a boundary in the wrong range
+    /// - SizeBuckets::label() does not label the default ranges as expected
+    /// - parse_size_buckets() accepts an empty, zero, malformed, or non-increasing list
+    #[test]
+    fn grouping_size_buckets() {
+        // create inputs
+        let date = datetime!(2023-01-15 12:00 UTC);
+        let path_buf = PathBuf::from("./grouping_size_buckets_test");
+        let options = GroupOptions {
+            size_buckets: parse_size_buckets(Some(String::from("1KB,4K"))).unwrap(),
+            ..GroupOptions::default()
+        };
+
+        // create mock directory with files just below, exactly on, and above each boundary
+        create_dir_all(&path_buf).unwrap();
+        let sizes = [0, 1023, 1024, 4095, 4096, 10_000];
+        for size in sizes {
+            write(path_buf.join(size.to_string()), vec![0u8; size]).unwrap();
+        }
+
+        // run test
+        let test_result: Vec<PathBuf> = sizes
+            .iter()
+            .map(|size| {
+                folder_for(
+                    &[GroupBy::Size],
+                    Path::new("target"),
+                    &path_buf.join(size.to_string()),
+                    date,
+                    '_',
+                    &options,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        let expected: Vec<PathBuf> = ["0-1K", "0-1K", "1K-4K", "1K-4K", "4K+", "4K+"]
+            .iter()
+            .map(|label| Path::new("target").join(label))
+            .collect();
+        assert_eq!(test_result, expected);
+
+        let buckets = SizeBuckets::default();
+        assert_eq!(buckets.label(1 << 20), "1M-100M");
+        assert_eq!(buckets.label((1 << 30) - 1), "100M-1G");
+        assert_eq!(buckets.label(5 << 30), "1G+");
+        assert_eq!(
+            parse_grouping(Some(String::from("size"))),
+            Ok(vec![GroupBy::Size])
+        );
+        for value in ["", "0,1M", "1M,1M", "100M,1M", "1M,", "1.5M"] {
+            assert!(parse_size_buckets(Some(value.to_string())).is_err());
+        }
+        assert!(parse_size_buckets(None).is_err());
+    }
+
+    /// verifies the built-in strategies place files in the same folders as the criteria they stand for
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - DateStrategy, ExtensionStrategy, or SizeStrategy return another folder than GroupBy::segment()
+    #[test]
+    fn grouping_strategies() {
+        // create inputs
+        let path_buf = PathBuf::from("./grouping_strategies_test");
+        let file = path_buf.join("photo.JPG");
+
+        // create mock directory, the file is stamped 2001-09 and falls in the smallest size range
+        create_dir_all(&path_buf).unwrap();
+        write(&file, "contents").unwrap();
+        let meta = file.metadata().unwrap();
+        let date = file_date(&file, &DateSource::Created).unwrap();
+
+        // run test
+        let test_result: Vec<PathBuf> = [
+            &DateStrategy::default() as &dyn GroupStrategy,
+            &ExtensionStrategy::default(),
+            &SizeStrategy::default(),
+        ]
+        .iter()
+        .map(|strategy| strategy.target_subdir(&file, &meta).unwrap())
+        .collect();
+        let expected: Vec<PathBuf> = [GroupBy::Date, GroupBy::Extension, GroupBy::Size]
+            .iter()
+            .map(|group| {
+                group
+                    .segment(&file, date, '_', &GroupOptions::default())
+                    .unwrap()
+            })
+            .collect();
+        let configured: Vec<PathBuf> = [
+            &ExtensionStrategy::new().alias("jpg", "photos") as &dyn GroupStrategy,
+            &SizeStrategy::new(vec![4, 1 << 20]).unwrap(),
+        ]
+        .iter()
+        .map(|strategy| strategy.target_subdir(&file, &meta).unwrap())
+        .collect();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(test_result, expected);
+        assert_eq!(test_result[1], PathBuf::from("jpg"));
+        assert_eq!(test_result[2], PathBuf::from("0-1M"));
+        assert_eq!(configured, [PathBuf::from("photos"), PathBuf::from("4-1M")]);
+        for boundaries in [vec![], vec![0, 4], vec![4, 4]] {
+            assert!(SizeStrategy::new(boundaries).is_err());
+        }
+    }
+
+    /// verifies GroupBy::Regex places files in folders named after the text captured from their name, one nested folder
+    /// per capture group, and files whose name does not match in `ungrouped`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - folder_for() does not use the captured text, or the whole match for a pattern without groups
+    /// - folder_for() does not nest multiple captures or does not fall back to `ungrouped`
+    /// - parse_group_regex() accepts a malformed pattern
+    #[test]
+    fn grouping_regex() {
+        let date = datetime!(2023-01-15 12:00 UTC);
+        let folder = |pattern: &str, name: &str| {
+            let options = GroupOptions {
+                group_regex: Some(parse_group_regex(Some(pattern.to_string())).unwrap()),
+                ..GroupOptions::default()
+            };
+            folder_for(
+                &[GroupBy::Regex],
+                Path::new("target"),
+                &Path::new("source").join(name),
+                date,
+                '_',
+                &options,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            folder(r"^(PROJ\d+)_", "PROJ1234_whatever.pdf"),
+            Path::new("target/PROJ1234")
+        );
+        assert_eq!(
+            folder(r"PROJ\d+", "notes PROJ77.txt"),
+            Path::new("target/PROJ77")
+        );
+        assert_eq!(
+            folder(r"^(PROJ\d+)_", "invoice.pdf"),
+            Path::new("target/ungrouped")
+        );
+        assert_eq!(
+            folder(r"^([A-Z]+)-(\d{4})", "ACME-2023-report.pdf"),
+            Path::new("target/ACME/2023")
+        );
+        assert_eq!(
+            folder(r"^(x)?(y)?", "file.txt"),
+            Path::new("target/ungrouped")
+        );
+        assert_eq!(
+            parse_grouping(Some(String::from("regex,date"))),
+            Ok(vec![GroupBy::Regex, GroupBy::Date])
+        );
+        assert!(parse_group_regex(Some(String::from("(PROJ\\d+"))).is_err());
+        assert!(parse_group_regex(None).is_err());
+    }
+
+    /// verifies GroupBy::Owner places a file owned by the current user in a folder named after them
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_grouping() does not accept `owner`
+    /// - folder_for() does not name the folder after the owner of the file
+    /// - folder_for() does not error on a missing file
+    #[cfg(unix)]
+    #[test]
+    fn grouping_owner() {
+        use std::fs::{remove_file, File};
+        use std::os::unix::fs::MetadataExt;
+
+        // create inputs
+        let date = datetime!(2023-01-15 12:00 UTC);
+        let path = PathBuf::from("./grouping_owner_test.txt");
+        File::create(&path).unwrap();
+
+        // run test
+        let grouping = parse_grouping(Some(String::from("owner,date"))).unwrap();
+        let test_result = folder_for(
+            &grouping,
+            Path::new("target"),
+            &path,
+            date,
+            '_',
+            &GroupOptions::default(),
+        );
+        let uid = path.metadata().unwrap().uid();
+
+        // clean up mock file
+        remove_file(&path).unwrap();
+
+        assert_eq!(
+            test_result.unwrap(),
+            Path::new("target")
+                .join(owner_name(uid))
+                .join("2023/2023-01")
+        );
+        assert!(folder_for(
+            &grouping,
+            Path::new("target"),
+            &path,
+            date,
+            '_',
+            &GroupOptions::default()
+        )
+        .is_err());
+        if uid == 0 {
+            assert_eq!(owner_name(uid), "root");
+        }
+    }
+
+    /// verifies GroupBy::Camera places a JPEG in a folder named after the camera in its EXIF data, ahead of its date
+    /// folders, and files without a camera in an `unknown_camera` folder
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_grouping() does not accept `camera`
+    /// - folder_for() does not name the folder after the sanitized make and model
+    /// - folder_for() does not fall back to `unknown_camera` for a file without EXIF data
+    #[test]
+    fn grouping_camera() {
+        use crate::tools::exif::sample_jpeg;
+
+        // create inputs
+        let date = datetime!(2023-01-15 12:00 UTC);
+        let path_buf = PathBuf::from("./grouping_camera_test");
+
+        // create mock directory
+        create_dir_all(&path_buf).unwrap();
+        write(
+            path_buf.join("a.jpg"),
+            sample_jpeg(Some("FUJIFILM"), Some("X100V/2"), true),
+        )
+        .unwrap();
+        write(path_buf.join("notes.txt"), "no exif").unwrap();
+
+        // run test
+        let grouping = parse_grouping(Some(String::from("camera,date"))).unwrap();
+        let folder = |name: &str| {
+            folder_for(
+                &grouping,
+                Path::new("target"),
+                &path_buf.join(name),
+                date,
+                '_',
+                &GroupOptions::default(),
+            )
+        };
+        let test_result = [folder("a.jpg"), folder("notes.txt")];
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        let [camera, unknown] = test_result;
+        assert_eq!(
+            camera.unwrap(),
+            PathBuf::from("target/FUJIFILM_X100V_2/2023/2023-01")
+        );
+        assert_eq!(
+            unknown.unwrap(),
+            PathBuf::from("target/unknown_camera/2023/2023-01")
+        );
+    }
+}
@@ -0,0 +1,84 @@
+// declare cargo crates
+use std::fs::{read_to_string, rename, write};
+use std::io;
+use std::path::Path;
+use time::OffsetDateTime;
+
+/// name of the file inside the target directory that holds the start time of the last successful `--since-last-run`
+/// organize, hidden so it is not mistaken for an organized file
+pub const STATE_FILE: &str = ".fileman_rs_last_run";
+
+/// read_last_run() returns the start time of the last successful run recorded in `target`, or None when no run has been
+/// recorded yet or the state file cannot be read or parsed, in which case every file is organized
+///
+/// # Arguments
+///
+/// `target` a Path that corresponds to the target directory
+///
+/// # Errors
+///
+/// None
+pub fn read_last_run(target: &Path) -> Option<OffsetDateTime> {
+    let text = read_to_string(target.join(STATE_FILE)).ok()?;
+    let nanos = text.trim().parse::<i128>().ok()?;
+
+    OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()
+}
+
+/// write_last_run() records `started` as the start time of the last successful run in `target`, the state is written to
+/// a temporary file first and renamed into place so an interrupted write never leaves a truncated state behind
+///
+/// # Arguments
+///
+/// `target` a Path that corresponds to the target directory
+/// `started` the time the run started
+///
+/// # Errors
+///
+/// - the state file cannot be written or renamed into place
+pub fn write_last_run(target: &Path, started: OffsetDateTime) -> io::Result<()> {
+    let temporary = target.join(format!("{STATE_FILE}.tmp"));
+    write(&temporary, started.unix_timestamp_nanos().to_string())?;
+
+    rename(temporary, target.join(STATE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all};
+    use std::path::PathBuf;
+    use time::macros::datetime;
+
+    /// verifies write_last_run() and read_last_run() round trip a time and that a missing or corrupt state reads as None
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - read_last_run() does not return the time written by write_last_run()
+    /// - read_last_run() does not treat a missing or corrupt state as no previous run
+    #[test]
+    fn last_run_round_trip() {
+        // create inputs
+        let path_buf = PathBuf::from("./last_run_round_trip_test");
+        let started = datetime!(2023-01-15 12:00:00.123456789 UTC);
+
+        // create mock directory
+        create_dir_all(&path_buf).unwrap();
+
+        // run test
+        let missing = read_last_run(&path_buf);
+        write_last_run(&path_buf, started).unwrap();
+        let recorded = read_last_run(&path_buf);
+        write(path_buf.join(STATE_FILE), "not a time").unwrap();
+        let corrupt = read_last_run(&path_buf);
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!((missing, recorded, corrupt), (None, Some(started), None));
+    }
+}
@@ -0,0 +1,23 @@
+/// LinkMode enum: how organize places files in the target when it links them rather than moving them, leaving every
+/// source file where it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkMode {
+    /// a hard link sharing the inode of the source file, copied instead when the target is on another file system
+    Hard,
+}
+
+/// parse_link_mode() parses the value of `--link`, `hard`
+///
+/// # Arguments
+///
+/// `value` the value following `--link`
+///
+/// # Errors
+///
+/// - `value` is not `hard`
+pub fn parse_link_mode(value: Option<String>) -> Result<LinkMode, &'static str> {
+    match value.as_deref() {
+        Some("hard") => Ok(LinkMode::Hard),
+        _ => Err("'--link' must be followed by 'hard'"),
+    }
+}
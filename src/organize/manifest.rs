@@ -0,0 +1,172 @@
+// declare cargo crates
+use std::fs::write;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// declare local code
+use crate::tools::json::Json;
+
+/// ManifestEntry struct: where a moved file was found, relative to the source directory it came from, and where it
+/// was moved to, enough to rebuild the original directory tree
+#[derive(Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// PathBuf to the source directory the file was found in, empty when it lies outside of every source
+    pub source: PathBuf,
+
+    /// PathBuf of the file relative to &self.source
+    pub original: PathBuf,
+
+    /// PathBuf the file was moved to
+    pub destination: PathBuf,
+}
+
+/// manifest_entries() returns a ManifestEntry for every one of `moves`, the original path of a file is taken relative
+/// to the innermost of `sources` containing it, so files of sources nested in one another keep their own tree
+///
+/// # Arguments
+///
+/// `sources` PathBufs to the source directories of the run
+/// `moves` the original path and destination of every moved file, in the order they were moved
+///
+/// # Errors
+///
+/// None
+pub fn manifest_entries(sources: &[PathBuf], moves: &[(PathBuf, PathBuf)]) -> Vec<ManifestEntry> {
+    let mut sources: Vec<&PathBuf> = sources.iter().collect();
+    sources.sort_by_key(|source| std::cmp::Reverse(source.components().count()));
+
+    moves
+        .iter()
+        .map(|(file, destination)| {
+            let (source, original) = sources
+                .iter()
+                .find_map(|source| {
+                    file.strip_prefix(source)
+                        .ok()
+                        .map(|relative| ((*source).clone(), relative.to_path_buf()))
+                })
+                .unwrap_or_else(|| (PathBuf::new(), file.clone()));
+            ManifestEntry {
+                source,
+                original,
+                destination: destination.clone(),
+            }
+        })
+        .collect()
+}
+
+/// write_manifest() writes `entries` to `path` as CSV with a `source,original,destination` header when `path` ends in
+/// `.csv`, or as a JSON object listing them under `files` otherwise
+///
+/// # Arguments
+///
+/// `path` a Path the manifest is written to
+/// `entries` the ManifestEntry of every moved file
+///
+/// # Errors
+///
+/// - `path` cannot be written
+pub fn write_manifest(path: &Path, entries: &[ManifestEntry]) -> io::Result<()> {
+    let csv = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+
+    let text = if csv {
+        let mut text = String::from("source,original,destination\n");
+        for entry in entries {
+            let fields =
+                [&entry.source, &entry.original, &entry.destination].map(|path| csv_field(path));
+            text.push_str(&fields.join(","));
+            text.push('\n');
+        }
+        text
+    } else {
+        let path = |path: &PathBuf| Json::from(path.display().to_string());
+        let files = entries
+            .iter()
+            .map(|entry| {
+                Json::object([
+                    ("source", path(&entry.source)),
+                    ("original", path(&entry.original)),
+                    ("destination", path(&entry.destination)),
+                ])
+            })
+            .collect();
+        format!("{}\n", Json::object([("files", Json::Array(files))]))
+    };
+
+    write(path, text)
+}
+
+/// csv_field() returns `path` as a CSV field, quoted with any quotes doubled when it contains a comma, quote, or line
+/// break
+///
+/// # Arguments
+///
+/// `path` a Path to write as a field
+///
+/// # Errors
+///
+/// None
+fn csv_field(path: &Path) -> String {
+    let field = path.display().to_string();
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, read_to_string, remove_dir_all};
+
+    /// verifies manifest_entries() takes every original path relative to the innermost source containing it and that
+    /// write_manifest() writes CSV or JSON depending on the extension
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - manifest_entries() records a path relative to the wrong source or not relative at all
+    /// - write_manifest() does not quote a CSV field containing a comma or does not write JSON
+    #[test]
+    fn manifest_write_manifest() {
+        // create inputs
+        let path_buf = PathBuf::from("./manifest_write_manifest_test");
+        let sources = [PathBuf::from("./a"), PathBuf::from("./a/b")];
+        let moves = [
+            (PathBuf::from("./a/x/1.jpg"), PathBuf::from("./t/1.jpg")),
+            (PathBuf::from("./a/b/y,z.jpg"), PathBuf::from("./t/2.jpg")),
+            (PathBuf::from("./c.jpg"), PathBuf::from("./t/3.jpg")),
+        ];
+
+        // create mock directory
+        create_dir_all(&path_buf).unwrap();
+
+        // run test
+        let entries = manifest_entries(&sources, &moves);
+        write_manifest(&path_buf.join("manifest.csv"), &entries).unwrap();
+        write_manifest(&path_buf.join("manifest.json"), &entries[..1]).unwrap();
+        let csv = read_to_string(path_buf.join("manifest.csv"));
+        let json = read_to_string(path_buf.join("manifest.json"));
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(entries[0].original, PathBuf::from("x/1.jpg"));
+        assert_eq!(entries[1].source, PathBuf::from("./a/b"));
+        assert_eq!(entries[2].source, PathBuf::new());
+        assert_eq!(
+            csv.unwrap(),
+            "source,original,destination\n./a,x/1.jpg,./t/1.jpg\n./a/b,\"y,z.jpg\",./t/2.jpg\n,./c.jpg,./t/3.jpg\n"
+        );
+        assert_eq!(
+            json.unwrap(),
+            "{\"files\":[{\"source\":\"./a\",\"original\":\"x/1.jpg\",\"destination\":\"./t/1.jpg\"}]}\n"
+        );
+    }
+}
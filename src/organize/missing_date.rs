@@ -0,0 +1,36 @@
+/// name of the folder of the target, and the label of the file names in it, that MissingDate::UnknownFolder routes
+/// files without a usable date to
+pub const UNKNOWN_DATE: &str = "unknown_date";
+
+/// MissingDate enum: what an organize run does with a file whose date cannot be determined, such as a file system
+/// without creation times or a sidecar without a readable date
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MissingDate {
+    /// the file fails like any other error, which ends the run unless `--keep-going` is given
+    #[default]
+    Fail,
+
+    /// the file is left in place and listed as skipped
+    Skip,
+
+    /// the file is organized into the `unknown_date` folder of the target instead of a date folder
+    UnknownFolder,
+}
+
+/// parse_on_missing_date() parses the value of `--on-missing-date`, one of `fail`, `skip`, or `unknown-folder`
+///
+/// # Arguments
+///
+/// `value` the value following `--on-missing-date`
+///
+/// # Errors
+///
+/// - `value` is not `fail`, `skip`, or `unknown-folder`
+pub fn parse_on_missing_date(value: Option<String>) -> Result<MissingDate, &'static str> {
+    match value.as_deref() {
+        Some("fail") => Ok(MissingDate::Fail),
+        Some("skip") => Ok(MissingDate::Skip),
+        Some("unknown-folder") => Ok(MissingDate::UnknownFolder),
+        _ => Err("'--on-missing-date' must be followed by 'fail', 'skip', or 'unknown-folder'"),
+    }
+}
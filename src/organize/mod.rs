@@ -1,162 +1,5411 @@
 // declare cargo crates
-use std::collections::HashMap;
-use std::ffi::OsString;
-use std::fs::{create_dir_all, rename};
-use std::io;
-use std::path::PathBuf;
-use time::macros::format_description;
+use std::collections::{HashMap, HashSet};
+use std::fs::{read_to_string, write, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::SystemTime;
+use time::OffsetDateTime;
 
 // declare local code
-use super::tools::collect_files::collect_files;
-use super::tools::get_num_files::get_num_files;
-use super::RunTask;
+use super::tools::args::separate_args;
+use super::tools::cancel::Cancel;
+use super::tools::checksums::merge_checksums;
+use super::tools::compound_ext::parse_compound_extensions;
+use super::tools::confirm::confirm;
+use super::tools::count_named_files::next_named_index;
+use super::tools::dates::{date_label, file_date, parse_date_source, DateSource};
+use super::tools::ensured_dirs::EnsuredDirs;
+use super::tools::error::{with_path, PathContext};
+use super::tools::file_in_use::is_file_in_use;
+use super::tools::file_system::{FileSystem, RealFileSystem};
+use super::tools::file_walker::FileWalker;
+use super::tools::filter::{parse_filter, FileInfo, Filter};
+use super::tools::free_space::{available_inodes, available_space, copy_estimate};
+use super::tools::hidden::Hidden;
+use super::tools::modes::{parse_mode, set_mode};
+use super::tools::move_file::{link_file, move_file, safe_move_file};
+use super::tools::parse_size::{format_size, parse_size};
+use super::tools::path_list::read_paths;
+use super::tools::paths::expand_paths;
+use super::tools::progress::{
+    open_progress_stream, parse_progress_format, Progress, ProgressFormat, ProgressUpdate,
+};
+use super::tools::quarantine::quarantine;
+use super::tools::retry::{parse_retry_delay, Retry};
+use super::tools::sanitize_filename::sanitize_filename;
+use super::tools::sha256::hash_file;
+use super::tools::symlinks::SymlinkPolicy;
+use super::tools::throttle::{parse_throttle, Throttle};
+use super::tools::timezone::{parse_timezone, TimeZone};
+use super::{Logger, RunTask, SkipReason, TaskReport};
+
+// declare local modules
+mod duplicates;
+mod grouping;
+mod last_run;
+mod link_mode;
+mod manifest;
+mod missing_date;
+mod naming;
+mod plan;
+mod sort_order;
+mod stats;
+mod target_exists;
+mod template;
+use duplicates::TargetContents;
+use grouping::{
+    checked_subdir, folder_for, parse_ext_alias, parse_group_regex, parse_grouping,
+    parse_size_buckets, CustomStrategy, GroupBy, GroupOptions,
+};
+pub use grouping::{DateStrategy, ExtensionStrategy, GroupStrategy, SizeStrategy};
+use last_run::{read_last_run, write_last_run};
+use link_mode::{parse_link_mode, LinkMode};
+use manifest::{manifest_entries, write_manifest};
+use missing_date::{parse_on_missing_date, MissingDate, UNKNOWN_DATE};
+use naming::{
+    parse_folder_name, parse_index_scope, parse_index_width, parse_name_case, parse_name_char,
+    parse_name_mode, parse_same_name_policy, IndexScope, NameMode, Naming, SameNamePolicy,
+};
+use plan::{plan_status, render_plan, render_tree, PlannedMove};
+use sort_order::{parse_sort_order, SortOrder};
+use stats::Stats;
+use target_exists::{parse_target_exists, TargetExists};
+use template::{parse_date_template, parse_granularity};
+
+/// organize options that are followed by a value, see separate_args()
+pub(crate) const VALUE_OPTIONS: &[&str] = &[
+    "--replace-char",
+    "--index-width",
+    "--index-scope",
+    "--index-sep",
+    "--date-index-sep",
+    "--naming",
+    "--confirm-over",
+    "--files-from",
+    "--replace-spaces",
+    "--case",
+    "--manifest",
+    "--checksum-manifest",
+    "--quarantine",
+    "--filter",
+    "--progress-format",
+    "--progress-fd",
+    "--same-name-policy",
+    "--retry",
+    "--retry-delay",
+    "--throttle",
+    "--max-files-per-sec",
+    "--preserve",
+    "--dir-mode",
+    "--file-mode",
+    "--date",
+    "--timezone",
+    "--group",
+    "--group-regex",
+    "--target-exists",
+    "--on-missing-date",
+    "--batch-size",
+    "--ext-alias",
+    "--compound-ext-list",
+    "--size-buckets",
+    "--date-template",
+    "--granularity",
+    "--target-subdir",
+    "--preserve-top",
+    "--no-ext-label",
+    "--exclude-dir",
+    "--min-free-inodes",
+    "--max-copy-size",
+    "--output-structure",
+    "--depth",
+    "--sort-order",
+    "--link",
+];
+
+/// organize options that are not followed by a value, listed along with VALUE_OPTIONS by the capabilities task
+pub(crate) const FLAG_OPTIONS: &[&str] = &[
+    "--prefix-from-parent",
+    "--keep-going",
+    "--plan",
+    "--stats-only",
+    "--since-last-run",
+    "--batch-fsync",
+    "--skip-duplicates",
+    "--dedupe-within-target",
+    "--force",
+    "--resume",
+    "--reverse",
+    "--safe-move",
+    "--fail-on-empty",
+    "--separate-sources",
+    "--include-empty-dirs",
+    "-0",
+    "--null",
+    "--deref-symlinks",
+    "--skip-hidden",
+    "--hidden-only",
+    "--case-insensitive-ext",
+    "--compound-ext",
+    "--follow",
+    "--no-follow",
+];
 
 /// Organize_Task struct: PathBufs correspond to source and target directories
 #[derive(Debug, PartialEq, Eq)]
 pub struct OrganizeTask {
-    /// PathBuf to directory containing unorganized files
-    source: PathBuf,
+    /// PathBufs to the directories containing unorganized files, in the order they were given
+    sources: Vec<PathBuf>,
+
+    /// whether the output of every source is nested under a folder of &self.target named after it
+    separate_sources: bool,
+
+    /// PathBuf to directory containing organized files
+    target: PathBuf,
+
+    /// optional folder inside &self.target that all date folders are nested under
+    target_subdir: Option<String>,
+
+    /// number of leading folders of the path of each file relative to its source that are kept as folders in front
+    /// of the grouping folders, 0 for none
+    preserve_top: usize,
+
+    /// whether the empty directories of the sources are recreated in &self.target within the folders kept by
+    /// &self.preserve_top
+    include_empty_dirs: bool,
+
+    /// FileWalker used to collect files from the source directory
+    walker: FileWalker,
+
+    /// which date of a file decides the folder it is organized into
+    date: DateSource,
+
+    /// time zone file dates are converted to before they are grouped and labelled
+    timezone: TimeZone,
+
+    /// criteria whose folders are nested in order to build the folder a file is organized into
+    grouping: Vec<GroupBy>,
+
+    /// settings of the grouping criteria that take any, such as extension aliases and size ranges
+    group_options: GroupOptions,
+
+    /// GroupStrategy given by a library user that decides folders in place of &self.grouping, see with_strategy()
+    strategy: Option<CustomStrategy>,
+
+    /// flag that stops the run after the file being organized once set, see with_cancel()
+    cancel: Option<Cancel>,
+
+    /// options controlling how organized files are named
+    naming: Naming,
+
+    /// order files are organized in and so given their counters, None for the sorted order they were collected in
+    sort_order: Option<SortOrder>,
+
+    /// whether &self.sort_order is reversed
+    reverse: bool,
+
+    /// whether to record per-file errors and continue rather than stopping at the first one
+    keep_going: bool,
+
+    /// directory every file that fails is moved into next to a note describing the failure, None to leave failed
+    /// files in place
+    quarantine: Option<PathBuf>,
+
+    /// how often and how patiently a move that failed with a transient error, such as a file in use by another
+    /// process or a timeout on a network mount, is retried
+    retry: Retry,
+
+    /// whether files copied across file systems get the permissions and ownership of the original
+    preserve_perms: bool,
+
+    /// whether every file is copied and verified by its digest before the original is removed, even within one file
+    /// system, see safe_move_file()
+    safe_move: bool,
+
+    /// how files are linked into the target instead of being moved, leaving the sources in place, None to move them
+    link: Option<LinkMode>,
+
+    /// Unix permission bits given to every directory the run creates, None to keep those given by the umask
+    dir_mode: Option<u32>,
+
+    /// Unix permission bits given to every file placed in the target, None to keep those it arrives with
+    file_mode: Option<u32>,
+
+    /// whether a symbolic link is left in place and the file it points to is copied into the target, rather than the
+    /// link itself being moved
+    deref_symlinks: bool,
+
+    /// whether to print how the organized layout compares against the current target instead of moving anything
+    plan: bool,
+
+    /// whether the plan is printed as the folder tree of the target with the number of files each folder would receive
+    /// instead of one line per file
+    plan_tree: bool,
+
+    /// number of folder levels below the target printed in a plan tree, None for every level
+    tree_depth: Option<usize>,
+
+    /// whether to print aggregates of the collected files, such as the number of files per month and extension,
+    /// instead of moving anything
+    stats_only: bool,
+
+    /// whether to organize only files dated after the start of the last successful run recorded in &self.target
+    since_last_run: bool,
+
+    /// number of files after which the log is flushed and the `--since-last-run` state is updated, None to do so only
+    /// at the end of the run
+    batch_size: Option<usize>,
+
+    /// whether the log is synced to disk after every batch
+    batch_fsync: bool,
+
+    /// whether files whose contents are already held anywhere in &self.target are skipped instead of added again
+    skip_duplicates: bool,
+
+    /// what happens to a file whose date cannot be determined
+    on_missing_date: MissingDate,
+
+    /// whether a target volume without room for every file that has to be copied is a warning rather than an error
+    force: bool,
+
+    /// what happens when &self.target already holds something when the run starts
+    target_exists: TargetExists,
+
+    /// whether finding no files in one of &self.sources is an error rather than a warning
+    fail_on_empty: bool,
+
+    /// file listing the files to organize in place of scanning &self.sources, `-` for stdin
+    files_from: Option<PathBuf>,
+
+    /// whether the paths of &self.files_from are separated by NUL bytes rather than newlines
+    null: bool,
+
+    /// file the original path relative to its source and the destination of every moved file are written to once the
+    /// run completes, None to not write one
+    manifest: Option<PathBuf>,
+
+    /// file the SHA-256 digest and path relative to &self.target of every file placed in the target are written to in
+    /// the format of `sha256sum` once the run completes, merged with the lines earlier runs wrote to it, None to not
+    /// write one
+    checksum_manifest: Option<PathBuf>,
+
+    /// number of collected files above which the run asks for confirmation before moving anything, None to never ask
+    confirm_over: Option<usize>,
+
+    /// expression a collected file must match to be organized, None to organize every file
+    filter: Option<Filter>,
+
+    /// whether to skip files the log file records as already moved by an earlier, interrupted run
+    resume: bool,
+
+    /// how progress is reported while files are organized
+    progress_format: ProgressFormat,
+
+    /// file descriptor JSON progress lines are written to in place of stderr, None for stderr, only used on Unix
+    progress_fd: Option<u32>,
+
+    /// number of inodes that must remain free on the target volume once every new file and directory is created, None
+    /// to not check inodes, only checked on Unix
+    min_free_inodes: Option<u64>,
+
+    /// size in bytes above which a file that would have to be copied across file systems is skipped rather than
+    /// copied, None for no limit
+    max_copy_size: Option<u64>,
+
+    /// maximum average rate in bytes per second that files are copied across file systems at, None for no limit
+    throttle: Option<u64>,
+
+    /// maximum average number of files moved per second, None for no limit
+    max_files_per_sec: Option<u64>,
+}
+
+/// RunTask trait implementation for Organize variant of Config enum
+impl RunTask for OrganizeTask {
+    /// run_task() driver function for Organize variant of Config, organizes files from &self.sources into &self.target based on files creation date,
+    /// or their last modification date if &self.date is DateSource::Modified
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Config enum
+    /// `logger` a Logger that records every move, skip, and failed move
+    ///
+    /// # Errors
+    ///
+    /// - open_progress_stream() call fails
+    /// - see run_with()
+    fn run_task(&self, logger: &mut Logger) -> Result<TaskReport, io::Error> {
+        let progress = match self.progress_format {
+            ProgressFormat::Human => Progress::new(true, None),
+            ProgressFormat::Jsonl => {
+                Progress::new(false, None).with_jsonl(open_progress_stream(self.progress_fd)?)
+            }
+        };
+        self.run_with(&RealFileSystem, logger, progress)
+    }
+}
+
+impl OrganizeTask {
+    /// with_strategy() returns the OrganizeTask with `strategy` deciding the folder every file is organized into in
+    /// place of the criteria given to `--group`, so library users can plug in their own folder assignment
+    ///
+    /// # Arguments
+    ///
+    /// `self` the OrganizeTask
+    /// `strategy` the boxed GroupStrategy to organize with
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn with_strategy(self, strategy: Box<dyn GroupStrategy>) -> Self {
+        Self {
+            strategy: Some(CustomStrategy(strategy)),
+            ..self
+        }
+    }
+
+    /// with_cancel() returns the OrganizeTask stopping once `flag` is set, checked before every file so the file being
+    /// organized is always completed. A cancelled run flushes the log, writes its manifests for the files organized so
+    /// far, and returns a TaskReport marked as cancelled, without recording the run for `--since-last-run` unless a
+    /// `--batch-size` checkpoint covers the files organized before it
+    ///
+    /// # Arguments
+    ///
+    /// `self` the OrganizeTask
+    /// `flag` the flag another thread or a signal handler sets to cancel the run
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn with_cancel(self, flag: Arc<AtomicBool>) -> Self {
+        Self {
+            cancel: Some(Cancel(flag)),
+            ..self
+        }
+    }
+
+    /// run_with_progress() organizes files exactly as run_task() does, calling `on_file` after every file is processed
+    /// with the number of files and bytes processed so far, the current rate, and the estimated time remaining, so
+    /// library users can render their own progress. No progress line is drawn on stderr
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `logger` a Logger that records every move, skip, and failed move
+    /// `on_file` callback given the processed file and the ProgressUpdate of the run after it
+    ///
+    /// # Errors
+    ///
+    /// - see run_with()
+    pub fn run_with_progress(
+        &self,
+        logger: &mut Logger,
+        on_file: &mut dyn FnMut(&Path, &ProgressUpdate),
+    ) -> io::Result<TaskReport> {
+        self.run_with(&RealFileSystem, logger, Progress::new(false, Some(on_file)))
+    }
+
+    /// run_with() body of run_task() performing every file system operation on `fs`, so tests can exercise error
+    /// handling with a FileSystem that fails on demand
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `fs` the FileSystem files are collected from and organized on
+    /// `logger` a Logger that records every move, skip, and failed move
+    /// `progress` the Progress every organized file is reported to, unused in plan mode
+    ///
+    /// # Errors
+    ///
+    /// - TargetExists::check() call fails
+    /// - FileWalker::walk_in() call fails
+    /// - no files are found in one of &self.sources and &self.fail_on_empty is set
+    /// - &self.files_from cannot be opened or read
+    /// - Logger::completed_moves() call fails when &self.resume is set
+    /// - metadata is not retrievable for a collected file when &self.filter is set
+    /// - confirm_run() call fails
+    /// - a source directory cannot be read when &self.include_empty_dirs is set
+    /// - organize() call fails
+    /// - recreate_empty_dirs() call fails
+    /// - plan_on() call fails
+    fn run_with(
+        &self,
+        fs: &dyn FileSystem,
+        logger: &mut Logger,
+        mut progress: Progress,
+    ) -> io::Result<TaskReport> {
+        // a target that is in use or meant to be filled from scratch is refused before anything is looked at
+        self.target_exists.check(&self.target)?;
+
+        // empty vector to store PathBufs of found files
+        let mut file_vec: Vec<PathBuf> = Vec::new();
+
+        // an empty source is most often a mistyped path, so it is reported rather than passing silently
+        let mut empty: Vec<String> = Vec::new();
+
+        // PathBufs for all files found at the source directories, a file inside sources nested in one another is
+        // only collected once, a list of files given instead is organized as is
+        let mut collected: HashSet<PathBuf> = HashSet::new();
+
+        // date a file is organized by when a `.filemanrc` above it chooses another than &self.date
+        let mut dates: HashMap<PathBuf, DateSource> = HashMap::new();
+
+        // directories found in the sources when &self.include_empty_dirs is set
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        if let Some(list) = &self.files_from {
+            file_vec = if list.as_os_str() == "-" {
+                read_paths(&mut io::stdin().lock(), self.null)?
+            } else {
+                read_paths(
+                    &mut BufReader::new(File::open(list).path_context(list)?),
+                    self.null,
+                )?
+            };
+        }
+        for source in self.sources.iter().filter(|_| self.files_from.is_none()) {
+            let found = file_vec.len();
+            self.walker.walk_in(fs, source, &mut |entry| {
+                if !entry.is_dir {
+                    file_vec.push(entry.path.clone());
+                    if let Some(date) = &entry.date {
+                        dates.insert(entry.path.clone(), date.clone());
+                    }
+                } else if self.include_empty_dirs {
+                    dirs.push(entry.path.clone());
+                }
+                Ok(())
+            })?;
+            if file_vec.len() == found {
+                let message = format!(
+                    "no files found in '{}', check that it is the intended source directory",
+                    source.display()
+                );
+                if self.fail_on_empty {
+                    return Err(io::Error::new(io::ErrorKind::NotFound, message));
+                }
+                empty.push(message);
+            }
+        }
+        file_vec.retain(|file| collected.insert(file.clone()));
+        let files = &mut file_vec;
+
+        // files an earlier run already moved are left alone, logged moves whose destination is missing are redone
+        let mut resumed: Vec<PathBuf> = Vec::new();
+        if self.resume {
+            let moved = logger.completed_moves()?;
+            let (done, remaining) = files.drain(..).partition(|file| moved.contains(file));
+            resumed = done;
+            *files = remaining;
+        }
+
+        // files the filter does not select are left where they are, ages are measured from the same instant for all
+        let mut filtered: Vec<PathBuf> = Vec::new();
+        if let Some(filter) = &self.filter {
+            let now = SystemTime::now();
+            let mut selected: Vec<PathBuf> = Vec::new();
+            for file in files.drain(..) {
+                let metadata = fs.metadata(&file).path_context(&file)?;
+                if filter.matches(&FileInfo::new(&file, &metadata, now).path_context(&file)?) {
+                    selected.push(file);
+                } else {
+                    filtered.push(file);
+                }
+            }
+            *files = selected;
+        }
+
+        // statistics only read the files, so there is neither anything to confirm nor anything to report
+        if self.stats_only {
+            for line in self.stats_on(fs, files, &dates).render() {
+                println!("{line}");
+            }
+            return Ok(TaskReport {
+                warnings: empty,
+                ..TaskReport::default()
+            });
+        }
+
+        // a large job waits for a yes before anything is moved, nothing is moved in plan mode so it never asks
+        if !self.plan {
+            self.confirm_run(files.len(), &mut io::stdin().lock(), &mut io::stderr())?;
+        }
+
+        // nothing is moved in plan mode so there is nothing to report
+        let mut report = if self.plan {
+            let plan = self.plan_on(files, &dates)?;
+            let lines = if self.plan_tree {
+                render_tree(&plan, &self.target, self.tree_depth)
+            } else {
+                render_plan(&plan)
+            };
+            for line in lines {
+                println!("{line}");
+            }
+            TaskReport::default()
+        } else {
+            // directories are looked at before any file is moved out of them, which would leave them empty as well
+            let mut empty_dirs: Vec<PathBuf> = Vec::new();
+            for dir in dirs {
+                if fs.read_dir(&dir).path_context(&dir)?.is_empty() {
+                    empty_dirs.push(dir);
+                }
+            }
+
+            let mut report = self.organize(fs, files, &dates, logger, &mut progress, None)?;
+            for file in filtered {
+                logger.log("skip", &file, None)?;
+                report.skip(file, SkipReason::Filtered, "not selected by --filter");
+            }
+
+            // the log already records these files, logging them again would only grow it with every resumed run
+            for file in resumed {
+                report.skip(
+                    file,
+                    SkipReason::AlreadyDone,
+                    "already processed by the resumed run",
+                );
+            }
+            if !report.cancelled {
+                self.recreate_empty_dirs(fs, &empty_dirs, &mut report)?;
+            }
+            report
+        };
+        report.warnings.extend(empty);
+
+        Ok(report)
+    }
+
+    /// run_on() organizes exactly `files` into &self.target without scanning &self.source, allowing callers that have
+    /// already enumerated or filtered files themselves to reuse the naming and move logic. Every path is assumed to be a
+    /// file, files that vanish before they are organized are skipped and any other per-file error ends the run unless
+    /// &self.keep_going is set. Problems that do not stop a file from being organized, such as an extension that is not
+    /// valid unicode, are collected as warnings in the returned TaskReport, which is printed at the end of the run
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `files` PathBufs of the files to organize
+    /// `logger` a Logger that records every move, skip, and failed move
+    ///
+    /// # Errors
+    ///
+    /// - metadata is not retrievable for a given file
+    /// - creation or modification date is not retrievable for a given file and &self.on_missing_date is Fail
+    /// - next_named_index() call fails
+    /// - EnsuredDirs::ensure() call fails
+    /// - TargetContents::new() call fails when &self.skip_duplicates is set
+    /// - move_file() call fails
+    /// - Logger::log() call fails
+    /// - write_last_run() call fails
+    /// - write_manifest() call fails
+    /// - hash_file() call fails for a file placed in the target or the checksum manifest cannot be read or written
+    /// - merge_checksums() call fails for the checksum manifest written by an earlier run
+    /// - set_mode() call fails for a directory created by the run or a file placed in the target
+    /// - files copied from other volumes do not fit on the target volume, see check_free_space() and check_free_inodes()
+    pub fn run_on(&self, files: &[PathBuf], logger: &mut Logger) -> io::Result<TaskReport> {
+        self.organize(
+            &RealFileSystem,
+            files,
+            &HashMap::new(),
+            logger,
+            &mut Progress::hidden(),
+            None,
+        )
+    }
+
+    /// stats_on() gathers the Stats of `files` from the same dates and extensions an organize run would use, without
+    /// moving anything. A file whose date cannot be read is counted as undated and one whose size cannot be read as empty
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `fs` the FileSystem the sizes are read from
+    /// `files` PathBufs of the files to analyze
+    /// `dates` the date source of every file organized by another date than &self.date
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn stats_on(
+        &self,
+        fs: &dyn FileSystem,
+        files: &[PathBuf],
+        dates: &HashMap<PathBuf, DateSource>,
+    ) -> Stats {
+        let mut stats = Stats::default();
+        for file in files {
+            let date = file_date(file, dates.get(file).unwrap_or(&self.date))
+                .ok()
+                .map(|date| self.timezone.convert(date));
+            let extension = self
+                .group_options
+                .compound_exts
+                .extension(file)
+                .map(|extension| extension.to_string_lossy().to_lowercase());
+            let size = fs.metadata(file).map_or(0, |metadata| metadata.len());
+            stats.add(file, size, date, extension);
+        }
+
+        stats
+    }
+
+    /// plan_on() computes where every one of `files` would be organized to, exactly as run_on() would name them if every
+    /// move succeeded, and compares each planned move against the current contents of the target directory without
+    /// creating directories or moving anything
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `files` PathBufs of the files to plan
+    /// `dates` the date source of every file organized by another date than &self.date
+    ///
+    /// # Errors
+    ///
+    /// - see run_on()
+    /// - plan_status() call fails
+    fn plan_on(
+        &self,
+        files: &[PathBuf],
+        dates: &HashMap<PathBuf, DateSource>,
+    ) -> io::Result<Vec<PlannedMove>> {
+        let mut plan: Vec<PlannedMove> = Vec::new();
+        self.organize(
+            &RealFileSystem,
+            files,
+            dates,
+            &mut Logger::disabled(),
+            &mut Progress::hidden(),
+            Some(&mut plan),
+        )?;
+
+        Ok(plan)
+    }
+
+    /// organize() shared body of run_on() and plan_on(), when `plan` is given every move is recorded in it instead of
+    /// being performed and no directories are created
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `fs` the FileSystem directories are created and files are moved on
+    /// `files` PathBufs of the files to organize
+    /// `dates` the date source of every file organized by another date than &self.date, see DirConfig
+    /// `logger` a Logger that records every move, skip, and failed move
+    /// `progress` the Progress every file headed for the target is reported to once processed
+    /// `plan` where planned moves are recorded, None to perform them
+    ///
+    /// # Errors
+    ///
+    /// - see run_on() and plan_on()
+    fn organize(
+        &self,
+        fs: &dyn FileSystem,
+        files: &[PathBuf],
+        dates: &HashMap<PathBuf, DateSource>,
+        logger: &mut Logger,
+        progress: &mut Progress,
+        mut plan: Option<&mut Vec<PlannedMove>>,
+    ) -> io::Result<TaskReport> {
+        // summary of the run
+        let mut report = TaskReport::default();
+
+        // shared by every move so the limit applies to the run as a whole
+        let mut throttle = Throttle::new(self.throttle);
+
+        // paces moves rather than bytes, for file systems where metadata operations are the bottleneck, each move
+        // counts as one unit so both limits apply and the stricter of the two decides
+        let mut file_throttle = Throttle::new(self.max_files_per_sec);
+
+        // files dated up to the start of the last successful run were organized by it, the start of this run is taken
+        // before any file is looked at so files arriving while it runs are picked up by the next one
+        let started = OffsetDateTime::now_utc();
+        let since = if self.since_last_run {
+            read_last_run(&self.target)
+        } else {
+            None
+        };
+
+        // target directory, YYYY-MM label, size, and date of every file, computed up front so the number of files headed
+        // to each directory is known before any of them are named and the remaining time can be estimated from the start
+        let mut planned: Vec<(&PathBuf, PathBuf, String, u64, OffsetDateTime)> = Vec::new();
+
+        // directory the grouping folders are placed in
+        let root = match &self.target_subdir {
+            Some(subdir) => self.target.join(subdir),
+            None => self.target.clone(),
+        };
+
+        // folder of root every source's files are nested under, longest first so a file inside sources nested in one
+        // another belongs to the innermost
+        let mut source_roots: Vec<(&PathBuf, PathBuf)> = Vec::new();
+        if self.separate_sources {
+            for source in &self.sources {
+                if let Some(name) = source.file_name() {
+                    let name = sanitize_filename(&name.to_string_lossy(), self.naming.replacement);
+                    source_roots.push((source, root.join(name)));
+                }
+            }
+            source_roots.sort_by_key(|(source, _)| std::cmp::Reverse(source.components().count()));
+        }
+
+        // files already in the target are listed once before any file is looked at, and only hashed once an incoming file
+        // of the same size turns up
+        let mut archived = if self.skip_duplicates {
+            Some(TargetContents::new(fs, &self.target)?)
+        } else {
+            None
+        };
+
+        // number of files headed to a given directory under a given label
+        let mut incoming: HashMap<(PathBuf, String), usize> = HashMap::new();
+
+        for file in files {
+            // creation date of file, None for a file without one that is routed to the unknown date folder, a file
+            // that vanished is skipped as such whatever &self.on_missing_date says
+            let c_date = match file_date(file, dates.get(file).unwrap_or(&self.date)) {
+                Ok(c_date) => Some(self.timezone.convert(c_date)),
+                Err(err)
+                    if self.on_missing_date == MissingDate::Fail
+                        || err.kind() == io::ErrorKind::NotFound =>
+                {
+                    self.record_error(fs, &mut report, logger, file, None, err)?;
+                    continue;
+                }
+                Err(err) if self.on_missing_date == MissingDate::Skip => {
+                    logger.log("skip", file, None)?;
+                    report.skip(
+                        file.clone(),
+                        SkipReason::MissingDate,
+                        format!("date cannot be determined: {err}"),
+                    );
+                    continue;
+                }
+                Err(_) => None,
+            };
+            if since.is_some_and(|since| c_date.is_some_and(|c_date| c_date <= since)) {
+                logger.log("skip", file, None)?;
+                report.skip(
+                    file.clone(),
+                    SkipReason::NotNewer,
+                    "not dated after the last run",
+                );
+                continue;
+            }
+
+            // YYYY-MM label that prefixes every organized file name, after the name of its folder if requested
+            let label = self.naming.label(
+                file,
+                c_date.map_or_else(|| String::from(UNKNOWN_DATE), date_label),
+            );
+
+            // target directory PathBuf, one set of folders per grouping criterion below the leading folders of the
+            // file that are preserved
+            let file_root = source_roots
+                .iter()
+                .find(|(source, _)| file.starts_with(source))
+                .map_or(&root, |(_, source_root)| source_root);
+            let file_root = file_root.join(self.preserved_top(file));
+            let dir = match (c_date, &self.strategy) {
+                (None, _) => Ok(file_root.join(UNKNOWN_DATE)),
+                (Some(_), Some(CustomStrategy(strategy))) => fs
+                    .metadata(file)
+                    .and_then(|meta| strategy.target_subdir(file, &meta))
+                    .and_then(checked_subdir)
+                    .map(|subdir| file_root.join(subdir)),
+                (Some(c_date), None) => folder_for(
+                    &self.grouping,
+                    &file_root,
+                    file,
+                    c_date,
+                    self.naming.replacement,
+                    &self.group_options,
+                ),
+            };
+            let dir = match dir {
+                Ok(dir) => dir,
+                Err(err) => {
+                    self.record_error(fs, &mut report, logger, file, None, err)?;
+                    continue;
+                }
+            };
+
+            // a file whose size cannot be read only skews the estimate, any real problem surfaces at the move
+            let size = fs.metadata(file).map_or(0, |metadata| metadata.len());
+
+            // a file too large to copy across file systems is left in place rather than starting a long transfer
+            if let Some(max) = self.max_copy_size.filter(|&max| size > max) {
+                match fs.same_device(file, &self.target) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        logger.log("skip", file, None)?;
+                        report.skip(
+                            file.clone(),
+                            SkipReason::TooLarge,
+                            format!(
+                                "{} would be copied to another file system, larger than --max-copy-size {}",
+                                format_size(size),
+                                format_size(max)
+                            ),
+                        );
+                        continue;
+                    }
+                    Err(err) => {
+                        self.record_error(fs, &mut report, logger, file, None, err)?;
+                        continue;
+                    }
+                }
+            }
+
+            // a file whose contents the target already holds somewhere is not imported a second time under a new name
+            if let Some(archived) = &mut archived {
+                match archived.find(file, size) {
+                    Ok(None) => {}
+                    Ok(Some(original)) => {
+                        logger.log("skip", file, Some(original))?;
+                        report.skip(
+                            file.clone(),
+                            SkipReason::Duplicate,
+                            format!("duplicate of {}", original.display()),
+                        );
+                        continue;
+                    }
+                    Err(err) => {
+                        self.record_error(fs, &mut report, logger, file, None, err)?;
+                        continue;
+                    }
+                }
+            }
+            *incoming.entry((dir.clone(), label.clone())).or_insert(0) += 1;
+
+            // files without a date sort after every dated one, so a checkpoint before them covers every dated file
+            planned.push((file, dir, label, size, c_date.unwrap_or(started)));
+        }
+        progress.start(
+            planned.len(),
+            planned.iter().map(|(_, _, _, size, _)| size).sum(),
+        );
+
+        // counters are given in the order files are organized in, the sort is stable so equal files keep the order
+        // they were collected in
+        if let Some(order) = self.sort_order {
+            planned.sort_by(|a, b| {
+                let ordering = order.compare((a.0, a.3, a.4), (b.0, b.3, b.4));
+                if self.reverse {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+
+        // batches checkpoint the state of `--since-last-run`, which only covers every file dated up to a point once
+        // files are organized oldest first
+        if self.since_last_run && self.batch_size.is_some() {
+            planned.sort_by_key(|(_, _, _, _, date)| *date);
+        }
+
+        // files from other volumes are copied, make sure they fit before the first one is moved
+        if plan.is_none() {
+            self.check_free_space(fs, &planned, &mut report)?;
+            self.check_free_inodes(fs, &planned, &mut report)?;
+        }
+
+        // cache to hold the next counter of a given directory and label and the width its counters are padded to, a
+        // directory holds several labels when a date template groups more than a month together or names are prefixed
+        let mut count_cache: HashMap<(PathBuf, String), (usize, usize)> = HashMap::new();
+
+        // counter given to each original file path without its extension per target directory and label, see
+        // SameNamePolicy::Shared
+        let mut siblings: HashMap<(PathBuf, (PathBuf, String)), usize> = HashMap::new();
+
+        // every target the run has placed or planned a file at, so a shared counter never gives two files one name
+        let mut taken: HashSet<PathBuf> = HashSet::new();
+
+        // whether each directory a file was headed to existed before the run
+        let mut visited: HashMap<PathBuf, bool> = HashMap::new();
+
+        // the digest and path relative to the target of every file placed in it, for the checksum manifest
+        let mut checksums: Vec<([u8; 32], PathBuf)> = Vec::new();
+
+        // directories created by the run, a directory another process creates at the same time is not an error
+        let ensured = EnsuredDirs::new();
+
+        /* in global scope a single counter is shared by every directory instead, it starts past every index already
+        used under the same label in any target directory so no existing file is overwritten, moves are performed one
+        at a time so a plain counter suffices */
+        let mut global: Option<(usize, usize)> = match self.naming.index_scope {
+            IndexScope::Folder => None,
+            // hashed names carry no counter
+            IndexScope::Global if self.naming.mode == NameMode::Hash => None,
+            IndexScope::Global => {
+                let mut next = 0;
+                let mut scanned: HashSet<(&PathBuf, &String)> = HashSet::new();
+                for (_, dir, label, _, _) in &planned {
+                    if scanned.insert((dir, label)) && dir.is_dir() {
+                        next = next.max(
+                            next_named_index(dir, label, self.naming.counter_sep())
+                                .path_context(dir)?,
+                        );
+                    }
+                }
+                Some((
+                    next,
+                    self.naming.width(next + planned.len().saturating_sub(1)),
+                ))
+            }
+        };
+
+        // iterate over collected files
+        for (index, (file, dir, label, size, c_date)) in planned.into_iter().enumerate() {
+            // a cancelled run stops between files, the batches completed so far are committed as usual
+            if plan.is_none() && self.cancel.as_ref().is_some_and(Cancel::is_set) {
+                report.cancelled = true;
+                if self.batch_size.is_some() {
+                    self.checkpoint(logger, &report, c_date, started)?;
+                } else {
+                    logger.sync(false)?;
+                }
+                break;
+            }
+
+            // every completed batch is committed before the next starts, so an interruption loses at most one batch
+            if let Some(batch_size) = self.batch_size.filter(|_| plan.is_none()) {
+                if index > 0 && index % batch_size == 0 {
+                    self.checkpoint(logger, &report, c_date, started)?;
+                }
+            }
+
+            // seed the counter the first time a directory and label are seen
+            let key = (dir.clone(), label.clone());
+            let (next, width) = match count_cache.get(&key) {
+                Some(&counter) => counter,
+                None => {
+                    // a directory is only looked at by the first label that reaches it
+                    let existed = match visited.get(&dir) {
+                        Some(&existed) => existed,
+                        None => {
+                            let existed = dir.exists();
+                            if existed {
+                                report.record_dir(dir.clone(), false);
+                            } else if plan.is_none() {
+                                // since the grouping folder(s) such as ./[subdir/]YYYY/YYYY-MM do/does not exist in
+                                // target directory yet, create it/them unless only planning
+                                let missing: Vec<PathBuf> = dir
+                                    .ancestors()
+                                    .take_while(|ancestor| !ancestor.exists())
+                                    .map(Path::to_path_buf)
+                                    .collect();
+                                let created = ensured.ensure(fs, &dir)?.unwrap_or(false);
+                                report.record_dir(dir.clone(), created);
+
+                                // every folder the run created gets the requested mode, not only the deepest
+                                if let Some(mode) = self.dir_mode.filter(|_| created) {
+                                    for created in &missing {
+                                        set_mode(created, mode).path_context(created)?;
+                                    }
+                                }
+                            }
+                            visited.insert(dir.clone(), existed);
+                            existed
+                        }
+                    };
+
+                    // one past the largest index of the previously organized files in the target directory, so a gap
+                    // left by a removed file is never filled with a name that is still taken, files that do not
+                    // follow the naming pattern (such as the user's own) do not affect numbering, hashed names need
+                    // no scan at all, and counting starts at zero in a new directory
+                    let next = if existed && self.naming.mode == NameMode::Counter {
+                        next_named_index(&dir, &label, self.naming.counter_sep())
+                            .path_context(&dir)?
+                    } else {
+                        0
+                    };
+
+                    // counters in this directory run up to the next free index plus every incoming file
+                    let largest = incoming.get(&key).map_or(next, |count| next + count - 1);
+                    let counter = (next, self.naming.width(largest));
+                    count_cache.insert(key.clone(), counter);
+                    counter
+                }
+            };
+            let (next, width) = global.unwrap_or((next, width));
+
+            // a file sharing its stem and source folder with one already organized under the same folder and label
+            // reuses that file's counter, so pairs such as IMG_001.jpg and IMG_001.raw stay associated
+            let sibling = (self.naming.same_name == SameNamePolicy::Shared
+                && self.naming.mode == NameMode::Counter)
+                .then(|| (file.with_extension(""), key.clone()));
+            let shared = sibling
+                .as_ref()
+                .and_then(|sibling| siblings.get(sibling).copied());
+
+            // the organized name is still valid but no longer carries the exact extension of the original
+            let extension = self.group_options.compound_exts.extension(file);
+            if extension.is_some_and(|extension| extension.to_str().is_none()) {
+                report.warnings.push(format!(
+                    "{}: extension is not valid unicode, invalid characters were replaced with U+FFFD",
+                    file.display()
+                ));
+            }
+
+            /* siblings whose extensions end up the same, such as IMG.jpg and IMG.JPG under `--case lower`, cannot share
+            a name, so a shared counter whose name is already taken on disk or by this run is replaced by a fresh one */
+            let shared = shared.filter(|&index| {
+                let target = dir.join(self.naming.file_name(&label, index, width, extension));
+                !taken.contains(&target) && !target.exists()
+            });
+            let index = shared.unwrap_or(next);
+
+            // add final formatting to target file for move
+            let target = match self.naming.mode {
+                NameMode::Counter => {
+                    dir.join(self.naming.file_name(&label, index, width, extension))
+                }
+                NameMode::Hash => match self.hashed_target(file, &dir, &label) {
+                    Ok(target) => target,
+                    Err(err) => {
+                        let action = self.record_error(fs, &mut report, logger, file, None, err)?;
+                        progress.advance(file, size, action)?;
+                        continue;
+                    }
+                },
+            };
+
+            // a hashed name that is already taken holds the same contents, a re-run leaves the file where it is
+            if self.naming.mode == NameMode::Hash && plan.is_none() && target.exists() {
+                logger.log("skip", file, Some(&target))?;
+                report.skip(
+                    file.clone(),
+                    SkipReason::Duplicate,
+                    format!("identical to {}", target.display()),
+                );
+                progress.advance(file, size, "skip")?;
+                continue;
+            }
+
+            // with &self.deref_symlinks the file a symbolic link points to is copied and the link is left in place, so the
+            // real file is never moved away from wherever it lives
+            let real = if self.deref_symlinks && file.is_symlink() {
+                match file.canonicalize() {
+                    Ok(real) => Some(real),
+                    Err(err) => {
+                        let action = self.record_error(fs, &mut report, logger, file, None, err)?;
+                        progress.advance(file, size, action)?;
+                        continue;
+                    }
+                }
+            } else {
+                None
+            };
+
+            if plan.is_none() {
+                file_throttle.consume(1);
+            }
+
+            // move file to target using YYYY-MM_#, copying instead when target is on another file system, the
+            // counter only advances once the file has actually been moved so skips leave no gaps, a planned move is
+            // assumed to succeed so later files are named as a real run would name them
+            let action = match plan.as_deref_mut() {
+                Some(plan) => {
+                    taken.insert(target.clone());
+                    plan.push(PlannedMove {
+                        status: plan_status(file, &dir, &target)?,
+                        source: file.clone(),
+                        target,
+                    });
+                    "move"
+                }
+                None => match self.retry.run(&mut report.retries, || match &real {
+                    Some(real) => fs
+                        .copy(real, &target, &mut throttle, self.preserve_perms)
+                        .map(|_| "copy"),
+                    None if self.link == Some(LinkMode::Hard) => {
+                        link_file(fs, file, &target, &mut throttle, self.preserve_perms)
+                            .map(|linked| if linked { "link" } else { "copy" })
+                    }
+                    None if self.safe_move => {
+                        safe_move_file(fs, file, &target, &mut throttle, self.preserve_perms)
+                            .map(|()| "move")
+                    }
+                    None => move_file(fs, file, &target, &mut throttle, self.preserve_perms)
+                        .map(|()| "move"),
+                }) {
+                    Ok(action) => {
+                        // a linked file, or the copy made where it cannot be linked, leaves the original in place
+                        match real {
+                            Some(real) => report.symlinks.push((file.clone(), real)),
+                            None if self.link.is_some() => {
+                                report.links.push((file.clone(), target.clone()))
+                            }
+                            None => report.moves.push((file.clone(), target.clone())),
+                        }
+                        logger.log(action, file, Some(&target))?;
+                        report.moved += 1;
+                        taken.insert(target.clone());
+
+                        // a link shares its permissions with the original, which is left undisturbed
+                        if let Some(mode) = self.file_mode.filter(|_| action != "link") {
+                            set_mode(&target, mode).path_context(&target)?;
+                        }
+                        if self.checksum_manifest.is_some() {
+                            checksums.push((
+                                hash_file(&target).path_context(&target)?,
+                                target
+                                    .strip_prefix(&self.target)
+                                    .unwrap_or(&target)
+                                    .to_path_buf(),
+                            ));
+                        }
+                        action
+                    }
+                    Err(err) => {
+                        self.record_error(fs, &mut report, logger, file, Some(&target), err)?
+                    }
+                },
+            };
+
+            // a sibling reuses a counter that has already advanced
+            if matches!(action, "move" | "copy" | "link") && shared.is_none() {
+                count_cache.insert(key, (next + 1, width));
+                if let Some(counter) = &mut global {
+                    counter.0 += 1;
+                }
+                // a sibling given a fresh counter after a collision leaves the pair with the first counter
+                if let Some(sibling) = sibling {
+                    siblings.entry(sibling).or_insert(next);
+                }
+            }
+            progress.advance(file, size, action)?;
+        }
+        progress.finish();
+
+        // a run with failures is repeated in full next time so the failed files are not left behind
+        if self.since_last_run && plan.is_none() && report.failed.is_empty() && !report.cancelled {
+            write_last_run(&self.target, started)?;
+        }
+
+        // the manifest records where moved files came from so their original tree can be rebuilt
+        if let Some(manifest) = self.manifest.as_ref().filter(|_| plan.is_none()) {
+            let organized: Vec<(PathBuf, PathBuf)> =
+                report.moves.iter().chain(&report.links).cloned().collect();
+            write_manifest(manifest, &manifest_entries(&self.sources, &organized))
+                .path_context(manifest)?;
+        }
+        // files placed by earlier runs stay listed, so incremental runs keep the whole target covered
+        if let Some(manifest) = self.checksum_manifest.as_ref().filter(|_| plan.is_none()) {
+            let existing = match read_to_string(manifest) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+                existing => existing.path_context(manifest)?,
+            };
+            let text = merge_checksums(&existing, &checksums).path_context(manifest)?;
+            write(manifest, text).path_context(manifest)?;
+        }
+
+        Ok(report)
+    }
+
+    /// hashed_target() returns the path `file` is organized to in `dir` with NameMode::Hash, named after the first digits
+    /// of its content digest unless a file with different contents already holds that name, in which case the whole
+    /// digest is used
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `file` a Path that corresponds to the file being organized
+    /// `dir` a Path that corresponds to the folder it is organized into
+    /// `label` the label that prefixes the organized file name
+    ///
+    /// # Errors
+    ///
+    /// - hash_file() call fails
+    fn hashed_target(&self, file: &Path, dir: &Path, label: &str) -> io::Result<PathBuf> {
+        let digest = hash_file(file).path_context(file)?;
+        let extension = self.group_options.compound_exts.extension(file);
+        let target = dir.join(self.naming.hashed_name(label, &digest, false, extension));
+
+        if target.is_file() && hash_file(&target).path_context(&target)? != digest {
+            return Ok(dir.join(self.naming.hashed_name(label, &digest, true, extension)));
+        }
+
+        Ok(target)
+    }
+
+    /// checkpoint() commits the batches completed so far before the file dated `next` is organized, the log is flushed
+    /// (and synced to disk with &self.batch_fsync) and, with &self.since_last_run, the state records every file dated
+    /// before `next` as organized, as files are organized oldest first. The state is left alone once a file failed so the
+    /// failed file is retried by the next run
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `logger` the Logger of the run
+    /// `report` the TaskReport of the batches completed so far
+    /// `next` the date of the first file of the next batch
+    /// `started` the time the run started, the state never records a later time
+    ///
+    /// # Errors
+    ///
+    /// - Logger::sync() call fails
+    /// - write_last_run() call fails
+    fn checkpoint(
+        &self,
+        logger: &mut Logger,
+        report: &TaskReport,
+        next: OffsetDateTime,
+        started: OffsetDateTime,
+    ) -> io::Result<()> {
+        logger.sync(self.batch_fsync)?;
+        if self.since_last_run && report.failed.is_empty() {
+            let completed = (next - time::Duration::nanoseconds(1)).min(started);
+            write_last_run(&self.target, completed)?;
+        }
+
+        Ok(())
+    }
+
+    /// check_free_space() compares the combined size of the `planned` files that will be copied into &self.target
+    /// against the space available on its volume, a shortfall is an error unless &self.force is set, in which case it
+    /// is recorded as a warning in `report`. Platforms that cannot report free space are not checked
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `fs` the FileSystem files are moved on
+    /// `planned` every file to be organized along with its target directory, label, size, and date
+    /// `report` the TaskReport of the current run
+    ///
+    /// # Errors
+    ///
+    /// - copy_estimate() or available_space() call fails
+    /// - the files to be copied do not fit on the target volume and &self.force is not set
+    fn check_free_space(
+        &self,
+        fs: &dyn FileSystem,
+        planned: &[(&PathBuf, PathBuf, String, u64, OffsetDateTime)],
+        report: &mut TaskReport,
+    ) -> io::Result<()> {
+        let files: Vec<(&Path, u64)> = planned
+            .iter()
+            .map(|(file, _, _, size, _)| (file.as_path(), *size))
+            .collect();
+        let required = copy_estimate(fs, &files, &self.target)?;
+        if required == 0 {
+            return Ok(());
+        }
+
+        match available_space(&self.target)? {
+            Some(available) if available < required => self.shortfall(
+                report,
+                format!(
+                    "{} bytes have to be copied but only {} bytes are available on the volume of {}",
+                    required,
+                    available,
+                    self.target.display()
+                ),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// check_free_inodes() compares the number of inodes the run will use on the volume of &self.target, one per file
+    /// that is copied rather than renamed and one per directory that does not exist yet, against the inodes available
+    /// there less &self.min_free_inodes. Nothing is checked unless &self.min_free_inodes is set or on volumes that do
+    /// not limit inodes, a shortfall is handled as in check_free_space()
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `fs` the FileSystem files are moved on
+    /// `planned` every file to be organized along with its target directory, label, size, and date
+    /// `report` the TaskReport of the current run
+    ///
+    /// # Errors
+    ///
+    /// - FileSystem::same_device() or available_inodes() call fails
+    /// - the new files and directories would leave fewer than &self.min_free_inodes inodes and &self.force is not set
+    fn check_free_inodes(
+        &self,
+        fs: &dyn FileSystem,
+        planned: &[(&PathBuf, PathBuf, String, u64, OffsetDateTime)],
+        report: &mut TaskReport,
+    ) -> io::Result<()> {
+        let reserve = match self.min_free_inodes {
+            Some(reserve) => reserve,
+            None => return Ok(()),
+        };
+        let available = match available_inodes(&self.target)? {
+            Some(available) => available,
+            None => return Ok(()),
+        };
+
+        // every missing directory is counted once, along with missing parents such as the year of a new month
+        let mut missing: HashSet<&Path> = HashSet::new();
+        let mut copied: u64 = 0;
+        for (file, dir, _, _, _) in planned {
+            for dir in dir.ancestors().take_while(|dir| !dir.exists()) {
+                missing.insert(dir);
+            }
+            if !fs.same_device(file, &self.target)? {
+                copied += 1;
+            }
+        }
+
+        let required = copied + missing.len() as u64;
+        if available.saturating_sub(required) < reserve {
+            return self.shortfall(
+                report,
+                format!(
+                    "{} files and directories have to be created but only {} inodes are available on the volume of {}, {} of which must remain free",
+                    required,
+                    available,
+                    self.target.display(),
+                    reserve
+                ),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// shortfall() handles a target volume without enough room for the run, the `message` is recorded as a warning in
+    /// `report` if &self.force is set and returned as an error otherwise
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `report` the TaskReport of the current run
+    /// `message` description of what does not fit
+    ///
+    /// # Errors
+    ///
+    /// - &self.force is not set
+    fn shortfall(&self, report: &mut TaskReport, message: String) -> io::Result<()> {
+        if self.force {
+            report.warnings.push(message);
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::StorageFull,
+                message + ", pass --force to organize anyway",
+            ))
+        }
+    }
+
+    /// confirm_run() asks on `output` whether to organize the `count` collected files when there are more than
+    /// &self.confirm_over, and reads the answer from `input`, smaller jobs run without asking
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `count` number of files that would be organized
+    /// `input` where the answer is read from
+    /// `output` where the question is written to
+    ///
+    /// # Errors
+    ///
+    /// - the question is asked and not answered with a yes
+    /// - confirm() call fails
+    fn confirm_run(
+        &self,
+        count: usize,
+        input: &mut dyn BufRead,
+        output: &mut dyn Write,
+    ) -> io::Result<()> {
+        let limit = match self.confirm_over {
+            Some(limit) if count > limit => limit,
+            _ => return Ok(()),
+        };
+
+        let question = format!(
+            "{count} files found in '{}', more than --confirm-over {limit}, organize them into '{}'?",
+            self.sources
+                .iter()
+                .map(|source| source.display().to_string())
+                .collect::<Vec<String>>()
+                .join("', '"),
+            self.target.display()
+        );
+        if confirm(&question, input, output)? {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "organize was not confirmed, no files were moved",
+            ))
+        }
+    }
+
+    /// preserved_top() returns the first &self.preserve_top folders of the path of `file` relative to the innermost
+    /// of &self.sources it lies in, fewer for a file in a shallower folder and none for a file directly inside a
+    /// source or outside every source, such as one listed with `--files-from`
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `file` a Path that corresponds to a collected file
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn preserved_top(&self, file: &Path) -> PathBuf {
+        if self.preserve_top == 0 {
+            return PathBuf::new();
+        }
+        let relative = self
+            .sources
+            .iter()
+            .filter_map(|source| file.strip_prefix(source).ok())
+            .min_by_key(|relative| relative.components().count());
+
+        relative
+            .and_then(Path::parent)
+            .map(|dirs| dirs.components().take(self.preserve_top).collect())
+            .unwrap_or_default()
+    }
+
+    /// recreate_empty_dirs() creates the folder of &self.target every one of `empty_dirs` corresponds to, that is the
+    /// folders &self.preserve_top keeps of its path relative to the innermost of &self.sources it lies in, below
+    /// &self.target_subdir and the folder of its source with &self.separate_sources, so a placeholder folder is not lost
+    /// when its source is organized. Every folder is recorded in `report` as created or already existing
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `fs` the FileSystem the folders are created on
+    /// `empty_dirs` PathBufs of the directories of the sources that were empty before the run
+    /// `report` the TaskReport of the current run
+    ///
+    /// # Errors
+    ///
+    /// - FileSystem::create_dir_all() call fails
+    /// - set_mode() call fails for a folder created when &self.dir_mode is set
+    fn recreate_empty_dirs(
+        &self,
+        fs: &dyn FileSystem,
+        empty_dirs: &[PathBuf],
+        report: &mut TaskReport,
+    ) -> io::Result<()> {
+        let root = match &self.target_subdir {
+            Some(subdir) => self.target.join(subdir),
+            None => self.target.clone(),
+        };
+
+        for dir in empty_dirs {
+            let Some((source, relative)) = self
+                .sources
+                .iter()
+                .filter_map(|source| Some((source, dir.strip_prefix(source).ok()?)))
+                .min_by_key(|(_, relative)| relative.components().count())
+            else {
+                continue;
+            };
+            let mut target = root.clone();
+            if let Some(name) = source.file_name().filter(|_| self.separate_sources) {
+                target.push(sanitize_filename(
+                    &name.to_string_lossy(),
+                    self.naming.replacement,
+                ));
+            }
+            target.extend(relative.components().take(self.preserve_top));
+
+            let missing: Vec<PathBuf> = target
+                .ancestors()
+                .take_while(|ancestor| !ancestor.exists())
+                .map(Path::to_path_buf)
+                .collect();
+            fs.create_dir_all(&target).path_context(&target)?;
+            if let Some(mode) = self.dir_mode {
+                for created in &missing {
+                    set_mode(created, mode).path_context(created)?;
+                }
+            }
+            report.record_dir(target, !missing.is_empty());
+        }
+
+        Ok(())
+    }
+
+    /// record_error() handles a per-file error, files that no longer exist, and files that are in use by another process
+    /// when they are moved, are recorded as skipped, any other error is recorded as failed if &self.keep_going is set and
+    /// returned otherwise. A failed file is then moved into &self.quarantine if one is given, a file that cannot be
+    /// quarantined stays in place and is listed as a warning. Returns the action logged for the file, `skip` or `fail`
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to OrganizeTask
+    /// `fs` the FileSystem a failed file is quarantined on
+    /// `report` the TaskReport of the current run
+    /// `logger` a Logger that records the skip or failure
+    /// `file` a Path that corresponds to the file that caused the error
+    /// `target` an optional Path that corresponds to where the file was being moved
+    /// `err` the error encountered
+    ///
+    /// # Errors
+    ///
+    /// - `err` is neither a NotFound error nor a file in use error at the move, and &self.keep_going is not set
+    /// - Logger::log() call fails
+    fn record_error(
+        &self,
+        fs: &dyn FileSystem,
+        report: &mut TaskReport,
+        logger: &mut Logger,
+        file: &Path,
+        target: Option<&Path>,
+        err: io::Error,
+    ) -> io::Result<&'static str> {
+        // the file was removed by another process between collection and processing
+        if err.kind() == io::ErrorKind::NotFound {
+            logger.log("skip", file, target)?;
+            report.skip(
+                file.to_path_buf(),
+                SkipReason::Vanished,
+                "file vanished before it could be organized",
+            );
+            return Ok("skip");
+        }
+
+        // only a move has a target, the file is left in place for a later run once the other process releases it
+        if target.is_some() && is_file_in_use(&err) {
+            logger.log("skip", file, target)?;
+            report.skip(
+                file.to_path_buf(),
+                SkipReason::InUse,
+                "file in use by another process",
+            );
+            return Ok("skip");
+        }
+
+        logger.log("fail", file, target)?;
+        if !self.keep_going {
+            return Err(with_path(err, file));
+        }
+        report.failed.push((file.to_path_buf(), err.to_string()));
+
+        if let Some(dir) = &self.quarantine {
+            match quarantine(fs, dir, file, &err) {
+                Ok(destination) => logger.log("quarantine", file, Some(&destination))?,
+                Err(quarantine_err) => report.warnings.push(format!(
+                    "{} could not be quarantined: {quarantine_err}",
+                    file.display()
+                )),
+            }
+        }
+
+        Ok("fail")
+    }
+
+    /// OrganizeTask struct initializer from already known paths, all options take their defaults, intended for library
+    /// users that do not parse command line arguments
+    ///
+    /// # Arguments
+    ///
+    /// `source` - a PathBuf to the directory containing unorganized files
+    /// `target` - a PathBuf to the directory that will contain organized files
+    ///
+    /// # Errors
+    ///
+    /// - `source` does not correspond to valid directory
+    /// - `target` does not correspond to valid directory
+    pub fn from_paths(source: PathBuf, target: PathBuf) -> Result<Self, &'static str> {
+        // ensures the source path corresponds to a valid directory
+        if !source.is_dir() {
+            return Err("'source' path does not correspond to a valid directory");
+        }
+
+        // ensures the target path corresponds to a valid directory
+        if !target.is_dir() {
+            return Err("'target' path does not correspond to a valid directory");
+        }
+
+        Ok(Self {
+            sources: vec![source],
+            separate_sources: false,
+            target,
+            target_subdir: None,
+            preserve_top: 0,
+            include_empty_dirs: false,
+            walker: FileWalker::new(),
+            date: DateSource::default(),
+            timezone: TimeZone::default(),
+            grouping: vec![GroupBy::Date],
+            group_options: GroupOptions::default(),
+            strategy: None,
+            cancel: None,
+            naming: Naming::default(),
+            keep_going: false,
+            quarantine: None,
+            sort_order: None,
+            reverse: false,
+            safe_move: false,
+            link: None,
+            retry: Retry::default(),
+            preserve_perms: false,
+            dir_mode: None,
+            file_mode: None,
+            deref_symlinks: false,
+            plan: false,
+            plan_tree: false,
+            tree_depth: None,
+            stats_only: false,
+            since_last_run: false,
+            batch_size: None,
+            batch_fsync: false,
+            skip_duplicates: false,
+            on_missing_date: MissingDate::Fail,
+            force: false,
+            target_exists: TargetExists::Merge,
+            resume: false,
+            progress_format: ProgressFormat::Human,
+            progress_fd: None,
+            fail_on_empty: false,
+            files_from: None,
+            null: false,
+            manifest: None,
+            checksum_manifest: None,
+            confirm_over: None,
+            filter: None,
+            min_free_inodes: None,
+            max_copy_size: None,
+            throttle: None,
+            max_files_per_sec: None,
+        })
+    }
+
+    /// OrganizeTask struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// `args` - an iterator containing Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - a path refers to an undefined environment variable, see expand_path()
+    /// - `./source/` path not provided
+    /// - `./target/` path not provided
+    /// - see from_paths()
+    /// - an additional source does not correspond to valid directory
+    /// - `--separate-sources` is provided with a single source, or with sources that do not have distinct folder names
+    /// - `--replace-char` is not followed by a single character that is legal in file names
+    /// - `--index-width` is not followed by `auto` or a positive integer
+    /// - `--index-sep` is not followed by a single non-digit character that is legal in file names
+    /// - `--index-scope` is not followed by `global` or `folder`
+    /// - `--naming` is not followed by `counter` or `hash`
+    /// - `--same-name-policy` is not followed by `separate` or `shared`
+    /// - `--progress-format` is not followed by `human` or `jsonl`
+    /// - `--progress-fd` is not followed by a file descriptor number
+    /// - `--exclude-dir` is not followed by a name or pattern
+    /// - `--date` is not followed by `created`, `modified`, `sidecar`, or `sidecar=FIELD`
+    /// - `--timezone` is not followed by `utc`, `local`, or an offset such as `+02:00`
+    /// - `--target-subdir` is not followed by a single folder name that is legal in file names
+    /// - `--throttle` is not followed by a positive number of bytes per second
+    /// - `--max-files-per-sec` is not followed by a positive integer
+    /// - `--max-copy-size` is not followed by a size
+    /// - `--output-structure` is not followed by `dry-print`
+    /// - `--depth` is not followed by a non-negative integer
+    /// - `--group` is not followed by a comma separated list of grouping criteria
+    /// - `--group-regex` is not followed by a valid regular expression, or `--group` is given without `regex`
+    /// - `--group regex` is given without `--group-regex`
+    /// - `--batch-size` is not followed by a positive integer, or `--batch-fsync` is given without it
+    /// - `--target-exists` is not followed by `fail`, `merge`, or `require-empty`
+    /// - `--on-missing-date` is not followed by `fail`, `skip`, or `unknown-folder`
+    /// - `--stats-only` is combined with `--plan`, `--output-structure`, or a manifest
+    /// - `--ext-alias` is not followed by an extension and folder separated by `=`
+    /// - `--compound-ext-list` is not followed by a comma separated list of multi-part extensions
+    /// - `--size-buckets` is not followed by a comma separated list of increasing sizes
+    /// - `--date-template` is not followed by folders of text and known date tokens
+    /// - `--granularity` is not followed by `year`, `quarter`, `month`, or `day`
+    /// - `--retry` is not followed by a non-negative integer
+    /// - `--retry-delay` is not followed by a non-negative integer
+    /// - `--confirm-over` is not followed by a non-negative integer
+    /// - `--confirm-over` is provided along with `--files-from -`
+    /// - `--files-from` is not followed by a file, or `--null` is provided without it
+    /// - `--filter` is not followed by a valid filter expression
+    /// - `--min-free-inodes` is not followed by a non-negative integer
+    /// - `--preserve` is not followed by `perms`
+    /// - `--dir-mode` or `--file-mode` is not followed by an octal mode, or is given on a platform other than Unix
+    /// - both `--skip-hidden` and `--hidden-only` are provided
+    /// - an unknown option is provided
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
+        // separate options from the source and target paths so they may be given in any position
+        let (paths, options) = separate_args(args, VALUE_OPTIONS);
+        let paths = expand_paths(paths)?;
+        let mut paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+
+        // ensures source and target paths are provided, the last path is the target and every other one a source
+        let target = match paths.len() {
+            0 => return Err("no 'source' path provided"),
+            1 => return Err("no 'target' path provided"),
+            _ => paths.pop().unwrap(),
+        };
+        let mut paths = paths.into_iter();
+        let source = paths.next().unwrap();
+
+        // ensures both paths correspond to valid directories
+        let mut task = Self::from_paths(source, target)?;
+
+        // ensures every additional source corresponds to a valid directory
+        for source in paths {
+            if !source.is_dir() {
+                return Err("'source' path does not correspond to a valid directory");
+            }
+            task.sources.push(source);
+        }
+
+        // hidden file handling chosen so far, the two options contradict each other
+        let mut skip_or_only: Option<Hidden> = None;
+
+        // whether `--group` was given, a pattern without it groups by the pattern alone
+        let mut grouped = false;
+
+        // parse options
+        let mut args = options.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--replace-char" => {
+                    task.naming.replacement = match parse_name_char(args.next()) {
+                        Some(c) => c,
+                        None => return Err(
+                            "'--replace-char' must be followed by a single character that is legal in file names",
+                        ),
+                    }
+                }
+                "--index-width" => task.naming.index_width = parse_index_width(args.next())?,
+                "--index-scope" => task.naming.index_scope = parse_index_scope(args.next())?,
+                "--naming" => task.naming.mode = parse_name_mode(args.next())?,
+                "--prefix-from-parent" => task.naming.prefix_from_parent = true,
+                "--replace-spaces" => {
+                    task.naming.replace_spaces = match parse_name_char(args.next()) {
+                        Some(c) => Some(c),
+                        None => return Err(
+                            "'--replace-spaces' must be followed by a single character that is legal in file names",
+                        ),
+                    }
+                }
+                "--case" => task.naming.case = parse_name_case(args.next())?,
+                "--same-name-policy" => {
+                    task.naming.same_name = parse_same_name_policy(args.next())?
+                }
+                "--index-sep" => {
+                    // digits and dots would make the counter ambiguous
+                    task.naming.index_sep = match parse_name_char(args.next()) {
+                        Some(c) if !c.is_ascii_digit() && c != '.' => c,
+                        _ => return Err(
+                            "'--index-sep' must be followed by a single non-digit character that is legal in file names",
+                        ),
+                    }
+                }
+                "--date-index-sep" => {
+                    // a digit would run into the counter, a dot is not ambiguous as the whole label is matched first
+                    task.naming.date_index_sep = match parse_name_char(args.next()) {
+                        Some(c) if !c.is_ascii_digit() => Some(c),
+                        _ => return Err(
+                            "'--date-index-sep' must be followed by a single non-digit character that is legal in file names",
+                        ),
+                    }
+                }
+                "--keep-going" => task.keep_going = true,
+                "--plan" => task.plan = true,
+                "--stats-only" => task.stats_only = true,
+                "--target-exists" => task.target_exists = parse_target_exists(args.next())?,
+                "--on-missing-date" => {
+                    task.on_missing_date = parse_on_missing_date(args.next())?
+                }
+                "--output-structure" => {
+                    // the tree is a way of printing a plan so it implies one
+                    if args.next().as_deref() != Some("dry-print") {
+                        return Err("'--output-structure' must be followed by 'dry-print'");
+                    }
+                    task.plan = true;
+                    task.plan_tree = true;
+                }
+                "--depth" => {
+                    task.tree_depth = match args.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(depth)) => Some(depth),
+                        _ => return Err("'--depth' must be followed by a non-negative integer"),
+                    }
+                }
+                "--since-last-run" => task.since_last_run = true,
+                "--batch-size" => {
+                    task.batch_size = match args.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(size)) if size > 0 => Some(size),
+                        _ => return Err("'--batch-size' must be followed by a positive integer"),
+                    }
+                }
+                "--batch-fsync" => task.batch_fsync = true,
+                "--skip-duplicates" | "--dedupe-within-target" => task.skip_duplicates = true,
+                "--force" => task.force = true,
+                "--resume" => task.resume = true,
+                "--sort-order" => task.sort_order = Some(parse_sort_order(args.next())?),
+                "--reverse" => task.reverse = true,
+                "--safe-move" => task.safe_move = true,
+                "--link" => task.link = Some(parse_link_mode(args.next())?),
+                "--progress-format" => task.progress_format = parse_progress_format(args.next())?,
+                "--progress-fd" => {
+                    task.progress_fd = match args.next().map(|value| value.parse::<u32>()) {
+                        Some(Ok(fd)) => Some(fd),
+                        _ => return Err("'--progress-fd' must be followed by a file descriptor number"),
+                    }
+                }
+                "--fail-on-empty" => task.fail_on_empty = true,
+                "--separate-sources" => task.separate_sources = true,
+                "--include-empty-dirs" => task.include_empty_dirs = true,
+                "--files-from" => {
+                    task.files_from = match args.next() {
+                        Some(list) => Some(PathBuf::from(list)),
+                        None => return Err("'--files-from' must be followed by a file, or `-` for stdin"),
+                    }
+                }
+                "-0" | "--null" => task.null = true,
+                "--quarantine" => {
+                    task.quarantine = match args.next() {
+                        Some(dir) => Some(PathBuf::from(dir)),
+                        None => return Err("'--quarantine' must be followed by a directory"),
+                    }
+                }
+                "--checksum-manifest" => {
+                    task.checksum_manifest = match args.next() {
+                        Some(manifest) => Some(PathBuf::from(manifest)),
+                        None => return Err("'--checksum-manifest' must be followed by a file"),
+                    }
+                }
+                "--manifest" => {
+                    task.manifest = match args.next() {
+                        Some(manifest) => Some(PathBuf::from(manifest)),
+                        None => return Err("'--manifest' must be followed by a file"),
+                    }
+                }
+                "--deref-symlinks" => task.deref_symlinks = true,
+                "--filter" => task.filter = Some(parse_filter(args.next())?),
+                "--confirm-over" => {
+                    task.confirm_over = match args.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(limit)) => Some(limit),
+                        _ => {
+                            return Err("'--confirm-over' must be followed by a non-negative integer")
+                        }
+                    }
+                }
+                "--min-free-inodes" => {
+                    task.min_free_inodes = match args.next().map(|value| value.parse::<u64>()) {
+                        Some(Ok(reserve)) => Some(reserve),
+                        _ => {
+                            return Err(
+                                "'--min-free-inodes' must be followed by a non-negative integer",
+                            )
+                        }
+                    }
+                }
+                "--skip-hidden" | "--hidden-only" => {
+                    let hidden = if arg == "--skip-hidden" {
+                        Hidden::Skip
+                    } else {
+                        Hidden::Only
+                    };
+                    if skip_or_only.is_some_and(|previous| previous != hidden) {
+                        return Err("'--skip-hidden' and '--hidden-only' cannot be combined");
+                    }
+                    skip_or_only = Some(hidden);
+                    task.walker = task.walker.hidden(hidden);
+                }
+                "--retry-delay" => task.retry.delay = parse_retry_delay(args.next())?,
+                "--retry" => {
+                    task.retry.retries = match args.next().map(|value| value.parse::<u32>()) {
+                        Some(Ok(retry)) => retry,
+                        _ => return Err("'--retry' must be followed by a non-negative integer"),
+                    }
+                }
+                "--throttle" => task.throttle = Some(parse_throttle(args.next())?),
+                "--max-files-per-sec" => {
+                    task.max_files_per_sec = match args.next().map(|value| value.parse::<u64>()) {
+                        Some(Ok(rate)) if rate > 0 => Some(rate),
+                        _ => return Err("'--max-files-per-sec' must be followed by a positive integer"),
+                    }
+                }
+                "--max-copy-size" => {
+                    task.max_copy_size = match args.next().as_deref().and_then(parse_size) {
+                        Some(size) => Some(size),
+                        None => {
+                            return Err(
+                                "'--max-copy-size' must be followed by a size such as 500M or 2G",
+                            )
+                        }
+                    }
+                }
+                "--preserve" => {
+                    task.preserve_perms = match args.next().as_deref() {
+                        Some("perms") => true,
+                        _ => return Err("'--preserve' must be followed by 'perms'"),
+                    }
+                }
+                "--dir-mode" => {
+                    task.dir_mode = match parse_mode(args.next()) {
+                        Some(mode) => Some(mode),
+                        None => return Err("'--dir-mode' must be followed by an octal mode such as 775"),
+                    }
+                }
+                "--file-mode" => {
+                    task.file_mode = match parse_mode(args.next()) {
+                        Some(mode) => Some(mode),
+                        None => return Err("'--file-mode' must be followed by an octal mode such as 664"),
+                    }
+                }
+                "--date" => task.date = parse_date_source(args.next())?,
+                "--timezone" => task.timezone = parse_timezone(args.next())?,
+                "--group" => {
+                    task.grouping = parse_grouping(args.next())?;
+                    grouped = true;
+                }
+                "--group-regex" => {
+                    task.group_options.group_regex = Some(parse_group_regex(args.next())?)
+                }
+                "--case-insensitive-ext" => task.group_options.ext_aliases.enable(),
+                "--compound-ext" => task.group_options.compound_exts.enable(),
+                "--compound-ext-list" => task
+                    .group_options
+                    .compound_exts
+                    .set(parse_compound_extensions(args.next())?),
+                "--ext-alias" => {
+                    let (from, to) = parse_ext_alias(args.next())?;
+                    task.group_options.ext_aliases.insert(&from, &to);
+                }
+                "--date-template" => {
+                    task.group_options.date_template = Some(parse_date_template(args.next())?)
+                }
+                "--granularity" => {
+                    task.group_options.date_template = Some(parse_granularity(args.next())?)
+                }
+                "--size-buckets" => {
+                    task.group_options.size_buckets = parse_size_buckets(args.next())?
+                }
+                "--no-ext-label" => {
+                    task.group_options.no_ext_label = match parse_folder_name(args.next()) {
+                        Some(name) => Some(name),
+                        _ => return Err(
+                            "'--no-ext-label' must be followed by a single folder name that is legal in file names",
+                        ),
+                    }
+                }
+                "--target-subdir" => {
+                    // a single folder keeps organized files inside the target directory
+                    task.target_subdir = match parse_folder_name(args.next()) {
+                        Some(name) => Some(name),
+                        _ => return Err(
+                            "'--target-subdir' must be followed by a single folder name that is legal in file names",
+                        ),
+                    }
+                }
+                "--preserve-top" => {
+                    task.preserve_top = match args.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(count)) => count,
+                        _ => return Err("'--preserve-top' must be followed by a non-negative integer"),
+                    }
+                }
+                "--follow" => task.walker = task.walker.symlinks(SymlinkPolicy::Follow),
+                "--no-follow" => task.walker = task.walker.symlinks(SymlinkPolicy::NoFollow),
+                "--exclude-dir" => {
+                    task.walker = match args.next() {
+                        Some(pattern) => task.walker.exclude_dir(pattern),
+                        None => return Err("'--exclude-dir' must be followed by a name or pattern"),
+                    }
+                }
+                _ => return Err("provided option did not match any organize options"),
+            }
+        }
+
+        // a pattern only takes effect when grouping by it, which in turn needs a pattern
+        match (
+            task.grouping.contains(&GroupBy::Regex),
+            task.group_options.group_regex.is_some(),
+        ) {
+            (true, false) => return Err("'--group regex' requires '--group-regex'"),
+            (false, true) if grouped => {
+                return Err("'--group-regex' requires 'regex' among the criteria of '--group'")
+            }
+            (false, true) => task.grouping = vec![GroupBy::Regex],
+            _ => {}
+        }
+
+        // links have to be collected for their targets to be copied
+        if task.deref_symlinks && task.walker.symlink_policy() == SymlinkPolicy::Skip {
+            task.walker = task.walker.symlinks(SymlinkPolicy::NoFollow);
+        }
+
+        // NUL delimiting only applies to a list of files, and stdin cannot hold both the list and an answer
+        if task.null && task.files_from.is_none() {
+            return Err("'--null' requires '--files-from'");
+        }
+        if task.confirm_over.is_some() && task.files_from.as_deref() == Some(Path::new("-")) {
+            return Err("'--confirm-over' cannot ask for an answer on stdin while '--files-from -' reads it");
+        }
+
+        // modes are Unix permission bits, other platforms have nothing to apply them to
+        if !cfg!(unix) && (task.dir_mode.is_some() || task.file_mode.is_some()) {
+            return Err("'--dir-mode' and '--file-mode' are only supported on Unix");
+        }
+
+        // only a run that keeps going has failed files left to quarantine, and a plan moves nothing
+        if task.quarantine.is_some() && !task.keep_going {
+            return Err("'--quarantine' requires '--keep-going'");
+        }
+        if task.quarantine.is_some() && task.plan {
+            return Err("'--quarantine' cannot be combined with '--plan' or '--output-structure'");
+        }
+
+        // a link leaves the original in place, so there is neither a move to verify nor a link to resolve
+        if task.link.is_some() && (task.safe_move || task.deref_symlinks) {
+            return Err("'--link' cannot be combined with '--safe-move' or '--deref-symlinks'");
+        }
+
+        // files are only reordered once an order is chosen, and batches of `--since-last-run` need the oldest first
+        if task.reverse && task.sort_order.is_none() {
+            return Err("'--reverse' requires '--sort-order'");
+        }
+        if task.since_last_run
+            && task.batch_size.is_some()
+            && task
+                .sort_order
+                .is_some_and(|order| order != SortOrder::Date || task.reverse)
+        {
+            return Err("'--since-last-run' with '--batch-size' organizes the oldest files first and only allows '--sort-order date'");
+        }
+
+        // syncing happens between batches
+        if task.batch_fsync && task.batch_size.is_none() {
+            return Err("'--batch-fsync' requires '--batch-size'");
+        }
+
+        // statistics replace both moving and planning
+        if task.stats_only
+            && (task.plan || task.manifest.is_some() || task.checksum_manifest.is_some())
+        {
+            return Err("'--stats-only' cannot be combined with '--plan', '--output-structure', '--manifest', or '--checksum-manifest'");
+        }
+
+        // a plan moves nothing so there would be nothing to record
+        if task.plan && (task.manifest.is_some() || task.checksum_manifest.is_some()) {
+            return Err("'--manifest' and '--checksum-manifest' cannot be combined with '--plan' or '--output-structure'");
+        }
+
+        // empty directories are only placed within the folders kept from the sources, a list of files has none
+        if task.include_empty_dirs && (task.preserve_top == 0 || task.files_from.is_some()) {
+            return Err("'--include-empty-dirs' requires '--preserve-top' and cannot be combined with '--files-from'");
+        }
+
+        // the output of a source is only told apart by the folder named after it
+        if task.separate_sources {
+            if task.sources.len() < 2 {
+                return Err("'--separate-sources' requires more than one 'source' path");
+            }
+            let mut names: HashSet<String> = HashSet::new();
+            for source in &task.sources {
+                let name = source.file_name().map(|name| {
+                    sanitize_filename(&name.to_string_lossy(), task.naming.replacement)
+                });
+                if !name.is_some_and(|name| names.insert(name)) {
+                    return Err("'--separate-sources' requires every 'source' path to have a distinct folder name");
+                }
+            }
+        }
+
+        Ok(task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::collect_files::collect_files;
+    use crate::tools::file_system::MockFileSystem;
+    use std::cell::Cell;
+    use std::fs::{create_dir_all, read_to_string, remove_dir_all, remove_file, write, File};
+    use std::time::Duration;
+
+    /// dated_mock() creates the mock directory `./<name>_test` holding an empty `target` folder and a `source` folder
+    /// with every one of `files`, see dated_file(), and returns the paths of the mock directory, its source, and its
+    /// target
+    ///
+    /// # Arguments
+    ///
+    /// `name` the name of the test the mock directory is created for
+    /// `files` the path relative to the source folder and the contents of every file to create
+    ///
+    /// # Errors
+    ///
+    /// None, panics if a folder or file cannot be created
+    fn dated_mock(name: &str, files: &[(&str, &str)]) -> (PathBuf, PathBuf, PathBuf) {
+        let path_buf = PathBuf::from(format!("./{name}_test"));
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+
+        create_dir_all(&source).unwrap();
+        create_dir_all(&target).unwrap();
+        for (file, contents) in files {
+            dated_file(&source.join(file), contents);
+        }
+
+        (path_buf, source, target)
+    }
+
+    /// dated_file() writes `contents` to `file`, creating its folder as needed, and dates it 1_000_000_000 seconds after
+    /// the epoch, which falls in 2001-09, for tests organizing by `--date modified`
+    ///
+    /// # Arguments
+    ///
+    /// `file` a Path that corresponds to the file to create
+    /// `contents` the contents of the file
+    ///
+    /// # Errors
+    ///
+    /// None, panics if the folder or file cannot be created
+    fn dated_file(file: &Path, contents: impl AsRef<[u8]>) {
+        if let Some(dir) = file.parent() {
+            create_dir_all(dir).unwrap();
+        }
+        write(file, contents).unwrap();
+        File::options()
+            .write(true)
+            .open(file)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000))
+            .unwrap();
+    }
+
+    /// verifies OrganizeTask::new() works correctly with valid arguments passed in
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - `./source/` path not provided
+    /// - `./source/` does not correspond to valid directory
+    /// - `./target/` path not provided
+    /// - `./target/` does not correspond to valid directory
+    #[test]
+    fn organize_task_new_with_valid_args() {
+        // args iterator
+        let mut args = [
+            String::from("foo"),
+            String::from("bar"),
+            String::from("./src"),
+            String::from("./src/organize"),
+        ]
+        .into_iter();
+
+        // iterate to source location in iterator
+        args.next();
+        args.next();
+
+        // source PathBuf
+        let source = PathBuf::from("./src");
+
+        // target PathBuf
+        let target = PathBuf::from("./src/organize");
+
+        assert_eq!(
+            OrganizeTask::new(args),
+            Ok(OrganizeTask {
+                sources: vec![source],
+                separate_sources: false,
+                target,
+                target_subdir: None,
+                preserve_top: 0,
+                include_empty_dirs: false,
+                walker: FileWalker::new(),
+                date: DateSource::default(),
+                timezone: TimeZone::default(),
+                grouping: vec![GroupBy::Date],
+                group_options: GroupOptions::default(),
+                strategy: None,
+                cancel: None,
+                naming: Naming::default(),
+                keep_going: false,
+                quarantine: None,
+                sort_order: None,
+                reverse: false,
+                safe_move: false,
+                link: None,
+                retry: Retry::default(),
+                preserve_perms: false,
+                dir_mode: None,
+                file_mode: None,
+                deref_symlinks: false,
+                plan: false,
+                plan_tree: false,
+                tree_depth: None,
+                stats_only: false,
+                since_last_run: false,
+                batch_size: None,
+                batch_fsync: false,
+                skip_duplicates: false,
+                on_missing_date: MissingDate::Fail,
+                force: false,
+                target_exists: TargetExists::Merge,
+                resume: false,
+                progress_format: ProgressFormat::Human,
+                progress_fd: None,
+                fail_on_empty: false,
+                files_from: None,
+                null: false,
+                manifest: None,
+                checksum_manifest: None,
+                confirm_over: None,
+                filter: None,
+                min_free_inodes: None,
+                max_copy_size: None,
+                throttle: None,
+                max_files_per_sec: None,
+            })
+        );
+    }
+
+    /// verifies OrganizeTask::new() accepts options before, between, and after the source and target paths, and takes
+    /// every path but the last as a source
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::new() parses options differently depending on their position
+    /// - OrganizeTask::new() does not take extra paths as sources
+    /// - OrganizeTask::new() accepts `--separate-sources` with a single source or sources sharing a folder name
+    #[test]
+    fn organize_task_new_options_any_position() {
+        // args iterator builder
+        let args = |args: &[&str]| {
+            args.iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+        let expected = OrganizeTask {
+            date: DateSource::Modified,
+            keep_going: true,
+            quarantine: None,
+            sort_order: None,
+            reverse: false,
+            safe_move: false,
+            link: None,
+            ..OrganizeTask::from_paths(PathBuf::from("./src"), PathBuf::from("./src/organize"))
+                .unwrap()
+        };
+
+        for position in [
+            [
+                "--keep-going",
+                "--date",
+                "modified",
+                "./src",
+                "./src/organize",
+            ],
+            [
+                "./src",
+                "--keep-going",
+                "--date",
+                "modified",
+                "./src/organize",
+            ],
+            [
+                "./src",
+                "./src/organize",
+                "--keep-going",
+                "--date",
+                "modified",
+            ],
+            [
+                "--date",
+                "modified",
+                "./src",
+                "./src/organize",
+                "--keep-going",
+            ],
+        ] {
+            assert_eq!(OrganizeTask::new(args(&position)).as_ref(), Ok(&expected));
+        }
+        assert_eq!(
+            OrganizeTask::new(args(&["./src", "./src/organize", "./src/tools"]))
+                .unwrap()
+                .sources,
+            [PathBuf::from("./src"), PathBuf::from("./src/organize")]
+        );
+        assert!(OrganizeTask::new(args(&["./src", "./not_a_dir", "./src/tools"])).is_err());
+        assert!(OrganizeTask::new(args(&["./src", "./src/tools", "--separate-sources"])).is_err());
+        assert!(OrganizeTask::new(args(&[
+            "./src/organize",
+            "./src/../src/organize",
+            "./src/tools",
+            "--separate-sources"
+        ]))
+        .is_err());
+    }
+
+    /// verifies `--skip-hidden` leaves dotfiles in the source while organizing everything else, `--hidden-only`
+    /// organizes only dotfiles, and the two cannot be combined
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() organizes a dotfile with `--skip-hidden`
+    /// - OrganizeTask::run_task() organizes a regular file with `--hidden-only`
+    /// - OrganizeTask::new() accepts both options together
+    #[test]
+    fn organize_task_run_task_hidden() {
+        for (option, organized, left) in [
+            ("--skip-hidden", ["IMG_1.jpg"], [".DS_Store"]),
+            ("--hidden-only", [".DS_Store"], ["IMG_1.jpg"]),
+        ] {
+            // create inputs
+            let (path_buf, source, target) = dated_mock(
+                "organize_task_run_task_hidden",
+                &[(".DS_Store", ""), ("IMG_1.jpg", "")],
+            );
+
+            // run test
+            let task = OrganizeTask::new(
+                [
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from(option),
+                    String::from("--date"),
+                    String::from("modified"),
+                ]
+                .into_iter(),
+            )
+            .unwrap();
+            let report = task.run_task(&mut Logger::disabled()).unwrap();
+            let remaining: Vec<PathBuf> = source
+                .read_dir()
+                .unwrap()
+                .map(|item| item.unwrap().path())
+                .collect();
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            assert_eq!(report.moved, organized.len());
+            assert_eq!(remaining, left.map(|name| source.join(name)));
+        }
+        assert!(OrganizeTask::new(
+            ["./src", "./src/organize", "--skip-hidden", "--hidden-only"]
+                .into_iter()
+                .map(String::from)
+        )
+        .is_err());
+    }
+
+    /// verifies OrganizeTask::new() errors if source path is not provided
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::new() does not error if the source path is not provided
+    #[test]
+    fn organize_task_new_source_not_provided() {
+        // args iterator
+        let mut args = [String::from("foo"), String::from("bar")].into_iter();
+
+        // iterate to source location in iterator
+        args.next();
+        args.next();
+
+        assert!(OrganizeTask::new(args).is_err())
+    }
+
+    /// verifies OrganizeTask::new() errors if source path provided is not a real directory
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::new() does not error if the source path is not a valid directory
+    #[test]
+    fn organize_task_new_source_is_not_dir() {
+        // args iterator
+        let mut args = [
+            String::from("foo"),
+            String::from("bar"),
+            String::from("not_a_dir"),
+            String::from("./src/organize"),
+        ]
+        .into_iter();
+
+        // iterate to source location in iterator
+        args.next();
+        args.next();
+
+        assert!(OrganizeTask::new(args).is_err())
+    }
+
+    /// verifies OrganizeTask::new() errors if target path is not provided
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::new() does not error if the target path is not provided
+    #[test]
+    fn organize_task_new_target_not_provided() {
+        // args iterator
+        let mut args = [
+            String::from("foo"),
+            String::from("bar"),
+            String::from("not_a_dir"),
+        ]
+        .into_iter();
+
+        // iterate to source location in iterator
+        args.next();
+        args.next();
+
+        assert!(OrganizeTask::new(args).is_err())
+    }
+
+    /// verifies OrganizeTask::new() errors if target path provided is not a real directory
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::new() does not error if the target path is not a valid directory
+    #[test]
+    fn organize_task_new_target_is_not_dir() {
+        // args iterator
+        let mut args = [
+            String::from("foo"),
+            String::from("bar"),
+            String::from("./src"),
+            String::from("not_a_dir"),
+        ]
+        .into_iter();
+
+        // iterate to source location in iterator
+        args.next();
+        args.next();
+
+        assert!(OrganizeTask::new(args).is_err())
+    }
+
+    /// verifies OrganizeTask::new() expands environment variables in its paths before checking them and errors on an
+    /// undefined one
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::new() does not expand `$VAR` or `${VAR}` in the source and target paths
+    /// - OrganizeTask::new() does not error if a path refers to an undefined variable
+    #[test]
+    fn organize_task_new_expands_paths() {
+        // cargo sets the variable for every test it runs
+        let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let task = OrganizeTask::new(
+            [
+                "$CARGO_MANIFEST_DIR/src",
+                "${CARGO_MANIFEST_DIR}/src/organize",
+            ]
+            .map(String::from)
+            .into_iter(),
+        )
+        .unwrap();
+
+        assert_eq!(task.sources, [manifest_dir.join("src")]);
+        assert_eq!(task.target, manifest_dir.join("src/organize"));
+        assert!(OrganizeTask::new(
+            ["$FILEMAN_RS_UNDEFINED_TEST_VARIABLE/src", "./src"]
+                .map(String::from)
+                .into_iter()
+        )
+        .is_err());
+    }
+
+    /// verifies OrganizeTask::new() parses `--replace-char` and rejects unusable replacement characters
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::new() does not use the provided replacement character
+    /// - OrganizeTask::new() does not error if the replacement is illegal or longer than one character
+    #[test]
+    fn organize_task_new_replace_char() {
+        // args iterator builder
+        let args = |value: &str| {
+            [
+                String::from("./src"),
+                String::from("./src/organize"),
+                String::from("--replace-char"),
+                String::from(value),
+            ]
+            .into_iter()
+        };
+
+        assert_eq!(
+            OrganizeTask::new(args("-")).unwrap().naming.replacement,
+            '-'
+        );
+        assert!(OrganizeTask::new(args("?")).is_err());
+        assert!(OrganizeTask::new(args("--")).is_err());
+        assert!(OrganizeTask::new(args("")).is_err());
+    }
+
+    /// verifies OrganizeTask::run_task() numbers files after previously organized files only, ignoring unrelated files
+    /// already present in the target folder
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() counts unrelated files when numbering
+    #[test]
+    fn organize_task_run_task_ignores_unrelated_files() {
+        // create inputs, the target folder already holds one organized file and two of the user's own
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_ignores_unrelated_files",
+            &[("new.jpg", "")],
+        );
+        let month_dir = target.join("2001/2001-09");
+        create_dir_all(&month_dir).unwrap();
+        File::create(month_dir.join("2001-09_0.jpg")).unwrap();
+        File::create(month_dir.join("holiday.jpg")).unwrap();
+        File::create(month_dir.join("notes.txt")).unwrap();
+
+        // run test
+        let task = OrganizeTask {
+            sources: vec![source.clone()],
+            separate_sources: false,
+            target: target.clone(),
+            target_subdir: None,
+            preserve_top: 0,
+            include_empty_dirs: false,
+            walker: FileWalker::new(),
+            date: DateSource::Modified,
+            timezone: TimeZone::default(),
+            grouping: vec![GroupBy::Date],
+            group_options: GroupOptions::default(),
+            strategy: None,
+            cancel: None,
+            naming: Naming::default(),
+            keep_going: false,
+            quarantine: None,
+            sort_order: None,
+            reverse: false,
+            safe_move: false,
+            link: None,
+            retry: Retry::default(),
+            preserve_perms: false,
+            dir_mode: None,
+            file_mode: None,
+            deref_symlinks: false,
+            plan: false,
+            plan_tree: false,
+            tree_depth: None,
+            stats_only: false,
+            since_last_run: false,
+            batch_size: None,
+            batch_fsync: false,
+            skip_duplicates: false,
+            on_missing_date: MissingDate::Fail,
+            force: false,
+            target_exists: TargetExists::Merge,
+            resume: false,
+            progress_format: ProgressFormat::Human,
+            progress_fd: None,
+            fail_on_empty: false,
+            files_from: None,
+            null: false,
+            manifest: None,
+            checksum_manifest: None,
+            confirm_over: None,
+            filter: None,
+            min_free_inodes: None,
+            max_copy_size: None,
+            throttle: None,
+            max_files_per_sec: None,
+        };
+        task.run_task(&mut Logger::disabled()).unwrap();
+        let test_result = month_dir.join("2001-09_1.jpg").is_file();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert!(test_result);
+    }
+
+    /// verifies OrganizeTask::run_task() numbers files after the largest existing index of a folder whose indices have a
+    /// gap, so no previously organized file is overwritten
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() fills the gap with a name that is already taken
+    /// - OrganizeTask::run_task() replaces the contents of a previously organized file
+    #[test]
+    fn organize_task_run_task_gapped_indices() {
+        // create inputs, the target folder holds indices 0 and 2 but not 1
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_gapped_indices",
+            &[("a.jpg", "new"), ("b.jpg", "new")],
+        );
+        let month_dir = target.join("2001/2001-09");
+        create_dir_all(&month_dir).unwrap();
+        write(month_dir.join("2001-09_0.jpg"), "first").unwrap();
+        write(month_dir.join("2001-09_2.jpg"), "third").unwrap();
+
+        // run test
+        let mut task = OrganizeTask::from_paths(source.clone(), target.clone()).unwrap();
+        task.date = DateSource::Modified;
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+        let contents = [
+            "2001-09_0.jpg",
+            "2001-09_2.jpg",
+            "2001-09_3.jpg",
+            "2001-09_4.jpg",
+        ]
+        .map(|name| read_to_string(month_dir.join(name)).ok());
+        let gap = month_dir.join("2001-09_1.jpg").exists();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(report.moved, 2);
+        assert_eq!(
+            contents,
+            ["first", "third", "new", "new"].map(|contents| Some(String::from(contents)))
+        );
+        assert!(!gap);
+    }
+
+    /// verifies OrganizeTask::run_task() writes one log line per moved file
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() does not log every move
+    #[test]
+    fn organize_task_run_task_logs_moves() {
+        // create inputs
+        let path_buf = PathBuf::from("./organize_task_run_task_logs_moves_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let log_file = path_buf.join("organize.log");
+
+        // create mock directories
+        create_dir_all(source.join("nested")).unwrap();
+        create_dir_all(&target).unwrap();
+        for name in ["1.txt", "2.txt", "nested/3.txt"] {
+            File::create(source.join(name)).unwrap();
+        }
+
+        // run test
+        let task = OrganizeTask {
+            sources: vec![source.clone()],
+            separate_sources: false,
+            target: target.clone(),
+            target_subdir: None,
+            preserve_top: 0,
+            include_empty_dirs: false,
+            walker: FileWalker::new(),
+            date: DateSource::Modified,
+            timezone: TimeZone::default(),
+            grouping: vec![GroupBy::Date],
+            group_options: GroupOptions::default(),
+            strategy: None,
+            cancel: None,
+            naming: Naming::default(),
+            keep_going: false,
+            quarantine: None,
+            sort_order: None,
+            reverse: false,
+            safe_move: false,
+            link: None,
+            retry: Retry::default(),
+            preserve_perms: false,
+            dir_mode: None,
+            file_mode: None,
+            deref_symlinks: false,
+            plan: false,
+            plan_tree: false,
+            tree_depth: None,
+            stats_only: false,
+            since_last_run: false,
+            batch_size: None,
+            batch_fsync: false,
+            skip_duplicates: false,
+            on_missing_date: MissingDate::Fail,
+            force: false,
+            target_exists: TargetExists::Merge,
+            resume: false,
+            progress_format: ProgressFormat::Human,
+            progress_fd: None,
+            fail_on_empty: false,
+            files_from: None,
+            null: false,
+            manifest: None,
+            checksum_manifest: None,
+            confirm_over: None,
+            filter: None,
+            min_free_inodes: None,
+            max_copy_size: None,
+            throttle: None,
+            max_files_per_sec: None,
+        };
+        task.run_task(&mut Logger::open(&log_file).unwrap())
+            .unwrap();
+        let test_result = read_to_string(&log_file).unwrap();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(test_result.lines().count(), 3);
+        assert!(test_result
+            .lines()
+            .all(|line| line.split('\t').nth(1) == Some("move")));
+    }
+
+    /// verifies OrganizeTask::new() parses `--index-width` and `--index-sep`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::new() does not parse the counter options
+    /// - OrganizeTask::new() does not error if the counter separator is a digit
+    #[test]
+    fn organize_task_new_index_options() {
+        // args iterator builder
+        let args = |sep: &str| {
+            [
+                String::from("./src"),
+                String::from("./src/organize"),
+                String::from("--index-width"),
+                String::from("4"),
+                String::from("--index-sep"),
+                String::from(sep),
+            ]
+            .into_iter()
+        };
+
+        let naming = OrganizeTask::new(args("-")).unwrap().naming;
+
+        assert_eq!(naming.index_width, naming::IndexWidth::Fixed(4));
+        assert_eq!(naming.index_sep, '-');
+        assert!(OrganizeTask::new(args("1")).is_err());
+    }
+
+    /// verifies OrganizeTask::run_on() skips a collected file that vanished before it was processed and still
+    /// organizes the remaining files
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_on() errors when a collected file no longer exists
+    /// - OrganizeTask::run_on() does not record the vanished file as skipped
+    #[test]
+    fn organize_task_run_on_vanished_file() {
+        // create inputs
+        let path_buf = PathBuf::from("./organize_task_run_on_vanished_file_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+
+        // create mock directories
+        create_dir_all(&source).unwrap();
+        create_dir_all(&target).unwrap();
+        for name in ["1.txt", "2.txt", "3.txt"] {
+            File::create(source.join(name)).unwrap();
+        }
+
+        // collect files then remove one before it is processed
+        let mut files: Vec<PathBuf> = Vec::new();
+        collect_files(&FileWalker::new(), &source, &mut files).unwrap();
+        remove_file(source.join("2.txt")).unwrap();
+
+        // run test
+        let task = OrganizeTask {
+            sources: vec![source.clone()],
+            separate_sources: false,
+            target: target.clone(),
+            target_subdir: None,
+            preserve_top: 0,
+            include_empty_dirs: false,
+            walker: FileWalker::new(),
+            date: DateSource::Modified,
+            timezone: TimeZone::default(),
+            grouping: vec![GroupBy::Date],
+            group_options: GroupOptions::default(),
+            strategy: None,
+            cancel: None,
+            naming: Naming::default(),
+            keep_going: false,
+            quarantine: None,
+            sort_order: None,
+            reverse: false,
+            safe_move: false,
+            link: None,
+            retry: Retry::default(),
+            preserve_perms: false,
+            dir_mode: None,
+            file_mode: None,
+            deref_symlinks: false,
+            plan: false,
+            plan_tree: false,
+            tree_depth: None,
+            stats_only: false,
+            since_last_run: false,
+            batch_size: None,
+            batch_fsync: false,
+            skip_duplicates: false,
+            on_missing_date: MissingDate::Fail,
+            force: false,
+            target_exists: TargetExists::Merge,
+            resume: false,
+            progress_format: ProgressFormat::Human,
+            progress_fd: None,
+            fail_on_empty: false,
+            files_from: None,
+            null: false,
+            manifest: None,
+            checksum_manifest: None,
+            confirm_over: None,
+            filter: None,
+            min_free_inodes: None,
+            max_copy_size: None,
+            throttle: None,
+            max_files_per_sec: None,
+        };
+        let test_result = task.run_on(&files, &mut Logger::disabled());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        let report = test_result.unwrap();
+        assert_eq!(report.moved, 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].0, source.join("2.txt"));
+    }
+
+    /// verifies OrganizeTask::run_on() organizes only the given files even when the source directory holds others
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::from_paths() does not accept valid directories
+    /// - OrganizeTask::run_on() moves files that were not passed to it
+    #[test]
+    fn organize_task_run_on_given_files() {
+        // create inputs
+        let path_buf = PathBuf::from("./organize_task_run_on_given_files_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+
+        // create mock directories
+        create_dir_all(&source).unwrap();
+        create_dir_all(&target).unwrap();
+        for name in ["keep.txt", "move.txt"] {
+            File::create(source.join(name)).unwrap();
+        }
+
+        // run test
+        let task = OrganizeTask::from_paths(source.clone(), target.clone()).unwrap();
+        let report = task
+            .run_on(&[source.join("move.txt")], &mut Logger::disabled())
+            .unwrap();
+        let kept = source.join("keep.txt").exists();
+        let moved = source.join("move.txt").exists();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(report.moved, 1);
+        assert!(kept);
+        assert!(!moved);
+        assert!(OrganizeTask::from_paths(PathBuf::from("not_a_dir"), target).is_err());
+    }
+
+    /// verifies OrganizeTask::run_task() nests date folders under `--target-subdir` and keeps counting files already there
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::new() rejects a valid `--target-subdir` or accepts a path
+    /// - OrganizeTask::run_task() does not place files under the extra folder
+    #[test]
+    fn organize_task_run_task_target_subdir() {
+        // create inputs, the nested folder already holds one organized file
+        let (path_buf, source, target) =
+            dated_mock("organize_task_run_task_target_subdir", &[("new.txt", "")]);
+        let month_dir = target.join("trip/2001/2001-09");
+        create_dir_all(&month_dir).unwrap();
+        File::create(month_dir.join("2001-09_0.txt")).unwrap();
+        let args = |subdir: &str| {
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--target-subdir"),
+                subdir.to_string(),
+            ]
+            .into_iter()
+        };
+
+        // run test
+        let rejected = OrganizeTask::new(args("../trip")).is_err();
+        let task = OrganizeTask::new(args("trip")).unwrap();
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+        let test_result = month_dir.join("2001-09_1.txt").is_file();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert!(rejected);
+        assert!(test_result);
+        assert_eq!(report.existing_dirs, [month_dir]);
+    }
+
+    /// verifies OrganizeTask::run_task() organizes files from several sources into the same folders by default, and
+    /// under a folder named after each source with `--separate-sources`, each counting its own files
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() leaves files of any source unorganized
+    /// - OrganizeTask::run_task() mixes the files of two sources with `--separate-sources`
+    #[test]
+    fn organize_task_run_task_separate_sources() {
+        // create inputs
+        let path_buf = PathBuf::from("./organize_task_run_task_separate_sources_test");
+        let sources = [path_buf.join("phone"), path_buf.join("camera")];
+        let target = path_buf.join("target");
+        let run = |separate: bool| {
+            for source in &sources {
+                for name in ["a.jpg", "b.jpg"] {
+                    dated_file(&source.join(name), "");
+                }
+            }
+            create_dir_all(&target).unwrap();
+            let mut args: Vec<String> = sources
+                .iter()
+                .chain([&target])
+                .map(|path| path.display().to_string())
+                .collect();
+            args.extend(["--date", "modified"].map(String::from));
+            if separate {
+                args.push(String::from("--separate-sources"));
+            }
+            OrganizeTask::new(args.into_iter())
+                .unwrap()
+                .run_task(&mut Logger::disabled())
+                .unwrap();
+            let mut organized: Vec<PathBuf> = Vec::new();
+            collect_files(&FileWalker::new(), &target, &mut organized).unwrap();
+            organized.sort();
+            remove_dir_all(&path_buf).unwrap();
+            organized
+        };
+
+        // run test, cleaning up mock directories after each run
+        let shared = run(false);
+        let separate = run(true);
+
+        let month_dir = target.join("2001/2001-09");
+        assert_eq!(
+            shared,
+            (0..4)
+                .map(|index| month_dir.join(format!("2001-09_{index}.jpg")))
+                .collect::<Vec<PathBuf>>()
+        );
+        let expected = [
+            "camera/2001/2001-09/2001-09_0.jpg",
+            "camera/2001/2001-09/2001-09_1.jpg",
+            "phone/2001/2001-09/2001-09_0.jpg",
+            "phone/2001/2001-09/2001-09_1.jpg",
+        ]
+        .map(|path| target.join(path));
+        assert_eq!(separate, expected);
+    }
+
+    /// verifies OrganizeTask::run_on() reports exactly the target directories that did not exist before the run
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_on() reports an existing directory as created or vice versa
+    #[test]
+    fn organize_task_run_on_reports_dirs() {
+        // create inputs, 1_000_000_000 and 1_010_000_000 seconds after the epoch fall in 2001-09 and 2002-01
+        let path_buf = PathBuf::from("./organize_task_run_on_reports_dirs_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let dates = [
+            ("1.txt", 1_000_000_000),
+            ("2.txt", 1_000_000_000),
+            ("3.txt", 1_010_000_000),
+        ];
+
+        // create mock directories, only the 2001-09 folder exists beforehand
+        create_dir_all(&source).unwrap();
+        create_dir_all(target.join("2001/2001-09")).unwrap();
+        for (name, seconds) in dates {
+            File::create(source.join(name))
+                .unwrap()
+                .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+                .unwrap();
+        }
+
+        // run test
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let test_result = task.run_task(&mut Logger::disabled());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        let report = test_result.unwrap();
+        assert_eq!(report.created_dirs, [target.join("2002/2002-01")]);
+        assert_eq!(report.existing_dirs, [target.join("2001/2001-09")]);
+    }
+
+    /// verifies OrganizeTask::run_task() nests a folder per grouping criterion in the order given to `--group`, and
+    /// numbers files within each leaf folder
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() does not build the folders of a single or composed grouping
+    /// - OrganizeTask::run_task() shares counters between leaf folders
+    #[test]
+    fn organize_task_run_task_group() {
+        for (group, expected) in [
+            (
+                "extension",
+                [
+                    "jpg/2001-09_0.jpg",
+                    "jpg/2001-09_1.jpg",
+                    "mp4/2001-09_0.MP4",
+                ],
+            ),
+            (
+                "date,extension",
+                [
+                    "2001/2001-09/jpg/2001-09_0.jpg",
+                    "2001/2001-09/jpg/2001-09_1.jpg",
+                    "2001/2001-09/mp4/2001-09_0.MP4",
+                ],
+            ),
+        ] {
+            // create inputs, folders are named after the lowercased extension while file names keep its case
+            let (path_buf, source, target) = dated_mock(
+                "organize_task_run_task_group",
+                &[("a.jpg", ""), ("b.jpg", ""), ("c.MP4", "")],
+            );
+
+            // run test
+            let task = OrganizeTask::new(
+                [
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from("--date"),
+                    String::from("modified"),
+                    String::from("--group"),
+                    group.to_string(),
+                ]
+                .into_iter(),
+            )
+            .unwrap();
+            let report = task.run_task(&mut Logger::disabled()).unwrap();
+            let test_result: Vec<bool> = expected
+                .iter()
+                .map(|file| target.join(file).is_file())
+                .collect();
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            assert_eq!(report.moved, 3);
+            assert_eq!(test_result, [true, true, true]);
+        }
+    }
+
+    /// verifies a GroupStrategy given to OrganizeTask::with_strategy() decides the folders in place of `--group`, here
+    /// bucketing files by the project code their name starts with, and that an error it returns or a folder outside the
+    /// target fails only that file
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() does not place files in the folders returned by the strategy
+    /// - OrganizeTask::run_task() does not record a failed strategy as a failed file
+    /// - OrganizeTask::run_task() moves a file into an absolute folder or one outside the target
+    #[test]
+    fn organize_task_run_task_custom_strategy() {
+        // buckets files by the part of their name before the first `-`, the codes `up` and `abs` name folders outside
+        // the target
+        struct ProjectCode(PathBuf);
+        impl GroupStrategy for ProjectCode {
+            fn target_subdir(&self, path: &Path, _meta: &std::fs::Metadata) -> io::Result<PathBuf> {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                match name.split_once('-') {
+                    Some(("up", _)) => Ok(PathBuf::from("../escaped")),
+                    Some(("abs", _)) => Ok(self.0.clone()),
+                    Some((code, _)) => Ok(PathBuf::from(code.to_uppercase())),
+                    None => Err(io::Error::other("no project code")),
+                }
+            }
+        }
+
+        // create inputs
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_custom_strategy",
+            &[
+                ("prj1-plan.txt", ""),
+                ("PRJ1-notes.txt", ""),
+                ("prj2-draft.txt", ""),
+                ("misc.txt", ""),
+                ("up-notes.txt", ""),
+                ("abs-notes.txt", ""),
+            ],
+        );
+
+        // run test
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--group"),
+                String::from("extension"),
+                String::from("--keep-going"),
+            ]
+            .into_iter(),
+        )
+        .unwrap()
+        .with_strategy(Box::new(ProjectCode(
+            path_buf.canonicalize().unwrap().join("absolute"),
+        )));
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+        let test_result: Vec<bool> = [
+            "PRJ1/2001-09_0.txt",
+            "PRJ1/2001-09_1.txt",
+            "PRJ2/2001-09_0.txt",
+        ]
+        .iter()
+        .map(|file| target.join(file).is_file())
+        .collect();
+        let misc = source.join("misc.txt").is_file();
+        let outside = ["up-notes.txt", "abs-notes.txt"].map(|name| source.join(name).is_file());
+        let escaped = [path_buf.join("escaped"), path_buf.join("absolute")].map(|dir| dir.exists());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(report.moved, 3);
+        assert_eq!(test_result, [true, true, true]);
+        assert_eq!(report.failed.len(), 3);
+        assert!(report
+            .failed
+            .iter()
+            .any(|(_, err)| err.contains("no project code")));
+        assert!(misc);
+        assert_eq!(outside, [true, true]);
+        assert_eq!(escaped, [false, false]);
+    }
+
+    /// verifies `--group-regex` on its own organizes files into folders named after the code captured from their name,
+    /// and that it must be combined with `regex` when `--group` is given
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() does not group by the captured code or does not place other files in `ungrouped`
+    /// - OrganizeTask::new() accepts `--group regex` without a pattern, a pattern `--group` does not use, or an
+    ///   invalid pattern
+    #[test]
+    fn organize_task_run_task_group_regex() {
+        // create inputs
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_group_regex",
+            &[
+                ("PROJ1234_plan.pdf", ""),
+                ("PROJ99_notes.pdf", ""),
+                ("invoice.pdf", ""),
+            ],
+        );
+        let args = |extra: &[&str]| {
+            [source.display().to_string(), target.display().to_string()]
+                .into_iter()
+                .chain(extra.iter().map(|arg| arg.to_string()))
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+
+        // run test
+        let invalid = [
+            OrganizeTask::new(args(&["--group", "regex"])),
+            OrganizeTask::new(args(&["--group", "date", "--group-regex", "(PROJ\\d+)"])),
+            OrganizeTask::new(args(&["--group-regex", "(PROJ\\d+"])),
+        ];
+        let report = OrganizeTask::new(args(&[
+            "--date",
+            "modified",
+            "--group-regex",
+            "^(PROJ\\d+)_",
+        ]))
+        .unwrap()
+        .run_task(&mut Logger::disabled())
+        .unwrap();
+        let test_result: Vec<bool> = [
+            "PROJ1234/2001-09_0.pdf",
+            "PROJ99/2001-09_0.pdf",
+            "ungrouped/2001-09_0.pdf",
+        ]
+        .iter()
+        .map(|file| target.join(file).is_file())
+        .collect();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert!(invalid.iter().all(Result::is_err));
+        assert_eq!(report.moved, 3);
+        assert_eq!(test_result, [true, true, true]);
+    }
+
+    /// verifies `--stats-only` aggregates the collected files by month and extension from the dates an organize run
+    /// would use, counts files without a usable date, and moves nothing
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::stats_on() miscounts the files of a month, an extension, their size, or the undated files
+    /// - OrganizeTask::stats_on() does not find the oldest and newest file
+    /// - OrganizeTask::run_task() moves a file when `--stats-only` is given
+    /// - OrganizeTask::new() accepts `--stats-only` along with `--plan`
+    #[test]
+    fn organize_task_stats_only() {
+        // create inputs, 1_000_000_000 seconds after the epoch falls in 2001-09 and 1_110_000_000 in 2005-03
+        let path_buf = PathBuf::from("./organize_task_stats_only_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let args = |extra: &[&str]| {
+            [source.display().to_string(), target.display().to_string()]
+                .into_iter()
+                .chain(extra.iter().map(|arg| arg.to_string()))
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+        let files = [
+            ("a.jpg", 1_000_000_000, 100),
+            ("b.JPG", 1_000_000_000, 200),
+            ("c.png", 1_110_000_000, 300),
+            ("notes", 1_110_000_000, 400),
+        ];
+
+        // create mock directories
+        create_dir_all(&source).unwrap();
+        create_dir_all(&target).unwrap();
+        for (name, modified, size) in files {
+            let file = File::create(source.join(name)).unwrap();
+            file.set_len(size).unwrap();
+            file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(modified))
+                .unwrap();
+        }
+
+        // run test, a file that vanished after it was collected has no date
+        let task = OrganizeTask::new(args(&["--date", "modified", "--stats-only"])).unwrap();
+        let mut collected: Vec<PathBuf> =
+            files.iter().map(|(name, _, _)| source.join(name)).collect();
+        collected.push(source.join("vanished.jpg"));
+        let stats = task.stats_on(&RealFileSystem, &collected, &HashMap::new());
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+        let untouched = files.iter().all(|(name, _, _)| source.join(name).is_file());
+        let combined = OrganizeTask::new(args(&["--stats-only", "--plan"]));
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(stats.files, 5);
+        assert_eq!(stats.total_size, 1000);
+        assert_eq!(
+            stats.months.into_iter().collect::<Vec<(String, usize)>>(),
+            [(String::from("2001-09"), 2), (String::from("2005-03"), 2)]
+        );
+        assert_eq!(
+            stats
+                .extensions
+                .into_iter()
+                .collect::<Vec<(String, usize)>>(),
+            [
+                (String::from("(none)"), 1),
+                (String::from("jpg"), 3),
+                (String::from("png"), 1)
+            ]
+        );
+        assert_eq!(stats.oldest.unwrap().1, source.join("a.jpg"));
+        assert_eq!(stats.newest.unwrap().1, source.join("c.png"));
+        assert_eq!(stats.undated, 1);
+        assert_eq!(report.moved, 0);
+        assert!(untouched);
+        assert!(combined.is_err());
+    }
+
+    /// verifies `--on-missing-date` fails on a file whose date cannot be read by default, leaves it in place with `skip`,
+    /// and organizes it into the unknown date folder with `unknown-folder`, while dated files are organized as usual
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_on() does not fail on the undated file with `fail`
+    /// - OrganizeTask::run_on() moves the undated file or does not list it as skipped with `skip`
+    /// - OrganizeTask::run_on() does not move the undated file to ./target/unknown_date with `unknown-folder`
+    #[test]
+    fn organize_task_run_on_missing_date() {
+        let run = |policy: &str| {
+            // create inputs, the sidecar of a.jpg holds no readable date while that of b.jpg does
+            let path_buf =
+                PathBuf::from(format!("./organize_task_run_on_missing_date_{policy}_test"));
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+
+            // create mock directories
+            create_dir_all(&source).unwrap();
+            create_dir_all(&target).unwrap();
+            for (name, contents) in [
+                ("a.jpg", ""),
+                ("a.json", r#"{"date": "yesterday"}"#),
+                ("b.jpg", ""),
+                ("b.json", r#"{"date": "2001-09-09"}"#),
+            ] {
+                write(source.join(name), contents).unwrap();
+            }
+
+            // run test, only the images are organized so the sidecars stay next to them
+            let task = OrganizeTask::new(
+                [
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from("--date"),
+                    String::from("sidecar"),
+                    String::from("--on-missing-date"),
+                    String::from(policy),
+                ]
+                .into_iter(),
+            )
+            .unwrap();
+            let files = [source.join("a.jpg"), source.join("b.jpg")];
+            let report = task.run_on(&files, &mut Logger::disabled());
+            let kept = files[0].exists();
+            let unknown = target.join("unknown_date/unknown_date_0.jpg").is_file();
+            let dated = target.join("2001/2001-09/2001-09_0.jpg").is_file();
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            (
+                report
+                    .map(|report| (report.moved, report.skipped.len()))
+                    .ok(),
+                kept,
+                unknown,
+                dated,
+            )
+        };
+
+        assert_eq!(run("fail"), (None, true, false, false));
+        assert_eq!(run("skip"), (Some((1, 1)), true, false, true));
+        assert_eq!(run("unknown-folder"), (Some((2, 0)), false, true, true));
+        assert!(OrganizeTask::new(
+            [".", ".", "--on-missing-date", "later"]
+                .map(String::from)
+                .into_iter()
+        )
+        .is_err());
+    }
+
+    /// verifies a `.filemanrc` in the source changes the date the files of its subtree are organized by and leaves out
+    /// the subtrees and names it excludes, while the `.filemanrc` files themselves stay in place
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() organizes a file by the date of the task rather than that of its `.filemanrc`
+    /// - OrganizeTask::run_task() organizes a skipped or excluded file, or a `.filemanrc`
+    #[test]
+    fn organize_task_run_task_dir_config() {
+        // create inputs, scans take their dates from sidecars which are excluded so they stay next to the images
+        let path_buf = PathBuf::from("./organize_task_run_task_dir_config_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+
+        // create mock directories
+        create_dir_all(source.join("drafts")).unwrap();
+        create_dir_all(source.join("scans")).unwrap();
+        create_dir_all(&target).unwrap();
+        for (name, contents) in [
+            ("a.jpg", ""),
+            ("drafts/.filemanrc", "skip = true\n"),
+            ("drafts/c.jpg", ""),
+            ("scans/.filemanrc", "date = sidecar\nexclude = *.json\n"),
+            ("scans/b.jpg", ""),
+            ("scans/b.json", r#"{"date": "2001-09-09"}"#),
+        ] {
+            write(source.join(name), contents).unwrap();
+        }
+
+        // run test
+        let task = OrganizeTask::new(
+            [source.display().to_string(), target.display().to_string()].into_iter(),
+        )
+        .unwrap();
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+        let dated = target.join("2001/2001-09/2001-09_0.jpg").is_file();
+        let kept = [
+            "drafts/.filemanrc",
+            "drafts/c.jpg",
+            "scans/.filemanrc",
+            "scans/b.json",
+        ]
+        .map(|name| source.join(name).is_file());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(report.moved, 2);
+        assert!(dated);
+        assert_eq!(kept, [true; 4]);
+    }
+
+    /// verifies `--target-exists` organizes into an empty target with every action, and that against a target holding
+    /// files `merge` organizes while `fail` and `require-empty` refuse, the latter accepting a tree of empty folders
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() refuses a target its `--target-exists` action accepts, or the other way around
+    /// - OrganizeTask::run_task() moves a file before refusing the target
+    /// - OrganizeTask::new() accepts an unknown action
+    #[test]
+    fn organize_task_run_task_target_exists() {
+        // whether each action organizes into an empty target, one holding an empty folder, and one holding a file
+        for (action, expected) in [
+            ("merge", [true, true, true]),
+            ("fail", [true, false, false]),
+            ("require-empty", [true, true, false]),
+        ] {
+            for (state, expected) in expected.into_iter().enumerate() {
+                // create inputs
+                let (path_buf, source, target) =
+                    dated_mock("organize_task_run_task_target_exists", &[("a.txt", "")]);
+                if state > 0 {
+                    create_dir_all(target.join("2001")).unwrap();
+                }
+                if state > 1 {
+                    write(target.join("2001/existing.txt"), "in use").unwrap();
+                }
+
+                // run test
+                let result = OrganizeTask::new(
+                    [
+                        source.display().to_string(),
+                        target.display().to_string(),
+                        String::from("--date"),
+                        String::from("modified"),
+                        String::from("--target-exists"),
+                        String::from(action),
+                    ]
+                    .into_iter(),
+                )
+                .unwrap()
+                .run_task(&mut Logger::disabled());
+                let in_place = source.join("a.txt").is_file();
+
+                // clean up mock directories
+                remove_dir_all(&path_buf).unwrap();
+
+                match result {
+                    Ok(report) => {
+                        assert!(expected, "{action} organized into target {state}");
+                        assert_eq!(report.moved, 1);
+                    }
+                    Err(err) => {
+                        assert!(!expected, "{action} refused target {state}");
+                        assert_eq!(err.kind(), io::ErrorKind::DirectoryNotEmpty);
+                        assert!(err.to_string().contains(&target.display().to_string()));
+                        assert!(in_place);
+                    }
+                }
+            }
+        }
+        assert!(OrganizeTask::new(
+            ["./src", "./src/organize", "--target-exists", "replace"]
+                .into_iter()
+                .map(String::from)
+        )
+        .is_err());
+    }
+
+    /// verifies `--max-files-per-sec` paces the moves of a run so K files at a rate of R take at least K/R seconds
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() moves files faster than `--max-files-per-sec` allows
+    /// - OrganizeTask::new() accepts a rate of zero
+    #[test]
+    fn organize_task_run_task_max_files_per_sec() {
+        // create inputs
+        let (path_buf, source, target) =
+            dated_mock("organize_task_run_task_max_files_per_sec", &[]);
+        for index in 0..5 {
+            dated_file(&source.join(format!("{index}.txt")), "");
+        }
+        let args = |rate: &str| {
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--max-files-per-sec"),
+                String::from(rate),
+            ]
+            .into_iter()
+        };
+
+        // run test, 5 files at 20 files per second take at least a quarter of a second
+        let task = OrganizeTask::new(args("20")).unwrap();
+        let zero = OrganizeTask::new(args("0"));
+        let started = std::time::Instant::now();
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+        let elapsed = started.elapsed();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(report.moved, 5);
+        assert!(elapsed >= Duration::from_millis(250));
+        assert!(zero.is_err());
+    }
+
+    /// verifies `--no-ext-label` names the extension folder of files without an extension and rejects unsafe names
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() does not place extension-less files in the folder named by `--no-ext-label`
+    /// - OrganizeTask::run_task() gives an extension-less file an extension
+    /// - OrganizeTask::new() accepts a label that is not a single legal folder name
+    #[test]
+    fn organize_task_run_task_no_ext_label() {
+        // create inputs
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_no_ext_label",
+            &[("README", ""), ("Makefile", ""), ("a.jpg", "")],
+        );
+
+        // run test
+        let args = |label: &str| {
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--group"),
+                String::from("extension"),
+                String::from("--no-ext-label"),
+                label.to_string(),
+            ]
+            .into_iter()
+        };
+        let report = OrganizeTask::new(args("plain text"))
+            .unwrap()
+            .run_task(&mut Logger::disabled());
+        let test_result: Vec<bool> = [
+            "plain text/2001-09_0",
+            "plain text/2001-09_1",
+            "jpg/2001-09_0.jpg",
+        ]
+        .iter()
+        .map(|file| target.join(file).is_file())
+        .collect();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(report.unwrap().moved, 3);
+        assert_eq!(test_result, [true, true, true]);
+        for label in ["a/b", "..", "", "CON"] {
+            assert!(OrganizeTask::new(args(label)).is_err());
+        }
+    }
+
+    /// verifies OrganizeTask::record_error() skips a file in use at the move so the run continues, while other move
+    /// errors still stop it
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::record_error() returns a file in use error or does not record it as skipped
+    /// - OrganizeTask::record_error() skips a file in use error that did not happen at the move
+    /// - OrganizeTask::new() does not parse `--retry`
+    #[test]
+    fn organize_task_record_error_file_in_use() {
+        // create inputs
+        let task = OrganizeTask::new(
+            ["./src", "./src/organize", "--retry", "3"]
+                .into_iter()
+                .map(String::from),
+        )
+        .unwrap();
+        let file = Path::new("./src/locked.txt");
+        let target = Path::new("./src/organize/2001/2001-09/2001-09_0.txt");
+        let busy = || io::Error::from(io::ErrorKind::ResourceBusy);
+        let mut report = TaskReport::default();
+        let mut logger = Logger::disabled();
+
+        // run test
+        let at_move = task.record_error(
+            &RealFileSystem,
+            &mut report,
+            &mut logger,
+            file,
+            Some(target),
+            busy(),
+        );
+        let before_move = task.record_error(
+            &RealFileSystem,
+            &mut report,
+            &mut logger,
+            file,
+            None,
+            busy(),
+        );
+        let other = task.record_error(
+            &RealFileSystem,
+            &mut report,
+            &mut logger,
+            file,
+            Some(target),
+            io::Error::other("disk full"),
+        );
+
+        assert_eq!(task.retry.retries, 3);
+        assert!(at_move.is_ok());
+        assert!(before_move.is_err());
+        assert!(other.is_err());
+        assert_eq!(
+            report.skipped,
+            [(
+                file.to_path_buf(),
+                SkipReason::InUse,
+                String::from("file in use by another process")
+            )]
+        );
+        assert!(OrganizeTask::new(
+            ["./src", "./src/organize", "--retry", "-1"]
+                .into_iter()
+                .map(String::from)
+        )
+        .is_err());
+    }
+
+    /// verifies OrganizeTask::run_on() organizes a file whose extension is not valid unicode and reports a warning
+    /// rather than panicking, only unix allows such names to be created
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_on() does not move the file or does not warn about its extension
+    #[cfg(unix)]
+    #[test]
+    fn organize_task_run_on_warns_non_unicode_extension() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        // create inputs
+        let (path_buf, source, target) =
+            dated_mock("organize_task_run_on_warns_non_unicode_extension", &[]);
+        let file = source.join(OsStr::from_bytes(b"photo.jp\xffg"));
+        dated_file(&file, "");
+
+        // run test
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let test_result = task.run_on(std::slice::from_ref(&file), &mut Logger::disabled());
+        let moved = target.join("2001/2001-09/2001-09_0.jp\u{FFFD}g").is_file();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        let report = test_result.unwrap();
+        assert!(moved);
+        assert_eq!(report.moved, 1);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("extension is not valid unicode"));
+    }
+
+    /// verifies OrganizeTask::run_with() organizes files through the copy fallback when renames cross file systems, and
+    /// with `--keep-going` records failed copies without losing the originals
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_with() does not copy files whose rename crosses file systems or leaves the originals behind
+    /// - OrganizeTask::run_with() stops on or does not record a failed copy, or removes the original
+    #[test]
+    fn organize_task_run_with_cross_device() {
+        for (copy_error, moved, failed) in
+            [(None, 2, 0), (Some(io::ErrorKind::PermissionDenied), 0, 2)]
+        {
+            // create inputs
+            let (path_buf, source, target) = dated_mock(
+                "organize_task_run_with_cross_device",
+                &[("a.txt", ""), ("b.txt", "")],
+            );
+            let fs = MockFileSystem {
+                rename_error: Some(io::ErrorKind::CrossesDevices),
+                copy_error,
+                ..MockFileSystem::default()
+            };
+
+            // run test
+            let task = OrganizeTask {
+                date: DateSource::Modified,
+                keep_going: true,
+                quarantine: None,
+                sort_order: None,
+                reverse: false,
+                safe_move: false,
+                link: None,
+                ..OrganizeTask::from_paths(source.clone(), target.clone()).unwrap()
+            };
+            let test_result = task.run_with(&fs, &mut Logger::disabled(), Progress::hidden());
+            let organized = [
+                target.join("2001/2001-09/2001-09_0.txt").is_file(),
+                target.join("2001/2001-09/2001-09_1.txt").is_file(),
+                source.join("a.txt").is_file(),
+                source.join("b.txt").is_file(),
+            ];
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            let report = test_result.unwrap();
+            assert_eq!((report.moved, report.failed.len()), (moved, failed));
+            assert_eq!(
+                organized,
+                [moved == 2, moved == 2, failed == 2, failed == 2]
+            );
+        }
+    }
+
+    /// verifies an error that stops the run names the file it was encountered on and keeps its kind
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_with() does not stop on the failed move
+    /// - the returned error does not contain the path of the file or is of another kind
+    #[test]
+    fn organize_task_run_with_error_names_file() {
+        // create inputs
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_with_error_names_file",
+            &[("locked.txt", "")],
+        );
+        let fs = MockFileSystem {
+            rename_error: Some(io::ErrorKind::PermissionDenied),
+            ..MockFileSystem::default()
+        };
+
+        // run test
+        let task = OrganizeTask {
+            date: DateSource::Modified,
+            ..OrganizeTask::from_paths(source.clone(), target.clone()).unwrap()
+        };
+        let test_result = task.run_with(&fs, &mut Logger::disabled(), Progress::hidden());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        let err = test_result.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(err
+            .to_string()
+            .contains(&source.join("locked.txt").display().to_string()));
+    }
+
+    /// verifies `--retry` and `--retry-delay` retry a move that timed out until it succeeds, counting every retry in
+    /// the TaskReport, and give up once the retries are used up
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::new() does not parse `--retry` or `--retry-delay`
+    /// - OrganizeTask::run_with() does not retry a timed out move or does not count its retries
+    /// - OrganizeTask::run_with() retries more often than `--retry` allows
+    #[test]
+    fn organize_task_run_with_retry() {
+        // create inputs
+        let (path_buf, source, target) =
+            dated_mock("organize_task_run_with_retry", &[("remote.txt", "")]);
+        let task = |retries: &str| {
+            OrganizeTask::new(
+                [
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from("--date"),
+                    String::from("modified"),
+                    String::from("--retry"),
+                    String::from(retries),
+                    String::from("--retry-delay"),
+                    String::from("1"),
+                ]
+                .into_iter(),
+            )
+            .unwrap()
+        };
+        let flaky = || MockFileSystem {
+            timed_out_renames: Cell::new(2),
+            ..MockFileSystem::default()
+        };
+
+        // run test, the first run gives up after a single retry so the file is still in place for the second
+        let too_few = task("1").run_with(&flaky(), &mut Logger::disabled(), Progress::hidden());
+        let retried = task("3").run_with(&flaky(), &mut Logger::disabled(), Progress::hidden());
+        let moved = target.join("2001/2001-09/2001-09_0.txt").is_file();
+        let delay = task("3").retry.delay;
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(delay, Duration::from_millis(1));
+        assert_eq!(too_few.unwrap_err().kind(), io::ErrorKind::TimedOut);
+        let retried = retried.unwrap();
+        assert_eq!(retried.moved, 1);
+        assert_eq!(retried.retries, 2);
+        assert!(moved);
+    }
+
+    /// verifies `--max-copy-size` skips a file larger than the limit that would be copied across file systems, while
+    /// smaller files are still copied and renames are never limited
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_with() copies the oversized file or does not list it as skipped
+    /// - OrganizeTask::run_with() skips a file below the limit or a large file that is renamed
+    #[test]
+    fn organize_task_run_with_max_copy_size() {
+        for (fs, moved) in [
+            (
+                MockFileSystem {
+                    rename_error: Some(io::ErrorKind::CrossesDevices),
+                    ..MockFileSystem::default()
+                },
+                1,
+            ),
+            (MockFileSystem::default(), 2),
+        ] {
+            // create inputs
+            let (path_buf, source, target) =
+                dated_mock("organize_task_run_with_max_copy_size", &[]);
+            for (name, size) in [("large.bin", 4096), ("small.bin", 16)] {
+                dated_file(&source.join(name), vec![0u8; size]);
+            }
+
+            // run test
+            let task = OrganizeTask::new(
+                vec![
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from("--date"),
+                    String::from("modified"),
+                    String::from("--max-copy-size"),
+                    String::from("1K"),
+                ]
+                .into_iter(),
+            )
+            .unwrap();
+            let test_result = task.run_with(&fs, &mut Logger::disabled(), Progress::hidden());
+            let kept = source.join("large.bin").is_file();
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            let report = test_result.unwrap();
+            assert_eq!(report.moved, moved);
+            assert_eq!(kept, moved == 1);
+            // only the large file is skipped, and only when it would be copied
+            assert_eq!(
+                report
+                    .skipped
+                    .iter()
+                    .map(|(file, _, _)| file.clone())
+                    .collect::<Vec<_>>(),
+                [source.join("large.bin")][..2 - moved]
+            );
+        }
+        assert!(OrganizeTask::new(
+            vec![
+                String::from("./src"),
+                String::from("./src"),
+                String::from("--max-copy-size"),
+                String::from("big"),
+            ]
+            .into_iter()
+        )
+        .is_err());
+    }
+
+    /// verifies `--since-last-run` organizes every file on the first run, only files dated after its start on the second
+    /// run, and every file again when the state is corrupt
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() skips a file on the first run or after the state is corrupted
+    /// - OrganizeTask::run_task() organizes a file dated before the last run again or does not count it as skipped
+    #[test]
+    fn organize_task_run_task_since_last_run() {
+        // create inputs
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_since_last_run",
+            &[("a.txt", ""), ("b.txt", "")],
+        );
+
+        // run test, the second run sees one old file and one dated after the first run started, set a minute ahead so
+        // coarse file system timestamps cannot place it before the recorded start
+        let task = OrganizeTask {
+            date: DateSource::Modified,
+            since_last_run: true,
+            force: false,
+            resume: false,
+            progress_format: ProgressFormat::Human,
+            progress_fd: None,
+            fail_on_empty: false,
+            files_from: None,
+            null: false,
+            manifest: None,
+            checksum_manifest: None,
+            confirm_over: None,
+            filter: None,
+            min_free_inodes: None,
+            ..OrganizeTask::from_paths(source.clone(), target.clone()).unwrap()
+        };
+        let first = task.run_task(&mut Logger::disabled()).unwrap().moved;
+        let recorded = last_run::read_last_run(&target).is_some();
+        dated_file(&source.join("old.txt"), "");
+        File::create(source.join("new.txt"))
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        let second = task.run_task(&mut Logger::disabled()).unwrap();
+        let old_left = source.join("old.txt").is_file();
+        write(target.join(last_run::STATE_FILE), "corrupt").unwrap();
+        let third = task.run_task(&mut Logger::disabled()).unwrap().moved;
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!((first, second.moved, third), (2, 1, 1));
+        assert_eq!(second.skip_counts(), [(SkipReason::NotNewer, 1)]);
+        assert!(second.to_string().contains("\n  skips not_newer: 1"));
+        assert!(recorded);
+        assert!(old_left);
+    }
+
+    /// verifies `--batch-size` with `--since-last-run` records every completed batch in the state, so a run interrupted
+    /// partway leaves a state covering only the files of the batches it completed and the next run organizes the rest
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_with() does not stop at the interrupted move
+    /// - the state records less than the completed batch or any file of the interrupted one
+    /// - the next run does not organize exactly the files of the interrupted batch
+    #[test]
+    fn organize_task_run_with_batch_checkpoint() {
+        // create inputs, four files dated forty days apart from 2001-09 on, the newest in the smallest source folder
+        let path_buf = PathBuf::from("./organize_task_run_with_batch_checkpoint_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let dates: Vec<SystemTime> = (0..4)
+            .map(|index| {
+                SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000 + index * 40 * 86_400)
+            })
+            .collect();
+        let interrupted = MockFileSystem {
+            renames_before_error: std::cell::Cell::new(Some(2)),
+            ..MockFileSystem::default()
+        };
+
+        // create mock directories, names run against the dates so only sorting by date puts them in order
+        create_dir_all(&source).unwrap();
+        create_dir_all(&target).unwrap();
+        for (name, date) in ["d.txt", "c.txt", "b.txt", "a.txt"].iter().zip(&dates) {
+            File::create(source.join(name))
+                .unwrap()
+                .set_modified(*date)
+                .unwrap();
+        }
+
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--since-last-run"),
+                String::from("--batch-size"),
+                String::from("2"),
+                String::from("--batch-fsync"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+
+        // run test, the third move is interrupted so only the first batch completes
+        let first = task.run_with(&interrupted, &mut Logger::disabled(), Progress::hidden());
+        let state = last_run::read_last_run(&target);
+        let left: Vec<bool> = ["d.txt", "c.txt", "b.txt", "a.txt"]
+            .iter()
+            .map(|name| source.join(name).is_file())
+            .collect();
+        let second = task.run_task(&mut Logger::disabled()).unwrap();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(first.unwrap_err().kind(), io::ErrorKind::Interrupted);
+        let third = OffsetDateTime::from(dates[2]);
+        assert_eq!(state, Some(third - time::Duration::nanoseconds(1)));
+        assert_eq!(left, [false, false, true, true]);
+        assert_eq!(second.moved, 2);
+    }
+
+    /// verifies `--sort-order` numbers the files of a month folder by date, size, or name, and `--reverse` by the
+    /// opposite order, while files are numbered in the order they were collected in without it
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() gives counters in another order than the chosen one
+    /// - OrganizeTask::new() accepts `--reverse` without `--sort-order` or an unknown order
+    #[test]
+    fn organize_task_run_task_sort_order() {
+        for (options, expected) in [
+            (&[][..], ["aaa", "b", "cc"]),
+            (&["--sort-order", "date"][..], ["cc", "aaa", "b"]),
+            (
+                &["--sort-order", "date", "--reverse"][..],
+                ["b", "aaa", "cc"],
+            ),
+            (&["--sort-order", "size"][..], ["b", "cc", "aaa"]),
+            (&["--sort-order", "name"][..], ["aaa", "b", "cc"]),
+        ] {
+            // create inputs, three files an hour apart in 2001-09 whose names, dates, and sizes run in different orders
+            let path_buf = PathBuf::from("./organize_task_run_task_sort_order_test");
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+
+            // create mock directories
+            create_dir_all(&source).unwrap();
+            create_dir_all(&target).unwrap();
+            for (hours, (name, contents)) in [("c.txt", "cc"), ("a.txt", "aaa"), ("b.txt", "b")]
+                .into_iter()
+                .enumerate()
+            {
+                write(source.join(name), contents).unwrap();
+                File::options()
+                    .write(true)
+                    .open(source.join(name))
+                    .unwrap()
+                    .set_modified(
+                        SystemTime::UNIX_EPOCH
+                            + Duration::from_secs(1_000_000_000 + hours as u64 * 3600),
+                    )
+                    .unwrap();
+            }
+
+            // run test
+            let task = OrganizeTask::new(
+                [
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from("--date"),
+                    String::from("modified"),
+                ]
+                .into_iter()
+                .chain(options.iter().map(|option| option.to_string())),
+            )
+            .unwrap();
+            let report = task.run_task(&mut Logger::disabled()).unwrap();
+            let test_result = [0, 1, 2].map(|index| {
+                read_to_string(target.join(format!("2001/2001-09/2001-09_{index}.txt"))).unwrap()
+            });
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            assert_eq!(report.moved, 3);
+            assert_eq!(test_result, expected, "{options:?}");
+        }
+        for invalid in [&["--reverse"][..], &["--sort-order", "random"][..]] {
+            assert!(
+                OrganizeTask::new([".", "."].iter().chain(invalid).map(|arg| arg.to_string()))
+                    .is_err()
+            );
+        }
+    }
+
+    /// verifies `--link hard` leaves every source file in place and links it into the target sharing its inode, and
+    /// copies it instead when the target is on another file system
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_with() moves a source file, or places a copy where a link is possible
+    /// - OrganizeTask::run_with() does not copy a file a link cannot reach
+    /// - OrganizeTask::new() accepts `--link` along with `--safe-move` or an unknown link mode
+    #[test]
+    fn organize_task_run_with_link() {
+        let run = |fs: MockFileSystem| {
+            // create inputs
+            let (path_buf, source, target) =
+                dated_mock("organize_task_run_with_link", &[("a.jpg", "a")]);
+
+            // run test
+            let task = OrganizeTask::new(
+                [
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from("--date"),
+                    String::from("modified"),
+                    String::from("--link"),
+                    String::from("hard"),
+                ]
+                .into_iter(),
+            )
+            .unwrap();
+            let report = task
+                .run_with(&fs, &mut Logger::disabled(), Progress::hidden())
+                .unwrap();
+            let organized = target.join("2001/2001-09/2001-09_0.jpg");
+            let contents = read_to_string(&organized).unwrap();
+            let kept = source.join("a.jpg").is_file();
+            #[cfg(unix)]
+            let shared = {
+                use std::os::unix::fs::MetadataExt;
+                let (original, linked) = (
+                    source.join("a.jpg").metadata().unwrap(),
+                    organized.metadata().unwrap(),
+                );
+                Some(original.ino() == linked.ino() && linked.nlink() == 2)
+            };
+            #[cfg(not(unix))]
+            let shared: Option<bool> = None;
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            assert_eq!((report.moved, contents.as_str(), kept), (1, "a", true));
+            shared
+        };
+
+        let linked = run(MockFileSystem::default());
+        let copied = run(MockFileSystem {
+            rename_error: Some(io::ErrorKind::CrossesDevices),
+            ..MockFileSystem::default()
+        });
+        if cfg!(unix) {
+            assert_eq!((linked, copied), (Some(true), Some(false)));
+        }
+        for invalid in [&["--link", "hard", "--safe-move"][..], &["--link", "soft"]] {
+            let args = [".", "."].iter().chain(invalid).map(|arg| arg.to_string());
+            assert!(OrganizeTask::new(args.collect::<Vec<_>>().into_iter()).is_err());
+        }
+    }
+
+    /// verifies `--safe-move` organizes by a verified copy even where a rename would be possible, and fails a file
+    /// whose copy does not match it without removing the original
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_with() renames a file or leaves it in the source after a verified copy
+    /// - OrganizeTask::run_with() removes a file whose copy does not match it
+    #[test]
+    fn organize_task_run_with_safe_move() {
+        let run = |fs: MockFileSystem| {
+            // create inputs
+            let (path_buf, source, target) =
+                dated_mock("organize_task_run_with_safe_move", &[("a.jpg", "a")]);
+
+            // run test
+            let task = OrganizeTask::new(
+                [
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from("--date"),
+                    String::from("modified"),
+                    String::from("--safe-move"),
+                    String::from("--keep-going"),
+                ]
+                .into_iter(),
+            )
+            .unwrap();
+            let report = task
+                .run_with(&fs, &mut Logger::disabled(), Progress::hidden())
+                .unwrap();
+            let kept = source.join("a.jpg").is_file();
+            let copied = target.join("2001/2001-09/2001-09_0.jpg").is_file();
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            (report.moved, report.failed.len(), kept, copied)
+        };
+
+        // renames fail so only a copy can organize the file
+        let no_rename = MockFileSystem {
+            rename_error: Some(io::ErrorKind::PermissionDenied),
+            ..MockFileSystem::default()
+        };
+        assert_eq!(run(no_rename.clone()), (1, 0, false, true));
+        assert_eq!(
+            run(MockFileSystem {
+                corrupt_copies: true,
+                ..no_rename
+            }),
+            (0, 1, true, true)
+        );
+    }
+
+    /// verifies a run stops after the file it is working on once its cancel flag is set, and reports the rest of the
+    /// files as neither moved nor failed
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_with_progress() moves files after the flag is set or does not report the run as cancelled
+    #[test]
+    fn organize_task_run_with_cancel() {
+        // create inputs
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_with_cancel",
+            &[("a.jpg", "a.jpg"), ("b.jpg", "b.jpg"), ("c.jpg", "c.jpg")],
+        );
+        let flag = Arc::new(AtomicBool::new(false));
+
+        // run test, the flag is set as the first file finishes as Ctrl-C would be
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+            ]
+            .into_iter(),
+        )
+        .unwrap()
+        .with_cancel(flag.clone());
+        let report = task
+            .run_with_progress(&mut Logger::disabled(), &mut |_, _| {
+                flag.store(true, std::sync::atomic::Ordering::Relaxed)
+            })
+            .unwrap();
+        let left = source.read_dir().unwrap().count();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert!(report.cancelled);
+        assert_eq!(report.moved, 1);
+        assert!(report.failed.is_empty());
+        assert_eq!(left, 2);
+        assert_eq!(report.exit_code(), crate::EXIT_PARTIAL_FAILURE);
+    }
+
+    /// verifies `--index-scope global` numbers files across every folder in processing order starting past existing
+    /// indices, while `--index-scope folder` keeps a counter per folder
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() does not share the counter between folders in global scope
+    /// - OrganizeTask::run_task() reuses an existing index in global scope or shares counters in folder scope
+    #[test]
+    fn organize_task_run_task_index_scope() {
+        for (scope, expected) in [
+            (
+                "folder",
+                [
+                    "2001/2001-09/2001-09_0.txt",
+                    "2002/2002-01/2002-01_5.txt",
+                    "2001/2001-09/2001-09_1.txt",
+                ],
+            ),
+            (
+                "global",
+                [
+                    "2001/2001-09/2001-09_5.txt",
+                    "2002/2002-01/2002-01_6.txt",
+                    "2001/2001-09/2001-09_7.txt",
+                ],
+            ),
+        ] {
+            // create inputs, 1_000_000_000 and 1_010_000_000 seconds after the epoch fall in 2001-09 and 2002-01
+            let path_buf = PathBuf::from("./organize_task_run_task_index_scope_test");
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+            let dates = [
+                ("1.txt", 1_000_000_000),
+                ("2.txt", 1_010_000_000),
+                ("3.txt", 1_000_000_000),
+            ];
+
+            // create mock directories, 2002-01 already holds a file whose index leaves a gap below it
+            create_dir_all(&source).unwrap();
+            create_dir_all(target.join("2002/2002-01")).unwrap();
+            File::create(target.join("2002/2002-01/2002-01_4.txt")).unwrap();
+            for (name, seconds) in dates {
+                File::create(source.join(name))
+                    .unwrap()
+                    .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+                    .unwrap();
+            }
+
+            // run test
+            let task = OrganizeTask::new(
+                [
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from("--date"),
+                    String::from("modified"),
+                    String::from("--index-scope"),
+                    scope.to_string(),
+                ]
+                .into_iter(),
+            )
+            .unwrap();
+            let report = task.run_task(&mut Logger::disabled()).unwrap();
+            let test_result: Vec<bool> = expected
+                .iter()
+                .map(|file| target.join(file).is_file())
+                .collect();
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            assert_eq!(report.moved, 3);
+            assert_eq!(test_result, [true, true, true]);
+        }
+    }
+
+    /// verifies `--min-free-inodes` stops a run that would leave too few inodes before anything is moved, and only warns
+    /// under `--force`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() moves a file or does not error when the reserve cannot be met
+    /// - OrganizeTask::run_task() does not organize the file and warn when `--force` is given
+    #[cfg(unix)]
+    #[test]
+    fn organize_task_run_task_min_free_inodes() {
+        use crate::tools::free_space::available_inodes;
+
+        for force in [false, true] {
+            // create inputs
+            let path_buf = PathBuf::from("./organize_task_run_task_min_free_inodes_test");
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+
+            // create mock directories
+            create_dir_all(&source).unwrap();
+            create_dir_all(&target).unwrap();
+            File::create(source.join("a.txt")).unwrap();
+
+            // run test, no volume has this many inodes to spare
+            let mut args = vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--min-free-inodes"),
+                u64::MAX.to_string(),
+            ];
+            if force {
+                args.push(String::from("--force"));
+            }
+            let task = OrganizeTask::new(args.into_iter()).unwrap();
+            let limited = available_inodes(&target).unwrap().is_some();
+            let test_result = task.run_task(&mut Logger::disabled());
+            let kept = source.join("a.txt").exists();
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            // volumes that do not limit inodes are not checked
+            if !limited {
+                assert!(test_result.is_ok());
+            } else if force {
+                let report = test_result.unwrap();
+                assert_eq!((report.moved, report.warnings.len(), kept), (1, 1, false));
+            } else {
+                assert_eq!(test_result.unwrap_err().kind(), io::ErrorKind::StorageFull);
+                assert!(kept);
+            }
+        }
+    }
+
+    /// verifies an empty source is reported as a warning naming it, and is an error under `--fail-on-empty`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() does not warn about an empty source or the warning does not name it
+    /// - OrganizeTask::run_task() does not error on an empty source when `--fail-on-empty` is given
+    #[test]
+    fn organize_task_run_task_empty_source() {
+        // create inputs
+        let path_buf = PathBuf::from("./organize_task_run_task_empty_source_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+
+        // create mock directories
+        create_dir_all(&source).unwrap();
+        create_dir_all(&target).unwrap();
+
+        // run test
+        let run = |fail_on_empty: bool| {
+            let mut args = vec![source.display().to_string(), target.display().to_string()];
+            if fail_on_empty {
+                args.push(String::from("--fail-on-empty"));
+            }
+            OrganizeTask::new(args.into_iter())
+                .unwrap()
+                .run_task(&mut Logger::disabled())
+        };
+        let warned = run(false);
+        let failed = run(true);
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        let report = warned.unwrap();
+        assert_eq!(report.moved, 0);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains(&source.display().to_string()));
+        let err = failed.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().contains(&source.display().to_string()));
+    }
+
+    /// verifies `--prefix-from-parent` puts the name of the folder each file came from in front of its label, files at
+    /// the root of the source take the name of the source, and each prefix keeps its own counter
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() does not name a file after its parent folder
+    /// - OrganizeTask::run_task() shares counters between prefixes or overwrites an earlier organized file
+    #[test]
+    fn organize_task_run_task_prefix_from_parent() {
+        // create inputs, the target already holds a file from inbox
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_prefix_from_parent",
+            &[("inbox/a.jpg", ""), ("camera/b.jpg", ""), ("c.jpg", "")],
+        );
+        let folder = target.join("2001/2001-09");
+        create_dir_all(&folder).unwrap();
+        write(folder.join("inbox_2001-09_0.jpg"), "earlier").unwrap();
+
+        // run test
+        let task = OrganizeTask::new(
+            vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--prefix-from-parent"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let test_result = task.run_task(&mut Logger::disabled());
+        let organized = [
+            "inbox_2001-09_0.jpg",
+            "inbox_2001-09_1.jpg",
+            "camera_2001-09_0.jpg",
+            "source_2001-09_0.jpg",
+        ]
+        .map(|name| folder.join(name).is_file());
+        let earlier = read_to_string(folder.join("inbox_2001-09_0.jpg"));
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(test_result.unwrap().moved, 3);
+        assert_eq!(organized, [true; 4]);
+        assert_eq!(earlier.unwrap(), "earlier");
+    }
+
+    /// verifies `--date-index-sep` places the chosen character between the date label and the counter, counting on
+    /// from the files already named with it, while `--index-sep` still joins the parent folder prefix
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() does not use the chosen separator before the counter
+    /// - OrganizeTask::new() accepts a digit as the separator
+    #[test]
+    fn organize_task_run_task_date_index_sep() {
+        // create inputs, the target already holds a file from inbox
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_date_index_sep",
+            &[("inbox/a.jpg", ""), ("inbox/b", "")],
+        );
+        let folder = target.join("2001/2001-09");
+        create_dir_all(&folder).unwrap();
+        write(folder.join("inbox-2001-09.0.jpg"), "earlier").unwrap();
+        let args = |sep: &str| {
+            vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--prefix-from-parent"),
+                String::from("--index-sep"),
+                String::from("-"),
+                String::from("--date-index-sep"),
+                String::from(sep),
+            ]
+            .into_iter()
+        };
+
+        // run test
+        let task = OrganizeTask::new(args(".")).unwrap();
+        let test_result = task.run_task(&mut Logger::disabled());
+        let mut names: Vec<String> = folder
+            .read_dir()
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(test_result.unwrap().moved, 2);
+        assert_eq!(
+            names,
+            [
+                "inbox-2001-09.0.jpg",
+                "inbox-2001-09.1.jpg",
+                "inbox-2001-09.2"
+            ]
+        );
+        assert_eq!(Naming::default().counter_sep(), '_');
+        assert!(OrganizeTask::new(args("1")).is_err());
+        assert!(OrganizeTask::new(args("--")).is_err());
+    }
+
+    /// verifies `--same-name-policy shared` gives a jpg/raw pair from the same folder the same counter, continuing after
+    /// the files already in the target, while a file with another stem gets the next counter
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() gives files differing only by extension separate counters
+    /// - OrganizeTask::run_task() reuses a counter for a file with another stem
+    #[test]
+    fn organize_task_run_task_same_name_shared() {
+        // create inputs, the target already holds five organized files
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_same_name_shared",
+            &[
+                ("IMG_001.jpg", ""),
+                ("IMG_001.raw", ""),
+                ("IMG_002.jpg", ""),
+            ],
+        );
+        let folder = target.join("2001/2001-09");
+        create_dir_all(&folder).unwrap();
+        for index in 0..5 {
+            File::create(folder.join(format!("2001-09_{index}.jpg"))).unwrap();
+        }
+
+        // run test
+        let task = OrganizeTask::new(
+            vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--same-name-policy"),
+                String::from("shared"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let test_result = task.run_task(&mut Logger::disabled());
+        let organized = [
+            "2001-09_5.jpg",
+            "2001-09_5.raw",
+            "2001-09_6.jpg",
+            "2001-09_6.raw",
+        ]
+        .map(|name| folder.join(name).is_file());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(test_result.unwrap().moved, 3);
+        assert_eq!(organized, [true, true, true, false]);
+        assert!(parse_same_name_policy(Some(String::from("paired"))).is_err());
+    }
+
+    /// verifies `--same-name-policy shared` gives a sibling whose organized name would collide with its pair's, such as
+    /// IMG.jpg and IMG.JPG under `--case lower`, a fresh counter instead of overwriting the first file
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() places two siblings at the same name or reports a move that lost a file
+    #[test]
+    fn organize_task_run_task_same_name_collision() {
+        // create inputs, two siblings differing only by the case of their extension
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_same_name_collision",
+            &[
+                ("IMG.JPG", "upper"),
+                ("IMG.jpg", "lower"),
+                ("IMG.raw", "raw"),
+            ],
+        );
+        let folder = target.join("2001/2001-09");
+        // a case-insensitive file system holds only one of the two, which leaves nothing to collide
+        let distinct = source.read_dir().unwrap().count() == 3;
+
+        // run test
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--same-name-policy"),
+                String::from("shared"),
+                String::from("--case"),
+                String::from("lower"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+        let mut contents: Vec<String> = folder
+            .read_dir()
+            .unwrap()
+            .map(|entry| read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        contents.sort();
+        let names = ["2001-09_0.jpg", "2001-09_0.raw", "2001-09_1.jpg"]
+            .map(|name| folder.join(name).is_file());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        if distinct {
+            assert_eq!(report.moved, 3);
+            assert!(report.failed.is_empty());
+            assert_eq!(contents, ["lower", "raw", "upper"]);
+            assert_eq!(names, [true; 3]);
+        }
+    }
+
+    /// verifies `--confirm-over` runs unattended at or below the threshold and asks above it, moving on only after a yes
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::confirm_run() asks for confirmation at or below the threshold
+    /// - OrganizeTask::confirm_run() does not ask above the threshold or proceeds without a yes
+    #[test]
+    fn organize_task_confirm_over() {
+        // create inputs
+        let task = OrganizeTask::new(
+            ["./src", "./src/organize", "--confirm-over", "2"]
+                .map(String::from)
+                .into_iter(),
+        )
+        .unwrap();
+        let run = |count: usize, answer: &str| {
+            let mut output: Vec<u8> = Vec::new();
+            let result = task.confirm_run(count, &mut answer.as_bytes(), &mut output);
+            (result.is_ok(), String::from_utf8(output).unwrap())
+        };
 
-    /// PathBuf to directory containing organized files
-    target: PathBuf,
-}
+        // at or below the threshold nothing is read, even without an answer
+        assert_eq!(run(0, ""), (true, String::new()));
+        assert_eq!(run(2, ""), (true, String::new()));
 
-/// RunTask trait implementation for Organize variant of Config enum
-impl RunTask for OrganizeTask {
-    /// run_task() driver function for Organize variant of Config, organizes files from &self.source into &self.target based on files last modification date.
-    /// This function will change to using creation dates whenever it is possible to move files across file systems (determine if files exist on same fs)
+        // above it only a yes lets the run continue
+        let (confirmed, question) = run(3, "y\n");
+        assert!(confirmed);
+        assert!(question.starts_with("3 files found in './src', more than --confirm-over 2"));
+        assert!(!run(3, "n\n").0);
+        assert!(!run(3, "").0);
+        assert_eq!(task.confirm_over, Some(2));
+        assert!(OrganizeTask::new(
+            ["./src", "./src/organize", "--confirm-over", "many"]
+                .map(String::from)
+                .into_iter()
+        )
+        .is_err());
+    }
+
+    /// verifies `--deref-symlinks` copies the file a symbolic link points to from outside the source into the target,
+    /// leaves both the link and the real file in place, and records the link in the report
     ///
     /// # Arguments
     ///
-    /// `&self` reference to Config enum
+    /// None
     ///
     /// # Errors
     ///
-    /// - collect_files() call fails
-    /// - metadata is not retrievable for a given file
-    /// - modification date is not retrievable for a given file
-    /// - get_num_files() call fails
-    /// - fs::create_dir_all() call fails
-    /// - fs::rename() call fails
-    fn run_task(&self) -> Result<(), io::Error> {
-        // empty vector to store PathBufs of found files
-        let mut file_vec: Vec<PathBuf> = Vec::new();
+    /// - OrganizeTask::run_task() moves the link or does not copy the contents of the file it points to
+    /// - OrganizeTask::run_task() removes the link or the real file
+    /// - OrganizeTask::run_task() does not record the link and the file it points to in the report
+    #[cfg(unix)]
+    #[test]
+    fn organize_task_run_task_deref_symlinks() {
+        use std::os::unix::fs::symlink;
 
-        // iterator containing PathBufs for all files found at the source directory
-        let files = collect_files(&self.source, &mut file_vec)?.iter();
+        // create inputs, the real file lives outside of the source
+        let (path_buf, source, target) = dated_mock("organize_task_run_task_deref_symlinks", &[]);
+        let outside = path_buf.join("outside");
+        dated_file(&outside.join("photo.jpg"), "contents");
+        let real = outside.join("photo.jpg").canonicalize().unwrap();
+        symlink(&real, source.join("link.jpg")).unwrap();
 
-        /* cache to hold the number of files in a given directory, used for naming files, string is
-        used as PathBufs map to different keys, and OsString does not implement the Eq and Hash
-        Traits, this will cause paths containing non unicode to break when unwrapped below however
-        this appears to be rare */
-        let mut count_cache: HashMap<String, usize> = HashMap::new();
+        // run test
+        let task = OrganizeTask::new(
+            vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--deref-symlinks"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let test_result = task.run_task(&mut Logger::disabled());
+        let organized = target.join("2001/2001-09/2001-09_0.jpg");
+        let copied = (organized.is_symlink(), read_to_string(&organized));
+        let link = source.join("link.jpg").is_symlink();
+        let original = read_to_string(outside.join("photo.jpg"));
 
-        // temporary counter to hold the number of files in a directory
-        let mut count: usize;
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
 
-        // iterate over collected files
-        for file in files {
-            // creation date of file
-            let c_date: time::OffsetDateTime = file.metadata()?.modified()?.into();
-
-            // formatted creation date PathBuf
-            let fc_date = PathBuf::from(
-                c_date
-                    .format(&format_description!("[year]/[year]-[month]"))
-                    .unwrap(),
-            ); // assumes .format will not error which is reasonable
-
-            // target directory / file PathBuf
-            let mut target: PathBuf = [&self.target, &fc_date].iter().collect();
-            let key = &target.to_str().unwrap().to_string();
-
-            // check the hashmap to see if target_folder exists
-            if count_cache.contains_key(key) {
-                // if exists increment the counter
-                count_cache
-                    .entry(key.clone())
-                    .and_modify(|count| *count += 1);
-            } else {
-                if target.exists() {
-                    // get the number of files in the target directory + 1
-                    count = get_num_files(&target)? + 1;
-                } else {
-                    // since ./YYYY/YYYY-MM folder(s) does/do not exist in target directory yet, create it/them
-                    create_dir_all(&target)?;
+        let report = test_result.unwrap();
+        assert_eq!(report.moved, 1);
+        assert_eq!(report.symlinks, [(source.join("link.jpg"), real)]);
+        assert!(!copied.0);
+        assert_eq!(copied.1.unwrap(), "contents");
+        assert!(link);
+        assert_eq!(original.unwrap(), "contents");
+    }
 
-                    // set the counter to one as this is a new directory
-                    count = 1;
-                }
-                count_cache.insert(key.clone(), count);
+    /// verifies `--manifest` records the path every moved file had relative to its source along with its destination
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() does not write the manifest or records a path that is not relative to the source
+    /// - OrganizeTask::new() does not error when `--manifest` is combined with `--plan`
+    #[test]
+    fn organize_task_run_task_manifest() {
+        // create inputs
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_manifest",
+            &[("top.jpg", ""), ("trip/day1/a.jpg", "")],
+        );
+        let manifest = path_buf.join("manifest.csv");
+
+        // run test
+        let args = |extra: &str| {
+            vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--manifest"),
+                manifest.display().to_string(),
+                String::from(extra),
+            ]
+            .into_iter()
+        };
+        let report = OrganizeTask::new(args("--keep-going"))
+            .unwrap()
+            .run_task(&mut Logger::disabled());
+        let test_result = read_to_string(&manifest);
+        let plan = OrganizeTask::new(args("--plan"));
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(report.unwrap().moved, 2);
+        let lines: Vec<String> = test_result.unwrap().lines().map(String::from).collect();
+        let line = |original: &str, name: &str| {
+            format!(
+                "{},{original},{}",
+                source.display(),
+                target.join("2001/2001-09").join(name).display()
+            )
+        };
+        assert_eq!(
+            lines,
+            [
+                String::from("source,original,destination"),
+                line("top.jpg", "2001-09_0.jpg"),
+                line("trip/day1/a.jpg", "2001-09_1.jpg"),
+            ]
+        );
+        assert!(plan.is_err());
+    }
+
+    /// verifies the files of a linked directory followed with `--follow` are named and recorded by their logical path
+    /// inside the source, not by the location the link resolves to
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() prefixes a file with the name of the folder the link resolves to
+    /// - the manifest records a file by its resolved path
+    #[cfg(unix)]
+    #[test]
+    fn organize_task_run_task_follow_linked_dir() {
+        use std::os::unix::fs::symlink;
+
+        // create inputs, the source holds a link named trip to a folder named elsewhere outside of it
+        let (path_buf, source, target) =
+            dated_mock("organize_task_run_task_follow_linked_dir", &[]);
+        let outside = path_buf.join("elsewhere");
+        let manifest = path_buf.join("manifest.csv");
+        dated_file(&outside.join("a.jpg"), "");
+        symlink("../elsewhere", source.join("trip")).unwrap();
+
+        // run test
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--follow"),
+                String::from("--prefix-from-parent"),
+                String::from("--manifest"),
+                manifest.display().to_string(),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+        let organized = target.join("2001/2001-09/trip_2001-09_0.jpg");
+        let test_result = read_to_string(&manifest).unwrap();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            report.moves,
+            [(source.join("trip/a.jpg"), organized.clone())]
+        );
+        assert_eq!(
+            test_result,
+            format!(
+                "source,original,destination\n{},trip/a.jpg,{}\n",
+                source.display(),
+                organized.display()
+            )
+        );
+    }
+
+    /// verifies `--dir-mode` and `--file-mode` give every directory the run creates and every file it places the
+    /// requested permission bits, while a directory that already existed keeps its own
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() leaves a created directory or a moved file with the permissions of the umask
+    /// - OrganizeTask::run_task() changes the permissions of the target directory, which it did not create
+    /// - OrganizeTask::new() accepts a mode that is not octal
+    #[cfg(unix)]
+    #[test]
+    fn organize_task_run_task_modes() {
+        use std::fs::{metadata, set_permissions, Permissions};
+        use std::os::unix::fs::PermissionsExt;
+
+        // create inputs, modes more restrictive than requested show they were applied
+        let (path_buf, source, target) =
+            dated_mock("organize_task_run_task_modes", &[("a.jpg", "")]);
+        set_permissions(&target, Permissions::from_mode(0o700)).unwrap();
+        let file = source.join("a.jpg");
+        set_permissions(&file, Permissions::from_mode(0o600)).unwrap();
+
+        // run test
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--dir-mode"),
+                String::from("2775"),
+                String::from("--file-mode"),
+                String::from("664"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+        let mode = |path: PathBuf| metadata(path).unwrap().permissions().mode() & 0o7777;
+        let test_result = [
+            mode(target.clone()),
+            mode(target.join("2001")),
+            mode(target.join("2001/2001-09")),
+            mode(target.join("2001/2001-09/2001-09_0.jpg")),
+        ];
+        let invalid = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--dir-mode"),
+                String::from("rwx"),
+            ]
+            .into_iter(),
+        );
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(report.moved, 1);
+        assert_eq!(test_result, [0o700, 0o2775, 0o2775, 0o664]);
+        assert!(invalid.is_err());
+    }
+
+    /// verifies a symbolic link in the source is left alone by default and moved as the link itself with `--no-follow`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() moves the link without `--no-follow`
+    /// - OrganizeTask::run_task() moves the file the link points to or copies its contents with `--no-follow`
+    #[cfg(unix)]
+    #[test]
+    fn organize_task_run_task_symlink_policy() {
+        use std::os::unix::fs::symlink;
+
+        for no_follow in [false, true] {
+            // create inputs, the real file lives outside of the source
+            let (path_buf, source, target) =
+                dated_mock("organize_task_run_task_symlink_policy", &[]);
+            let outside = path_buf.join("outside");
+            dated_file(&outside.join("photo.jpg"), "contents");
+            let real = outside.join("photo.jpg").canonicalize().unwrap();
+            symlink(&real, source.join("link.jpg")).unwrap();
+
+            // run test
+            let mut args = vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+            ];
+            if no_follow {
+                args.push(String::from("--no-follow"));
             }
+            let test_result = OrganizeTask::new(args.into_iter())
+                .unwrap()
+                .run_task(&mut Logger::disabled());
+            let organized = target.join("2001/2001-09/2001-09_0.jpg");
+            let moved = (organized.is_symlink(), source.join("link.jpg").is_symlink());
+            let original = read_to_string(outside.join("photo.jpg"));
 
-            // add final formatting to target file for move
-            target.push(format!(
-                "{}_{}.{}",
-                &key[key.len() - 7..],
-                count_cache.get(&key.clone()).unwrap() - 1, //this will not error as above code ensures that this key is valid
-                file.extension()
-                    .unwrap_or(&OsString::from("")) // handles no file extension case
-                    .to_str()
-                    .unwrap()
-                    .to_string()
-            ));
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
 
-            // move file to target using YYYY-MM_#
-            // may want to use rename if on same file system and fs::copy / fs::remove_file if not
-            // look into partial copies
-            rename(file, target)?;
+            assert_eq!(test_result.unwrap().moved, usize::from(no_follow));
+            assert_eq!(moved, (no_follow, !no_follow));
+            assert_eq!(original.unwrap(), "contents");
         }
+    }
 
-        Ok(())
+    /// verifies `--filter` organizes only the collected files the expression selects and leaves the rest in place
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() moves a file the filter does not select or leaves one it does in place
+    #[test]
+    fn organize_task_run_task_filter() {
+        // create inputs, only the large jpg matches
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_filter",
+            &[
+                ("large.jpg", "0123456789"),
+                ("small.jpg", "0"),
+                ("large.png", "0123456789"),
+            ],
+        );
+
+        // run test
+        let task = OrganizeTask::new(
+            vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--filter"),
+                String::from("ext in [JPG] and size > 5 and age > 365d"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let test_result = task.run_task(&mut Logger::disabled());
+        let organized = read_to_string(target.join("2001/2001-09/2001-09_0.jpg"));
+        let remaining =
+            ["large.jpg", "small.jpg", "large.png"].map(|name| source.join(name).is_file());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(test_result.unwrap().moved, 1);
+        assert_eq!(organized.unwrap(), "0123456789");
+        assert_eq!(remaining, [false, true, true]);
     }
-}
 
-impl OrganizeTask {
-    /// OrganizeTask struct initializer
+    /// verifies files skipped for different reasons are counted per reason, both in the summary and the JSON report
     ///
     /// # Arguments
     ///
-    /// `args` - an iterator containing Strings to be used as arguments
+    /// None
     ///
     /// # Errors
     ///
-    /// - `./source/` path not provided
-    /// - `./source/` does not correspond to valid directory
-    /// - `./target/` path not provided
-    /// - `./target/` does not correspond to valid directory
-    pub fn new(mut args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
-        // ensures source path is provided
-        let source = match args.next() {
-            Some(arg) => PathBuf::from(arg),
-            None => return Err("no 'source' path provided"),
-        };
+    /// - OrganizeTask::run_task() does not record a file left out by `--filter` or a duplicate under its reason
+    /// - TaskReport::skip_counts() miscounts the reasons
+    #[test]
+    fn organize_task_run_task_skip_reasons() {
+        // create inputs, the target already holds the contents of dup.jpg
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_skip_reasons",
+            &[
+                ("dup.jpg", "dup"),
+                ("new.jpg", "new"),
+                ("a.png", "a"),
+                ("b.png", "b"),
+            ],
+        );
+        create_dir_all(target.join("archive")).unwrap();
+        write(target.join("archive/old.jpg"), "dup").unwrap();
 
-        // ensures the source path corresponds to a valid directory
-        if !source.is_dir() {
-            return Err("'source' path does not correspond to a valid directory");
-        }
+        // run test
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--filter"),
+                String::from("ext == jpg"),
+                String::from("--skip-duplicates"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(report.moved, 1);
+        assert_eq!(
+            report.skip_counts(),
+            [(SkipReason::Filtered, 2), (SkipReason::Duplicate, 1)]
+        );
+        assert!(report
+            .to_string()
+            .contains("\n  skips filtered: 2\n  skips duplicate: 1\n"));
+        assert!(report
+            .to_json()
+            .to_string()
+            .contains(r#""skip_reasons":{"filtered":2,"duplicate":1}"#));
+    }
+
+    /// verifies `--files-from` with `--null` organizes exactly the listed files, including one whose name contains a
+    /// newline, and leaves unlisted files in the source
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::new() accepts `--null` without `--files-from`
+    /// - OrganizeTask::run_task() splits the listed name at its newline or moves an unlisted file
+    #[cfg(unix)]
+    #[test]
+    fn organize_task_run_task_files_from_null() {
+        use crate::tools::path_list::write_paths;
 
-        // ensures target path is provided
-        let target = match args.next() {
-            Some(arg) => PathBuf::from(arg),
-            None => return Err("no 'target' path provided"),
+        // create inputs, only the file with a newline in its name is listed
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_files_from_null",
+            &[("first\nsecond.txt", ""), ("unlisted.txt", "")],
+        );
+        let list = path_buf.join("list");
+        let listed = source.join("first\nsecond.txt");
+        write_paths(
+            &mut File::create(&list).unwrap(),
+            std::slice::from_ref(&listed),
+            true,
+        )
+        .unwrap();
+        let args = |extra: &[&str]| {
+            let mut args = vec![source.display().to_string(), target.display().to_string()];
+            args.extend(
+                ["--date", "modified"]
+                    .iter()
+                    .chain(extra)
+                    .map(|arg| arg.to_string()),
+            );
+            args.into_iter()
         };
 
-        // ensures the target path corresponds to a valid directory
-        if !target.is_dir() {
-            return Err("'target' path does not correspond to a valid directory");
-        }
+        // run test
+        let rejected = OrganizeTask::new(args(&["--null"])).is_err();
+        let list_arg = list.display().to_string();
+        let task = OrganizeTask::new(args(&["--files-from", &list_arg, "-0"])).unwrap();
+        let test_result = task.run_task(&mut Logger::disabled());
+        let organized = target.join("2001/2001-09/2001-09_0.txt").is_file();
+        let remaining = [listed.exists(), source.join("unlisted.txt").is_file()];
 
-        Ok(Self { source, target })
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert!(rejected);
+        assert_eq!(test_result.unwrap().moved, 1);
+        assert!(organized);
+        assert_eq!(remaining, [false, true]);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// verifies `--compound-ext` groups and names `archive.tar.gz` by its whole `tar.gz` extension
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() groups the archive under `gz` or names it without `.tar.gz`
+    #[test]
+    fn organize_task_run_task_compound_ext() {
+        // create inputs
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_compound_ext",
+            &[("archive.tar.gz", "")],
+        );
 
-    /// verifies OrganizeTask::new() works correctly with valid arguments passed in
+        // run test
+        let task = OrganizeTask::new(
+            vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--group"),
+                String::from("extension"),
+                String::from("--compound-ext"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let test_result = task.run_task(&mut Logger::disabled());
+        let organized = target.join("tar.gz/2001-09_0.tar.gz").is_file();
+        let split = target.join("gz").exists();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(test_result.unwrap().moved, 1);
+        assert!(organized);
+        assert!(!split);
+    }
+
+    /// verifies `--resume` skips files a partial log records as moved and redoes logged moves that did not complete
     ///
     /// # Arguments
     ///
@@ -164,56 +5413,130 @@ mod tests {
     ///
     /// # Errors
     ///
-    /// - `./source/` path not provided
-    /// - `./source/` does not correspond to valid directory
-    /// - `./target/` path not provided
-    /// - `./target/` does not correspond to valid directory
+    /// - OrganizeTask::run_task() organizes a file whose logged move completed or does not count it as skipped
+    /// - OrganizeTask::run_task() skips a file whose logged destination is missing or that is not logged at all
     #[test]
-    fn organize_task_new_with_valid_args() {
-        // args iterator
-        let mut args = [
-            String::from("foo"),
-            String::from("bar"),
-            String::from("./src"),
-            String::from("./src/organize"),
-        ]
-        .into_iter();
+    fn organize_task_run_task_resume() {
+        // create inputs
+        let path_buf = PathBuf::from("./organize_task_run_task_resume_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let log = path_buf.join("organize.log");
 
-        // iterate to source location in iterator
-        args.next();
-        args.next();
+        // create mock directories, a.txt was logged and copied but left behind, b.txt was logged but never arrived
+        create_dir_all(&source).unwrap();
+        create_dir_all(target.join("earlier")).unwrap();
+        for file in ["a.txt", "b.txt", "c.txt"] {
+            File::create(source.join(file)).unwrap();
+        }
+        File::create(target.join("earlier").join("a.txt")).unwrap();
+        write(
+            &log,
+            format!(
+                "2001-09-09T01:46:40Z\tmove\t{}\t{}\n2001-09-09T01:46:41Z\tmove\t{}\t{}\n",
+                source.join("a.txt").display(),
+                target.join("earlier").join("a.txt").display(),
+                source.join("b.txt").display(),
+                target.join("earlier").join("b.txt").display(),
+            ),
+        )
+        .unwrap();
 
-        // source PathBuf
-        let source = PathBuf::from("./src");
+        // run test
+        let task = OrganizeTask::new(
+            vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--resume"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let mut logger = Logger::open(&log).unwrap();
+        let test_result = task.run_task(&mut logger);
+        let remaining: Vec<bool> = ["a.txt", "b.txt", "c.txt"]
+            .iter()
+            .map(|file| source.join(file).exists())
+            .collect();
+        let without_log = task.run_task(&mut Logger::disabled());
 
-        // target PathBuf
-        let target = PathBuf::from("./src/organize");
+        // clean up mock directories
+        drop(logger);
+        remove_dir_all(&path_buf).unwrap();
 
-        assert_eq!(OrganizeTask::new(args), Ok(OrganizeTask { source, target }));
+        let report = test_result.unwrap();
+        assert_eq!(report.moved, 2);
+        assert_eq!(report.skip_counts(), [(SkipReason::AlreadyDone, 1)]);
+        assert_eq!(remaining, [true, false, false]);
+        assert!(without_log.is_err());
     }
 
-    /// verifies OrganizeTask::new() errors if source path is not provided
+    /// verifies `--naming hash` gives files with identical contents identical names across runs regardless of processing
+    /// order, and that a re-run leaves an already organized file in place
     ///
     /// # Arguments
     ///
-    /// none
+    /// None
     ///
     /// # Errors
     ///
-    /// - OrganizeTask::new() does not error if the source path is not provided
+    /// - OrganizeTask::run_task() names identical contents differently in two runs or names files by a counter
+    /// - OrganizeTask::run_task() overwrites a file already organized under the same hashed name
     #[test]
-    fn organize_task_new_source_not_provided() {
-        // args iterator
-        let mut args = [String::from("foo"), String::from("bar")].into_iter();
+    fn organize_task_run_task_hash_naming() {
+        // create inputs
+        let path_buf = PathBuf::from("./organize_task_run_task_hash_naming_test");
+        let run = |name: &str, files: &[(&str, &str)]| {
+            let source = path_buf.join(name).join("source");
+            let target = path_buf.join(name).join("target");
+            create_dir_all(&target).unwrap();
+            for (file, contents) in files {
+                dated_file(&source.join(file), contents);
+            }
 
-        // iterate to source location in iterator
-        args.next();
-        args.next();
+            let task = OrganizeTask::new(
+                [
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from("--date"),
+                    String::from("modified"),
+                    String::from("--naming"),
+                    String::from("hash"),
+                ]
+                .into_iter(),
+            )
+            .unwrap();
+            let report = task.run_task(&mut Logger::disabled()).unwrap();
+            let mut names: Vec<String> = target
+                .join("2001/2001-09")
+                .read_dir()
+                .unwrap()
+                .map(|item| item.unwrap().file_name().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            (report, names, source)
+        };
 
-        assert!(OrganizeTask::new(args).is_err())
+        // run test, the second run sees the same contents under other names and in another order
+        let (first, first_names, _) = run("first", &[("a.txt", "same"), ("b.txt", "other")]);
+        let (second, second_names, _) = run("second", &[("z.txt", "other"), ("c.txt", "same")]);
+        let (_, _, source) = run("first", &[("d.txt", "same")]);
+        let rerun_kept = source.join("d.txt").exists();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!((first.moved, second.moved), (2, 2));
+        assert_eq!(first_names, second_names);
+        assert!(first_names
+            .iter()
+            .all(|name| name.len() == "2001-09_".len() + 8 + ".txt".len()
+                && name.starts_with("2001-09_")));
+        assert!(rerun_kept);
     }
 
-    /// verifies OrganizeTask::new() errors if source path provided is not a real directory
+    /// verifies `--skip-duplicates` leaves a file whose contents are already archived anywhere in the target in place
+    /// rather than adding it under a new number, while files with new contents of the same size are still organized
     ///
     /// # Arguments
     ///
@@ -221,52 +5544,128 @@ mod tests {
     ///
     /// # Errors
     ///
-    /// - OrganizeTask::new() does not error if the source path is not a valid directory
+    /// - OrganizeTask::run_task() organizes a file whose contents the target already holds
+    /// - OrganizeTask::run_task() skips a file of the same size with other contents
     #[test]
-    fn organize_task_new_source_is_not_dir() {
-        // args iterator
-        let mut args = [
-            String::from("foo"),
-            String::from("bar"),
-            String::from("not_a_dir"),
-            String::from("./src/organize"),
-        ]
-        .into_iter();
+    fn organize_task_run_task_skip_duplicates() {
+        // create inputs, the archived copy sits in another month than the incoming file would be organized to
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_skip_duplicates",
+            &[("a.txt", "same"), ("b.txt", "diff")],
+        );
+        let archived = target.join("1999/1999-01");
+        create_dir_all(&archived).unwrap();
+        write(archived.join("1999-01_0.txt"), "same").unwrap();
 
-        // iterate to source location in iterator
-        args.next();
-        args.next();
+        // run test
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--skip-duplicates"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+        let kept = source.join("a.txt").is_file();
+        let organized = read_to_string(target.join("2001/2001-09/2001-09_0.txt")).unwrap();
+        let numbered = target.join("2001/2001-09/2001-09_1.txt").exists();
 
-        assert!(OrganizeTask::new(args).is_err())
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(report.moved, 1);
+        assert_eq!(
+            report.skipped,
+            [(
+                source.join("a.txt"),
+                SkipReason::Duplicate,
+                format!("duplicate of {}", archived.join("1999-01_0.txt").display())
+            )]
+        );
+        assert!(kept);
+        assert_eq!(organized, "diff");
+        assert!(!numbered);
     }
 
-    /// verifies OrganizeTask::new() errors if target path is not provided
+    /// verifies OrganizeTask::plan_on() compares the planned layout against the target without changing anything
     ///
     /// # Arguments
     ///
-    /// none
+    /// None
     ///
     /// # Errors
     ///
-    /// - OrganizeTask::new() does not error if the target path is not provided
+    /// - OrganizeTask::plan_on() misses a file already in the target or plans a path that is taken
+    /// - OrganizeTask::plan_on() moves a file or creates a directory
     #[test]
-    fn organize_task_new_target_not_provided() {
-        // args iterator
-        let mut args = [
-            String::from("foo"),
-            String::from("bar"),
-            String::from("not_a_dir"),
-        ]
-        .into_iter();
+    fn organize_task_plan_on() {
+        // create inputs, 1_000_000_000 and 1_010_000_000 seconds after the epoch fall in 2001-09 and 2002-01
+        let path_buf = PathBuf::from("./organize_task_plan_on_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let month_dir = target.join("2001/2001-09");
+        let files = [
+            ("a.txt", "same", 1_000_000_000),
+            ("b.txt", "new", 1_000_000_000),
+            ("c.txt", "x", 1_010_000_000),
+        ];
 
-        // iterate to source location in iterator
-        args.next();
-        args.next();
+        /* create mock directories, 2001-09 already holds a copy of a.txt and a file whose index leaves a gap below it,
+        which planned names never fill */
+        create_dir_all(&source).unwrap();
+        create_dir_all(&month_dir).unwrap();
+        write(month_dir.join("2001-09_0.txt"), "same").unwrap();
+        write(month_dir.join("2001-09_3.txt"), "other").unwrap();
+        for (name, contents, seconds) in files {
+            write(source.join(name), contents).unwrap();
+            File::options()
+                .write(true)
+                .open(source.join(name))
+                .unwrap()
+                .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+                .unwrap();
+        }
 
-        assert!(OrganizeTask::new(args).is_err())
+        // run test
+        let mut task = OrganizeTask::from_paths(source.clone(), target.clone()).unwrap();
+        task.date = DateSource::Modified;
+        task.plan = true;
+        let mut collected: Vec<PathBuf> = Vec::new();
+        collect_files(&FileWalker::new(), &source, &mut collected).unwrap();
+        let test_result = task.plan_on(&collected, &HashMap::new());
+        let exit_code = task.run_task(&mut Logger::disabled()).unwrap().exit_code();
+        let untouched = (source.join("a.txt").is_file(), target.join("2002").exists());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        let plan = test_result.unwrap();
+        assert_eq!(exit_code, crate::EXIT_SUCCESS);
+        assert_eq!(untouched, (true, false));
+        assert_eq!(
+            plan.iter()
+                .map(|planned| &planned.status)
+                .collect::<Vec<_>>(),
+            [
+                &plan::PlanStatus::Unchanged(month_dir.join("2001-09_0.txt")),
+                &plan::PlanStatus::Added,
+                &plan::PlanStatus::Added,
+            ]
+        );
+        assert_eq!(plan[1].target, month_dir.join("2001-09_5.txt"));
+        assert_eq!(plan[2].target, target.join("2002/2002-01/2002-01_0.txt"));
+        assert_eq!(
+            render_plan(&plan).last().unwrap(),
+            "2 added, 1 unchanged, 0 conflicting"
+        );
     }
 
-    /// verifies OrganizeTask::new() errors if target path provided is not a real directory
+    /// verifies `--preserve-top` keeps the leading folders of every file in front of its date folders, fewer for a
+    /// file in a shallower folder and none for a file directly inside the source
     ///
     /// # Arguments
     ///
@@ -274,22 +5673,202 @@ mod tests {
     ///
     /// # Errors
     ///
-    /// - OrganizeTask::new() does not error if the target path is not a valid directory
+    /// - OrganizeTask::run_task() drops a preserved folder, keeps too many, or prefixes a file of the source itself
+    /// - OrganizeTask::new() accepts a negative `--preserve-top`
     #[test]
-    fn organize_task_new_target_is_not_dir() {
-        // args iterator
-        let mut args = [
-            String::from("foo"),
-            String::from("bar"),
-            String::from("./src"),
-            String::from("not_a_dir"),
+    fn organize_task_run_task_preserve_top() {
+        for (count, expected) in [
+            (
+                1,
+                [
+                    "2001/2001-09/2001-09_0.jpg",
+                    "trip/2001/2001-09/2001-09_0.jpg",
+                    "trip/2001/2001-09/2001-09_1.jpg",
+                ],
+            ),
+            (
+                2,
+                [
+                    "2001/2001-09/2001-09_0.jpg",
+                    "trip/2001/2001-09/2001-09_0.jpg",
+                    "trip/day1/2001/2001-09/2001-09_0.jpg",
+                ],
+            ),
+        ] {
+            // create inputs
+            let (path_buf, source, target) = dated_mock(
+                "organize_task_run_task_preserve_top",
+                &[
+                    ("a.jpg", ""),
+                    ("trip/b.jpg", ""),
+                    ("trip/day1/raw/c.jpg", ""),
+                ],
+            );
+
+            // run test
+            let task = OrganizeTask::new(
+                [
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from("--date"),
+                    String::from("modified"),
+                    String::from("--preserve-top"),
+                    count.to_string(),
+                ]
+                .into_iter(),
+            )
+            .unwrap();
+            let report = task.run_task(&mut Logger::disabled()).unwrap();
+            let test_result = expected.map(|file| target.join(file).is_file());
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            assert_eq!(report.moved, 3);
+            assert_eq!(test_result, [true; 3], "{count}");
+        }
+        assert!(OrganizeTask::new(
+            [".", ".", "--preserve-top", "-1"]
+                .map(String::from)
+                .into_iter()
+        )
+        .is_err());
+    }
+
+    /// verifies `--include-empty-dirs` recreates the empty directories of the source within the folders kept by
+    /// `--preserve-top`, and is refused without it
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() drops an empty directory, keeps more of it than `--preserve-top` allows, or recreates
+    ///   a directory only emptied by the run
+    /// - OrganizeTask::new() accepts `--include-empty-dirs` without `--preserve-top`
+    #[test]
+    fn organize_task_run_task_include_empty_dirs() {
+        // create inputs
+        let (path_buf, source, target) = dated_mock(
+            "organize_task_run_task_include_empty_dirs",
+            &[("trip/day1/a.jpg", "")],
+        );
+        for dir in ["trip/empty", "placeholder", "deep/a/b"] {
+            create_dir_all(source.join(dir)).unwrap();
+        }
+
+        // run test
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("modified"),
+                String::from("--preserve-top"),
+                String::from("2"),
+                String::from("--include-empty-dirs"),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let report = task.run_task(&mut Logger::disabled()).unwrap();
+        let test_result = [
+            "trip/day1/2001/2001-09/2001-09_0.jpg",
+            "trip/empty",
+            "placeholder",
+            "deep/a",
         ]
-        .into_iter();
+        .map(|path| target.join(path).exists());
+        let truncated = target.join("deep/a/b").exists();
+        let day1 = target.join("trip/day1").read_dir().unwrap().count();
 
-        // iterate to source location in iterator
-        args.next();
-        args.next();
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
 
-        assert!(OrganizeTask::new(args).is_err())
+        assert_eq!(report.moved, 1);
+        assert_eq!(test_result, [true; 4]);
+        assert!(!truncated);
+        assert_eq!(day1, 1);
+        assert!(report.created_dirs.contains(&target.join("placeholder")));
+        assert!(OrganizeTask::new(
+            [".", ".", "--include-empty-dirs"]
+                .map(String::from)
+                .into_iter()
+        )
+        .is_err());
+    }
+
+    /// verifies `--quarantine` moves a file that cannot be organized into the quarantine directory next to a note
+    /// describing the failure, while the other files are organized as usual
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_on() leaves the failed file in the source or does not write its note
+    /// - OrganizeTask::run_on() does not list the quarantined file as failed
+    /// - OrganizeTask::new() accepts `--quarantine` without `--keep-going`
+    #[test]
+    fn organize_task_run_on_quarantine() {
+        // create inputs, the sidecar of a.jpg holds no readable date while that of b.jpg does
+        let path_buf = PathBuf::from("./organize_task_run_on_quarantine_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let quarantine = path_buf.join("quarantine");
+
+        // create mock directories
+        create_dir_all(&source).unwrap();
+        create_dir_all(&target).unwrap();
+        for (name, contents) in [
+            ("a.jpg", "a"),
+            ("a.json", r#"{"date": "yesterday"}"#),
+            ("b.jpg", "b"),
+            ("b.json", r#"{"date": "2001-09-09"}"#),
+        ] {
+            write(source.join(name), contents).unwrap();
+        }
+
+        // run test
+        let task = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--date"),
+                String::from("sidecar"),
+                String::from("--keep-going"),
+                String::from("--quarantine"),
+                quarantine.display().to_string(),
+            ]
+            .into_iter(),
+        )
+        .unwrap();
+        let files = [source.join("a.jpg"), source.join("b.jpg")];
+        let report = task.run_on(&files, &mut Logger::disabled()).unwrap();
+        let kept = files[0].exists();
+        let quarantined = read_to_string(quarantine.join("a.jpg"));
+        let note = read_to_string(quarantine.join("a.jpg.error"));
+        let dated = target.join("2001/2001-09/2001-09_0.jpg").is_file();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(report.moved, 1);
+        assert_eq!(report.failed.len(), 1);
+        assert!(report.warnings.is_empty());
+        assert!(!kept);
+        assert_eq!(quarantined.unwrap(), "a");
+        let note = note.unwrap();
+        assert!(note.starts_with(&format!("source: {}\nerror: ", files[0].display())));
+        assert!(note.contains(&report.failed[0].1), "{note}");
+        assert!(dated);
+        assert!(OrganizeTask::new(
+            [".", ".", "--quarantine", "./quarantine"]
+                .map(String::from)
+                .into_iter()
+        )
+        .is_err());
     }
 }
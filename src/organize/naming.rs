@@ -0,0 +1,594 @@
+// declare cargo crates
+use std::ffi::OsStr;
+use std::path::Path;
+
+// declare local code
+use crate::tools::sanitize_filename::{is_illegal_char, sanitize_filename};
+use crate::tools::sha256::to_hex;
+
+/// number of hexadecimal digits of the content digest used by NameMode::Hash
+const SHORT_HASH: usize = 8;
+
+/// IndexWidth enum: number of digits the counter of an organized file name is zero padded to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexWidth {
+    /// pad every counter to a fixed number of digits, a width of one leaves counters unpadded
+    Fixed(usize),
+
+    /// pad the counters of each folder to the number of digits of the largest counter expected in that folder
+    Auto,
+}
+
+/// IndexScope enum: which organized files share a counter
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IndexScope {
+    /// every folder counts its own files from zero, or from the files already in it
+    #[default]
+    Folder,
+
+    /// a single counter runs across every folder so indices reflect the order files were processed in
+    Global,
+}
+
+/// NameMode enum: what follows the label of an organized file name
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NameMode {
+    /// a counter that continues after the files already in the folder
+    #[default]
+    Counter,
+
+    /// the first hexadecimal digits of the SHA-256 digest of the contents, so identical files are always given the same
+    /// name regardless of the order they are processed in
+    Hash,
+}
+
+/// SameNamePolicy enum: how files that differ only by extension, such as `IMG_001.jpg` and `IMG_001.raw`, are numbered
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SameNamePolicy {
+    /// every file is given its own counter
+    #[default]
+    Separate,
+
+    /// files sharing a stem in the same source folder that are organized into the same folder under the same label are
+    /// given the same counter, so `IMG_001.jpg` and `IMG_001.raw` become `2023-01_5.jpg` and `2023-01_5.raw`
+    Shared,
+}
+
+/// NameCase enum: the case the parts of an organized file name taken from the original file are converted to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NameCase {
+    /// the case of the original is kept
+    #[default]
+    Keep,
+
+    /// every letter is lowercased
+    Lower,
+
+    /// every letter is uppercased
+    Upper,
+}
+
+/// Naming struct: options controlling how organized files are named, `<label><sep><index>.<extension>`, or
+/// `<label><sep><hash>.<extension>` with NameMode::Hash, where `<sep>` is given by Naming::counter_sep(), the label may
+/// carry the name of the parent folder of the original file, see Naming::label()
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Naming {
+    /// number of digits the counter is padded to
+    pub index_width: IndexWidth,
+
+    /// character placed between the label and the counter, and between the parent folder prefix and the date label
+    pub index_sep: char,
+
+    /// character placed between the date label and the counter in place of &self.index_sep, None to use it
+    pub date_index_sep: Option<char>,
+
+    /// character used in place of characters that are illegal in file names
+    pub replacement: char,
+
+    /// which organized files share a counter
+    pub index_scope: IndexScope,
+
+    /// whether names end in a counter or a content hash
+    pub mode: NameMode,
+
+    /// whether the label is prefixed with the name of the folder the original file was in
+    pub prefix_from_parent: bool,
+
+    /// whether files that differ only by extension share a counter
+    pub same_name: SameNamePolicy,
+
+    /// character spaces are replaced with in the parts of the name taken from the original file, None to keep them
+    pub replace_spaces: Option<char>,
+
+    /// case the parts of the name taken from the original file are converted to
+    pub case: NameCase,
+}
+
+impl Default for Naming {
+    /// default Naming matches the original `YYYY-MM_#.ext` naming, counters are not padded
+    fn default() -> Self {
+        Self {
+            index_width: IndexWidth::Fixed(1),
+            index_sep: '_',
+            date_index_sep: None,
+            replacement: '_',
+            index_scope: IndexScope::Folder,
+            mode: NameMode::Counter,
+            prefix_from_parent: false,
+            same_name: SameNamePolicy::Separate,
+            replace_spaces: None,
+            case: NameCase::Keep,
+        }
+    }
+}
+
+impl Naming {
+    /// width() returns the number of digits counters are padded to in a folder whose largest counter is
+    /// `largest_index`
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Naming
+    /// `largest_index` the largest counter expected in the folder
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn width(&self, largest_index: usize) -> usize {
+        match self.index_width {
+            IndexWidth::Fixed(width) => width,
+            IndexWidth::Auto => largest_index.to_string().len(),
+        }
+    }
+
+    /// counter_sep() returns the character placed between the label and the counter or hash of an organized file,
+    /// &self.date_index_sep when one was given and &self.index_sep otherwise
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Naming
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn counter_sep(&self) -> char {
+        self.date_index_sep.unwrap_or(self.index_sep)
+    }
+
+    /// original_part() returns a part of an organized file name taken from the original file, such as its extension,
+    /// sanitized and then with its spaces replaced by &self.replace_spaces and its case converted to &self.case
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Naming
+    /// `part` the part of the original name
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn original_part(&self, part: &str) -> String {
+        let mut part = sanitize_filename(part, self.replacement);
+        if let Some(replacement) = self.replace_spaces {
+            part = part.replace(' ', &replacement.to_string());
+        }
+
+        match self.case {
+            NameCase::Keep => part,
+            NameCase::Lower => part.to_lowercase(),
+            NameCase::Upper => part.to_uppercase(),
+        }
+    }
+
+    /// label() returns the label of an organized file dated `date_label`, prefixed with the sanitized name of the
+    /// folder `file` is in and &self.index_sep when &self.prefix_from_parent is set, such as `inbox_2023-01`. Files
+    /// whose parent has no name, such as those directly inside a source given as `.`, keep the bare date label
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Naming
+    /// `file` a Path that corresponds to the original file
+    /// `date_label` the `YYYY-MM` label of the date of the file
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn label(&self, file: &Path, date_label: String) -> String {
+        let parent = file.parent().and_then(Path::file_name);
+        match parent {
+            Some(parent) if self.prefix_from_parent => format!(
+                "{}{}{}",
+                self.original_part(&parent.to_string_lossy()),
+                self.index_sep,
+                date_label
+            ),
+            _ => date_label,
+        }
+    }
+
+    /// file_name() builds the name of an organized file from its label, counter, and the extension of the original
+    /// file, the extension is sanitized (non unicode characters are replaced with U+FFFD) and normalized, see
+    /// original_part(), and files without an extension are given none so names never end in a dot
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Naming
+    /// `label` the label that prefixes every organized file name in a folder, for example `2023-01`
+    /// `index` the counter of the file within its folder
+    /// `width` the number of digits `index` is padded to
+    /// `extension` the extension of the original file
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn file_name(
+        &self,
+        label: &str,
+        index: usize,
+        width: usize,
+        extension: Option<&OsStr>,
+    ) -> String {
+        format!(
+            "{}{}{:0width$}{}",
+            label,
+            self.counter_sep(),
+            index,
+            self.extension(extension)
+        )
+    }
+
+    /// hashed_name() builds the name of an organized file from its label, the SHA-256 digest of its contents, and the
+    /// extension of the original file, see file_name()
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Naming
+    /// `label` the label that prefixes every organized file name in a folder, for example `2023-01`
+    /// `digest` the SHA-256 digest of the contents of the file
+    /// `full` whether the whole digest is used rather than its first digits, for the rare short hash shared by files
+    /// with different contents
+    /// `extension` the extension of the original file
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn hashed_name(
+        &self,
+        label: &str,
+        digest: &[u8; 32],
+        full: bool,
+        extension: Option<&OsStr>,
+    ) -> String {
+        let mut hash = to_hex(digest);
+        if !full {
+            hash.truncate(SHORT_HASH);
+        }
+
+        format!(
+            "{}{}{}{}",
+            label,
+            self.counter_sep(),
+            hash,
+            self.extension(extension)
+        )
+    }
+
+    /// extension() returns the sanitized extension of an organized file including its leading dot, or nothing for files
+    /// without one
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Naming
+    /// `extension` the extension of the original file
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn extension(&self, extension: Option<&OsStr>) -> String {
+        match extension {
+            Some(extension) => format!(".{}", self.original_part(&extension.to_string_lossy())),
+            None => String::new(),
+        }
+    }
+}
+
+/// parse_name_mode() parses the value of `--naming`, either `counter` or `hash`
+///
+/// # Arguments
+///
+/// `value` the value following `--naming`
+///
+/// # Errors
+///
+/// - `value` is neither `counter` nor `hash`
+pub fn parse_name_mode(value: Option<String>) -> Result<NameMode, &'static str> {
+    match value.as_deref() {
+        Some("counter") => Ok(NameMode::Counter),
+        Some("hash") => Ok(NameMode::Hash),
+        _ => Err("'--naming' must be followed by 'counter' or 'hash'"),
+    }
+}
+
+/// parse_same_name_policy() parses the value of `--same-name-policy`, either `separate` or `shared`
+///
+/// # Arguments
+///
+/// `value` the value following `--same-name-policy`
+///
+/// # Errors
+///
+/// - `value` is neither `separate` nor `shared`
+pub fn parse_same_name_policy(value: Option<String>) -> Result<SameNamePolicy, &'static str> {
+    match value.as_deref() {
+        Some("separate") => Ok(SameNamePolicy::Separate),
+        Some("shared") => Ok(SameNamePolicy::Shared),
+        _ => Err("'--same-name-policy' must be followed by 'separate' or 'shared'"),
+    }
+}
+
+/// parse_name_case() parses the value of `--case`, one of `keep`, `lower`, or `upper`
+///
+/// # Arguments
+///
+/// `value` the value following `--case`
+///
+/// # Errors
+///
+/// - `value` is not `keep`, `lower`, or `upper`
+pub fn parse_name_case(value: Option<String>) -> Result<NameCase, &'static str> {
+    match value.as_deref() {
+        Some("keep") => Ok(NameCase::Keep),
+        Some("lower") => Ok(NameCase::Lower),
+        Some("upper") => Ok(NameCase::Upper),
+        _ => Err("'--case' must be followed by 'lower', 'upper', or 'keep'"),
+    }
+}
+
+/// parse_index_width() parses the value of `--index-width`, either `auto` or a positive integer
+///
+/// # Arguments
+///
+/// `value` the value following `--index-width`
+///
+/// # Errors
+///
+/// - `value` is neither `auto` nor a positive integer
+pub fn parse_index_width(value: Option<String>) -> Result<IndexWidth, &'static str> {
+    match value.as_deref() {
+        Some("auto") => Ok(IndexWidth::Auto),
+        Some(value) => match value.parse::<usize>() {
+            Ok(width) if width > 0 => Ok(IndexWidth::Fixed(width)),
+            _ => Err("'--index-width' must be followed by 'auto' or a positive integer"),
+        },
+        None => Err("'--index-width' must be followed by 'auto' or a positive integer"),
+    }
+}
+
+/// parse_index_scope() parses the value of `--index-scope`, either `global` or `folder`
+///
+/// # Arguments
+///
+/// `value` the value following `--index-scope`
+///
+/// # Errors
+///
+/// - `value` is neither `global` nor `folder`
+pub fn parse_index_scope(value: Option<String>) -> Result<IndexScope, &'static str> {
+    match value.as_deref() {
+        Some("folder") => Ok(IndexScope::Folder),
+        Some("global") => Ok(IndexScope::Global),
+        _ => Err("'--index-scope' must be followed by 'global' or 'folder'"),
+    }
+}
+
+/// parse_name_char() parses an option value that must be a single character which is legal in file names, returns
+/// None if `value` is missing, is not exactly one character, or is illegal in file names
+///
+/// # Arguments
+///
+/// `value` the value following the option
+///
+/// # Errors
+///
+/// None
+pub fn parse_name_char(value: Option<String>) -> Option<char> {
+    let value = value?;
+    let mut chars = value.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !is_illegal_char(c) => Some(c),
+        _ => None,
+    }
+}
+
+/// parse_folder_name() parses an option value that must be a single folder name which is legal in file names, returns
+/// None if `value` is missing, contains a path separator, or would be changed by sanitize_filename()
+///
+/// # Arguments
+///
+/// `value` the value following the option
+///
+/// # Errors
+///
+/// None
+pub fn parse_folder_name(value: Option<String>) -> Option<String> {
+    let value = value?;
+
+    // sanitizing replaces separators and rejects `.`, `..`, and reserved names, so only safe names are unchanged
+    (sanitize_filename(&value, '_') == value).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies Naming::file_name() pads counters and uses the chosen separator
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Naming::file_name() does not produce the expected name
+    #[test]
+    fn naming_file_name() {
+        // create inputs
+        let naming = Naming {
+            index_width: IndexWidth::Fixed(4),
+            index_sep: '-',
+            date_index_sep: None,
+            replacement: '_',
+            index_scope: IndexScope::Folder,
+            mode: NameMode::Counter,
+            prefix_from_parent: false,
+            same_name: SameNamePolicy::Separate,
+            replace_spaces: None,
+            case: NameCase::Keep,
+        };
+
+        assert_eq!(
+            Naming::default().file_name("2023-01", 5, 1, Some(OsStr::new("jpg"))),
+            "2023-01_5.jpg"
+        );
+        assert_eq!(
+            naming.file_name("2023-01", 5, naming.width(5), Some(OsStr::new("j?g"))),
+            "2023-01-0005.j_g"
+        );
+        assert_eq!(naming.file_name("2023-01", 12345, 4, None), "2023-01-12345");
+    }
+
+    /// verifies Naming::label() and Naming::file_name() replace spaces and convert the case of the parent folder and
+    /// extension under every combination of `--replace-spaces` and `--case`, leaving the date label alone
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Naming::label() or Naming::file_name() does not transform the parts taken from the original file as chosen
+    /// - Naming::label() transforms the date label
+    #[test]
+    fn naming_original_part() {
+        for (replace_spaces, case, label, extension) in [
+            (None, NameCase::Keep, "My Trip_2023-Jan", ".Tar Gz"),
+            (None, NameCase::Lower, "my trip_2023-Jan", ".tar gz"),
+            (None, NameCase::Upper, "MY TRIP_2023-Jan", ".TAR GZ"),
+            (Some('-'), NameCase::Keep, "My-Trip_2023-Jan", ".Tar-Gz"),
+            (Some('-'), NameCase::Lower, "my-trip_2023-Jan", ".tar-gz"),
+            (Some('-'), NameCase::Upper, "MY-TRIP_2023-Jan", ".TAR-GZ"),
+        ] {
+            // create inputs
+            let naming = Naming {
+                prefix_from_parent: true,
+                replace_spaces,
+                case,
+                ..Naming::default()
+            };
+
+            // run test
+            let test_label = naming.label(
+                Path::new("./source/My Trip/a.jpg"),
+                String::from("2023-Jan"),
+            );
+            let test_name = naming.file_name(&test_label, 0, 1, Some(OsStr::new("Tar Gz")));
+
+            assert_eq!(test_label, label);
+            assert_eq!(test_name, format!("{label}_0{extension}"));
+        }
+    }
+
+    /// verifies padded names sort in counter order when compared as strings
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - sorting padded names as strings does not preserve counter order
+    #[test]
+    fn naming_padded_names_sort() {
+        // create inputs
+        let naming = Naming {
+            index_width: IndexWidth::Auto,
+            ..Naming::default()
+        };
+        let width = naming.width(11);
+
+        // run test
+        let names: Vec<String> = (0..12)
+            .map(|index| naming.file_name("2023-01", index, width, Some(OsStr::new("jpg"))))
+            .collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+
+        assert_eq!(width, 2);
+        assert_eq!(sorted, names);
+    }
+
+    /// verifies parse_index_width(), parse_index_scope(), parse_name_case(), and parse_name_char() accept valid values and reject invalid
+    /// ones
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_index_width(), parse_index_scope(), parse_name_case(), or parse_name_char() do not parse valid values
+    /// - parse_index_width(), parse_index_scope(), parse_name_case(), or parse_name_char() do not reject invalid values
+    #[test]
+    fn naming_parse_options() {
+        assert_eq!(
+            parse_index_width(Some(String::from("auto"))),
+            Ok(IndexWidth::Auto)
+        );
+        assert_eq!(
+            parse_index_width(Some(String::from("4"))),
+            Ok(IndexWidth::Fixed(4))
+        );
+        assert!(parse_index_width(Some(String::from("0"))).is_err());
+        assert!(parse_index_width(None).is_err());
+
+        assert_eq!(
+            parse_index_scope(Some(String::from("global"))),
+            Ok(IndexScope::Global)
+        );
+        assert!(parse_index_scope(Some(String::from("run"))).is_err());
+
+        assert_eq!(
+            parse_name_case(Some(String::from("lower"))),
+            Ok(NameCase::Lower)
+        );
+        assert!(parse_name_case(Some(String::from("title"))).is_err());
+        assert!(parse_name_case(None).is_err());
+
+        assert_eq!(parse_name_char(Some(String::from("-"))), Some('-'));
+        assert_eq!(parse_name_char(Some(String::from(":"))), None);
+        assert_eq!(parse_name_char(Some(String::from("--"))), None);
+        assert_eq!(parse_name_char(None), None);
+    }
+
+    /// verifies parse_folder_name() accepts plain folder names and rejects paths and unsafe names
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_folder_name() rejects a plain folder name
+    /// - parse_folder_name() accepts a path, a relative component, or a reserved name
+    #[test]
+    fn parse_folder_name_values() {
+        assert_eq!(
+            parse_folder_name(Some(String::from("photos 2023"))),
+            Some(String::from("photos 2023"))
+        );
+        for value in ["a/b", "..", ".", "", "CON", "c:"] {
+            assert_eq!(parse_folder_name(Some(value.to_string())), None);
+        }
+        assert_eq!(parse_folder_name(None), None);
+    }
+}
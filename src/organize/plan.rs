@@ -0,0 +1,279 @@
+// declare cargo crates
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// declare local code
+use crate::tools::sha256::hash_file;
+
+/// PlanStatus enum: how a planned move compares against what is already in the target directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanStatus {
+    /// the file would be organized to a free path and no identical file is in its folder yet
+    Added,
+
+    /// a file with identical contents is already in the folder, the PathBuf is that file
+    Unchanged(PathBuf),
+
+    /// the planned path is already taken by a file with different contents
+    Conflicting,
+}
+
+/// PlannedMove struct: a file and the path it would be organized to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedMove {
+    /// PathBuf to the file in the source directory
+    pub source: PathBuf,
+
+    /// PathBuf the file would be moved to
+    pub target: PathBuf,
+
+    /// how the move compares against the current contents of the target directory
+    pub status: PlanStatus,
+}
+
+/// plan_status() compares the planned move of `source` to `target` against the current contents of `dir`, the folder
+/// containing `target`, files are identical when their sizes and SHA-256 hashes match
+///
+/// # Arguments
+///
+/// `source` a Path that corresponds to the file to be organized
+/// `dir` a Path that corresponds to the folder the file would be organized into
+/// `target` a Path that corresponds to the path the file would be moved to
+///
+/// # Errors
+///
+/// - metadata is not retrievable for `source` or an item in `dir`
+/// - `dir` cannot be read
+/// - hash_file() call fails
+pub fn plan_status(source: &Path, dir: &Path, target: &Path) -> io::Result<PlanStatus> {
+    // nothing can be in the way of a folder that does not exist yet
+    if !dir.is_dir() {
+        return Ok(PlanStatus::Added);
+    }
+
+    let size = source.metadata()?.len();
+    let mut hash: Option<[u8; 32]> = None;
+
+    // sorted so the reported identical file does not depend on directory order
+    let mut items: Vec<PathBuf> = Vec::new();
+    for item in dir.read_dir()? {
+        items.push(item?.path());
+    }
+    items.sort();
+
+    for item in items {
+        // only files of equal size can be identical, the source is hashed at most once
+        if !item.is_file() || item.metadata()?.len() != size {
+            continue;
+        }
+        let source_hash = match hash {
+            Some(hash) => hash,
+            None => *hash.insert(hash_file(source)?),
+        };
+        if hash_file(&item)? == source_hash {
+            return Ok(PlanStatus::Unchanged(item));
+        }
+    }
+
+    if target.exists() {
+        Ok(PlanStatus::Conflicting)
+    } else {
+        Ok(PlanStatus::Added)
+    }
+}
+
+/// render_plan() builds the lines printed for a plan, one per planned move followed by the number of moves in each
+/// category
+///
+/// # Arguments
+///
+/// `plan` the planned moves in the order they would be performed
+///
+/// # Errors
+///
+/// None
+pub fn render_plan(plan: &[PlannedMove]) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let (mut added, mut unchanged, mut conflicting) = (0, 0, 0);
+
+    for planned in plan {
+        let source = planned.source.display();
+        let target = planned.target.display();
+        lines.push(match &planned.status {
+            PlanStatus::Added => {
+                added += 1;
+                format!("added       {source} -> {target}")
+            }
+            PlanStatus::Unchanged(existing) => {
+                unchanged += 1;
+                format!("unchanged   {source} = {}", existing.display())
+            }
+            PlanStatus::Conflicting => {
+                conflicting += 1;
+                format!("conflicting {source} -> {target}")
+            }
+        });
+    }
+    lines.push(format!(
+        "{added} added, {unchanged} unchanged, {conflicting} conflicting"
+    ));
+
+    lines
+}
+
+/// render_tree() builds the lines printed for a plan shown as the folder tree of `root`, one line per folder indented
+/// by its depth along with the number of planned files it and its subfolders would receive, folders nested deeper than
+/// `depth` are left out and their files counted in their ancestor at that depth
+///
+/// # Arguments
+///
+/// `plan` the planned moves in the order they would be performed
+/// `root` a Path that corresponds to the target directory every planned file is moved into
+/// `depth` number of folder levels below `root` to print, None to print every level
+///
+/// # Errors
+///
+/// None
+pub fn render_tree(plan: &[PlannedMove], root: &Path, depth: Option<usize>) -> Vec<String> {
+    // PathBufs relative to root sort parents directly before their children
+    let mut folders: BTreeMap<PathBuf, usize> = BTreeMap::new();
+
+    for planned in plan {
+        let Some(dir) = planned.target.parent() else {
+            continue;
+        };
+        let mut folder = PathBuf::new();
+        for component in dir.strip_prefix(root).unwrap_or(dir).components() {
+            if depth.is_some_and(|depth| folder.components().count() >= depth) {
+                break;
+            }
+            folder.push(component);
+            *folders.entry(folder.clone()).or_insert(0) += 1;
+        }
+    }
+
+    let count = |files: usize| match files {
+        1 => String::from("1 file"),
+        files => format!("{files} files"),
+    };
+    let mut lines = vec![format!("{}/ ({})", root.display(), count(plan.len()))];
+    for (folder, files) in folders {
+        lines.push(format!(
+            "{}{}/ ({})",
+            "  ".repeat(folder.components().count()),
+            folder
+                .file_name()
+                .map_or(String::new(), |name| name.to_string_lossy().into_owned()),
+            count(files)
+        ));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+
+    /// verifies plan_status() tells apart files already in the folder, planned paths that are taken, and free paths
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - plan_status() does not find an identical file or reports a file of equal size but different contents
+    /// - plan_status() does not report a taken path as conflicting
+    #[test]
+    fn plan_status_categories() {
+        // create inputs
+        let path_buf = PathBuf::from("./plan_status_categories_test");
+        let source = path_buf.join("source");
+        let dir = path_buf.join("target/2001/2001-09");
+
+        // create mock directories, the folder holds a copy of one source file and a different file of equal size
+        create_dir_all(&source).unwrap();
+        create_dir_all(&dir).unwrap();
+        write(source.join("copy.txt"), "same").unwrap();
+        write(source.join("new.txt"), "newer").unwrap();
+        write(dir.join("2001-09_0.txt"), "diff").unwrap();
+        write(dir.join("2001-09_1.txt"), "same").unwrap();
+
+        // run test
+        let test_result = [
+            plan_status(&source.join("copy.txt"), &dir, &dir.join("2001-09_2.txt")),
+            plan_status(&source.join("new.txt"), &dir, &dir.join("2001-09_0.txt")),
+            plan_status(&source.join("new.txt"), &dir, &dir.join("2001-09_2.txt")),
+            plan_status(&source.join("new.txt"), &path_buf.join("missing"), &dir),
+        ]
+        .map(|status| status.unwrap());
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            test_result,
+            [
+                PlanStatus::Unchanged(dir.join("2001-09_1.txt")),
+                PlanStatus::Conflicting,
+                PlanStatus::Added,
+                PlanStatus::Added,
+            ]
+        );
+    }
+
+    /// verifies render_tree() counts the planned files received by every folder and rolls deeper folders up to the depth
+    /// limit
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - render_tree() reports a wrong count for a folder or does not include the files of its subfolders
+    /// - render_tree() prints folders deeper than the depth limit
+    #[test]
+    fn render_tree_counts() {
+        // create inputs
+        let root = Path::new("target");
+        let plan: Vec<PlannedMove> = [
+            "2001/2001-09/jpg/2001-09_0.jpg",
+            "2001/2001-09/jpg/2001-09_1.jpg",
+            "2001/2001-09/png/2001-09_0.png",
+            "2001/2001-11/jpg/2001-11_0.jpg",
+            "2002/2002-01/jpg/2002-01_0.jpg",
+        ]
+        .iter()
+        .map(|target| PlannedMove {
+            source: PathBuf::from("source/file"),
+            target: root.join(target),
+            status: PlanStatus::Added,
+        })
+        .collect();
+
+        assert_eq!(
+            render_tree(&plan, root, None),
+            [
+                "target/ (5 files)",
+                "  2001/ (4 files)",
+                "    2001-09/ (3 files)",
+                "      jpg/ (2 files)",
+                "      png/ (1 file)",
+                "    2001-11/ (1 file)",
+                "      jpg/ (1 file)",
+                "  2002/ (1 file)",
+                "    2002-01/ (1 file)",
+                "      jpg/ (1 file)",
+            ]
+        );
+        assert_eq!(
+            render_tree(&plan, root, Some(1)),
+            ["target/ (5 files)", "  2001/ (4 files)", "  2002/ (1 file)"]
+        );
+        assert_eq!(render_tree(&plan, root, Some(0)), ["target/ (5 files)"]);
+    }
+}
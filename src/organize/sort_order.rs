@@ -0,0 +1,61 @@
+use std::cmp::Ordering;
+use std::path::Path;
+
+use time::OffsetDateTime;
+
+/// SortOrder enum: order collected files are organized in, which decides the counters they are given, files that
+/// compare equal keep the sorted order they were collected in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// by file name, ignoring the folders they were found in
+    Name,
+
+    /// by the date they are organized by, oldest first, files without a usable date last
+    Date,
+
+    /// by size, smallest first
+    Size,
+}
+
+impl SortOrder {
+    /// compare() orders two files by &self
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to SortOrder
+    /// `a` the path, size, and date of the first file
+    /// `b` the path, size, and date of the second file
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn compare(
+        &self,
+        a: (&Path, u64, OffsetDateTime),
+        b: (&Path, u64, OffsetDateTime),
+    ) -> Ordering {
+        match self {
+            SortOrder::Name => a.0.file_name().cmp(&b.0.file_name()),
+            SortOrder::Date => a.2.cmp(&b.2),
+            SortOrder::Size => a.1.cmp(&b.1),
+        }
+    }
+}
+
+/// parse_sort_order() parses the value of `--sort-order`, one of `name`, `date`, or `size`
+///
+/// # Arguments
+///
+/// `value` the value following `--sort-order`
+///
+/// # Errors
+///
+/// - `value` is not `name`, `date`, or `size`
+pub fn parse_sort_order(value: Option<String>) -> Result<SortOrder, &'static str> {
+    match value.as_deref() {
+        Some("name") => Ok(SortOrder::Name),
+        Some("date") => Ok(SortOrder::Date),
+        Some("size") => Ok(SortOrder::Size),
+        _ => Err("'--sort-order' must be followed by 'name', 'date', or 'size'"),
+    }
+}
@@ -0,0 +1,213 @@
+// declare cargo crates
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+// declare local code
+use crate::tools::dates::date_label;
+use crate::tools::parse_size::format_size_human;
+
+/// name files without an extension are counted under in Stats::extensions
+const NO_EXTENSION: &str = "(none)";
+
+/// Stats struct: aggregates of the files an organize run would process, gathered by `--stats-only` without moving
+/// anything so the layout can be judged before choosing how to organize
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// number of files looked at
+    pub files: usize,
+
+    /// combined size of the files in bytes
+    pub total_size: u64,
+
+    /// number of dated files by `YYYY-MM` label, the label an organize run would give them
+    pub months: BTreeMap<String, usize>,
+
+    /// number of files by lowercased extension, `(none)` for files without one
+    pub extensions: BTreeMap<String, usize>,
+
+    /// date and path of the oldest dated file
+    pub oldest: Option<(OffsetDateTime, PathBuf)>,
+
+    /// date and path of the newest dated file
+    pub newest: Option<(OffsetDateTime, PathBuf)>,
+
+    /// number of files without a usable date, which an organize run would fail on
+    pub undated: usize,
+}
+
+impl Stats {
+    /// add() counts `file` into the aggregates, the first of several files sharing the oldest or newest date is kept
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Stats
+    /// `file` a Path that corresponds to the file
+    /// `size` the size of the file in bytes
+    /// `date` the date the file would be organized by, None when it has no usable date
+    /// `extension` the extension the file would be grouped by, None when it has none
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn add(
+        &mut self,
+        file: &Path,
+        size: u64,
+        date: Option<OffsetDateTime>,
+        extension: Option<String>,
+    ) {
+        self.files += 1;
+        self.total_size += size;
+        *self
+            .extensions
+            .entry(extension.unwrap_or_else(|| String::from(NO_EXTENSION)))
+            .or_insert(0) += 1;
+
+        let Some(date) = date else {
+            self.undated += 1;
+            return;
+        };
+        *self.months.entry(date_label(date)).or_insert(0) += 1;
+        if self
+            .oldest
+            .as_ref()
+            .is_none_or(|(oldest, _)| date < *oldest)
+        {
+            self.oldest = Some((date, file.to_path_buf()));
+        }
+        if self
+            .newest
+            .as_ref()
+            .is_none_or(|(newest, _)| date > *newest)
+        {
+            self.newest = Some((date, file.to_path_buf()));
+        }
+    }
+
+    /// render() returns the lines printed by `--stats-only`, the totals followed by the number of files per year with
+    /// every month indented below it, the number of files per extension, the oldest and newest file, and the number of
+    /// files without a usable date
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Stats
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn render(&self) -> Vec<String> {
+        let mut lines: Vec<String> = vec![format!(
+            "{} files, {}",
+            self.files,
+            format_size_human(self.total_size)
+        )];
+
+        // labels sort by year first, so every year is followed by its months
+        let mut years: BTreeMap<&str, usize> = BTreeMap::new();
+        for (label, count) in &self.months {
+            *years.entry(&label[..label.len() - 3]).or_insert(0) += count;
+        }
+        for (year, count) in years {
+            lines.push(format!("{year}: {count}"));
+            for (label, count) in self.months.range(format!("{year}-")..format!("{year}.")) {
+                lines.push(format!("  {label}: {count}"));
+            }
+        }
+
+        lines.push(String::from("extensions:"));
+        for (extension, count) in &self.extensions {
+            lines.push(format!("  {extension}: {count}"));
+        }
+
+        for (name, extreme) in [("oldest", &self.oldest), ("newest", &self.newest)] {
+            if let Some((date, file)) = extreme {
+                lines.push(format!("{name}: {} {}", format_date(*date), file.display()));
+            }
+        }
+        lines.push(format!("undated: {}", self.undated));
+
+        lines
+    }
+}
+
+/// format_date() formats `date` as `YYYY-MM-DD HH:MM:SS` in the offset it carries
+///
+/// # Arguments
+///
+/// `date` the date to format
+///
+/// # Errors
+///
+/// None
+fn format_date(date: OffsetDateTime) -> String {
+    // formatted by hand rather than with a format description as this cannot fail
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day(),
+        date.hour(),
+        date.minute(),
+        date.second()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    /// verifies Stats::render() lists every year followed by its months, the extensions, and the oldest and newest file
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Stats::add() does not count a file into the aggregates
+    /// - Stats::render() does not group months under their year or omits a line
+    #[test]
+    fn stats_render() {
+        let mut stats = Stats::default();
+        stats.add(
+            Path::new("a.jpg"),
+            1024,
+            Some(datetime!(2023-01-15 12:00 UTC)),
+            Some(String::from("jpg")),
+        );
+        stats.add(
+            Path::new("b.jpg"),
+            1024,
+            Some(datetime!(2022-12-31 23:59:59 UTC)),
+            Some(String::from("jpg")),
+        );
+        stats.add(
+            Path::new("c"),
+            1024,
+            Some(datetime!(2023-03-01 00:00 UTC)),
+            None,
+        );
+        stats.add(Path::new("d.txt"), 0, None, Some(String::from("txt")));
+
+        assert_eq!(
+            stats.render(),
+            [
+                "4 files, 3.0 KiB",
+                "2022: 1",
+                "  2022-12: 1",
+                "2023: 2",
+                "  2023-01: 1",
+                "  2023-03: 1",
+                "extensions:",
+                "  (none): 1",
+                "  jpg: 2",
+                "  txt: 1",
+                "oldest: 2022-12-31 23:59:59 b.jpg",
+                "newest: 2023-03-01 00:00:00 c",
+                "undated: 1",
+            ]
+        );
+    }
+}
@@ -0,0 +1,100 @@
+// declare cargo crates
+use std::fs::read_dir;
+use std::io;
+use std::path::Path;
+
+// declare local code
+use crate::tools::error::PathContext;
+
+/// TargetExists enum: what an organize run does when the target directory already holds something when it starts
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TargetExists {
+    /// organized files are added alongside whatever the target already holds
+    #[default]
+    Merge,
+
+    /// the run stops when the target holds anything at all, to protect a directory that is in use
+    Fail,
+
+    /// the run stops when the target holds a file anywhere below it, a tree of empty folders is accepted
+    RequireEmpty,
+}
+
+impl TargetExists {
+    /// check() returns an error naming `target` when its contents are not acceptable for this TargetExists
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to TargetExists
+    /// `target` a Path that corresponds to the target directory
+    ///
+    /// # Errors
+    ///
+    /// - `target` holds anything for TargetExists::Fail, or a file below it for TargetExists::RequireEmpty
+    /// - `target` or one of its folders cannot be read
+    pub fn check(&self, target: &Path) -> io::Result<()> {
+        let (occupied, held) = match self {
+            Self::Merge => return Ok(()),
+            Self::Fail => (
+                read_dir(target).path_context(target)?.next().is_some(),
+                "is not empty",
+            ),
+            Self::RequireEmpty => (holds_file(target)?, "already contains files"),
+        };
+        if !occupied {
+            return Ok(());
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::DirectoryNotEmpty,
+            format!(
+                "target directory {} {held}, refusing to organize into it with '--target-exists {}'",
+                target.display(),
+                match self {
+                    Self::Fail => "fail",
+                    _ => "require-empty",
+                }
+            ),
+        ))
+    }
+}
+
+/// holds_file() returns whether anything other than a directory lies anywhere below `dir`, symbolic links count as
+/// files and are not followed
+///
+/// # Arguments
+///
+/// `dir` a Path that corresponds to a directory
+///
+/// # Errors
+///
+/// - `dir` or one of the directories below it cannot be read
+fn holds_file(dir: &Path) -> io::Result<bool> {
+    for item in read_dir(dir).path_context(dir)? {
+        let item = item.path_context(dir)?;
+        let path = item.path();
+        if !item.file_type().path_context(&path)?.is_dir() || holds_file(&path)? {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// parse_target_exists() parses the value of `--target-exists`, one of `fail`, `merge`, or `require-empty`
+///
+/// # Arguments
+///
+/// `value` the value following `--target-exists`
+///
+/// # Errors
+///
+/// - `value` is not `fail`, `merge`, or `require-empty`
+pub fn parse_target_exists(value: Option<String>) -> Result<TargetExists, &'static str> {
+    match value.as_deref() {
+        Some("merge") => Ok(TargetExists::Merge),
+        Some("fail") => Ok(TargetExists::Fail),
+        Some("require-empty") => Ok(TargetExists::RequireEmpty),
+        _ => Err("'--target-exists' must be followed by 'fail', 'merge', or 'require-empty'"),
+    }
+}
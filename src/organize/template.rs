@@ -0,0 +1,290 @@
+// declare cargo crates
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+// declare local code
+use crate::tools::sanitize_filename::is_illegal_char;
+
+/// Token enum: a part of a date that may be placed in a DateTemplate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    /// four digit year, `[year]`
+    Year,
+
+    /// two digit month, `[month]`
+    Month,
+
+    /// name of the month, `[month-long]`
+    MonthLong,
+
+    /// first three letters of the name of the month, `[month-short]`
+    MonthShort,
+
+    /// two digit day of the month, `[day]`
+    Day,
+
+    /// quarter of the year from 1 to 4, `[quarter]`
+    Quarter,
+
+    /// two digit ISO 8601 week number from 01 to 53, `[week]`
+    Week,
+
+    /// name of the day of the week, `[weekday]`
+    Weekday,
+}
+
+impl Token {
+    /// parse() returns the Token named `name`, or None if no token has that name
+    ///
+    /// # Arguments
+    ///
+    /// `name` the text between the brackets of a token
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "year" => Self::Year,
+            "month" => Self::Month,
+            "month-long" => Self::MonthLong,
+            "month-short" => Self::MonthShort,
+            "day" => Self::Day,
+            "quarter" => Self::Quarter,
+            "week" => Self::Week,
+            "weekday" => Self::Weekday,
+            _ => return None,
+        })
+    }
+
+    /// render() formats this part of `date`
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Token
+    /// `date` the date being formatted
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn render(&self, date: OffsetDateTime) -> String {
+        match self {
+            Self::Year => format!("{:04}", date.year()),
+            Self::Month => format!("{:02}", u8::from(date.month())),
+            Self::MonthLong => date.month().to_string(),
+            Self::MonthShort => date.month().to_string()[..3].to_string(),
+            Self::Day => format!("{:02}", date.day()),
+            Self::Quarter => ((u8::from(date.month()) - 1) / 3 + 1).to_string(),
+            Self::Week => format!("{:02}", date.iso_week()),
+            Self::Weekday => date.weekday().to_string(),
+        }
+    }
+}
+
+/// Piece enum: a run of literal text or a Token within a folder of a DateTemplate
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    /// text copied into the folder name as is
+    Literal(String),
+
+    /// a part of the date
+    Token(Token),
+}
+
+/// DateTemplate struct: the folders date grouping places files in, written as `/` separated folder names made of
+/// literal text and bracketed tokens, such as `[year]/Q[quarter]/[month-long]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateTemplate {
+    /// pieces of every folder in the order folders are nested
+    folders: Vec<Vec<Piece>>,
+}
+
+impl DateTemplate {
+    /// render() returns the relative path of folders `date` is grouped into
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to DateTemplate
+    /// `date` the date of the file
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn render(&self, date: OffsetDateTime) -> PathBuf {
+        self.folders
+            .iter()
+            .map(|pieces| {
+                pieces
+                    .iter()
+                    .map(|piece| match piece {
+                        Piece::Literal(text) => text.clone(),
+                        Piece::Token(token) => token.render(date),
+                    })
+                    .collect::<String>()
+            })
+            .collect()
+    }
+}
+
+/// parse_date_template() parses the value of `--date-template`, every folder must be non-empty and may only contain
+/// the tokens `[year]`, `[month]`, `[month-long]`, `[month-short]`, `[day]`, `[quarter]`, `[week]`, and `[weekday]`
+/// along with text that is legal in file names
+///
+/// # Arguments
+///
+/// `value` the value following `--date-template`
+///
+/// # Errors
+///
+/// - `value` is missing, has an empty folder or a folder made only of dots, or contains an unknown or unclosed
+///   token or a character that is illegal in file names
+pub fn parse_date_template(value: Option<String>) -> Result<DateTemplate, &'static str> {
+    const ERROR: &str = "'--date-template' must be followed by '/' separated folders of text and the tokens [year], [month], [month-long], [month-short], [day], [quarter], [week], and [weekday], such as [year]/Q[quarter]";
+
+    let value = value.ok_or(ERROR)?;
+    let mut folders: Vec<Vec<Piece>> = Vec::new();
+
+    for folder in value.split('/') {
+        // `.` and `..` would leave the target directory, names of dots only are rejected along with them
+        if folder.is_empty() || folder.chars().all(|c| c == '.') {
+            return Err(ERROR);
+        }
+
+        let mut pieces: Vec<Piece> = Vec::new();
+        let mut rest = folder;
+        while !rest.is_empty() {
+            match rest.find('[') {
+                Some(0) => {
+                    let end = rest.find(']').ok_or(ERROR)?;
+                    pieces.push(Piece::Token(Token::parse(&rest[1..end]).ok_or(ERROR)?));
+                    rest = &rest[end + 1..];
+                }
+                found => {
+                    let end = found.unwrap_or(rest.len());
+                    let text = &rest[..end];
+                    if text.chars().any(|c| c == ']' || is_illegal_char(c)) {
+                        return Err(ERROR);
+                    }
+                    pieces.push(Piece::Literal(text.to_string()));
+                    rest = &rest[end..];
+                }
+            }
+        }
+        folders.push(pieces);
+    }
+
+    Ok(DateTemplate { folders })
+}
+
+/// parse_granularity() parses the value of `--granularity`, `year`, `quarter`, `month`, or `day`, into the built-in
+/// DateTemplate of that layout, `YYYY`, `YYYY/YYYY-Qn`, `YYYY/YYYY-MM`, or `YYYY/YYYY-MM/YYYY-MM-DD`
+///
+/// # Arguments
+///
+/// `value` the value following `--granularity`
+///
+/// # Errors
+///
+/// - `value` is missing or is not one of the granularities
+pub fn parse_granularity(value: Option<String>) -> Result<DateTemplate, &'static str> {
+    let template = match value.as_deref() {
+        Some("year") => "[year]",
+        Some("quarter") => "[year]/[year]-Q[quarter]",
+        Some("month") => "[year]/[year]-[month]",
+        Some("day") => "[year]/[year]-[month]/[year]-[month]-[day]",
+        _ => {
+            return Err("'--granularity' must be followed by 'year', 'quarter', 'month', or 'day'")
+        }
+    };
+
+    parse_date_template(Some(template.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    /// verifies DateTemplate renders quarter, week number, and name tokens for known dates
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - DateTemplate::render() does not give the known quarter or ISO week of a date
+    /// - DateTemplate::render() does not render month and weekday names or literal text
+    /// - parse_date_template() accepts an unknown or unclosed token, an empty folder, or an illegal character
+    #[test]
+    fn date_template_tokens() {
+        // create inputs
+        let template =
+            parse_date_template(Some(String::from("[year]/Q[quarter]/W[week] [weekday]"))).unwrap();
+        let names =
+            parse_date_template(Some(String::from("[month-long]-[month-short]/[day]"))).unwrap();
+
+        // 2023-01-01 is a Sunday in the last ISO week of 2022, 2024-12-30 a Monday in the first of 2025
+        assert_eq!(
+            template.render(datetime!(2023-01-01 12:00 UTC)),
+            PathBuf::from("2023/Q1/W52 Sunday")
+        );
+        assert_eq!(
+            template.render(datetime!(2023-05-15 12:00 UTC)),
+            PathBuf::from("2023/Q2/W20 Monday")
+        );
+        assert_eq!(
+            template.render(datetime!(2023-09-30 12:00 UTC)),
+            PathBuf::from("2023/Q3/W39 Saturday")
+        );
+        assert_eq!(
+            template.render(datetime!(2024-12-30 12:00 UTC)),
+            PathBuf::from("2024/Q4/W01 Monday")
+        );
+        assert_eq!(
+            names.render(datetime!(2023-09-05 12:00 UTC)),
+            PathBuf::from("September-Sep/05")
+        );
+
+        for value in [
+            "[year]/[decade]",
+            "[year",
+            "year]",
+            "[year]//[month]",
+            "[year]/../[month]",
+            "[year]:[month]",
+            "",
+        ] {
+            assert!(parse_date_template(Some(value.to_string())).is_err());
+        }
+        assert!(parse_date_template(None).is_err());
+    }
+
+    /// verifies every granularity gives its built-in layout for a fixed date
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_granularity() gives the wrong folders for a granularity
+    /// - parse_granularity() accepts an unknown or missing granularity
+    #[test]
+    fn granularity_layouts() {
+        // create inputs
+        let date = datetime!(2023-08-07 12:00 UTC);
+        let render = |value: &str| {
+            parse_granularity(Some(value.to_string()))
+                .unwrap()
+                .render(date)
+        };
+
+        assert_eq!(render("year"), PathBuf::from("2023"));
+        assert_eq!(render("quarter"), PathBuf::from("2023/2023-Q3"));
+        assert_eq!(render("month"), PathBuf::from("2023/2023-08"));
+        assert_eq!(render("day"), PathBuf::from("2023/2023-08/2023-08-07"));
+        assert!(parse_granularity(Some(String::from("week"))).is_err());
+        assert!(parse_granularity(None).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+// declare cargo crates
+use std::fs::{read_dir, remove_dir};
+use std::io;
+use std::path::{Path, PathBuf};
+
+// declare local code
+use super::tools::args::separate_args;
+use super::tools::error::PathContext;
+use super::tools::file_walker::FileWalker;
+use super::tools::paths::expand_paths;
+use super::{Logger, RunTask, TaskReport};
+
+/// purge options that are followed by a value, see separate_args()
+pub(crate) const VALUE_OPTIONS: &[&str] = &["--exclude-dir"];
+
+/// purge options that are not followed by a value, listed along with VALUE_OPTIONS by the capabilities task
+pub(crate) const FLAG_OPTIONS: &[&str] = &["--dry-run"];
+
+/// PurgeTask struct: PathBuf corresponds to the directory whose empty subdirectories are removed
+#[derive(Debug, PartialEq, Eq)]
+pub struct PurgeTask {
+    /// PathBuf to directory searched for empty directories, it is never removed itself
+    dir: PathBuf,
+
+    /// FileWalker whose excluded directories are left in place and count as content of their parent
+    walker: FileWalker,
+
+    /// whether to only print the directories that would be removed
+    dry_run: bool,
+}
+
+/// RunTask trait implementation for Purge variant of Config enum
+impl RunTask for PurgeTask {
+    /// run_task() driver function for Purge variant of Config, removes every empty directory below &self.dir from the
+    /// bottom up, so a directory that only held empty directories is removed as well
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Config enum
+    /// `logger` a Logger that records every removed directory
+    ///
+    /// # Errors
+    ///
+    /// - purge() call fails
+    /// - Logger::log() call fails
+    fn run_task(&self, logger: &mut Logger) -> Result<TaskReport, io::Error> {
+        let mut removed: Vec<PathBuf> = Vec::new();
+        self.purge(&self.dir, &mut removed)?;
+
+        let action = if self.dry_run {
+            "would remove"
+        } else {
+            "removed"
+        };
+        for dir in &removed {
+            if !self.dry_run {
+                logger.log("remove", dir, None)?;
+            }
+            println!("{action} {}", dir.display());
+        }
+        println!("{action} {} empty directories", removed.len());
+
+        let processed = if self.dry_run {
+            "empty directories would be removed"
+        } else {
+            "empty directories removed"
+        };
+        Ok(TaskReport {
+            moved: removed.len(),
+            ..TaskReport::worded(processed, "failed")
+        })
+    }
+}
+
+impl PurgeTask {
+    /// PurgeTask struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// `args` - an iterator containing Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - a path refers to an undefined environment variable, see expand_path()
+    /// - `./dir/` path not provided
+    /// - `./dir/` does not correspond to valid directory
+    /// - more than one path is provided
+    /// - `--exclude-dir` is not followed by a name or pattern
+    /// - an unknown option is provided
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
+        // separate options from the dir path so they may be given in any position
+        let (paths, options) = separate_args(args, VALUE_OPTIONS);
+        let paths = expand_paths(paths)?;
+        let mut paths = paths.into_iter();
+
+        // ensures dir path is provided
+        let dir = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => return Err("no 'dir' path provided"),
+        };
+
+        // ensures the dir path corresponds to a valid directory
+        if !dir.is_dir() {
+            return Err("'dir' path does not correspond to a valid directory");
+        }
+
+        // errors if extra paths are provided
+        if paths.next().is_some() {
+            return Err("purge accepts exactly one 'dir' path");
+        }
+
+        // default options
+        let mut walker = FileWalker::new();
+        let mut dry_run = false;
+
+        // parse options
+        let mut args = options.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--dry-run" => dry_run = true,
+                "--exclude-dir" => {
+                    walker = match args.next() {
+                        Some(pattern) => walker.exclude_dir(pattern),
+                        None => {
+                            return Err("'--exclude-dir' must be followed by a name or pattern")
+                        }
+                    }
+                }
+                _ => return Err("provided option did not match any purge options"),
+            }
+        }
+
+        Ok(Self {
+            dir,
+            walker,
+            dry_run,
+        })
+    }
+
+    /// purge() removes every empty directory below `dir`, or only records it when &self.dry_run is set, and returns
+    /// whether `dir` is left empty. Subdirectories are handled before the directory holding them so one that is empty
+    /// once its own empty subdirectories are gone is removed too, symbolic links are never followed and count as
+    /// content like files do
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to PurgeTask
+    /// `dir` a Path that corresponds to the directory to purge
+    /// `removed` where the removed directories are placed in the order they are removed
+    ///
+    /// # Errors
+    ///
+    /// - `dir` or one of its subdirectories cannot be read
+    /// - metadata is not retrievable for an item
+    /// - an empty directory cannot be removed
+    fn purge(&self, dir: &Path, removed: &mut Vec<PathBuf>) -> io::Result<bool> {
+        let mut items: Vec<PathBuf> = Vec::new();
+        for item in read_dir(dir).path_context(dir)? {
+            items.push(item.path_context(dir)?.path());
+        }
+        items.sort();
+
+        let mut empty = true;
+        for item in items {
+            if !item.symlink_metadata().path_context(&item)?.is_dir()
+                || self.walker.is_excluded(&item)
+            {
+                empty = false;
+            } else if self.purge(&item, removed)? {
+                if !self.dry_run {
+                    remove_dir(&item).path_context(&item)?;
+                }
+                removed.push(item);
+            } else {
+                empty = false;
+            }
+        }
+
+        Ok(empty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, File};
+
+    /// verifies PurgeTask::new() parses the directory and options
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - PurgeTask::new() does not parse valid arguments
+    /// - PurgeTask::new() does not error on a missing directory or an unknown option
+    #[test]
+    fn purge_task_new() {
+        // args iterator builder
+        let args = |values: &[&str]| {
+            values
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+
+        assert_eq!(
+            PurgeTask::new(args(&["./src", "--dry-run"])),
+            Ok(PurgeTask {
+                dir: PathBuf::from("./src"),
+                walker: FileWalker::new(),
+                dry_run: true,
+            })
+        );
+        assert!(PurgeTask::new(args(&[])).is_err());
+        assert!(PurgeTask::new(args(&["./src", "./src"])).is_err());
+        assert!(PurgeTask::new(args(&["./src", "--recursive"])).is_err());
+    }
+
+    /// verifies PurgeTask::purge() removes nested empty directories bottom up, including directories that only held
+    /// empty directories, keeps every directory with a file in it or below it, and removes nothing on a dry run
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - PurgeTask::purge() keeps an empty directory or one left empty by removing its children
+    /// - PurgeTask::purge() removes a directory holding a file, an excluded directory, or the root
+    /// - PurgeTask::purge() removes anything or reports different directories on a dry run
+    /// - PurgeTask::run_task() does not count the directories of a dry run in its summary
+    #[test]
+    fn purge_task_purge_nested() {
+        // create inputs
+        let path_buf = PathBuf::from("./purge_task_purge_nested_test");
+        let task = |dry_run: bool| PurgeTask {
+            dir: path_buf.clone(),
+            walker: FileWalker::new().exclude_dir(String::from("keep")),
+            dry_run,
+        };
+
+        // create mock directories, a/b/c and d hold nothing, e/f holds a file next to the empty e/g
+        for dir in ["a/b/c", "d", "e/f", "e/g", "keep"] {
+            create_dir_all(path_buf.join(dir)).unwrap();
+        }
+        File::create(path_buf.join("e/f/photo.jpg")).unwrap();
+
+        // run test
+        let mut planned: Vec<PathBuf> = Vec::new();
+        let dry_result = task(true).purge(&path_buf, &mut planned);
+        let dry_report = task(true).run_task(&mut Logger::disabled());
+        let untouched = path_buf.join("a/b/c").is_dir();
+        let mut removed: Vec<PathBuf> = Vec::new();
+        let test_result = task(false).purge(&path_buf, &mut removed);
+        let remaining = ["a", "d", "e/f", "e/g", "keep"].map(|dir| path_buf.join(dir).is_dir());
+        let root = path_buf.is_dir();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        let expected = [
+            path_buf.join("a/b/c"),
+            path_buf.join("a/b"),
+            path_buf.join("a"),
+            path_buf.join("d"),
+            path_buf.join("e/g"),
+        ];
+        assert!(!dry_result.unwrap());
+        assert!(untouched);
+        assert_eq!(planned, expected);
+        assert!(dry_report
+            .unwrap()
+            .to_string()
+            .starts_with("5 empty directories would be removed, 0 skipped, 0 failed,"));
+        assert!(!test_result.unwrap());
+        assert_eq!(removed, expected);
+        assert_eq!(remaining, [false, false, true, false, true]);
+        assert!(root);
+    }
+}
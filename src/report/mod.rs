@@ -0,0 +1,594 @@
+// declare cargo crates
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+// declare local code
+use super::tools::json::Json;
+
+/// exit code of a run that processed every file it did not skip
+pub const EXIT_SUCCESS: u8 = 0;
+
+/// exit code of a run whose arguments could not be parsed, nothing is done
+pub const EXIT_CONFIG_ERROR: u8 = 1;
+
+/// exit code of a run that kept going past failed files but processed at least one file
+pub const EXIT_PARTIAL_FAILURE: u8 = 2;
+
+/// exit code of a run that stopped on an error or in which every file it did not skip failed
+pub const EXIT_FAILURE: u8 = 3;
+
+/// SkipReason enum: why a file was skipped, so the skips of a large run can be counted by cause, see
+/// TaskReport::skip_counts()
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SkipReason {
+    /// the file was not selected by a filter expression
+    Filtered,
+
+    /// the target already holds a file with the same contents
+    Duplicate,
+
+    /// the date the file is organized by cannot be determined
+    MissingDate,
+
+    /// the file would have to be copied to another file system and is larger than allowed
+    TooLarge,
+
+    /// the file does not lie below the prefix its destination is derived from
+    OutsidePrefix,
+
+    /// the file was removed by another process before it could be processed
+    Vanished,
+
+    /// another process had the file open or locked when it was processed
+    InUse,
+
+    /// the file is not dated after the start of the last run, which already organized it
+    NotNewer,
+
+    /// the log of the run being resumed records the file as already processed
+    AlreadyDone,
+
+    /// the entry is a symbolic link to a directory visited as an entry of its own, see SymlinkPolicy::NoFollow, which
+    /// a task that stores file contents has nothing to read from
+    LinkedDir,
+}
+
+impl SkipReason {
+    /// as_str() returns the name the reason is listed under in the summary and the JSON report
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to SkipReason
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::Filtered => "filtered",
+            SkipReason::Duplicate => "duplicate",
+            SkipReason::MissingDate => "missing_date",
+            SkipReason::TooLarge => "too_large",
+            SkipReason::OutsidePrefix => "outside_prefix",
+            SkipReason::Vanished => "vanished",
+            SkipReason::InUse => "in_use",
+            SkipReason::NotNewer => "not_newer",
+            SkipReason::AlreadyDone => "already_done",
+            SkipReason::LinkedDir => "linked_dir",
+        }
+    }
+}
+
+/// TaskReport struct: summary of the work performed by a task, returned by RunTask::run_task()
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TaskReport {
+    /// number of files moved (or otherwise processed) successfully
+    pub moved: usize,
+
+    /// files that were skipped along with the category and description of the reason they were skipped
+    pub skipped: Vec<(PathBuf, SkipReason, String)>,
+
+    /// files that could not be processed along with the error encountered, only populated when a task is allowed to
+    /// keep going past errors
+    pub failed: Vec<(PathBuf, String)>,
+
+    /// target directories that did not exist before the run and were created by it, deduplicated and sorted
+    pub created_dirs: Vec<PathBuf>,
+
+    /// target directories that were used by the run but already existed beforehand, deduplicated and sorted
+    pub existing_dirs: Vec<PathBuf>,
+
+    /// problems that did not stop a file from being processed but may need the user's attention, such as a name that
+    /// had to be altered
+    pub warnings: Vec<String>,
+
+    /// symbolic links whose target was processed in their place, along with the file they point to
+    pub symlinks: Vec<(PathBuf, PathBuf)>,
+
+    /// files moved by the run along with where they were moved to, in the order they were moved, left empty by tasks
+    /// that only count the files they process
+    pub moves: Vec<(PathBuf, PathBuf)>,
+
+    /// files placed at a destination while the original was left at its source, such as by `--link hard`, along with
+    /// where they were placed, in the order they were placed
+    pub links: Vec<(PathBuf, PathBuf)>,
+
+    /// number of times an operation was attempted again after a transient error, see Retry
+    pub retries: usize,
+
+    /// whether the run was cancelled before every file was processed, see Cancel
+    pub cancelled: bool,
+
+    /// bytes of storage freed by the run, such as by removing or linking duplicates
+    pub reclaimed: u64,
+
+    /// words the summary describes the files counted by `moved` and `failed` with, `moved` and `failed` when None, set
+    /// by tasks that check or copy the files they process rather than moving them, see TaskReport::worded()
+    pub wording: Option<(&'static str, &'static str)>,
+}
+
+impl TaskReport {
+    /// worded() returns an empty TaskReport whose summary describes the processed files as `processed` and the files
+    /// that could not be processed as `failed`
+    ///
+    /// # Arguments
+    ///
+    /// `processed` the word the summary uses for the files counted by `moved`
+    /// `failed` the word the summary uses for the files listed in `failed`
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn worded(processed: &'static str, failed: &'static str) -> Self {
+        TaskReport {
+            wording: Some((processed, failed)),
+            ..TaskReport::default()
+        }
+    }
+
+    /// record_dir() records a target directory used by the run, directories are kept sorted and deduplicated
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to TaskReport
+    /// `dir` a PathBuf that corresponds to the target directory
+    /// `created` whether the run created `dir` rather than finding it already in place
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn record_dir(&mut self, dir: PathBuf, created: bool) {
+        let dirs = if created {
+            &mut self.created_dirs
+        } else {
+            &mut self.existing_dirs
+        };
+
+        if let Err(index) = dirs.binary_search(&dir) {
+            dirs.insert(index, dir);
+        }
+    }
+
+    /// skip() records `file` as skipped for `reason`, described by `detail`
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to TaskReport
+    /// `file` a PathBuf that corresponds to the skipped file
+    /// `reason` the category of the reason
+    /// `detail` the reason as shown next to the file in the summary
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn skip(&mut self, file: PathBuf, reason: SkipReason, detail: impl Into<String>) {
+        self.skipped.push((file, reason, detail.into()));
+    }
+
+    /// skip_counts() returns the number of skipped files for every reason at least one file was skipped for, in the
+    /// order the reasons are declared in
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to TaskReport
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn skip_counts(&self) -> Vec<(SkipReason, usize)> {
+        let mut counts: BTreeMap<SkipReason, usize> = BTreeMap::new();
+        for (_, reason, _) in &self.skipped {
+            *counts.entry(*reason).or_insert(0) += 1;
+        }
+
+        counts.into_iter().collect()
+    }
+
+    /// exit_code() returns the process exit code of a run that finished with this report, skipped files do not count
+    /// as failures and a run that did nothing, such as a plan, succeeds, while a cancelled run counts as failed as files
+    /// were left unprocessed
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to TaskReport
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn exit_code(&self) -> u8 {
+        if self.failed.is_empty() && !self.cancelled {
+            EXIT_SUCCESS
+        } else if self.moved > 0 {
+            EXIT_PARTIAL_FAILURE
+        } else {
+            EXIT_FAILURE
+        }
+    }
+
+    /// to_json() returns the report as a JSON object holding the summary counts and one entry per moved, linked,
+    /// skipped, or failed file, which `restore` reads back to undo the moves and links. Paths that are not valid unicode have their invalid
+    /// characters replaced, the entries of such files additionally hold the raw bytes of the path under `source_bytes`
+    /// or `destination_bytes` so it can be restored exactly
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to TaskReport
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn to_json(&self) -> Json {
+        let path = |path: &PathBuf| Json::from(path.display().to_string());
+        let raw = |mut entry: Json, paths: &[(&str, &PathBuf)]| {
+            if let Json::Object(pairs) = &mut entry {
+                for (key, path) in paths.iter().filter(|(_, path)| path.to_str().is_none()) {
+                    let bytes = path.as_os_str().as_encoded_bytes().iter();
+                    let bytes = bytes.map(|&byte| Json::from(byte as usize)).collect();
+                    pairs.push((format!("{key}_bytes"), Json::Array(bytes)));
+                }
+            }
+            entry
+        };
+
+        let mut entries: Vec<Json> = Vec::new();
+        for (source, destination) in &self.moves {
+            let entry = Json::object([
+                ("action", Json::from("moved")),
+                ("source", path(source)),
+                ("destination", path(destination)),
+            ]);
+            entries.push(raw(
+                entry,
+                &[("source", source), ("destination", destination)],
+            ));
+        }
+        for (source, destination) in &self.links {
+            let entry = Json::object([
+                ("action", Json::from("linked")),
+                ("source", path(source)),
+                ("destination", path(destination)),
+            ]);
+            entries.push(raw(
+                entry,
+                &[("source", source), ("destination", destination)],
+            ));
+        }
+        for (source, category, reason) in &self.skipped {
+            let entry = Json::object([
+                ("action", Json::from("skipped")),
+                ("source", path(source)),
+                ("category", Json::from(category.as_str())),
+                ("reason", Json::from(reason.as_str())),
+            ]);
+            entries.push(raw(entry, &[("source", source)]));
+        }
+        for (source, error) in &self.failed {
+            let entry = Json::object([
+                ("action", Json::from("failed")),
+                ("source", path(source)),
+                ("error", Json::from(error.as_str())),
+            ]);
+            entries.push(raw(entry, &[("source", source)]));
+        }
+
+        Json::object([
+            ("moved", Json::from(self.moved)),
+            ("skipped", Json::from(self.skipped.len())),
+            (
+                "skip_reasons",
+                Json::Object(
+                    self.skip_counts()
+                        .into_iter()
+                        .map(|(reason, count)| (reason.as_str().to_string(), Json::from(count)))
+                        .collect(),
+                ),
+            ),
+            ("failed", Json::from(self.failed.len())),
+            ("retries", Json::from(self.retries)),
+            ("cancelled", Json::from(self.cancelled)),
+            ("entries", Json::Array(entries)),
+            (
+                "created_dirs",
+                Json::Array(self.created_dirs.iter().map(path).collect()),
+            ),
+            (
+                "warnings",
+                Json::Array(
+                    self.warnings
+                        .iter()
+                        .map(|warning| Json::from(warning.as_str()))
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
+
+/// report_json_from_args() removes `--report-json <path>` from `args` if present and returns the path the JSON report
+/// of the run is to be written to, see TaskReport::to_json()
+///
+/// # Arguments
+///
+/// `args` a mutable vector of Strings to be used as arguments
+///
+/// # Errors
+///
+/// - `--report-json` is not followed by a path
+pub fn report_json_from_args(args: &mut Vec<String>) -> Result<Option<PathBuf>, &'static str> {
+    let index = match args.iter().position(|arg| arg == "--report-json") {
+        Some(index) => index,
+        None => return Ok(None),
+    };
+
+    // ensures a report path is provided
+    if index + 1 >= args.len() {
+        return Err("'--report-json' must be followed by a path");
+    }
+
+    let path = args.remove(index + 1);
+    args.remove(index);
+
+    Ok(Some(PathBuf::from(path)))
+}
+
+/// Display trait implementation for TaskReport, used to print the summary from main.rs
+impl fmt::Display for TaskReport {
+    /// fmt() writes a one line summary, which mentions retries and reclaimed bytes only if there were any and whether
+    /// the run was cancelled, followed by the number of skipped files per reason and one line per created directory, dereferenced
+    /// symbolic link, skipped or failed file, and warning
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to TaskReport
+    /// `f` the formatter to write into
+    ///
+    /// # Errors
+    ///
+    /// - writing to `f` fails
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (processed, failed) = self.wording.unwrap_or(("moved", "failed"));
+        write!(
+            f,
+            "{} {processed}, {} skipped, {} {failed}, {} directories created",
+            self.moved,
+            self.skipped.len(),
+            self.failed.len(),
+            self.created_dirs.len()
+        )?;
+        if self.retries > 0 {
+            write!(f, ", {} retries", self.retries)?;
+        }
+        if self.reclaimed > 0 {
+            write!(f, ", {} bytes reclaimed", self.reclaimed)?;
+        }
+        if self.cancelled {
+            write!(f, ", cancelled")?;
+        }
+        for (reason, count) in self.skip_counts() {
+            write!(f, "\n  skips {}: {count}", reason.as_str())?;
+        }
+
+        for dir in &self.created_dirs {
+            write!(f, "\n  created {}", dir.display())?;
+        }
+        for (link, real) in &self.symlinks {
+            write!(f, "\n  symlink {} -> {}", link.display(), real.display())?;
+        }
+        for (path, _, reason) in &self.skipped {
+            write!(f, "\n  skipped {}: {}", path.display(), reason)?;
+        }
+        for (path, error) in &self.failed {
+            write!(f, "\n  failed {}: {}", path.display(), error)?;
+        }
+        for warning in &self.warnings {
+            write!(f, "\n  warning {warning}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies TaskReport is displayed as a summary line followed by the skips per reason, every skipped and failed file,
+    /// and every warning
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - the displayed TaskReport does not match the expected summary
+    #[test]
+    fn task_report_display() {
+        // create inputs
+        let report = TaskReport {
+            moved: 3,
+            skipped: vec![
+                (
+                    PathBuf::from("a.jpg"),
+                    SkipReason::Vanished,
+                    String::from("file vanished"),
+                ),
+                (
+                    PathBuf::from("f.jpg"),
+                    SkipReason::Duplicate,
+                    String::from("duplicate of 2023/2023-01/2023-01_0.jpg"),
+                ),
+                (
+                    PathBuf::from("g.jpg"),
+                    SkipReason::Duplicate,
+                    String::from("duplicate of 2023/2023-01/2023-01_1.jpg"),
+                ),
+            ],
+            failed: vec![(PathBuf::from("b.jpg"), String::from("permission denied"))],
+            created_dirs: vec![PathBuf::from("2023/2023-01")],
+            existing_dirs: vec![PathBuf::from("2022/2022-12")],
+            warnings: vec![String::from("c.jpg: extension is not valid unicode")],
+            symlinks: vec![(PathBuf::from("d.jpg"), PathBuf::from("/photos/d.jpg"))],
+            moves: vec![(
+                PathBuf::from("e.jpg"),
+                PathBuf::from("2023/2023-01/2023-01_0.jpg"),
+            )],
+            links: vec![],
+            retries: 2,
+            cancelled: true,
+            reclaimed: 0,
+            wording: None,
+        };
+
+        assert_eq!(
+            report.to_string(),
+            "3 moved, 3 skipped, 1 failed, 1 directories created, 2 retries, cancelled\n  skips duplicate: 2\n  skips vanished: 1\n  created 2023/2023-01\n  symlink d.jpg -> /photos/d.jpg\n  skipped a.jpg: file vanished\n  skipped f.jpg: duplicate of 2023/2023-01/2023-01_0.jpg\n  skipped g.jpg: duplicate of 2023/2023-01/2023-01_1.jpg\n  failed b.jpg: permission denied\n  warning c.jpg: extension is not valid unicode"
+        );
+
+        let worded = TaskReport {
+            moved: 2,
+            ..TaskReport::worded("verified", "mismatched")
+        };
+        assert_eq!(
+            worded.to_string(),
+            "2 verified, 0 skipped, 0 mismatched, 0 directories created"
+        );
+    }
+
+    /// verifies TaskReport::to_json() lists every moved, linked, skipped, and failed file along with the summary counts
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - the JSON report does not match the expected document
+    #[test]
+    fn task_report_to_json() {
+        // create inputs
+        let report = TaskReport {
+            moved: 1,
+            skipped: vec![(
+                PathBuf::from("a.jpg"),
+                SkipReason::Vanished,
+                String::from("file vanished"),
+            )],
+            failed: vec![(PathBuf::from("b.jpg"), String::from("permission denied"))],
+            created_dirs: vec![PathBuf::from("2023/2023-01")],
+            moves: vec![(
+                PathBuf::from("e.jpg"),
+                PathBuf::from("2023/2023-01/2023-01_0.jpg"),
+            )],
+            links: vec![(
+                PathBuf::from("f.jpg"),
+                PathBuf::from("2023/2023-01/2023-01_1.jpg"),
+            )],
+            ..TaskReport::default()
+        };
+
+        assert_eq!(
+            report.to_json().to_string(),
+            concat!(
+                r#"{"moved":1,"skipped":1,"skip_reasons":{"vanished":1},"failed":1,"retries":0,"cancelled":false,"entries":["#,
+                r#"{"action":"moved","source":"e.jpg","destination":"2023/2023-01/2023-01_0.jpg"},"#,
+                r#"{"action":"linked","source":"f.jpg","destination":"2023/2023-01/2023-01_1.jpg"},"#,
+                r#"{"action":"skipped","source":"a.jpg","category":"vanished","reason":"file vanished"},"#,
+                r#"{"action":"failed","source":"b.jpg","error":"permission denied"}],"#,
+                r#""created_dirs":["2023/2023-01"],"warnings":[]}"#
+            )
+        );
+    }
+
+    /// verifies TaskReport::record_dir() keeps directories sorted, deduplicated, and split by whether they were created
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - TaskReport::record_dir() records a directory twice, out of order, or in the wrong list
+    #[test]
+    fn task_report_record_dir() {
+        // create inputs
+        let mut report = TaskReport::default();
+
+        // run test
+        report.record_dir(PathBuf::from("b"), true);
+        report.record_dir(PathBuf::from("a"), true);
+        report.record_dir(PathBuf::from("b"), true);
+        report.record_dir(PathBuf::from("c"), false);
+
+        assert_eq!(
+            report.created_dirs,
+            [PathBuf::from("a"), PathBuf::from("b")]
+        );
+        assert_eq!(report.existing_dirs, [PathBuf::from("c")]);
+    }
+
+    /// verifies TaskReport::exit_code() tells apart runs without failures, with some failures, and with only failures,
+    /// and counts a cancelled run as failed
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - TaskReport::exit_code() does not follow the documented exit code convention
+    #[test]
+    fn task_report_exit_code() {
+        // create inputs
+        let failed = || vec![(PathBuf::from("b.jpg"), String::from("permission denied"))];
+        let skipped = TaskReport {
+            skipped: vec![(
+                PathBuf::from("a.jpg"),
+                SkipReason::Vanished,
+                String::from("file vanished"),
+            )],
+            ..TaskReport::default()
+        };
+        let partial = TaskReport {
+            moved: 1,
+            failed: failed(),
+            ..TaskReport::default()
+        };
+        let total = TaskReport {
+            failed: failed(),
+            ..TaskReport::default()
+        };
+        let cancelled = TaskReport {
+            moved: 1,
+            cancelled: true,
+            ..TaskReport::default()
+        };
+
+        assert_eq!(TaskReport::default().exit_code(), EXIT_SUCCESS);
+        assert_eq!(skipped.exit_code(), EXIT_SUCCESS);
+        assert_eq!(partial.exit_code(), EXIT_PARTIAL_FAILURE);
+        assert_eq!(total.exit_code(), EXIT_FAILURE);
+        assert_eq!(cancelled.exit_code(), EXIT_PARTIAL_FAILURE);
+    }
+}
@@ -0,0 +1,602 @@
+// declare cargo crates
+use std::fs::read_to_string;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// declare local code
+use super::tools::args::separate_args;
+use super::tools::ensured_dirs::EnsuredDirs;
+use super::tools::error::PathContext;
+use super::tools::file_system::{FileSystem, RealFileSystem};
+use super::tools::json::Json;
+use super::tools::move_file::move_file;
+use super::tools::paths::expand_paths;
+use super::tools::sha256::hash_file;
+use super::tools::throttle::Throttle;
+use super::{Logger, RunTask, SkipReason, TaskReport};
+
+/// restore options that are followed by a value, see separate_args()
+pub(crate) const VALUE_OPTIONS: &[&str] = &[];
+
+/// restore options that are not followed by a value, listed along with VALUE_OPTIONS by the capabilities task
+pub(crate) const FLAG_OPTIONS: &[&str] = &["--skip-missing"];
+
+/// RestoreTask struct: PathBuf corresponds to the JSON report of the run whose moves are reversed
+#[derive(Debug, PartialEq, Eq)]
+pub struct RestoreTask {
+    /// PathBuf to the JSON report written with `--report-json`
+    report: PathBuf,
+
+    /// whether a moved file whose destination no longer exists is skipped rather than counted as a failure
+    skip_missing: bool,
+}
+
+/// RunTask trait implementation for Restore variant of Config enum
+impl RunTask for RestoreTask {
+    /// run_task() driver function for Restore variant of Config, moves every file listed as moved in &self.report from
+    /// its destination back to its source and removes every file listed as linked
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Config enum
+    /// `logger` a Logger that records every restored and failed file
+    ///
+    /// # Errors
+    ///
+    /// - &self.report cannot be read
+    /// - read_entries() call fails
+    /// - restore() call fails
+    fn run_task(&self, logger: &mut Logger) -> Result<TaskReport, io::Error> {
+        let text = read_to_string(&self.report).path_context(&self.report)?;
+        let moves = read_entries(&text, "moved")?;
+        let links = read_entries(&text, "linked")?;
+        self.restore(&RealFileSystem, &moves, &links, logger)
+    }
+}
+
+impl RestoreTask {
+    /// RestoreTask struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// `args` - an iterator containing Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - a path refers to an undefined environment variable, see expand_path()
+    /// - `./report.json` path not provided
+    /// - `./report.json` does not correspond to a valid file
+    /// - more than one path is provided
+    /// - an unknown option is provided
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
+        // separate options from the report path so they may be given in any position
+        let (paths, options) = separate_args(args, VALUE_OPTIONS);
+        let paths = expand_paths(paths)?;
+        let mut paths = paths.into_iter();
+
+        // ensures report path is provided
+        let report = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => return Err("no 'report' path provided"),
+        };
+
+        // ensures the report path corresponds to a valid file
+        if !report.is_file() {
+            return Err("'report' path does not correspond to a valid file");
+        }
+
+        // errors if extra paths are provided
+        if paths.next().is_some() {
+            return Err("restore accepts exactly one 'report' path");
+        }
+
+        // default options
+        let mut skip_missing = false;
+
+        // parse options
+        for arg in options {
+            match arg.as_str() {
+                "--skip-missing" => skip_missing = true,
+                _ => return Err("provided option did not match any restore options"),
+            }
+        }
+
+        Ok(Self {
+            report,
+            skip_missing,
+        })
+    }
+
+    /// restore() moves every destination of `moves` back to its source, last move first so a file moved more than once
+    /// ends up where it started, recreating the directories of the source as needed. A destination that no longer
+    /// exists or a source whose path has been taken since is left alone and listed in the returned TaskReport, as is
+    /// any file that cannot be moved, so one file does not stop the rest from being restored. The moves performed are
+    /// recorded in the TaskReport in turn so that a restore can itself be reversed. Every destination of `links` is
+    /// then removed while its source still holds the same contents, or moved back like a moved file once its source
+    /// is gone, as it is the only copy left
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to RestoreTask
+    /// `fs` the FileSystem files are moved on
+    /// `moves` the source and destination of every move to reverse, in the order they were performed
+    /// `links` the source and destination of every link to remove, in the order they were made
+    /// `logger` a Logger that records every restored, unlinked, and failed file
+    ///
+    /// # Errors
+    ///
+    /// - Logger::log() call fails
+    fn restore(
+        &self,
+        fs: &dyn FileSystem,
+        moves: &[(PathBuf, PathBuf)],
+        links: &[(PathBuf, PathBuf)],
+        logger: &mut Logger,
+    ) -> io::Result<TaskReport> {
+        let mut report = TaskReport::default();
+        let mut throttle = Throttle::new(None);
+        let ensured = EnsuredDirs::new();
+
+        let moves = moves
+            .iter()
+            .rev()
+            .map(|(source, destination)| (source, destination, false));
+        let links = links
+            .iter()
+            .rev()
+            .map(|(source, destination)| (source, destination, true));
+        for (source, destination, linked) in moves.chain(links) {
+            // nothing is overwritten, a file at the path of the source was put there after the move, while a link is
+            // only removed when its source still holds what was linked
+            let missing = fs.metadata(destination).is_err();
+            let taken = source.symlink_metadata().is_ok();
+            let problem = if missing {
+                Some("destination no longer exists")
+            } else if taken && !(linked && same_contents(source, destination)) {
+                Some("source path is already taken")
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                if missing && self.skip_missing {
+                    logger.log("skip", destination, Some(source))?;
+                    report.skip(destination.clone(), SkipReason::Vanished, problem);
+                } else {
+                    logger.log("fail", destination, Some(source))?;
+                    report
+                        .failed
+                        .push((destination.clone(), problem.to_string()));
+                }
+                continue;
+            }
+
+            if taken {
+                match fs.remove_file(destination) {
+                    Ok(()) => {
+                        logger.log("unlink", destination, Some(source))?;
+                        report.moved += 1;
+                    }
+                    Err(err) => {
+                        logger.log("fail", destination, Some(source))?;
+                        report.failed.push((destination.clone(), err.to_string()));
+                    }
+                }
+                continue;
+            }
+
+            match move_back(
+                fs,
+                source,
+                destination,
+                &ensured,
+                &mut throttle,
+                &mut report,
+            ) {
+                Ok(()) => {
+                    logger.log("restore", destination, Some(source))?;
+                    report.moved += 1;
+                    report.moves.push((destination.clone(), source.clone()));
+                }
+                Err(err) => {
+                    logger.log("fail", destination, Some(source))?;
+                    report.failed.push((destination.clone(), err.to_string()));
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// move_back() moves `destination` back to `source`, creating the directory of `source` first if it no longer exists
+///
+/// # Arguments
+///
+/// `fs` the FileSystem the file is moved on
+/// `source` a Path the file originally had
+/// `destination` a Path the file was moved to
+/// `ensured` the EnsuredDirs of the restore, so the directory of `source` is only created once
+/// `throttle` the Throttle limiting copies across file systems
+/// `report` the TaskReport the directory of `source` is recorded in
+///
+/// # Errors
+///
+/// - EnsuredDirs::ensure() call fails
+/// - move_file() call fails
+fn move_back(
+    fs: &dyn FileSystem,
+    source: &Path,
+    destination: &Path,
+    ensured: &EnsuredDirs,
+    throttle: &mut Throttle,
+    report: &mut TaskReport,
+) -> io::Result<()> {
+    if let Some(dir) = source.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        if let Some(created) = ensured.ensure(fs, dir)? {
+            report.record_dir(dir.to_path_buf(), created);
+        }
+    }
+
+    move_file(fs, destination, source, throttle, false)
+}
+
+/// same_contents() returns whether `source` and `destination` hold the same contents, as a hard link to `source` always
+/// does, false when either cannot be read
+///
+/// # Arguments
+///
+/// `source` a Path the file was linked from
+/// `destination` a Path the file was linked to
+///
+/// # Errors
+///
+/// None
+fn same_contents(source: &Path, destination: &Path) -> bool {
+    match (hash_file(source), hash_file(destination)) {
+        (Ok(source), Ok(destination)) => source == destination,
+        _ => false,
+    }
+}
+
+/// read_entries() returns the source and destination of every entry of a JSON report written with `--report-json`
+/// whose action is `action`, such as `moved` or `linked`, in the order they were performed
+///
+/// # Arguments
+///
+/// `text` the contents of the JSON report
+/// `action` the action of the entries to return
+///
+/// # Errors
+///
+/// - `text` is not valid JSON or not a JSON report
+/// - an entry of `action` does not have a string source and destination
+/// - entry_path() call fails
+pub fn read_entries(text: &str, action: &str) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let report = Json::parse(text).map_err(invalid)?;
+    let entries = match report.get("entries") {
+        Some(Json::Array(entries)) => entries,
+        _ => return Err(invalid("report does not list any entries")),
+    };
+
+    let mut moves: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for entry in entries {
+        if entry.get("action") != Some(&Json::from(action)) {
+            continue;
+        }
+        match (
+            entry_path(entry, "source")?,
+            entry_path(entry, "destination")?,
+        ) {
+            (Some(source), Some(destination)) => moves.push((source, destination)),
+            _ => return Err(invalid("entry without a source and destination")),
+        }
+    }
+
+    Ok(moves)
+}
+
+/// entry_path() returns the path stored under `key` in a report entry, taken from the raw bytes under `<key>_bytes`
+/// when the entry holds them as the path is not valid unicode, see TaskReport::to_json()
+///
+/// # Arguments
+///
+/// `entry` a Json object that corresponds to one entry of the report
+/// `key` the name of the path field, such as `source`
+///
+/// # Errors
+///
+/// - `<key>_bytes` is not an array of bytes
+/// - `<key>_bytes` is given on a platform other than Unix, where the path cannot be rebuilt from its bytes
+fn entry_path(entry: &Json, key: &str) -> io::Result<Option<PathBuf>> {
+    let bytes = match entry.get(&format!("{key}_bytes")) {
+        Some(Json::Array(bytes)) => bytes,
+        Some(_) => return Err(invalid("raw path bytes are not an array")),
+        None => {
+            return Ok(match entry.get(key) {
+                Some(Json::String(path)) => Some(PathBuf::from(path)),
+                _ => None,
+            });
+        }
+    };
+    let bytes = bytes
+        .iter()
+        .map(|byte| match byte {
+            Json::Number(byte) if byte.fract() == 0.0 && (0.0..=255.0).contains(byte) => {
+                Some(*byte as u8)
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| invalid("raw path bytes are not an array of bytes"))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+
+        Ok(Some(PathBuf::from(std::ffi::OsString::from_vec(bytes))))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = bytes;
+        Err(invalid(
+            "a path that is not valid unicode can only be restored on Unix",
+        ))
+    }
+}
+
+/// invalid() returns an InvalidData error carrying `message`, for a report that cannot be read back
+///
+/// # Arguments
+///
+/// `message` the description of what is wrong with the report
+///
+/// # Errors
+///
+/// None
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::organize::OrganizeTask;
+    use std::fs::{create_dir_all, remove_dir_all, remove_file, write};
+
+    /// verifies RestoreTask::new() parses the report path and options
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - RestoreTask::new() does not parse valid arguments
+    /// - RestoreTask::new() does not error on a missing report or an unknown option
+    #[test]
+    fn restore_task_new() {
+        // args iterator builder
+        let args = |values: &[&str]| {
+            values
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+
+        assert_eq!(
+            RestoreTask::new(args(&["./Cargo.toml", "--skip-missing"])),
+            Ok(RestoreTask {
+                report: PathBuf::from("./Cargo.toml"),
+                skip_missing: true,
+            })
+        );
+        assert!(RestoreTask::new(args(&[])).is_err());
+        assert!(RestoreTask::new(args(&["./src"])).is_err());
+        assert!(RestoreTask::new(args(&["./Cargo.toml", "./Cargo.toml"])).is_err());
+        assert!(RestoreTask::new(args(&["./Cargo.toml", "--only-failed"])).is_err());
+    }
+
+    /// verifies a JSON report of an organize run restores every file it moved to its original path, recreating its
+    /// directory, and lists a file whose organized copy is gone as skipped with `--skip-missing` or failed without
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - RestoreTask::run_task() does not move an organized file back to its original path
+    /// - RestoreTask::run_task() does not report a missing destination as skipped or failed
+    #[test]
+    fn restore_task_round_trip() {
+        // create inputs
+        let path_buf = PathBuf::from("./restore_task_round_trip_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let report_path = path_buf.join("report.json");
+        let files = [source.join("a.txt"), source.join("nested/b.txt")];
+
+        // create mock directories
+        create_dir_all(source.join("nested")).unwrap();
+        create_dir_all(&target).unwrap();
+        for file in &files {
+            write(file, file.display().to_string()).unwrap();
+        }
+
+        // run test, organize the files and write the report, then remove the organized copy of the first file and the
+        // folder the second came from
+        let organized = OrganizeTask::new(
+            [source.display().to_string(), target.display().to_string()].into_iter(),
+        )
+        .unwrap()
+        .run_task(&mut Logger::disabled())
+        .unwrap();
+        write(&report_path, organized.to_json().to_string()).unwrap();
+        let destination = |file: &PathBuf| {
+            organized
+                .moves
+                .iter()
+                .find(|(from, _)| from == file)
+                .map(|(_, to)| to.clone())
+                .unwrap()
+        };
+        let missing = destination(&files[0]);
+        remove_file(&missing).unwrap();
+        remove_dir_all(source.join("nested")).unwrap();
+        let restore = |extra: &[&str]| {
+            let mut args = vec![report_path.display().to_string()];
+            args.extend(extra.iter().map(|arg| arg.to_string()));
+            RestoreTask::new(args.into_iter())
+                .unwrap()
+                .run_task(&mut Logger::disabled())
+                .unwrap()
+        };
+        let skipped = restore(&["--skip-missing"]);
+        let restored = read_to_string(&files[1]);
+        let failed = restore(&[]);
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(organized.moved, 2);
+        assert_eq!(skipped.moved, 1);
+        assert_eq!(skipped.moves, [(destination(&files[1]), files[1].clone())]);
+        assert_eq!(
+            skipped.skipped,
+            [(
+                missing,
+                SkipReason::Vanished,
+                String::from("destination no longer exists")
+            )]
+        );
+        assert_eq!(skipped.created_dirs, [source.join("nested")]);
+        assert_eq!(restored.unwrap(), files[1].display().to_string());
+        assert_eq!(failed.moved, 0);
+        assert_eq!(failed.failed.len(), 2);
+    }
+
+    /// verifies a JSON report of an organize run with `--link hard` lists every link as linked, and that restoring it
+    /// removes a link whose source is still in place and moves back one whose source is gone
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - TaskReport::to_json() lists a link as moved
+    /// - RestoreTask::run_task() fails a link because its source still exists
+    /// - RestoreTask::run_task() does not move back a link whose source is gone
+    #[test]
+    fn restore_task_link_round_trip() {
+        // create inputs
+        let path_buf = PathBuf::from("./restore_task_link_round_trip_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let report_path = path_buf.join("report.json");
+        let files = [source.join("a.txt"), source.join("b.txt")];
+
+        // create mock directories
+        create_dir_all(&source).unwrap();
+        create_dir_all(&target).unwrap();
+        for file in &files {
+            write(file, file.display().to_string()).unwrap();
+        }
+
+        // run test, link the files and write the report, then remove the original of the second file
+        let organized = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--link"),
+                String::from("hard"),
+            ]
+            .into_iter(),
+        )
+        .unwrap()
+        .run_task(&mut Logger::disabled())
+        .unwrap();
+        let json = organized.to_json().to_string();
+        write(&report_path, &json).unwrap();
+        remove_file(&files[1]).unwrap();
+        let restored = RestoreTask::new([report_path.display().to_string()].into_iter())
+            .unwrap()
+            .run_task(&mut Logger::disabled())
+            .unwrap();
+        let contents = files.each_ref().map(|file| read_to_string(file).unwrap());
+        let left = organized
+            .links
+            .iter()
+            .filter(|(_, destination)| destination.exists())
+            .count();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert!(organized.moves.is_empty());
+        assert_eq!(organized.links.len(), 2);
+        assert!(json.contains(r#""action":"linked""#) && !json.contains(r#""action":"moved""#));
+        assert_eq!(restored.moved, 2);
+        assert!(restored.failed.is_empty());
+        assert_eq!(restored.moves.len(), 1);
+        assert_eq!(left, 0);
+        assert_eq!(
+            contents,
+            files.each_ref().map(|file| file.display().to_string())
+        );
+    }
+
+    /// verifies a file whose name is not valid unicode is restored to its exact original name from the raw bytes kept
+    /// in the JSON report
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - TaskReport::to_json() does not keep the raw bytes of the name
+    /// - RestoreTask::run_task() restores the file under a name with replaced characters
+    #[cfg(unix)]
+    #[test]
+    fn restore_task_non_unicode() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        // create inputs
+        let path_buf = PathBuf::from("./restore_task_non_unicode_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let report_path = path_buf.join("report.json");
+        let file = source.join(OsStr::from_bytes(b"bad\xff.jpg"));
+
+        // create mock directories
+        create_dir_all(&source).unwrap();
+        create_dir_all(&target).unwrap();
+        write(&file, "contents").unwrap();
+
+        // run test
+        let organized = OrganizeTask::new(
+            [source.display().to_string(), target.display().to_string()].into_iter(),
+        )
+        .unwrap()
+        .run_task(&mut Logger::disabled())
+        .unwrap();
+        let json = organized.to_json().to_string();
+        write(&report_path, &json).unwrap();
+        let restored = RestoreTask::new([report_path.display().to_string()].into_iter())
+            .unwrap()
+            .run_task(&mut Logger::disabled())
+            .unwrap();
+        let contents = read_to_string(&file);
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert!(json.contains(r#""source_bytes":["#));
+        assert_eq!(restored.moved, 1);
+        assert!(restored.failed.is_empty());
+        assert_eq!(contents.unwrap(), "contents");
+    }
+}
@@ -0,0 +1,565 @@
+// declare cargo crates
+use std::io;
+use std::path::PathBuf;
+
+// declare local code
+use super::tools::args::separate_args;
+use super::tools::collect_files::collect_files_in;
+use super::tools::error::{with_path, PathContext};
+use super::tools::file_system::{FileSystem, RealFileSystem};
+use super::tools::file_walker::FileWalker;
+use super::tools::move_file::move_file;
+use super::tools::paths::expand_paths;
+use super::tools::symlinks::SymlinkPolicy;
+use super::tools::throttle::{parse_throttle, Throttle};
+use super::tools::unique_path::unique_path;
+use super::{Logger, RunTask, TaskReport};
+
+/// split options that are followed by a value, see separate_args()
+pub(crate) const VALUE_OPTIONS: &[&str] = &["--parts", "--throttle", "--exclude-dir"];
+
+/// split options that are not followed by a value, listed along with VALUE_OPTIONS by the capabilities task
+pub(crate) const FLAG_OPTIONS: &[&str] = &["--by-size", "--follow", "--no-follow"];
+
+/// SplitTask struct: PathBufs correspond to source and target directories along with how files are partitioned
+#[derive(Debug, PartialEq, Eq)]
+pub struct SplitTask {
+    /// PathBuf to directory containing files to be split
+    source: PathBuf,
+
+    /// PathBuf to directory that will hold the `part_###` folders
+    target: PathBuf,
+
+    /// FileWalker used to collect files from the source directory
+    walker: FileWalker,
+
+    /// number of parts to split files into
+    parts: usize,
+
+    /// whether to balance parts by total bytes rather than number of files
+    by_size: bool,
+
+    /// maximum average rate in bytes per second that files are copied across file systems at, None for no limit
+    throttle: Option<u64>,
+}
+
+/// PartSummary struct: number of files and bytes placed in a single part
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PartSummary {
+    /// number of files placed in the part
+    pub files: usize,
+
+    /// total size in bytes of the files placed in the part
+    pub bytes: u64,
+}
+
+/// RunTask trait implementation for Split variant of Config enum
+impl RunTask for SplitTask {
+    /// run_task() driver function for Split variant of Config, distributes files from &self.source into &self.parts
+    /// folders named `part_###` inside &self.target and prints how many files and bytes landed in each part
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Config enum
+    /// `logger` a Logger that records every move and failed move
+    ///
+    /// # Errors
+    ///
+    /// - collect_files_in() call fails
+    /// - metadata is not retrievable for a given file
+    /// - FileSystem::create_dir_all() call fails
+    /// - move_file() call fails
+    /// - Logger::log() call fails
+    fn run_task(&self, logger: &mut Logger) -> Result<TaskReport, io::Error> {
+        let mut report = TaskReport::default();
+        let summaries = self.split(&RealFileSystem, &mut report, logger)?;
+        for (part, summary) in summaries.iter().enumerate() {
+            println!(
+                "{}: {} files, {} bytes",
+                self.part_name(part),
+                summary.files,
+                summary.bytes
+            );
+        }
+
+        report.moved = summaries.iter().map(|summary| summary.files).sum();
+
+        Ok(report)
+    }
+}
+
+impl SplitTask {
+    /// SplitTask struct initializer, options may appear before, between, or after the source and target paths
+    ///
+    /// # Arguments
+    ///
+    /// `args` - an iterator containing Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - a path refers to an undefined environment variable, see expand_path()
+    /// - `--parts` is not followed by a positive integer
+    /// - `--throttle` is not followed by a positive number of bytes per second
+    /// - `--exclude-dir` is not followed by a name or pattern
+    /// - `./source/` path not provided
+    /// - `./source/` does not correspond to valid directory
+    /// - `./target/` path not provided
+    /// - `./target/` does not correspond to valid directory
+    /// - an unknown option or additional path is provided
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
+        // separate options from the source and target paths so they may be given in any position
+        let (paths, options) = separate_args(args, VALUE_OPTIONS);
+        let paths = expand_paths(paths)?;
+
+        // default options
+        let mut parts: Option<usize> = None;
+        let mut by_size = false;
+        let mut throttle: Option<u64> = None;
+        let mut walker = FileWalker::new();
+
+        // parse options
+        let mut args = options.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--parts" => {
+                    parts = match args.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(value)) if value > 0 => Some(value),
+                        _ => return Err("'--parts' must be followed by a positive integer"),
+                    }
+                }
+                "--by-size" => by_size = true,
+                "--throttle" => throttle = Some(parse_throttle(args.next())?),
+                "--follow" => walker = walker.symlinks(SymlinkPolicy::Follow),
+                "--no-follow" => walker = walker.symlinks(SymlinkPolicy::NoFollow),
+                "--exclude-dir" => {
+                    walker = match args.next() {
+                        Some(pattern) => walker.exclude_dir(pattern),
+                        None => {
+                            return Err("'--exclude-dir' must be followed by a name or pattern")
+                        }
+                    }
+                }
+                _ => return Err("provided option did not match any split options"),
+            }
+        }
+
+        // ensures the number of parts is provided
+        let parts = match parts {
+            Some(parts) => parts,
+            None => return Err("no '--parts' count provided"),
+        };
+
+        let mut paths = paths.into_iter().map(PathBuf::from);
+
+        // ensures source path is provided and corresponds to a valid directory
+        let source = match paths.next() {
+            Some(path) => path,
+            None => return Err("no 'source' path provided"),
+        };
+        if !source.is_dir() {
+            return Err("'source' path does not correspond to a valid directory");
+        }
+
+        // ensures target path is provided and corresponds to a valid directory
+        let target = match paths.next() {
+            Some(path) => path,
+            None => return Err("no 'target' path provided"),
+        };
+        if !target.is_dir() {
+            return Err("'target' path does not correspond to a valid directory");
+        }
+
+        // errors if extra paths are provided
+        if paths.next().is_some() {
+            return Err("split accepts exactly one 'source' and one 'target' path");
+        }
+
+        Ok(Self {
+            source,
+            target,
+            walker,
+            parts,
+            by_size,
+            throttle,
+        })
+    }
+
+    /// split() moves every file found in &self.source into its assigned part and returns a summary of every part
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to SplitTask
+    /// `fs` the FileSystem files are collected from and moved on
+    /// `report` the TaskReport of the current run, records the part directories used
+    /// `logger` a Logger that records every move and failed move
+    ///
+    /// # Errors
+    ///
+    /// - see run_task()
+    fn split(
+        &self,
+        fs: &dyn FileSystem,
+        report: &mut TaskReport,
+        logger: &mut Logger,
+    ) -> io::Result<Vec<PartSummary>> {
+        // PathBufs and sizes of all files found at the source directory
+        let mut files: Vec<PathBuf> = Vec::new();
+        collect_files_in(fs, &self.walker, &self.source, &mut files)?;
+
+        let mut sizes: Vec<u64> = Vec::with_capacity(files.len());
+        for file in &files {
+            sizes.push(fs.metadata(file).path_context(file)?.len());
+        }
+
+        let assignments = assign_parts(&sizes, self.parts, self.by_size);
+        let mut summaries = vec![PartSummary::default(); self.parts];
+
+        // create every part up front so that empty parts still exist
+        for part in 0..self.parts {
+            let dir = self.target.join(self.part_name(part));
+            let created = !dir.exists();
+            fs.create_dir_all(&dir).path_context(&dir)?;
+            report.record_dir(dir, created);
+        }
+
+        // shared by every move so the limit applies to the run as a whole
+        let mut throttle = Throttle::new(self.throttle);
+
+        for ((file, size), part) in files.iter().zip(sizes).zip(assignments) {
+            // keep the original file name, suffixing it if another file with the same name is already in the part
+            let target = unique_path(
+                &self.target.join(self.part_name(part)),
+                &file.file_name().unwrap().to_string_lossy(), // collected files always have a file name
+            );
+
+            if let Err(err) = move_file(fs, file, &target, &mut throttle, false) {
+                logger.log("fail", file, Some(&target))?;
+                return Err(with_path(err, file));
+            }
+            logger.log("move", file, Some(&target))?;
+
+            summaries[part].files += 1;
+            summaries[part].bytes += size;
+        }
+
+        Ok(summaries)
+    }
+
+    /// part_name() returns the folder name of part `part`, zero padded to at least three digits
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to SplitTask
+    /// `part` zero based index of the part
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn part_name(&self, part: usize) -> String {
+        let width = (self.parts - 1).to_string().len().max(3);
+        format!("part_{part:0width$}")
+    }
+}
+
+/// assign_parts() returns the zero based part each file is assigned to, either round-robin by file order or, when
+/// `by_size` is set, greedily placing the largest remaining file into the part with the fewest bytes so far
+///
+/// # Arguments
+///
+/// `sizes` sizes in bytes of every file, in processing order
+/// `parts` number of parts to split files into, must be positive
+/// `by_size` whether to balance parts by total bytes rather than number of files
+///
+/// # Errors
+///
+/// None
+pub fn assign_parts(sizes: &[u64], parts: usize, by_size: bool) -> Vec<usize> {
+    if !by_size {
+        return (0..sizes.len()).map(|index| index % parts).collect();
+    }
+
+    // visit files from largest to smallest, ties keep processing order so results are deterministic
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by(|a, b| sizes[*b].cmp(&sizes[*a]));
+
+    let mut totals = vec![0u64; parts];
+    let mut assignments = vec![0usize; sizes.len()];
+    for index in order {
+        // lightest part, ties go to the lowest part
+        let part = (0..parts).min_by_key(|part| totals[*part]).unwrap(); // parts is positive
+        totals[part] += sizes[index];
+        assignments[index] = part;
+    }
+
+    assignments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::collect_files::collect_files;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+
+    /// verifies SplitTask::new() accepts options before, between, and after the paths
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - SplitTask::new() does not parse the paths and options
+    #[test]
+    fn split_task_new_with_valid_args() {
+        // args iterator
+        let args = [
+            String::from("--parts"),
+            String::from("10"),
+            String::from("./src"),
+            String::from("--by-size"),
+            String::from("./src/split"),
+        ]
+        .into_iter();
+
+        assert_eq!(
+            SplitTask::new(args),
+            Ok(SplitTask {
+                source: PathBuf::from("./src"),
+                target: PathBuf::from("./src/split"),
+                walker: FileWalker::new(),
+                parts: 10,
+                by_size: true,
+                throttle: None,
+            })
+        );
+    }
+
+    /// verifies SplitTask::new() errors when the part count is missing or invalid
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - SplitTask::new() does not error without a positive part count
+    #[test]
+    fn split_task_new_invalid_parts() {
+        // args iterator builder
+        let args = |parts: &[&str]| {
+            parts
+                .iter()
+                .map(|arg| arg.to_string())
+                .chain([String::from("./src"), String::from("./src/split")])
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+
+        assert!(SplitTask::new(args(&[])).is_err());
+        assert!(SplitTask::new(args(&["--parts", "0"])).is_err());
+        assert!(SplitTask::new(args(&["--parts", "many"])).is_err());
+    }
+
+    /// verifies assign_parts() distributes files round-robin by count
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - assign_parts() does not assign files round-robin
+    #[test]
+    fn assign_parts_by_count() {
+        assert_eq!(
+            assign_parts(&[9, 1, 1, 1, 1], 2, false),
+            vec![0, 1, 0, 1, 0]
+        );
+    }
+
+    /// verifies assign_parts() balances parts by total bytes when `by_size` is set
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - assign_parts() does not balance total bytes across parts
+    #[test]
+    fn assign_parts_by_size() {
+        // create inputs
+        let sizes = [1, 5, 3, 4, 3];
+
+        // run test
+        let assignments = assign_parts(&sizes, 2, true);
+        let mut totals = [0u64; 2];
+        for (size, part) in sizes.iter().zip(&assignments) {
+            totals[*part] += size;
+        }
+
+        assert_eq!(totals, [8, 8]);
+    }
+
+    /// verifies SplitTask::split() moves every file into its part and reports per-part totals
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - SplitTask::split() does not move files into the expected parts
+    /// - SplitTask::split() reports incorrect per-part totals
+    #[test]
+    fn split_task_split_by_count_and_size() {
+        for by_size in [false, true] {
+            // create inputs
+            let path_buf = PathBuf::from(format!("./split_task_split_{by_size}_test"));
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+
+            // create mock directories, a nested file shares its name with a top level file
+            create_dir_all(source.join("nested")).unwrap();
+            create_dir_all(&target).unwrap();
+            write(source.join("a.txt"), [0u8; 6]).unwrap();
+            write(source.join("b.txt"), [0u8; 2]).unwrap();
+            write(source.join("c.txt"), [0u8; 2]).unwrap();
+            write(source.join("nested/a.txt"), [0u8; 2]).unwrap();
+
+            // run test
+            let task = SplitTask {
+                source: source.clone(),
+                target: target.clone(),
+                walker: FileWalker::new(),
+                parts: 2,
+                by_size,
+                throttle: None,
+            };
+            let summaries = task
+                .split(
+                    &RealFileSystem,
+                    &mut TaskReport::default(),
+                    &mut Logger::disabled(),
+                )
+                .unwrap();
+            let mut moved: Vec<PathBuf> = Vec::new();
+            collect_files(&FileWalker::new(), &target, &mut moved).unwrap();
+            let mut remaining: Vec<PathBuf> = Vec::new();
+            collect_files(&FileWalker::new(), &source, &mut remaining).unwrap();
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            assert_eq!(moved.len(), 4);
+            assert!(remaining.is_empty());
+            if by_size {
+                assert_eq!(
+                    summaries,
+                    [
+                        PartSummary { files: 1, bytes: 6 },
+                        PartSummary { files: 3, bytes: 6 }
+                    ]
+                );
+            } else {
+                assert_eq!(
+                    summaries,
+                    [
+                        PartSummary { files: 2, bytes: 8 },
+                        PartSummary { files: 2, bytes: 4 }
+                    ]
+                );
+            }
+        }
+    }
+
+    /// verifies SplitTask::part_name() zero pads part numbers
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - SplitTask::part_name() does not pad to three digits or to the width of the largest part
+    #[test]
+    fn split_task_part_name() {
+        // create inputs
+        let mut task = SplitTask {
+            source: PathBuf::from("./src"),
+            target: PathBuf::from("./src/split"),
+            walker: FileWalker::new(),
+            parts: 10,
+            by_size: false,
+            throttle: None,
+        };
+
+        // run test
+        let small = task.part_name(9);
+        task.parts = 1001;
+        let large = task.part_name(9);
+
+        assert_eq!(small, "part_009");
+        assert_eq!(large, "part_0009");
+    }
+
+    /// verifies a symbolic link to a directory is left in place by default, moved into a part as the link itself with
+    /// `--no-follow`, and has the files it leads to split with `--follow`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - SplitTask::run_task() does not honor the symlink policy given on the command line
+    #[cfg(unix)]
+    #[test]
+    fn split_task_symlink_policy() {
+        use std::os::unix::fs::symlink;
+
+        for policy in [None, Some("--no-follow"), Some("--follow")] {
+            // create inputs
+            let path_buf = PathBuf::from("./split_task_symlink_policy_test");
+            let outside = path_buf.join("outside");
+            let source = path_buf.join("source");
+            let target = path_buf.join("target");
+
+            // create mock directories, the linked folder lives next to the source
+            for dir in [&outside, &source, &target] {
+                create_dir_all(dir).unwrap();
+            }
+            write(outside.join("b.txt"), "b").unwrap();
+            write(source.join("a.txt"), "a").unwrap();
+            symlink("../outside", source.join("linked")).unwrap();
+
+            // run test
+            let mut args = vec![
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--parts"),
+                String::from("1"),
+            ];
+            args.extend(policy.map(String::from));
+            let task = SplitTask::new(args.into_iter()).unwrap();
+            let report = task.run_task(&mut Logger::disabled());
+            let part = target.join(task.part_name(0));
+            let test_result = (
+                part.join("linked").is_symlink(),
+                part.join("b.txt").is_file(),
+                outside.join("b.txt").exists(),
+            );
+
+            // clean up mock directories
+            remove_dir_all(&path_buf).unwrap();
+
+            let (moved, expected) = match policy {
+                None => (1, (false, false, true)),
+                Some("--no-follow") => (2, (true, false, true)),
+                _ => (2, (false, true, false)),
+            };
+            assert_eq!(report.unwrap().moved, moved);
+            assert_eq!(test_result, expected);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+/// separate_args() splits the arguments of a task into its positional paths and its options so options may be given
+/// before, between, or after the paths. Arguments starting with `-` are options, those listed in `value_options` also
+/// take the argument after them as their value whatever it looks like, and everything after a lone `--` is positional
+/// so paths starting with `-` can still be passed. Both lists keep the order the arguments were given in
+///
+/// # Arguments
+///
+/// `args` an iterator containing the Strings following the task name
+/// `value_options` every option of the task that is followed by a value
+///
+/// # Errors
+///
+/// None
+pub fn separate_args(
+    mut args: impl Iterator<Item = String>,
+    value_options: &[&str],
+) -> (Vec<String>, Vec<String>) {
+    let mut positionals: Vec<String> = Vec::new();
+    let mut options: Vec<String> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            positionals.extend(args);
+            break;
+        }
+        if !arg.starts_with('-') {
+            positionals.push(arg);
+            continue;
+        }
+
+        // a missing value is left for the task to report
+        let takes_value = value_options.contains(&arg.as_str());
+        options.push(arg);
+        if takes_value {
+            options.extend(args.next());
+        }
+    }
+
+    (positionals, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies separate_args() keeps options and their values together wherever they appear relative to the paths
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - separate_args() treats an option value as a path or a path as an option value
+    /// - separate_args() does not treat arguments after `--` as paths
+    #[test]
+    fn separate_args_any_position() {
+        // args builder
+        let separate = |args: &[&str]| {
+            separate_args(
+                args.iter().map(|arg| arg.to_string()),
+                &["--date", "--index-sep"],
+            )
+        };
+        let paths = vec![String::from("./src"), String::from("./dst")];
+        let options = vec![
+            String::from("--keep-going"),
+            String::from("--date"),
+            String::from("modified"),
+        ];
+
+        for args in [
+            ["--keep-going", "--date", "modified", "./src", "./dst"],
+            ["./src", "--keep-going", "--date", "modified", "./dst"],
+            ["./src", "./dst", "--keep-going", "--date", "modified"],
+            ["--keep-going", "./src", "--date", "modified", "./dst"],
+        ] {
+            assert_eq!(separate(&args), (paths.clone(), options.clone()));
+        }
+
+        // values are taken verbatim even when they start with `-`, and `--` ends option parsing
+        assert_eq!(
+            separate(&["--index-sep", "-", "./src", "--", "-dst"]),
+            (
+                vec![String::from("./src"), String::from("-dst")],
+                vec![String::from("--index-sep"), String::from("-")]
+            )
+        );
+        assert_eq!(
+            separate(&["./src", "--date"]),
+            (vec![String::from("./src")], vec![String::from("--date")])
+        );
+    }
+}
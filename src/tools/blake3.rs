@@ -0,0 +1,489 @@
+/// number of bytes hashed into each leaf of the tree
+const CHUNK_LEN: usize = 1024;
+
+/// number of bytes compressed at once
+const BLOCK_LEN: usize = 64;
+
+/// initial chaining value, the same words as the initial hash value of SHA-256
+const IV: [u32; 8] = [
+    0x6A09_E667,
+    0xBB67_AE85,
+    0x3C6E_F372,
+    0xA54F_F53A,
+    0x510E_527F,
+    0x9B05_688C,
+    0x1F83_D9AB,
+    0x5BE0_CD19,
+];
+
+/// order the message words are permuted into between rounds
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// domain flag of the first block of a chunk
+const CHUNK_START: u32 = 1;
+
+/// domain flag of the last block of a chunk
+const CHUNK_END: u32 = 2;
+
+/// domain flag of a parent node
+const PARENT: u32 = 4;
+
+/// domain flag of the last compression of the root node
+const ROOT: u32 = 8;
+
+/// g() mixes two message words into a column or diagonal of the state
+///
+/// # Arguments
+///
+/// `state` the state being compressed
+/// `[a, b, c, d]` indices of the column or diagonal
+/// `mx` `my` the message words mixed in
+///
+/// # Errors
+///
+/// None
+fn g(state: &mut [u32; 16], [a, b, c, d]: [usize; 4], mx: u32, my: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(mx);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(my);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+/// compress() runs the compression function over a block of 16 message words and returns the full 16 word output
+///
+/// # Arguments
+///
+/// `chaining_value` the chaining value the block is compressed into
+/// `block_words` the block as 16 little endian words
+/// `counter` index of the chunk, 0 for parents and the root
+/// `block_len` number of bytes of the block that hold data
+/// `flags` domain flags of the block
+///
+/// # Errors
+///
+/// None
+fn compress(
+    chaining_value: &[u32; 8],
+    block_words: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut state = [
+        chaining_value[0],
+        chaining_value[1],
+        chaining_value[2],
+        chaining_value[3],
+        chaining_value[4],
+        chaining_value[5],
+        chaining_value[6],
+        chaining_value[7],
+        IV[0],
+        IV[1],
+        IV[2],
+        IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len,
+        flags,
+    ];
+    let mut block = *block_words;
+
+    for round in 0..7 {
+        // columns, then diagonals
+        g(&mut state, [0, 4, 8, 12], block[0], block[1]);
+        g(&mut state, [1, 5, 9, 13], block[2], block[3]);
+        g(&mut state, [2, 6, 10, 14], block[4], block[5]);
+        g(&mut state, [3, 7, 11, 15], block[6], block[7]);
+        g(&mut state, [0, 5, 10, 15], block[8], block[9]);
+        g(&mut state, [1, 6, 11, 12], block[10], block[11]);
+        g(&mut state, [2, 7, 8, 13], block[12], block[13]);
+        g(&mut state, [3, 4, 9, 14], block[14], block[15]);
+        if round < 6 {
+            block = MSG_PERMUTATION.map(|index| block[index]);
+        }
+    }
+
+    for index in 0..8 {
+        state[index] ^= state[index + 8];
+        state[index + 8] ^= chaining_value[index];
+    }
+
+    state
+}
+
+/// first_8_words() returns the chaining value held in the first half of a compression output
+///
+/// # Arguments
+///
+/// `words` output of compress()
+///
+/// # Errors
+///
+/// None
+fn first_8_words(words: [u32; 16]) -> [u32; 8] {
+    let mut first = [0; 8];
+    first.copy_from_slice(&words[..8]);
+    first
+}
+
+/// words_from_block() reads a block as 16 little endian words
+///
+/// # Arguments
+///
+/// `block` the block to read
+///
+/// # Errors
+///
+/// None
+fn words_from_block(block: &[u8; BLOCK_LEN]) -> [u32; 16] {
+    let mut words = [0; 16];
+    for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    words
+}
+
+/// Output struct: inputs of the last compression of a node, which is either a chaining value for its parent or, for
+/// the root, the digest
+struct Output {
+    /// chaining value the last block is compressed into
+    input_chaining_value: [u32; 8],
+
+    /// last block of the node as 16 little endian words
+    block_words: [u32; 16],
+
+    /// index of the chunk, 0 for parents
+    counter: u64,
+
+    /// number of bytes of the last block that hold data
+    block_len: u32,
+
+    /// domain flags of the last block, without ROOT
+    flags: u32,
+}
+
+impl Output {
+    /// chaining_value() returns the chaining value the node passes to its parent
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Output
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8_words(compress(
+            &self.input_chaining_value,
+            &self.block_words,
+            self.counter,
+            self.block_len,
+            self.flags,
+        ))
+    }
+
+    /// root_digest() returns the 32 byte digest of a node that is the root of the tree
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Output
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn root_digest(&self) -> [u8; 32] {
+        let words = compress(
+            &self.input_chaining_value,
+            &self.block_words,
+            0,
+            self.block_len,
+            self.flags | ROOT,
+        );
+        let mut digest = [0; 32];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(words) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+}
+
+/// parent_output() returns the Output of the parent of two chaining values
+///
+/// # Arguments
+///
+/// `left` `right` the chaining values of the two children
+///
+/// # Errors
+///
+/// None
+fn parent_output(left: [u32; 8], right: [u32; 8]) -> Output {
+    let mut block_words = [0; 16];
+    block_words[..8].copy_from_slice(&left);
+    block_words[8..].copy_from_slice(&right);
+
+    Output {
+        input_chaining_value: IV,
+        block_words,
+        counter: 0,
+        block_len: BLOCK_LEN as u32,
+        flags: PARENT,
+    }
+}
+
+/// ChunkState struct: a chunk that is being hashed, its last block is kept until the chunk is known to be complete
+#[derive(Debug, Clone)]
+struct ChunkState {
+    /// chaining value of the blocks compressed so far
+    chaining_value: [u32; 8],
+
+    /// index of the chunk in the message
+    chunk_counter: u64,
+
+    /// data that has not been compressed yet
+    block: [u8; BLOCK_LEN],
+
+    /// number of valid bytes in `block`
+    block_len: usize,
+
+    /// number of blocks of the chunk compressed so far
+    blocks_compressed: usize,
+}
+
+impl ChunkState {
+    /// ChunkState struct initializer for the chunk at `chunk_counter`
+    ///
+    /// # Arguments
+    ///
+    /// `chunk_counter` index of the chunk in the message
+    fn new(chunk_counter: u64) -> Self {
+        Self {
+            chaining_value: IV,
+            chunk_counter,
+            block: [0; BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+        }
+    }
+
+    /// len() returns the number of bytes fed into the chunk so far
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to ChunkState
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn len(&self) -> usize {
+        BLOCK_LEN * self.blocks_compressed + self.block_len
+    }
+
+    /// start_flag() returns CHUNK_START while the first block of the chunk has not been compressed
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to ChunkState
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    /// update() feeds `input` into the chunk
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to ChunkState
+    /// `input` the next bytes of the chunk, no more than fit into it
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            // a full block is only compressed once more input shows it is not the last of the chunk
+            if self.block_len == BLOCK_LEN {
+                self.chaining_value = first_8_words(compress(
+                    &self.chaining_value,
+                    &words_from_block(&self.block),
+                    self.chunk_counter,
+                    BLOCK_LEN as u32,
+                    self.start_flag(),
+                ));
+                self.blocks_compressed += 1;
+                self.block = [0; BLOCK_LEN];
+                self.block_len = 0;
+            }
+
+            let take = (BLOCK_LEN - self.block_len).min(input.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&input[..take]);
+            self.block_len += take;
+            input = &input[take..];
+        }
+    }
+
+    /// output() returns the Output of the chunk, which must be complete
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to ChunkState
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn output(&self) -> Output {
+        Output {
+            input_chaining_value: self.chaining_value,
+            block_words: words_from_block(&self.block),
+            counter: self.chunk_counter,
+            block_len: self.block_len as u32,
+            flags: self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+/// Blake3 struct: running BLAKE3 digest that can be fed data in chunks, producing the default 32 byte output
+#[derive(Debug, Clone)]
+pub struct Blake3 {
+    /// chunk currently being hashed
+    chunk_state: ChunkState,
+
+    /// chaining values of the complete subtrees to the left of the current chunk, largest first
+    cv_stack: Vec<[u32; 8]>,
+}
+
+impl Default for Blake3 {
+    /// default Blake3 is the digest of no data
+    fn default() -> Self {
+        Self {
+            chunk_state: ChunkState::new(0),
+            cv_stack: Vec::new(),
+        }
+    }
+}
+
+impl Blake3 {
+    /// Blake3 struct initializer for the digest of no data
+    ///
+    /// # Arguments
+    ///
+    /// None
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// update() feeds `data` into the digest
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Blake3
+    /// `data` the next bytes of the message
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // a full chunk is only finished once more input shows it is not the last one, which becomes the root
+            if self.chunk_state.len() == CHUNK_LEN {
+                let mut chaining_value = self.chunk_state.output().chaining_value();
+                let mut total_chunks = self.chunk_state.chunk_counter + 1;
+
+                // every trailing zero bit of the chunk count completes a subtree whose halves are merged
+                while total_chunks & 1 == 0 {
+                    let left = self.cv_stack.pop().unwrap(); // a completed subtree always has a left half
+                    chaining_value = parent_output(left, chaining_value).chaining_value();
+                    total_chunks >>= 1;
+                }
+                self.cv_stack.push(chaining_value);
+                self.chunk_state = ChunkState::new(self.chunk_state.chunk_counter + 1);
+            }
+
+            let take = (CHUNK_LEN - self.chunk_state.len()).min(data.len());
+            self.chunk_state.update(&data[..take]);
+            data = &data[take..];
+        }
+    }
+
+    /// finish() completes the digest
+    ///
+    /// # Arguments
+    ///
+    /// `self` the Blake3 to complete
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn finish(self) -> [u8; 32] {
+        let mut output = self.chunk_state.output();
+        for left in self.cv_stack.into_iter().rev() {
+            output = parent_output(left, output.chaining_value());
+        }
+
+        output.root_digest()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::sha256::to_hex;
+    use super::*;
+
+    /// verifies Blake3 produces the published digests and is independent of how data is chunked, including inputs
+    /// spanning several chunks
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Blake3 does not produce the published digests
+    /// - Blake3 depends on chunk boundaries
+    #[test]
+    fn blake3_test_vectors() {
+        // run test
+        let digest = |data: &[u8], chunk: usize| {
+            let mut blake3 = Blake3::new();
+            for part in data.chunks(chunk) {
+                blake3.update(part);
+            }
+            to_hex(&blake3.finish())
+        };
+        let long: Vec<u8> = (0..5000u32).map(|index| (index % 251) as u8).collect();
+
+        assert_eq!(
+            to_hex(&Blake3::new().finish()),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        assert_eq!(
+            digest(b"abc", 1),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+        assert_eq!(digest(&long, 7), digest(&long, 4096));
+        // the official test vectors hash the first bytes of 0, 1, ..., 250, 0, 1, ...
+        assert_eq!(
+            digest(&long[..1], 1),
+            "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213"
+        );
+        assert_eq!(
+            digest(&long[..1024], 100),
+            "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7"
+        );
+        assert_eq!(
+            digest(&long[..1025], 1025),
+            "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444"
+        );
+    }
+}
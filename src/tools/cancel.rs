@@ -0,0 +1,142 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// flag set by the first Ctrl-C once cancel_on_interrupt() is called
+static INTERRUPT_FLAG: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+/// Cancel struct: shared flag a task checks between files and stops at once it is set, compared by identity since two
+/// flags that are both unset are still different flags
+#[derive(Clone)]
+pub struct Cancel(pub Arc<AtomicBool>);
+
+impl Cancel {
+    /// is_set() returns whether the run has been asked to stop
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Cancel
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn is_set(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for Cancel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cancel({})", self.is_set())
+    }
+}
+
+impl PartialEq for Cancel {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Cancel {}
+
+/// cancel_on_interrupt() makes the first Ctrl-C set `flag` instead of ending the process, so a task checking it can
+/// stop cleanly after the file it is working on. A second Ctrl-C ends the process as usual, for a task that does not
+/// check the flag or takes too long to stop. Only the first call installs the handler, platforms without a supported
+/// handler leave Ctrl-C alone
+///
+/// # Arguments
+///
+/// `flag` the flag set by Ctrl-C
+///
+/// # Errors
+///
+/// None
+pub fn cancel_on_interrupt(flag: Arc<AtomicBool>) {
+    if INTERRUPT_FLAG.set(flag).is_ok() {
+        platform::install();
+    }
+}
+
+/// interrupted() body of the Ctrl-C handler, sets the flag given to cancel_on_interrupt() and returns whether there was
+/// one, only touches atomics so it is safe to run inside a signal handler
+///
+/// # Arguments
+///
+/// None
+///
+/// # Errors
+///
+/// None
+fn interrupted() -> bool {
+    match INTERRUPT_FLAG.get() {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::os::raw::c_int;
+
+    /// interrupt from the keyboard, the same number on every Unix
+    const SIGINT: c_int = 2;
+
+    /// default action of a signal, ending the process for SIGINT
+    const SIG_DFL: usize = 0;
+
+    extern "C" {
+        fn signal(signum: c_int, handler: usize) -> usize;
+    }
+
+    extern "C" fn handle(_: c_int) {
+        super::interrupted();
+
+        // SAFETY: signal() is async-signal-safe, restoring the default makes the next Ctrl-C end the process
+        unsafe { signal(SIGINT, SIG_DFL) };
+    }
+
+    pub fn install() {
+        // SAFETY: `handle` matches the signature of a signal handler and only touches atomics and signal()
+        unsafe { signal(SIGINT, handle as extern "C" fn(c_int) as usize) };
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// control event sent by Ctrl-C
+    const CTRL_C_EVENT: u32 = 0;
+
+    /// whether a Ctrl-C has already been handled, the next one is passed on so it ends the process
+    static HANDLED: AtomicBool = AtomicBool::new(false);
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn SetConsoleCtrlHandler(
+            handler: Option<unsafe extern "system" fn(u32) -> i32>,
+            add: i32,
+        ) -> i32;
+    }
+
+    unsafe extern "system" fn handle(event: u32) -> i32 {
+        if event != CTRL_C_EVENT || HANDLED.swap(true, Ordering::Relaxed) {
+            return 0;
+        }
+        i32::from(super::interrupted())
+    }
+
+    pub fn install() {
+        // SAFETY: `handle` matches the signature of a console control handler and only touches atomics
+        unsafe { SetConsoleCtrlHandler(Some(handle), 1) };
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    // no handler is installed
+    pub fn install() {}
+}
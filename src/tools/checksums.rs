@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// declare local code
+use super::sha256::to_hex;
+
+/// checksum_line() formats a line of a checksum manifest in the format written and read by `sha256sum`, the
+/// hexadecimal digest followed by two spaces and the path, without a line break
+///
+/// # Arguments
+///
+/// `digest` the SHA-256 digest of the file
+/// `path` a Path that corresponds to the file, relative to the directory the manifest is checked in
+///
+/// # Errors
+///
+/// None
+pub fn checksum_line(digest: &[u8; 32], path: &Path) -> String {
+    format!("{}  {}", to_hex(digest), path.display())
+}
+
+/// parse_checksums() reads every digest and path of a checksum manifest written by checksum_line() or `sha256sum`,
+/// which marks files hashed in binary mode with `*` in place of the second space. Empty lines are ignored
+///
+/// # Arguments
+///
+/// `text` the contents of the manifest
+///
+/// # Errors
+///
+/// - a line does not start with a 64 digit hexadecimal digest followed by a space, a space or `*`, and a path
+pub fn parse_checksums(text: &str) -> io::Result<Vec<([u8; 32], PathBuf)>> {
+    let mut checksums: Vec<([u8; 32], PathBuf)> = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        let parsed = line.split_at_checked(64).and_then(|(hex, rest)| {
+            let path = rest
+                .strip_prefix("  ")
+                .or_else(|| rest.strip_prefix(" *"))?;
+            Some((from_hex(hex)?, path)).filter(|(_, path)| !path.is_empty())
+        });
+        match parsed {
+            Some((digest, path)) => checksums.push((digest, PathBuf::from(path))),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {} is not a SHA-256 checksum line", number + 1),
+                ))
+            }
+        }
+    }
+
+    Ok(checksums)
+}
+
+/// merge_checksums() returns the text of a checksum manifest holding every line of `existing` followed by `added`, a
+/// line of `existing` whose path is listed in `added` is dropped as the file at that path has since been replaced
+///
+/// # Arguments
+///
+/// `existing` the contents of the manifest written by earlier runs, empty if there is none
+/// `added` the digest and path of every file placed since
+///
+/// # Errors
+///
+/// - parse_checksums() call fails for `existing`
+pub fn merge_checksums(existing: &str, added: &[([u8; 32], PathBuf)]) -> io::Result<String> {
+    let replaced: HashSet<&PathBuf> = added.iter().map(|(_, path)| path).collect();
+    let kept = parse_checksums(existing)?;
+    let kept = kept.iter().filter(|(_, path)| !replaced.contains(path));
+
+    Ok(kept
+        .chain(added)
+        .map(|(digest, path)| format!("{}\n", checksum_line(digest, path)))
+        .collect())
+}
+
+/// from_hex() parses a digest written as 64 hexadecimal digits of either case, returns None for anything else
+///
+/// # Arguments
+///
+/// `hex` the hexadecimal digest
+///
+/// # Errors
+///
+/// None
+fn from_hex(hex: &str) -> Option<[u8; 32]> {
+    let mut digest = [0u8; 32];
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    for (byte, pair) in digest.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+
+    Some(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies lines written by checksum_line() are read back by parse_checksums(), along with binary mode lines
+    /// written by `sha256sum`, and that malformed lines are rejected
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_checksums() does not return the digest and path written by checksum_line()
+    /// - parse_checksums() accepts a line with a short digest, a non hexadecimal digest, or no path
+    #[test]
+    fn checksums_round_trip() {
+        let digest: [u8; 32] = std::array::from_fn(|index| index as u8 * 7);
+        let line = checksum_line(&digest, Path::new("2023/2023-01/2023-01_0.jpg"));
+
+        assert_eq!(
+            line,
+            format!("{}  2023/2023-01/2023-01_0.jpg", to_hex(&digest))
+        );
+        assert_eq!(
+            parse_checksums(&format!(
+                "{line}\n\n{} *b.jpg\n",
+                to_hex(&digest).to_uppercase()
+            ))
+            .unwrap(),
+            [
+                (digest, PathBuf::from("2023/2023-01/2023-01_0.jpg")),
+                (digest, PathBuf::from("b.jpg"))
+            ]
+        );
+        for text in [
+            "abc  a.jpg".to_string(),
+            format!("{}  ", to_hex(&digest)),
+            format!("{} a.jpg", to_hex(&digest)),
+            format!("{}g  a.jpg", &to_hex(&digest)[1..]),
+        ] {
+            assert!(parse_checksums(&text).is_err());
+        }
+    }
+
+    /// verifies merge_checksums() keeps the lines of earlier runs, replaces the line of a path that was placed again,
+    /// and appends the new lines
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - merge_checksums() drops a line of an earlier run or keeps the replaced line of a path placed again
+    /// - merge_checksums() accepts a malformed existing manifest
+    #[test]
+    fn checksums_merge() {
+        let (old, new) = ([1u8; 32], [2u8; 32]);
+        let existing = format!(
+            "{}\n{}\n",
+            checksum_line(&old, Path::new("a.jpg")),
+            checksum_line(&old, Path::new("b.jpg"))
+        );
+        let added = [(new, PathBuf::from("b.jpg")), (new, PathBuf::from("c.jpg"))];
+
+        assert_eq!(
+            parse_checksums(&merge_checksums(&existing, &added).unwrap()).unwrap(),
+            [
+                (old, PathBuf::from("a.jpg")),
+                (new, PathBuf::from("b.jpg")),
+                (new, PathBuf::from("c.jpg"))
+            ]
+        );
+        assert_eq!(
+            merge_checksums("", &added[1..]).unwrap(),
+            format!("{}\n", checksum_line(&new, Path::new("c.jpg")))
+        );
+        assert!(merge_checksums("not a checksum line", &added).is_err());
+    }
+}
@@ -1,11 +1,24 @@
-use std::fs::{read_dir, ReadDir};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use super::file_system::{FileSystem, RealFileSystem};
+use super::file_walker::FileWalker;
+
+/// CollectOutcome struct: the files found by collect_files_lenient() along with the directories it could not read
+#[derive(Debug, Default)]
+pub struct CollectOutcome {
+    /// PathBufs of every file found in the readable part of the tree
+    pub files: Vec<PathBuf>,
+
+    /// directories that could not be read along with the error encountered, their contents are missing from `files`
+    pub errors: Vec<(PathBuf, io::Error)>,
+}
 
 /// collect_files() collects files from a PathBuf and returns an Vector of PathBufs of all found files
 ///
 /// # Arguments
 ///
+/// `walker` a FileWalker controlling which parts of the tree are visited, FileWalker::new() visits everything
 /// `path_buf` a PathBuf that corresponds to a directory
 /// `vec` a mutable vector to place file PathBufs into
 ///
@@ -15,29 +28,96 @@ use std::path::PathBuf;
 /// -`path_buf` corresponds to a protected object on the file system or maps to a broken link
 /// - path from item at `path_buf` cannot be obtained
 pub fn collect_files<'a>(
-    path_buf: &PathBuf,
+    walker: &FileWalker,
+    path_buf: &Path,
     vec: &'a mut Vec<PathBuf>,
 ) -> io::Result<&'a mut Vec<PathBuf>> {
-    // ensure that path_buf is a valid directory and read all items
-    let items: ReadDir = read_dir(path_buf)?;
+    collect_files_in(&RealFileSystem, walker, path_buf, vec)
+}
 
+/// collect_files_in() collects files from a PathBuf on `fs` in the same way as collect_files()
+///
+/// # Arguments
+///
+/// `fs` the FileSystem the tree is read from
+/// `walker` a FileWalker controlling which parts of the tree are visited
+/// `path_buf` a PathBuf that corresponds to a directory
+/// `vec` a mutable vector to place file PathBufs into
+///
+/// # Errors
+///
+/// - see collect_files()
+pub(crate) fn collect_files_in<'a>(
+    fs: &dyn FileSystem,
+    walker: &FileWalker,
+    path_buf: &Path,
+    vec: &'a mut Vec<PathBuf>,
+) -> io::Result<&'a mut Vec<PathBuf>> {
     // recursively search for all non-directory items within path_buf and push them into a vector
-    for item in items {
-        let item = item?.path();
-
-        if item.is_dir() {
-            collect_files(&item, vec)?;
-        } else {
-            vec.push(item)
+    walker.walk_in(fs, path_buf, &mut |entry| {
+        if !entry.is_dir {
+            vec.push(entry.path.clone());
         }
-    }
+        Ok(())
+    })?;
 
     Ok(vec)
 }
 
+/// collect_files_lenient() collects files from a PathBuf like collect_files(), but instead of stopping at the first
+/// directory that cannot be read it records the error and carries on with the rest of the tree
+///
+/// # Arguments
+///
+/// `walker` a FileWalker controlling which parts of the tree are visited, FileWalker::new() visits everything
+/// `path_buf` a PathBuf that corresponds to a directory
+///
+/// # Errors
+///
+/// None, every error is returned in CollectOutcome::errors
+pub fn collect_files_lenient(walker: &FileWalker, path_buf: &Path) -> CollectOutcome {
+    collect_files_lenient_in(&RealFileSystem, walker, path_buf)
+}
+
+/// collect_files_lenient_in() collects files from a PathBuf on `fs` in the same way as collect_files_lenient()
+///
+/// # Arguments
+///
+/// `fs` the FileSystem the tree is read from
+/// `walker` a FileWalker controlling which parts of the tree are visited
+/// `path_buf` a PathBuf that corresponds to a directory
+///
+/// # Errors
+///
+/// - see collect_files_lenient()
+pub(crate) fn collect_files_lenient_in(
+    fs: &dyn FileSystem,
+    walker: &FileWalker,
+    path_buf: &Path,
+) -> CollectOutcome {
+    let mut outcome = CollectOutcome::default();
+    let CollectOutcome { files, errors } = &mut outcome;
+
+    // the visitor never fails so the walk itself cannot either
+    let _ = walker.walk_lenient_in(
+        fs,
+        path_buf,
+        &mut |entry| {
+            if !entry.is_dir {
+                files.push(entry.path.clone());
+            }
+            Ok(())
+        },
+        errors,
+    );
+
+    outcome
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::file_system::MockFileSystem;
     use std::fs::{create_dir, create_dir_all, remove_dir_all, remove_file, File};
 
     /// verifies collect_files() errors if `path_buf` is not a valid directory
@@ -59,7 +139,7 @@ mod tests {
         File::create(&path_buf).unwrap();
 
         // run test
-        let test_result = collect_files(&path_buf, &mut vec);
+        let test_result = collect_files(&FileWalker::new(), &path_buf, &mut vec);
 
         // clean up temporary files
         remove_file(path_buf).unwrap();
@@ -96,7 +176,8 @@ mod tests {
         }
 
         // run test
-        let test_result: &mut Vec<PathBuf> = collect_files(&path_buf, &mut vec).unwrap();
+        let test_result: &mut Vec<PathBuf> =
+            collect_files(&FileWalker::new(), &path_buf, &mut vec).unwrap();
 
         // sort values for element-wise comparison
         test_result.sort();
@@ -140,7 +221,8 @@ mod tests {
         }
 
         // run test
-        let test_result: &mut Vec<PathBuf> = collect_files(&path_buf, &mut vec).unwrap();
+        let test_result: &mut Vec<PathBuf> =
+            collect_files(&FileWalker::new(), &path_buf, &mut vec).unwrap();
 
         // sort values for element-wise comparison
         test_result.sort();
@@ -150,4 +232,53 @@ mod tests {
 
         assert_eq!(test_result, &test_vec);
     }
+
+    /// verifies collect_files() stops at an unreadable directory while collect_files_lenient() records it and still
+    /// collects every other file
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - collect_files_in() does not fail on the unreadable directory
+    /// - collect_files_lenient_in() misses a readable file, collects a file of the unreadable directory, or does not
+    ///   record its error
+    #[test]
+    fn collect_files_lenient_unreadable() {
+        // create inputs
+        const UNREADABLE: &str = "./collect_files_lenient_unreadable_test/locked";
+        let path_buf = PathBuf::from("./collect_files_lenient_unreadable_test");
+        let fs = MockFileSystem {
+            unreadable_dir: Some(UNREADABLE),
+            ..MockFileSystem::default()
+        };
+
+        // create mock directory
+        create_dir_all(Path::new(UNREADABLE)).unwrap();
+        create_dir_all(path_buf.join("open")).unwrap();
+        for file in ["a.txt", "open/b.txt", "locked/c.txt"] {
+            File::create(path_buf.join(file)).unwrap();
+        }
+
+        // run test
+        let strict = collect_files_in(&fs, &FileWalker::new(), &path_buf, &mut Vec::new()).is_err();
+        let lenient = collect_files_lenient_in(&fs, &FileWalker::new(), &path_buf);
+        let missing = collect_files_lenient(&FileWalker::new(), &path_buf.join("missing"));
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert!(strict);
+        assert_eq!(
+            lenient.files,
+            [path_buf.join("a.txt"), path_buf.join("open/b.txt")]
+        );
+        assert_eq!(lenient.errors.len(), 1);
+        assert_eq!(lenient.errors[0].0, Path::new(UNREADABLE));
+        assert_eq!(lenient.errors[0].1.kind(), io::ErrorKind::PermissionDenied);
+        assert!(missing.files.is_empty());
+        assert_eq!(missing.errors.len(), 1);
+    }
 }
@@ -0,0 +1,163 @@
+use std::ffi::OsStr;
+use std::path::Path;
+
+// declare local code
+use super::sanitize_filename::sanitize_filename;
+
+/// multi-part extensions recognized once compound extensions are enabled, lowercased and without a leading dot
+pub const DEFAULT_COMPOUND_EXTENSIONS: &[&str] = &[
+    "tar.gz", "tar.bz2", "tar.xz", "tar.zst", "tar.lz", "tar.lzma", "tar.lz4",
+];
+
+/// CompoundExtensions struct: multi-part extensions such as `tar.gz` that are treated as a single extension, so that
+/// `archive.tar.gz` has the extension `tar.gz` rather than `gz`. Off by default, once enabled the default list applies
+/// unless another list is given
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CompoundExtensions {
+    /// the lowercased compound extensions recognized, None when compound extensions are disabled
+    list: Option<Vec<String>>,
+}
+
+impl CompoundExtensions {
+    /// enable() turns compound extensions on with DEFAULT_COMPOUND_EXTENSIONS, a list given earlier is kept
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to CompoundExtensions
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn enable(&mut self) {
+        if self.list.is_none() {
+            self.list = Some(
+                DEFAULT_COMPOUND_EXTENSIONS
+                    .iter()
+                    .map(|extension| extension.to_string())
+                    .collect(),
+            );
+        }
+    }
+
+    /// set() turns compound extensions on with `list` in place of the default list
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to CompoundExtensions
+    /// `list` the lowercased compound extensions to recognize, see parse_compound_extensions()
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn set(&mut self, list: Vec<String>) {
+        self.list = Some(list);
+    }
+
+    /// extension() returns the effective extension of `file`, the longest recognized compound extension its name ends
+    /// in (compared without regard to ASCII case) or otherwise `file.extension()`. A name made up of only the compound
+    /// extension, such as `.tar.gz`, falls back to `file.extension()` as well
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to CompoundExtensions
+    /// `file` a Path that corresponds to the file
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn extension<'a>(&self, file: &'a Path) -> Option<&'a OsStr> {
+        let compound = self.list.as_ref().and_then(|list| {
+            let name = file.file_name()?.to_str()?;
+            let lowercase = name.to_ascii_lowercase();
+            list.iter()
+                .filter(|extension| {
+                    lowercase.len() > extension.len() + 1
+                        && lowercase.ends_with(extension.as_str())
+                        && lowercase.as_bytes()[lowercase.len() - extension.len() - 1] == b'.'
+                })
+                .map(String::len)
+                .max()
+                // ASCII lowercasing keeps every byte in place so the suffix can be taken from the original name
+                .map(|len| OsStr::new(&name[name.len() - len..]))
+        });
+
+        compound.or_else(|| file.extension())
+    }
+}
+
+/// parse_compound_extensions() parses the value of `--compound-ext-list`, a comma separated list of multi-part
+/// extensions such as `tar.gz,tar.bz2`, a leading `.` on each is ignored
+///
+/// # Arguments
+///
+/// `value` the value following `--compound-ext-list`
+///
+/// # Errors
+///
+/// - `value` is missing, or contains an extension without an inner `.` or that is not legal in file names
+pub fn parse_compound_extensions(value: Option<String>) -> Result<Vec<String>, &'static str> {
+    const ERROR: &str =
+        "'--compound-ext-list' must be followed by a comma separated list of extensions, such as tar.gz,tar.bz2";
+
+    let mut list: Vec<String> = Vec::new();
+    for extension in value.ok_or(ERROR)?.split(',') {
+        let extension = extension.trim_start_matches('.').to_ascii_lowercase();
+
+        // every part must be non-empty so the extension is made of at least two
+        let compound = extension.contains('.') && extension.split('.').all(|part| !part.is_empty());
+        if !compound || sanitize_filename(&extension, '_') != extension {
+            return Err(ERROR);
+        }
+        list.push(extension);
+    }
+
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies compound extensions are only recognized once enabled, the longest match wins, case is ignored, and
+    /// files without a compound extension keep their usual one
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - CompoundExtensions::extension() does not return `tar.gz` for `archive.tar.gz` once enabled
+    /// - CompoundExtensions::extension() changes the extension of any other file
+    /// - parse_compound_extensions() does not parse a valid list or accepts an invalid one
+    #[test]
+    fn compound_extensions_extension() {
+        // create inputs
+        let disabled = CompoundExtensions::default();
+        let mut enabled = CompoundExtensions::default();
+        enabled.enable();
+        let mut custom = CompoundExtensions::default();
+        custom.set(parse_compound_extensions(Some(String::from("tar.gz,.backup.tar.gz"))).unwrap());
+        let extension = |compound: &CompoundExtensions, name: &'static str| {
+            compound.extension(Path::new(name)).and_then(OsStr::to_str)
+        };
+
+        assert_eq!(extension(&disabled, "archive.tar.gz"), Some("gz"));
+        assert_eq!(extension(&enabled, "archive.tar.gz"), Some("tar.gz"));
+        assert_eq!(extension(&enabled, "dir/ARCHIVE.Tar.BZ2"), Some("Tar.BZ2"));
+        assert_eq!(extension(&enabled, "photo.jpg"), Some("jpg"));
+        assert_eq!(extension(&enabled, "archive.gz"), Some("gz"));
+        assert_eq!(extension(&enabled, "mytar.gz"), Some("gz"));
+        assert_eq!(extension(&enabled, ".tar.gz"), Some("gz"));
+        assert_eq!(extension(&enabled, "README"), None);
+        assert_eq!(
+            extension(&custom, "db.backup.tar.gz"),
+            Some("backup.tar.gz")
+        );
+        assert_eq!(extension(&custom, "archive.tar.xz"), Some("xz"));
+        for value in ["gz", "tar.", "tar..gz", "tar.g/z", ""] {
+            assert!(parse_compound_extensions(Some(value.to_string())).is_err());
+        }
+        assert!(parse_compound_extensions(None).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use std::io::{self, BufRead, Write};
+
+/// confirm() asks `question` on `output` followed by ` [y/N] ` and reads the answer from a line of `input`, only `y`
+/// or `yes` in any case count as a yes so an empty line or the end of input, as when no one is there to answer, is a
+/// no
+///
+/// # Arguments
+///
+/// `question` the question to ask
+/// `input` where the answer is read from, stdin outside of tests
+/// `output` where the question is written to
+///
+/// # Errors
+///
+/// - the question cannot be written to or flushed from `output`
+/// - the answer cannot be read from `input`
+pub fn confirm(
+    question: &str,
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> io::Result<bool> {
+    write!(output, "{question} [y/N] ")?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies only a yes answer confirms and that the question is written with the choices
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - confirm() does not accept `y` or `YES`
+    /// - confirm() accepts any other answer or the end of input
+    #[test]
+    fn confirm_answers() {
+        let answer = |input: &str| {
+            let mut output: Vec<u8> = Vec::new();
+            let confirmed = confirm("continue?", &mut input.as_bytes(), &mut output).unwrap();
+            (confirmed, String::from_utf8(output).unwrap())
+        };
+
+        assert_eq!(answer("y\n"), (true, String::from("continue? [y/N] ")));
+        assert!(answer(" YES \n").0);
+        for input in ["n\n", "\n", "", "yeah\n"] {
+            assert!(!answer(input).0);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::fs::{File, Metadata};
+use std::io;
+
+/// copy_permissions() applies the permissions found in `metadata` onto `file`, the mode bits on Unix and the read only
+/// flag elsewhere. On Unix the owner and group are restored first, as changing them may clear the setuid and setgid
+/// bits, this only succeeds when the process may change ownership (typically when running as root) and is silently
+/// skipped otherwise
+///
+/// # Arguments
+///
+/// `metadata` Metadata of the file whose permissions should be restored
+/// `file` an open File to apply the permissions to
+///
+/// # Errors
+///
+/// - changing the owner of `file` fails for any reason other than lacking permission
+/// - File::set_permissions() call fails
+pub fn copy_permissions(metadata: &Metadata, file: &File) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{fchown, MetadataExt};
+
+        match fchown(file, Some(metadata.uid()), Some(metadata.gid())) {
+            Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {}
+            result => result?,
+        }
+    }
+
+    file.set_permissions(metadata.permissions())
+}
+
+// the test relies on unix mode bits
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir, remove_dir_all, set_permissions, Permissions};
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+
+    /// verifies copy_permissions() restores the mode bits of the source onto the target
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - the mode bits of the target differ from the source
+    #[test]
+    fn copy_permissions_mode_bits() {
+        // create inputs
+        let path_buf = PathBuf::from("./copy_permissions_mode_bits_test/");
+        let source = path_buf.join("source.txt");
+        let target = path_buf.join("target.txt");
+
+        // create mock directory with a source file only its owner may write and others may not read
+        create_dir(&path_buf).unwrap();
+        File::create(&source).unwrap();
+        set_permissions(&source, Permissions::from_mode(0o640)).unwrap();
+
+        // run test
+        copy_permissions(&source.metadata().unwrap(), &File::create(&target).unwrap()).unwrap();
+        let test_result = target.metadata().unwrap().permissions().mode() & 0o7777;
+
+        // clean up mock directory
+        remove_dir_all(path_buf).unwrap();
+
+        assert_eq!(test_result, 0o640);
+    }
+}
@@ -0,0 +1,161 @@
+use std::io;
+use std::path::Path;
+
+/// next_named_index() returns one more than the largest index among the files in a flat directory whose names follow
+/// the `<label><sep><index>` or `<label><sep><index>.<extension>` pattern used for organized files, or zero if there
+/// are none, so that an index counted up from it never reuses the name of an existing file even when existing indices
+/// have gaps, files with any other name are ignored (will not recursively search subdirectories)
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to a directory
+/// `label` the label that prefixes every organized file name in the directory, for example `2023-01`
+/// `sep` the character placed between the label and the index
+///
+/// # Errors
+///
+/// -`path` does not correspond to a directory
+/// -`path` corresponds to a protected object on the file system or maps to a broken link
+/// - path from item at `path` cannot be obtained
+pub fn next_named_index(path: &Path, label: &str, sep: char) -> io::Result<usize> {
+    let mut next: usize = 0;
+
+    for item in path.read_dir()? {
+        let item = item?.path();
+        if !item.is_file() {
+            continue;
+        }
+
+        // indices too large for a usize cannot be produced by a counter so they cannot collide either
+        let index = item.file_name().and_then(|name| {
+            named_index(&name.to_string_lossy(), label, sep)?
+                .parse::<usize>()
+                .ok()
+        });
+        if let Some(index) = index {
+            next = next.max(index.saturating_add(1));
+        }
+    }
+
+    Ok(next)
+}
+
+/// named_index() returns the digits of the index of `name` if it follows the `<label><sep><index>` or
+/// `<label><sep><index>.<extension>` pattern
+///
+/// # Arguments
+///
+/// `name` the file name to check
+/// `label` the label expected at the start of the name
+/// `sep` the character expected between the label and the index
+///
+/// # Errors
+///
+/// None
+fn named_index<'a>(name: &'a str, label: &str, sep: char) -> Option<&'a str> {
+    // strip the label and separator, anything else is not an organized file
+    let rest = name.strip_prefix(label)?.strip_prefix(sep)?;
+    let index = rest.split_once('.').map_or(rest, |(index, _)| index);
+
+    (!index.is_empty() && index.chars().all(|c| c.is_ascii_digit())).then_some(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir, remove_dir_all, File};
+    use std::path::PathBuf;
+
+    /// verifies next_named_index() only takes the indices of files that follow the naming pattern
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - next_named_index() takes the index of a file that does not follow the naming pattern
+    /// - next_named_index() misses a file that follows the naming pattern
+    #[test]
+    fn next_named_index_mixed_dir() {
+        // create inputs
+        let path_buf = PathBuf::from("./next_named_index_mixed_dir_test/");
+        let names = [
+            "2023-01_0.jpg",
+            "2023-01_1",
+            "2023-01_12.tar.gz",
+            "2023-01_.jpg",
+            "2023-01_x.jpg",
+            "2023-02_3.jpg",
+            "holiday.jpg",
+            "notes.txt",
+        ];
+
+        // create mock directory
+        create_dir(&path_buf).unwrap();
+        for name in names {
+            File::create(path_buf.join(name)).unwrap();
+        }
+
+        // run test
+        let test_result = next_named_index(&path_buf, "2023-01", '_').unwrap();
+
+        // clean up mock directory
+        remove_dir_all(path_buf).unwrap();
+
+        assert_eq!(test_result, 13);
+    }
+
+    /// verifies next_named_index() continues after the largest existing index rather than the number of files
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - next_named_index() does not return one more than the largest index of a matching file
+    #[test]
+    fn next_named_index_gaps() {
+        // create inputs
+        let path_buf = PathBuf::from("./next_named_index_gaps_test/");
+
+        // create mock directory, indices 1 to 6 are missing as a global counter placed them in other folders
+        create_dir(&path_buf).unwrap();
+        for name in [
+            "2023-01_0.jpg",
+            "2023-01_7.jpg",
+            "2023-02_9.jpg",
+            "2023-01_x.jpg",
+        ] {
+            File::create(path_buf.join(name)).unwrap();
+        }
+
+        // run test
+        let test_result = (
+            next_named_index(&path_buf, "2023-01", '_').unwrap(),
+            next_named_index(&path_buf, "2023-03", '_').unwrap(),
+        );
+
+        // clean up mock directory
+        remove_dir_all(path_buf).unwrap();
+
+        assert_eq!(test_result, (8, 0));
+    }
+
+    /// verifies next_named_index() errors if `path` is not a valid directory
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - next_named_index() does not error if `path` does not exist
+    #[test]
+    fn next_named_index_invalid_dir() {
+        assert!(
+            next_named_index(Path::new("./next_named_index_not_a_dir/"), "2023-01", '_').is_err()
+        );
+    }
+}
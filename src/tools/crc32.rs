@@ -0,0 +1,117 @@
+/// lookup table for the reflected CRC-32 polynomial 0xEDB88320 used by gzip and zip
+const TABLE: [u32; 256] = build_table();
+
+/// build_table() computes the CRC-32 lookup table at compile time
+///
+/// # Arguments
+///
+/// None
+///
+/// # Errors
+///
+/// None
+const fn build_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut index = 0;
+
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+
+    table
+}
+
+/// Crc32 struct: running CRC-32 checksum that can be fed data in chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc32 {
+    /// inverted running checksum
+    state: u32,
+}
+
+impl Default for Crc32 {
+    /// default Crc32 is the checksum of no data
+    fn default() -> Self {
+        Self { state: 0xFFFF_FFFF }
+    }
+}
+
+impl Crc32 {
+    /// Crc32 struct initializer for the checksum of no data
+    ///
+    /// # Arguments
+    ///
+    /// None
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// update() feeds `data` into the checksum
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Crc32
+    /// `data` the next chunk of data
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.state = TABLE[((self.state ^ *byte as u32) & 0xFF) as usize] ^ (self.state >> 8);
+        }
+    }
+
+    /// value() returns the checksum of all data fed so far
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Crc32
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn value(&self) -> u32 {
+        !self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies Crc32 produces the standard check value and is independent of how data is chunked
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - Crc32 does not produce the standard CRC-32 of `123456789`
+    /// - Crc32 depends on chunk boundaries
+    #[test]
+    fn crc32_check_value() {
+        // run test
+        let mut whole = Crc32::new();
+        whole.update(b"123456789");
+
+        let mut chunked = Crc32::new();
+        chunked.update(b"1234");
+        chunked.update(b"56789");
+
+        assert_eq!(Crc32::new().value(), 0);
+        assert_eq!(whole.value(), 0xCBF4_3926);
+        assert_eq!(chunked, whole);
+    }
+}
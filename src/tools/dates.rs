@@ -0,0 +1,309 @@
+use std::fs::Metadata;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use time::OffsetDateTime;
+
+// declare local code
+use super::name_date::{name_date, parse_name_pattern};
+use super::regex::Regex;
+use super::sidecar::sidecar_date;
+
+/// DateSource enum: which date of a file it is organized by
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum DateSource {
+    /// the date the file was created, see creation_time()
+    #[default]
+    Created,
+
+    /// the date the file was last modified
+    Modified,
+
+    /// the date recorded in a `.json` or `.xmp` sidecar next to the file, read from the given field or the default of
+    /// each format, see sidecar_date(), files without a sidecar fall back to their creation date
+    Sidecar(Option<String>),
+
+    /// the date embedded in the file name, read with the given pattern or the built-in patterns, see name_date(), files
+    /// whose name holds no date fall back to their creation date
+    Name(Option<Regex>),
+}
+
+/// parse_date_source() parses the value passed to a `--date` option
+///
+/// # Arguments
+///
+/// `value` the String following the option, if any
+///
+/// # Errors
+///
+/// - `value` is missing or is not `created`, `modified`, `sidecar`, `sidecar=FIELD`, `name`, or `name:PATTERN`
+/// - parse_name_pattern() call fails
+pub fn parse_date_source(value: Option<String>) -> Result<DateSource, &'static str> {
+    const ERROR: &str =
+        "'--date' must be followed by 'created', 'modified', 'sidecar', 'sidecar=FIELD', 'name', or 'name:PATTERN'";
+
+    match value.as_deref() {
+        Some("created") => Ok(DateSource::Created),
+        Some("modified") => Ok(DateSource::Modified),
+        Some("sidecar") => Ok(DateSource::Sidecar(None)),
+        Some("name") => Ok(DateSource::Name(None)),
+        Some(value) if value.starts_with("name:") => Ok(DateSource::Name(Some(
+            parse_name_pattern(&value["name:".len()..])?,
+        ))),
+        Some(value) => match value.strip_prefix("sidecar=") {
+            Some(field) if !field.is_empty() => Ok(DateSource::Sidecar(Some(field.to_string()))),
+            _ => Err(ERROR),
+        },
+        None => Err(ERROR),
+    }
+}
+
+/// file_date() returns the date a file is organized by
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to the file
+/// `source` which date of the file to use
+///
+/// # Errors
+///
+/// - metadata is not retrievable for the file
+/// - the requested date is not retrievable for the file
+/// - sidecar_date() call fails
+/// - name_date() call fails
+pub fn file_date(path: &Path, source: &DateSource) -> io::Result<OffsetDateTime> {
+    match source {
+        DateSource::Created => creation_time(path),
+        DateSource::Modified => Ok(path.metadata()?.modified()?.into()),
+        DateSource::Sidecar(field) => match sidecar_date(path, field.as_deref())? {
+            Some(date) => Ok(date),
+            None => creation_time(path),
+        },
+        DateSource::Name(pattern) => match name_date(path, pattern.as_ref())? {
+            Some(date) => Ok(date),
+            None => creation_time(path),
+        },
+    }
+}
+
+/// creation_time() returns the date a file was created. Windows reads the Win32 creation time and macOS reads
+/// `st_birthtime`, both of which are always available. Linux reads the `statx` birth time, which requires kernel 4.11
+/// or later and a file system that records it (such as ext4 or btrfs), and falls back to the last modification date
+/// when it is unavailable, other platforms do the same with whatever the standard library provides
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to the file
+///
+/// # Errors
+///
+/// - metadata is not retrievable for the file
+/// - on platforms with a fallback, neither the creation nor the modification date is retrievable
+pub fn creation_time(path: &Path) -> io::Result<OffsetDateTime> {
+    Ok(birth_time(&path.metadata()?)?.into())
+}
+
+/// birth_time() returns the Win32 creation time held in `metadata`
+///
+/// # Arguments
+///
+/// `metadata` Metadata of the file
+///
+/// # Errors
+///
+/// None
+#[cfg(windows)]
+fn birth_time(metadata: &Metadata) -> io::Result<SystemTime> {
+    use std::os::windows::fs::MetadataExt;
+    use std::time::Duration;
+
+    // FILETIME counts 100 nanosecond intervals since 1601-01-01, 11644473600 seconds before the Unix epoch
+    const EPOCH_OFFSET: u64 = 116_444_736_000_000_000;
+    let ticks = metadata.creation_time();
+
+    Ok(if ticks >= EPOCH_OFFSET {
+        SystemTime::UNIX_EPOCH + Duration::from_nanos((ticks - EPOCH_OFFSET) * 100)
+    } else {
+        SystemTime::UNIX_EPOCH - Duration::from_nanos((EPOCH_OFFSET - ticks) * 100)
+    })
+}
+
+/// birth_time() returns the `st_birthtime` held in `metadata`
+///
+/// # Arguments
+///
+/// `metadata` Metadata of the file
+///
+/// # Errors
+///
+/// None
+#[cfg(target_os = "macos")]
+fn birth_time(metadata: &Metadata) -> io::Result<SystemTime> {
+    use std::os::macos::fs::MetadataExt;
+    use std::time::Duration;
+
+    let offset = Duration::new(
+        metadata.st_birthtime().unsigned_abs(),
+        metadata.st_birthtime_nsec() as u32,
+    );
+
+    Ok(if metadata.st_birthtime() >= 0 {
+        SystemTime::UNIX_EPOCH + offset
+    } else {
+        SystemTime::UNIX_EPOCH - offset
+    })
+}
+
+/// birth_time() returns the `statx` birth time held in `metadata`, or the last modification date if the kernel or file
+/// system does not record one
+///
+/// # Arguments
+///
+/// `metadata` Metadata of the file
+///
+/// # Errors
+///
+/// - the modification date is not retrievable when falling back
+#[cfg(not(any(windows, target_os = "macos")))]
+fn birth_time(metadata: &Metadata) -> io::Result<SystemTime> {
+    // on Linux the standard library requests STATX_BTIME and errors when it is not part of the returned mask
+    metadata.created().or_else(|_| metadata.modified())
+}
+
+/// date_folder() returns the `YYYY/YYYY-MM` folder a file dated `date` is organized into
+///
+/// # Arguments
+///
+/// `date` the date of the file
+///
+/// # Errors
+///
+/// None
+pub fn date_folder(date: OffsetDateTime) -> PathBuf {
+    [format!("{:04}", date.year()), date_label(date)]
+        .iter()
+        .collect()
+}
+
+/// date_label() returns the `YYYY-MM` label of a file dated `date`, this is also the name of its date folder
+///
+/// # Arguments
+///
+/// `date` the date of the file
+///
+/// # Errors
+///
+/// None
+pub fn date_label(date: OffsetDateTime) -> String {
+    // formatted by hand rather than with a format description as this cannot fail
+    format!("{:04}-{:02}", date.year(), u8::from(date.month()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{remove_file, File};
+    use std::time::Duration;
+    use time::macros::datetime;
+
+    /// verifies date_folder() and date_label() format dates as `YYYY/YYYY-MM` and `YYYY-MM`
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - date_folder() or date_label() do not zero pad the month
+    #[test]
+    fn date_folder_and_label() {
+        // create inputs
+        let date = datetime!(2023-01-15 12:00 UTC);
+
+        assert_eq!(date_folder(date), PathBuf::from("2023/2023-01"));
+        assert_eq!(date_label(date), "2023-01");
+    }
+
+    /// verifies creation_time() reads the platform birth time, or on Linux falls back to the modification date only
+    /// when no birth time is recorded, and is not affected by later modifications
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - creation_time() does not match the birth time reported by the platform
+    /// - creation_time() follows the modification date while a birth time is available
+    #[test]
+    fn creation_time_platform_birth_time() {
+        // create inputs
+        let path = PathBuf::from("./creation_time_platform_birth_time_test.txt");
+        let file = File::create(&path).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        file.set_modified(modified).unwrap();
+
+        // run test
+        let created = creation_time(&path).unwrap();
+        let by_modified = file_date(&path, &DateSource::Modified).unwrap();
+        let birth = path.metadata().unwrap().created();
+
+        // clean up mock file
+        remove_file(&path).unwrap();
+
+        // Windows and macOS always record a birth time
+        #[cfg(any(windows, target_os = "macos"))]
+        assert!(birth.is_ok());
+
+        match birth {
+            Ok(birth) => {
+                assert_eq!(created, OffsetDateTime::from(birth));
+                assert_ne!(created, by_modified);
+            }
+            Err(_) => assert_eq!(created, by_modified),
+        }
+        assert_eq!(by_modified, OffsetDateTime::from(modified));
+    }
+
+    /// verifies parse_date_source() accepts `created`, `modified`, and `sidecar` with an optional field only
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_date_source() does not parse a valid source
+    /// - parse_date_source() does not error on a missing or unknown source
+    #[test]
+    fn parse_date_source_values() {
+        assert_eq!(
+            parse_date_source(Some(String::from("created"))),
+            Ok(DateSource::Created)
+        );
+        assert_eq!(
+            parse_date_source(Some(String::from("modified"))),
+            Ok(DateSource::Modified)
+        );
+        assert_eq!(
+            parse_date_source(Some(String::from("sidecar"))),
+            Ok(DateSource::Sidecar(None))
+        );
+        assert_eq!(
+            parse_date_source(Some(String::from("sidecar=meta.taken"))),
+            Ok(DateSource::Sidecar(Some(String::from("meta.taken"))))
+        );
+        assert!(parse_date_source(Some(String::from("sidecar="))).is_err());
+        assert_eq!(
+            parse_date_source(Some(String::from("name"))),
+            Ok(DateSource::Name(None))
+        );
+        assert!(matches!(
+            parse_date_source(Some(String::from(r"name:^(\d{4})(\d{2})(\d{2})"))),
+            Ok(DateSource::Name(Some(_)))
+        ));
+        assert!(parse_date_source(Some(String::from("name:"))).is_err());
+        assert!(parse_date_source(Some(String::from("accessed"))).is_err());
+        assert!(parse_date_source(None).is_err());
+    }
+}
@@ -0,0 +1,756 @@
+use std::io::{self, Read, Write};
+
+/// largest distance a match may reach back into previously seen data
+const WINDOW_SIZE: usize = 32 * 1024;
+
+/// amount of input gathered before a block is compressed
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// shortest and longest match that can be encoded
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+/// number of earlier positions tried for each match, trades speed for compression ratio
+const MAX_CHAIN: usize = 64;
+
+/// number of bits used to hash the next three bytes of input
+const HASH_BITS: u32 = 15;
+
+/// base lengths and extra bits of length codes 257 to 285
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// base distances and extra bits of distance codes 0 to 29
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// order in which code length code lengths are stored in a dynamic block header
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// DeflateEncoder struct: streams data into a raw DEFLATE (RFC 1951) stream using fixed Huffman codes, input is
+/// compressed one block at a time so memory stays bounded regardless of how much data is written
+pub struct DeflateEncoder<W: Write> {
+    /// destination of the compressed stream
+    inner: W,
+
+    /// bits not yet written to `inner`, least significant bit first
+    bit_buffer: u64,
+
+    /// number of valid bits in `bit_buffer`
+    bit_count: u32,
+
+    /// previously compressed data that matches may refer to, followed by input awaiting compression
+    window: Vec<u8>,
+
+    /// number of bytes at the start of `window` that have already been compressed
+    history: usize,
+}
+
+impl<W: Write> DeflateEncoder<W> {
+    /// DeflateEncoder struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// `inner` destination of the compressed stream
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            bit_buffer: 0,
+            bit_count: 0,
+            window: Vec::with_capacity(WINDOW_SIZE + BLOCK_SIZE),
+            history: 0,
+        }
+    }
+
+    /// finish() compresses any remaining input, terminates the stream, and returns the destination
+    ///
+    /// # Arguments
+    ///
+    /// `self` the DeflateEncoder to finish
+    ///
+    /// # Errors
+    ///
+    /// - writing to the destination fails
+    pub fn finish(mut self) -> io::Result<W> {
+        self.compress_block(true)?;
+
+        // pad the final partial byte with zeros
+        if self.bit_count > 0 {
+            self.bit_count = self.bit_count.div_ceil(8) * 8;
+            self.flush_bits()?;
+        }
+
+        Ok(self.inner)
+    }
+
+    /// write_bits() appends the lowest `count` bits of `value` to the stream
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to DeflateEncoder
+    /// `value` the bits to write, least significant bit first
+    /// `count` the number of bits to write, at most 32
+    ///
+    /// # Errors
+    ///
+    /// - writing to the destination fails
+    fn write_bits(&mut self, value: u32, count: u32) -> io::Result<()> {
+        self.bit_buffer |= (value as u64) << self.bit_count;
+        self.bit_count += count;
+
+        if self.bit_count >= 32 {
+            self.flush_bits()?;
+        }
+
+        Ok(())
+    }
+
+    /// flush_bits() writes every complete byte held in the bit buffer to the destination
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to DeflateEncoder
+    ///
+    /// # Errors
+    ///
+    /// - writing to the destination fails
+    fn flush_bits(&mut self) -> io::Result<()> {
+        let bytes = (self.bit_count / 8) as usize;
+        self.inner
+            .write_all(&self.bit_buffer.to_le_bytes()[..bytes])?;
+
+        self.bit_buffer = self.bit_buffer.checked_shr(bytes as u32 * 8).unwrap_or(0);
+        self.bit_count -= bytes as u32 * 8;
+
+        Ok(())
+    }
+
+    /// write_code() writes a Huffman code, which DEFLATE stores most significant bit first
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to DeflateEncoder
+    /// `code` the Huffman code
+    /// `length` the number of bits in `code`
+    ///
+    /// # Errors
+    ///
+    /// - writing to the destination fails
+    fn write_code(&mut self, code: u32, length: u32) -> io::Result<()> {
+        self.write_bits(code.reverse_bits() >> (32 - length), length)
+    }
+
+    /// write_symbol() writes a literal byte, length, or end of block symbol using the fixed literal/length code
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to DeflateEncoder
+    /// `symbol` a literal/length symbol from 0 to 287
+    ///
+    /// # Errors
+    ///
+    /// - writing to the destination fails
+    fn write_symbol(&mut self, symbol: u32) -> io::Result<()> {
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    /// write_match() writes a length/distance pair using the fixed codes
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to DeflateEncoder
+    /// `length` length of the match, from 3 to 258
+    /// `distance` distance back to the start of the match, from 1 to 32768
+    ///
+    /// # Errors
+    ///
+    /// - writing to the destination fails
+    fn write_match(&mut self, length: usize, distance: usize) -> io::Result<()> {
+        let code = LENGTH_BASE.partition_point(|base| *base as usize <= length) - 1;
+        self.write_symbol(257 + code as u32)?;
+        self.write_bits(
+            (length - LENGTH_BASE[code] as usize) as u32,
+            LENGTH_EXTRA[code] as u32,
+        )?;
+
+        let code = DIST_BASE.partition_point(|base| *base as usize <= distance) - 1;
+        self.write_code(code as u32, 5)?;
+        self.write_bits(
+            (distance - DIST_BASE[code] as usize) as u32,
+            DIST_EXTRA[code] as u32,
+        )
+    }
+
+    /// compress_block() compresses all pending input as a single fixed Huffman block, then keeps the most recent
+    /// data as history for the next block
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to DeflateEncoder
+    /// `last` whether this is the final block of the stream
+    ///
+    /// # Errors
+    ///
+    /// - writing to the destination fails
+    fn compress_block(&mut self, last: bool) -> io::Result<()> {
+        // block header, BFINAL then BTYPE = 01 (fixed Huffman codes)
+        self.write_bits(last as u32, 1)?;
+        self.write_bits(1, 2)?;
+
+        // hash chains over the window, history positions are inserted first so matches can reach into them
+        let window = std::mem::take(&mut self.window);
+        let mut head = vec![usize::MAX; 1 << HASH_BITS];
+        let mut prev = vec![usize::MAX; window.len()];
+        let hash = |position: usize| -> usize {
+            let value = (window[position] as u32) << 16
+                | (window[position + 1] as u32) << 8
+                | window[position + 2] as u32;
+            (value.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+        };
+        let insert = |position: usize, head: &mut Vec<usize>, prev: &mut Vec<usize>| {
+            if position + MIN_MATCH <= window.len() {
+                let key = hash(position);
+                prev[position] = head[key];
+                head[key] = position;
+            }
+        };
+        for position in 0..self.history {
+            insert(position, &mut head, &mut prev);
+        }
+
+        let mut position = self.history;
+        while position < window.len() {
+            // longest earlier match for the data at position
+            let (mut best_length, mut best_distance) = (0, 0);
+            if position + MIN_MATCH <= window.len() {
+                let limit = (window.len() - position).min(MAX_MATCH);
+                let mut candidate = head[hash(position)];
+                let mut chain = 0;
+
+                while candidate != usize::MAX
+                    && position - candidate <= WINDOW_SIZE
+                    && chain < MAX_CHAIN
+                {
+                    let length = window[candidate..candidate + limit]
+                        .iter()
+                        .zip(&window[position..position + limit])
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    if length > best_length {
+                        best_length = length;
+                        best_distance = position - candidate;
+                        if length == limit {
+                            break;
+                        }
+                    }
+                    candidate = prev[candidate];
+                    chain += 1;
+                }
+            }
+
+            if best_length >= MIN_MATCH {
+                self.write_match(best_length, best_distance)?;
+                for offset in 0..best_length {
+                    insert(position + offset, &mut head, &mut prev);
+                }
+                position += best_length;
+            } else {
+                self.write_symbol(window[position] as u32)?;
+                insert(position, &mut head, &mut prev);
+                position += 1;
+            }
+        }
+
+        // end of block symbol
+        self.write_symbol(256)?;
+
+        // keep the last WINDOW_SIZE bytes as history for the next block
+        let keep = window.len().min(WINDOW_SIZE);
+        self.window = window;
+        self.window.drain(..self.window.len() - keep);
+        self.history = keep;
+
+        Ok(())
+    }
+}
+
+/// Write trait implementation for DeflateEncoder, input is buffered until a full block is available
+impl<W: Write> Write for DeflateEncoder<W> {
+    /// write() buffers `buf` for compression, compressing a block whenever enough input has been gathered
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to DeflateEncoder
+    /// `buf` the data to compress
+    ///
+    /// # Errors
+    ///
+    /// - writing to the destination fails
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let space = self.history + BLOCK_SIZE - self.window.len();
+        let taken = buf.len().min(space);
+        self.window.extend_from_slice(&buf[..taken]);
+
+        if self.window.len() - self.history >= BLOCK_SIZE {
+            self.compress_block(false)?;
+        }
+
+        Ok(taken)
+    }
+
+    /// flush() flushes the destination, pending input stays buffered until the next block or finish()
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to DeflateEncoder
+    ///
+    /// # Errors
+    ///
+    /// - flushing the destination fails
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// BitReader struct: reads a DEFLATE stream one bit at a time, never consuming bytes beyond the current one so data
+/// following the stream (such as a gzip trailer) remains readable
+struct BitReader<'a> {
+    /// source of the compressed stream
+    inner: &'a mut dyn Read,
+
+    /// remaining bits of the current byte
+    byte: u32,
+
+    /// number of bits of `byte` not yet read
+    count: u32,
+}
+
+impl BitReader<'_> {
+    /// bit() reads the next bit of the stream
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to BitReader
+    ///
+    /// # Errors
+    ///
+    /// - the stream ends unexpectedly or reading from it fails
+    fn bit(&mut self) -> io::Result<u32> {
+        if self.count == 0 {
+            let mut byte = [0u8; 1];
+            self.inner.read_exact(&mut byte)?;
+            self.byte = byte[0] as u32;
+            self.count = 8;
+        }
+
+        let bit = self.byte & 1;
+        self.byte >>= 1;
+        self.count -= 1;
+
+        Ok(bit)
+    }
+
+    /// bits() reads a `count` bit value stored least significant bit first
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to BitReader
+    /// `count` the number of bits to read
+    ///
+    /// # Errors
+    ///
+    /// - see bit()
+    fn bits(&mut self, count: u32) -> io::Result<u32> {
+        let mut value = 0;
+        for index in 0..count {
+            value |= self.bit()? << index;
+        }
+
+        Ok(value)
+    }
+
+    /// decode() reads one symbol encoded with `huffman`
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to BitReader
+    /// `huffman` the Huffman code to decode with
+    ///
+    /// # Errors
+    ///
+    /// - see bit()
+    /// - the bits read do not form a valid code
+    fn decode(&mut self, huffman: &Huffman) -> io::Result<usize> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+
+        // canonical codes of each length follow directly after the codes of the previous length
+        for length in 1..16 {
+            code |= self.bit()? as i32;
+            let count = huffman.counts[length] as i32;
+            if code - first < count {
+                return Ok(huffman.symbols[(index + code - first) as usize] as usize);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err(invalid_data("invalid Huffman code in deflate stream"))
+    }
+}
+
+/// Huffman struct: canonical Huffman code described by the number of codes of each length
+struct Huffman {
+    /// number of codes of each length from 0 to 15
+    counts: [u16; 16],
+
+    /// symbols ordered by code
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Huffman struct initializer from the code length of every symbol
+    ///
+    /// # Arguments
+    ///
+    /// `lengths` code length of every symbol, zero for unused symbols
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        for length in lengths {
+            counts[*length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        // offsets of the first symbol of each length within the sorted symbols
+        let mut offsets = [0u16; 16];
+        for length in 1..15 {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, length) in lengths.iter().enumerate() {
+            if *length != 0 {
+                symbols[offsets[*length as usize] as usize] = symbol as u16;
+                offsets[*length as usize] += 1;
+            }
+        }
+
+        Self { counts, symbols }
+    }
+}
+
+/// invalid_data() creates an InvalidData error with `message`
+///
+/// # Arguments
+///
+/// `message` description of the problem
+///
+/// # Errors
+///
+/// None
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// inflate() decompresses a raw DEFLATE stream from `reader` into `writer`, only the most recent 32 KiB of output is
+/// kept in memory, bytes following the end of the stream are left unread in `reader`
+///
+/// # Arguments
+///
+/// `reader` source of the compressed stream
+/// `writer` destination of the decompressed data
+///
+/// # Errors
+///
+/// - reading from `reader` or writing to `writer` fails
+/// - the stream is malformed or ends unexpectedly
+pub fn inflate(reader: &mut dyn Read, writer: &mut dyn Write) -> io::Result<u64> {
+    let mut bits = BitReader {
+        inner: reader,
+        byte: 0,
+        count: 0,
+    };
+
+    // decompressed data, everything but the most recent window is written out once it grows large
+    let mut output: Vec<u8> = Vec::with_capacity(WINDOW_SIZE * 3);
+    let mut written: u64 = 0;
+
+    loop {
+        let last = bits.bit()? == 1;
+
+        match bits.bits(2)? {
+            // stored block, lengths start on the next byte boundary
+            0 => {
+                bits.count = 0;
+                let length = bits.bits(16)?;
+                if bits.bits(16)? != !length & 0xFFFF {
+                    return Err(invalid_data(
+                        "corrupt stored block length in deflate stream",
+                    ));
+                }
+                for _ in 0..length {
+                    output.push(bits.bits(8)? as u8);
+                }
+            }
+            // fixed Huffman codes
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                inflate_block(
+                    &mut bits,
+                    &mut output,
+                    &Huffman::new(&lengths),
+                    &Huffman::new(&[5; 30]),
+                )?;
+            }
+            // dynamic Huffman codes
+            2 => {
+                let (literals, distances) = read_dynamic_codes(&mut bits)?;
+                inflate_block(&mut bits, &mut output, &literals, &distances)?;
+            }
+            _ => return Err(invalid_data("invalid block type in deflate stream")),
+        }
+
+        // write out everything except the window that later matches may refer to
+        if output.len() > WINDOW_SIZE * 2 || last {
+            let keep = if last { 0 } else { WINDOW_SIZE };
+            let flushed = output.len() - keep;
+            writer.write_all(&output[..flushed])?;
+            output.drain(..flushed);
+            written += flushed as u64;
+        }
+
+        if last {
+            return Ok(written);
+        }
+    }
+}
+
+/// read_dynamic_codes() reads the literal/length and distance codes from a dynamic block header
+///
+/// # Arguments
+///
+/// `bits` a BitReader positioned after the block type
+///
+/// # Errors
+///
+/// - see BitReader::bit()
+/// - the header is malformed
+fn read_dynamic_codes(bits: &mut BitReader) -> io::Result<(Huffman, Huffman)> {
+    let literal_count = bits.bits(5)? as usize + 257;
+    let distance_count = bits.bits(5)? as usize + 1;
+    let code_length_count = bits.bits(4)? as usize + 4;
+
+    // code lengths of the code used to encode the other code lengths
+    let mut code_lengths = [0u8; 19];
+    for index in CODE_LENGTH_ORDER.iter().take(code_length_count) {
+        code_lengths[*index] = bits.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths);
+
+    // code lengths of both codes are stored back to back with run length encoding
+    let mut lengths: Vec<u8> = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (value, repeat) = match bits.decode(&code_length_code)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => match lengths.last() {
+                Some(previous) => (*previous, 3 + bits.bits(2)?),
+                None => {
+                    return Err(invalid_data(
+                        "repeat without previous length in deflate stream",
+                    ))
+                }
+            },
+            17 => (0, 3 + bits.bits(3)?),
+            _ => (0, 11 + bits.bits(7)?),
+        };
+        for _ in 0..repeat {
+            lengths.push(value);
+        }
+    }
+    if lengths.len() != literal_count + distance_count {
+        return Err(invalid_data("code lengths overrun in deflate stream"));
+    }
+
+    Ok((
+        Huffman::new(&lengths[..literal_count]),
+        Huffman::new(&lengths[literal_count..]),
+    ))
+}
+
+/// inflate_block() decodes the symbols of a Huffman compressed block into `output`
+///
+/// # Arguments
+///
+/// `bits` a BitReader positioned at the first symbol of the block
+/// `output` decompressed data so far, matches are copied from its tail
+/// `literals` the literal/length code of the block
+/// `distances` the distance code of the block
+///
+/// # Errors
+///
+/// - see BitReader::decode()
+/// - a match refers to data before the start of the stream
+fn inflate_block(
+    bits: &mut BitReader,
+    output: &mut Vec<u8>,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> io::Result<()> {
+    loop {
+        let symbol = bits.decode(literals)?;
+        match symbol {
+            0..=255 => output.push(symbol as u8),
+            256 => return Ok(()),
+            257..=285 => {
+                let code = symbol - 257;
+                let length =
+                    LENGTH_BASE[code] as usize + bits.bits(LENGTH_EXTRA[code] as u32)? as usize;
+
+                let code = bits.decode(distances)?;
+                if code >= 30 {
+                    return Err(invalid_data("invalid distance code in deflate stream"));
+                }
+                let distance =
+                    DIST_BASE[code] as usize + bits.bits(DIST_EXTRA[code] as u32)? as usize;
+                if distance > output.len() {
+                    return Err(invalid_data("distance too far back in deflate stream"));
+                }
+
+                // copy byte by byte as matches may overlap the data they produce
+                let start = output.len() - distance;
+                for offset in 0..length {
+                    output.push(output[start + offset]);
+                }
+            }
+            _ => return Err(invalid_data("invalid length code in deflate stream")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// deflate_and_inflate() compresses `data` in chunks of `chunk` bytes and decompresses the result
+    ///
+    /// # Arguments
+    ///
+    /// `data` the data to round trip
+    /// `chunk` the size of each write to the encoder
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn deflate_and_inflate(data: &[u8], chunk: usize) -> (usize, Vec<u8>) {
+        let mut encoder = DeflateEncoder::new(Vec::new());
+        for part in data.chunks(chunk.max(1)) {
+            encoder.write_all(part).unwrap();
+        }
+        let compressed = encoder.finish().unwrap();
+
+        let mut output: Vec<u8> = Vec::new();
+        inflate(&mut compressed.as_slice(), &mut output).unwrap();
+
+        (compressed.len(), output)
+    }
+
+    /// verifies DeflateEncoder output inflates back to the original data for empty, small, and multi-block inputs
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - inflate() of DeflateEncoder output does not reproduce the input
+    #[test]
+    fn deflate_round_trip() {
+        // create inputs, repetitive data spanning several blocks and pseudo random data that barely compresses
+        let repetitive: Vec<u8> = b"fileman_rs organizes files. "
+            .iter()
+            .cycle()
+            .take(BLOCK_SIZE * 3 + 17)
+            .copied()
+            .collect();
+        let mut state: u32 = 1;
+        let random: Vec<u8> = (0..BLOCK_SIZE + 5)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (state >> 16) as u8
+            })
+            .collect();
+
+        for (data, chunk) in [
+            (Vec::new(), 1),
+            (b"a".to_vec(), 1),
+            (repetitive.clone(), 1000),
+            (random.clone(), 4096),
+        ] {
+            assert_eq!(deflate_and_inflate(&data, chunk).1, data);
+        }
+
+        // repetitive data should shrink considerably
+        assert!(deflate_and_inflate(&repetitive, 1000).0 < repetitive.len() / 10);
+    }
+
+    /// verifies inflate() decodes stored and dynamic blocks produced by other encoders
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - inflate() does not decode a stored block
+    /// - inflate() does not decode a dynamic Huffman block
+    #[test]
+    fn inflate_other_block_types() {
+        // stored block containing "hello"
+        let stored = [0x01, 0x05, 0x00, 0xFA, 0xFF, b'h', b'e', b'l', b'l', b'o'];
+
+        // zlib (level 9) raw deflate of the text below, which zlib encodes as a dynamic Huffman block
+        let dynamic = [
+            0xD5, 0x8D, 0xC1, 0x0D, 0xC3, 0x40, 0x08, 0x04, 0x5B, 0xD9, 0x0A, 0xD2, 0x80, 0xAB,
+            0x21, 0x3E, 0x1C, 0x23, 0x71, 0x70, 0x02, 0x12, 0x29, 0xA9, 0x3E, 0xF8, 0x99, 0x12,
+            0xF2, 0xD9, 0xC7, 0xAC, 0x66, 0xD7, 0xE3, 0x41, 0x26, 0x1F, 0xC6, 0xF4, 0x17, 0x27,
+            0x0E, 0xD1, 0x4E, 0xB1, 0x72, 0x0C, 0x2A, 0x1E, 0x38, 0x5C, 0x07, 0x47, 0x6E, 0x50,
+            0xC9, 0xC2, 0x8A, 0xAE, 0x12, 0x84, 0x0A, 0xE6, 0x0D, 0xB9, 0x54, 0x1A, 0x52, 0x94,
+            0x94, 0xB8, 0xFD, 0xE8, 0x17, 0x6D, 0xCD, 0xFF, 0x7C, 0x7F, 0xF7, 0xB9, 0x82, 0x33,
+            0x71, 0x7F, 0xDA, 0xB8, 0xCA, 0xE9, 0x56, 0xA7, 0xBE, 0x41, 0xB1, 0x9F, 0xD2, 0x97,
+            0xB7, 0x2F,
+        ];
+
+        let mut output: Vec<u8> = Vec::new();
+        inflate(&mut stored.as_slice(), &mut output).unwrap();
+        assert_eq!(output, b"hello");
+
+        let mut output: Vec<u8> = Vec::new();
+        inflate(&mut dynamic.as_slice(), &mut output).unwrap();
+        let text = "organize moves files into dated folders; list prints a tree; split partitions files into parts; "
+            .repeat(3)
+            + "compress bundles monthly archives.";
+        assert_eq!(output, text.as_bytes());
+    }
+}
@@ -0,0 +1,339 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+#[cfg(feature = "blake3")]
+use super::blake3::Blake3;
+use super::sha256::{to_hex, Sha256};
+
+/// size of the buffer files are read into while hashing
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// DigestAlgorithm enum: which digest content hashing computes, both give 32 bytes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    /// SHA-256, as written by `sha256sum` and read back by the verify task
+    #[default]
+    Sha256,
+
+    /// BLAKE3, several times faster but not understood by `sha256sum`, only built with the `blake3` feature
+    #[cfg(feature = "blake3")]
+    Blake3,
+}
+
+/// Hasher enum: running digest of the chosen DigestAlgorithm
+enum Hasher {
+    Sha256(Sha256),
+    #[cfg(feature = "blake3")]
+    Blake3(Box<Blake3>),
+}
+
+impl Hasher {
+    /// Hasher enum initializer for the digest of no data
+    ///
+    /// # Arguments
+    ///
+    /// `algorithm` the digest to compute
+    fn new(algorithm: DigestAlgorithm) -> Self {
+        match algorithm {
+            DigestAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            #[cfg(feature = "blake3")]
+            DigestAlgorithm::Blake3 => Hasher::Blake3(Box::new(Blake3::new())),
+        }
+    }
+
+    /// update() feeds `data` into the digest
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Hasher
+    /// `data` the next bytes of the message
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(sha256) => sha256.update(data),
+            #[cfg(feature = "blake3")]
+            Hasher::Blake3(blake3) => blake3.update(data),
+        }
+    }
+
+    /// finish() completes the digest
+    ///
+    /// # Arguments
+    ///
+    /// `self` the Hasher to complete
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn finish(self) -> [u8; 32] {
+        match self {
+            Hasher::Sha256(sha256) => sha256.finish(),
+            #[cfg(feature = "blake3")]
+            Hasher::Blake3(blake3) => blake3.finish(),
+        }
+    }
+}
+
+/// parse_digest_algorithm() parses the value of `--hash`, `sha256` or, when built with the `blake3` feature, `blake3`
+///
+/// # Arguments
+///
+/// `value` the value following `--hash`
+///
+/// # Errors
+///
+/// - `value` is not a digest this build supports
+pub fn parse_digest_algorithm(value: Option<String>) -> Result<DigestAlgorithm, &'static str> {
+    match value.as_deref() {
+        Some("sha256") => Ok(DigestAlgorithm::Sha256),
+        #[cfg(feature = "blake3")]
+        Some("blake3") => Ok(DigestAlgorithm::Blake3),
+        #[cfg(not(feature = "blake3"))]
+        Some("blake3") => {
+            Err("'--hash blake3' requires fileman_rs to be built with the blake3 feature")
+        }
+        _ => Err("'--hash' must be followed by 'sha256' or 'blake3'"),
+    }
+}
+
+/// digest_file() returns the `algorithm` digest of the contents of the file at `path`. By default the file is streamed
+/// through a fixed size buffer so memory use does not depend on its size, with `mmap` it is memory mapped instead,
+/// which saves copying every byte of a very large file but must only be used for files that are not truncated while
+/// they are hashed. Platforms without memory maps, and empty files, are always streamed
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to the file
+/// `algorithm` the digest to compute
+/// `mmap` whether the file is memory mapped rather than streamed
+///
+/// # Errors
+///
+/// - the file cannot be opened, read, or mapped
+pub fn digest_file(path: &Path, algorithm: DigestAlgorithm, mmap: bool) -> io::Result<[u8; 32]> {
+    let mut file = File::open(path)?;
+    let mut hasher = Hasher::new(algorithm);
+
+    if mmap {
+        let len = file.metadata()?.len();
+        if let Some(mapping) = platform::map(&file, len)? {
+            hasher.update(mapping.bytes());
+            return Ok(hasher.finish());
+        }
+    }
+
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+/// hash_file() returns the `algorithm` digest of the contents of the file at `path` as lowercase hexadecimal, see
+/// digest_file()
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to the file
+/// `algorithm` the digest to compute
+/// `mmap` whether the file is memory mapped rather than streamed
+///
+/// # Errors
+///
+/// - digest_file() call fails
+pub fn hash_file(path: &Path, algorithm: DigestAlgorithm, mmap: bool) -> io::Result<String> {
+    Ok(to_hex(&digest_file(path, algorithm, mmap)?))
+}
+
+#[cfg(any(
+    all(target_os = "linux", target_pointer_width = "64"),
+    target_os = "macos"
+))]
+mod platform {
+    use std::ffi::c_void;
+    use std::fs::File;
+    use std::io;
+    use std::os::raw::c_int;
+    use std::os::unix::io::AsRawFd;
+
+    /// pages may be read, the same value on Linux and macOS
+    const PROT_READ: c_int = 1;
+
+    /// changes are private to the process, the same value on Linux and macOS
+    const MAP_PRIVATE: c_int = 2;
+
+    extern "C" {
+        fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: c_int,
+            flags: c_int,
+            fd: c_int,
+            offset: i64,
+        ) -> *mut c_void;
+        fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    }
+
+    /// Mapping struct: read only memory map of a whole file, unmapped when dropped
+    pub struct Mapping {
+        /// address the file is mapped at
+        addr: *mut c_void,
+
+        /// length of the file in bytes
+        len: usize,
+    }
+
+    impl Mapping {
+        /// bytes() returns the contents of the mapped file
+        ///
+        /// # Arguments
+        ///
+        /// `&self` reference to Mapping
+        ///
+        /// # Errors
+        ///
+        /// None
+        pub fn bytes(&self) -> &[u8] {
+            // SAFETY: `addr` points to `len` readable bytes that stay mapped for as long as self lives
+            unsafe { std::slice::from_raw_parts(self.addr as *const u8, self.len) }
+        }
+    }
+
+    impl Drop for Mapping {
+        fn drop(&mut self) {
+            // SAFETY: `addr` and `len` describe a mapping created by mmap() that nothing else unmaps
+            unsafe { munmap(self.addr, self.len) };
+        }
+    }
+
+    pub fn map(file: &File, len: u64) -> io::Result<Option<Mapping>> {
+        // an empty mapping is an error, and a file larger than the address space cannot be mapped at all
+        let Ok(len) = usize::try_from(len) else {
+            return Ok(None);
+        };
+        if len == 0 {
+            return Ok(None);
+        }
+
+        // SAFETY: a fresh private read only mapping of an open descriptor, the result is checked before use
+        let addr = unsafe {
+            mmap(
+                std::ptr::null_mut(),
+                len,
+                PROT_READ,
+                MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if addr as usize == usize::MAX {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Some(Mapping { addr, len }))
+    }
+}
+
+#[cfg(not(any(
+    all(target_os = "linux", target_pointer_width = "64"),
+    target_os = "macos"
+)))]
+mod platform {
+    use std::fs::File;
+    use std::io;
+
+    /// Mapping struct: never created on platforms without memory maps
+    pub enum Mapping {}
+
+    impl Mapping {
+        pub fn bytes(&self) -> &[u8] {
+            match *self {}
+        }
+    }
+
+    // files are always streamed
+    pub fn map(_file: &File, _len: u64) -> io::Result<Option<Mapping>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+    use std::path::PathBuf;
+
+    /// verifies streamed and memory mapped files give the known digests of their contents, including an empty file
+    /// and one spanning several buffers
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - hash_file() does not give the known digest of a fixture when streaming or memory mapping it
+    /// - digest_file() differs between streaming and memory mapping
+    /// - parse_digest_algorithm() accepts an unknown digest
+    #[test]
+    fn digest_file_stream_and_mmap() {
+        // create inputs
+        let path_buf = PathBuf::from("./digest_file_stream_and_mmap_test");
+        let large: Vec<u8> = (0..3 * BUFFER_SIZE + 17)
+            .map(|index| (index % 251) as u8)
+            .collect();
+
+        // create mock directory
+        create_dir_all(&path_buf).unwrap();
+        write(path_buf.join("abc.txt"), "abc").unwrap();
+        write(path_buf.join("empty.txt"), "").unwrap();
+        write(path_buf.join("large.bin"), &large).unwrap();
+
+        // run test
+        let hash = |name: &str, algorithm: DigestAlgorithm| {
+            [false, true].map(|mmap| hash_file(&path_buf.join(name), algorithm, mmap).unwrap())
+        };
+        let abc = hash("abc.txt", DigestAlgorithm::Sha256);
+        let empty = hash("empty.txt", DigestAlgorithm::Sha256);
+        let large_digests = [false, true].map(|mmap| {
+            digest_file(&path_buf.join("large.bin"), DigestAlgorithm::Sha256, mmap).unwrap()
+        });
+        #[cfg(feature = "blake3")]
+        let blake3 = hash("abc.txt", DigestAlgorithm::Blake3);
+        let missing = hash_file(&path_buf.join("missing.txt"), DigestAlgorithm::Sha256, true);
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        let sha256 = |data: &[u8]| {
+            let mut sha256 = Sha256::new();
+            sha256.update(data);
+            sha256.finish()
+        };
+        let abc_digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(abc, [abc_digest; 2]);
+        assert_eq!(
+            empty,
+            ["e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"; 2]
+        );
+        assert_eq!(large_digests, [sha256(&large); 2]);
+        #[cfg(feature = "blake3")]
+        assert_eq!(
+            blake3,
+            ["6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"; 2]
+        );
+        assert_eq!(missing.unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(
+            parse_digest_algorithm(Some(String::from("sha256"))),
+            Ok(DigestAlgorithm::Sha256)
+        );
+        assert!(parse_digest_algorithm(Some(String::from("md5"))).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+use std::fs::read_to_string;
+use std::io;
+use std::path::Path;
+
+use super::dates::{parse_date_source, DateSource};
+use super::error::PathContext;
+use super::glob::glob_match;
+
+/// name of the file a directory holds its DirConfig in, the file itself is never visited by a walk
+pub const DIR_CONFIG_FILE: &str = ".filemanrc";
+
+/// DirConfig struct: overrides read from the `.filemanrc` of a directory that apply to it and everything below it, a
+/// `.filemanrc` further down overrides them for its own subtree
+///
+/// A `.filemanrc` holds one `key = value` setting per line, blank lines and lines starting with `#` are ignored:
+///
+/// - `skip = true` leaves the directory and everything below it out of the walk, `skip = false` keeps it
+/// - `date = modified` organizes the files below by another date, taking the same values as `--date`
+/// - `exclude = *.tmp` leaves out every file or directory below whose name matches the glob, may be repeated
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DirConfig {
+    /// whether the subtree is left out of the walk, None when not set
+    pub skip: Option<bool>,
+
+    /// date the files of the subtree are organized by, None to use the date chosen for the task
+    pub date: Option<DateSource>,
+
+    /// glob patterns of names left out of the walk, those of every enclosing `.filemanrc` included
+    pub exclude: Vec<String>,
+}
+
+impl DirConfig {
+    /// parse() reads the settings of a `.filemanrc`
+    ///
+    /// # Arguments
+    ///
+    /// `text` the contents of the file
+    ///
+    /// # Errors
+    ///
+    /// - a line is neither blank, a comment, nor a `key = value` setting
+    /// - a key is not `skip`, `date`, or `exclude`
+    /// - `skip` is not `true` or `false`, `date` is not a valid date source, or `exclude` is empty
+    pub fn parse(text: &str) -> Result<Self, &'static str> {
+        let mut config = Self::default();
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err("every setting must be written as 'key = value'");
+            };
+            let value = value.trim();
+            match key.trim() {
+                "skip" => {
+                    config.skip = match value {
+                        "true" => Some(true),
+                        "false" => Some(false),
+                        _ => return Err("'skip' must be 'true' or 'false'"),
+                    }
+                }
+                "date" => {
+                    config.date =
+                        Some(parse_date_source(Some(value.to_string())).map_err(|_| {
+                            "'date' must be 'created', 'modified', 'sidecar', 'sidecar=FIELD', 'name', or 'name:PATTERN'"
+                        })?)
+                }
+                "exclude" if !value.is_empty() => config.exclude.push(value.to_string()),
+                "exclude" => return Err("'exclude' must be followed by a name or glob pattern"),
+                _ => return Err("settings must be one of 'skip', 'date', or 'exclude'"),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// merged() returns the settings in effect below a directory whose own `.filemanrc` holds `child`, settings given
+    /// by `child` override those of &self while exclude patterns of both apply
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to the DirConfig in effect for the parent directory
+    /// `child` the DirConfig read from the directory itself
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn merged(&self, child: &DirConfig) -> DirConfig {
+        DirConfig {
+            skip: child.skip.or(self.skip),
+            date: child.date.clone().or_else(|| self.date.clone()),
+            exclude: self.exclude.iter().chain(&child.exclude).cloned().collect(),
+        }
+    }
+
+    /// excludes() returns whether the name of the item at `path` matches any exclude pattern in effect
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to DirConfig
+    /// `path` a Path that corresponds to a file or directory
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn excludes(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name() else {
+            return false;
+        };
+        let name = name.to_string_lossy();
+
+        self.exclude
+            .iter()
+            .any(|pattern| glob_match(pattern, &name))
+    }
+}
+
+/// read_dir_config() returns the settings in effect inside `dir` below a parent where `parent` is in effect, that is
+/// `parent` merged with the `.filemanrc` of `dir` when it has one
+///
+/// # Arguments
+///
+/// `dir` a Path that corresponds to a directory
+/// `parent` the DirConfig in effect for the parent of `dir`
+///
+/// # Errors
+///
+/// - the `.filemanrc` of `dir` exists but cannot be read
+/// - DirConfig::parse() call fails, the error names the file
+pub fn read_dir_config(dir: &Path, parent: &DirConfig) -> io::Result<DirConfig> {
+    // a missing file, or a `dir` that is not a directory at all, leaves the settings of the parent in effect, the walk
+    // reports the latter itself
+    let path = dir.join(DIR_CONFIG_FILE);
+    if !path.is_file() {
+        return Ok(parent.clone());
+    }
+    let text = read_to_string(&path).path_context(&path)?;
+
+    match DirConfig::parse(&text) {
+        Ok(child) => Ok(parent.merged(&child)),
+        Err(err) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {err}", path.display()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies DirConfig::parse() reads every setting, ignores comments, and rejects unknown keys and values
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - DirConfig::parse() misreads a setting or accepts an invalid one
+    #[test]
+    fn dir_config_parse() {
+        let text = "# scans carry their dates in sidecars\nskip = false\ndate = sidecar=meta.taken\n\nexclude = *.tmp\nexclude=cache\n";
+
+        assert_eq!(
+            DirConfig::parse(text),
+            Ok(DirConfig {
+                skip: Some(false),
+                date: Some(DateSource::Sidecar(Some(String::from("meta.taken")))),
+                exclude: vec![String::from("*.tmp"), String::from("cache")],
+            })
+        );
+        assert_eq!(
+            DirConfig::parse("skip = true").map(|config| config.skip),
+            Ok(Some(true))
+        );
+        for invalid in [
+            "skip",
+            "skip = yes",
+            "date = yesterday",
+            "exclude =",
+            "depth = 2",
+        ] {
+            assert!(DirConfig::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    /// verifies DirConfig::merged() lets the settings of a child override those of its parent while keeping every
+    /// exclude pattern
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - DirConfig::merged() keeps a parent setting the child overrides or drops one it does not
+    #[test]
+    fn dir_config_merged() {
+        let parent = DirConfig {
+            skip: Some(false),
+            date: Some(DateSource::Modified),
+            exclude: vec![String::from("*.tmp")],
+        };
+        let child = DirConfig {
+            date: Some(DateSource::Created),
+            exclude: vec![String::from("cache")],
+            ..DirConfig::default()
+        };
+
+        let test_result = parent.merged(&child);
+
+        assert_eq!(test_result.skip, Some(false));
+        assert_eq!(test_result.date, Some(DateSource::Created));
+        assert!(test_result.excludes(Path::new("a/b.tmp")));
+        assert!(test_result.excludes(Path::new("a/cache")));
+        assert!(!test_result.excludes(Path::new("a/b.jpg")));
+        assert_eq!(
+            DirConfig::default().merged(&DirConfig::default()),
+            DirConfig::default()
+        );
+    }
+}
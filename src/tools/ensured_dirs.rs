@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// declare local code
+use super::error::with_path;
+use super::file_system::FileSystem;
+
+/// EnsuredDirs struct: the target directories a run has already made sure exist, shared between threads so that every
+/// directory is created at most once however many files are moved into it
+#[derive(Debug, Default)]
+pub struct EnsuredDirs {
+    /// PathBufs of the directories known to exist
+    dirs: Mutex<HashSet<PathBuf>>,
+}
+
+impl EnsuredDirs {
+    /// EnsuredDirs struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ensure() makes sure the directory at `dir` exists, creating it along with any missing parents the first time it
+    /// is asked for. Returns None when `dir` was already ensured, so callers only record it once, or whether this call
+    /// created it otherwise. A thread asking for a directory another thread is creating waits for it to finish, and a
+    /// directory created by another process in the meantime is not an error
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to EnsuredDirs
+    /// `fs` the FileSystem the directory is created on
+    /// `dir` a Path that corresponds to the directory
+    ///
+    /// # Errors
+    ///
+    /// - FileSystem::create_dir_all() call fails, unless `dir` exists as a directory afterwards
+    pub fn ensure(&self, fs: &dyn FileSystem, dir: &Path) -> io::Result<Option<bool>> {
+        // a thread that panicked cannot leave the set half updated, so a poisoned lock is still usable
+        let mut dirs = self
+            .dirs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if dirs.contains(dir) {
+            return Ok(None);
+        }
+
+        let created = !dir.exists();
+        match fs.create_dir_all(dir) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists && dir.is_dir() => {}
+            Err(err) => return Err(with_path(err, dir)),
+        }
+        dirs.insert(dir.to_path_buf());
+
+        Ok(Some(created))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::file_system::{MockFileSystem, RealFileSystem};
+    use std::fs::remove_dir_all;
+    use std::thread;
+
+    /// verifies many threads ensuring the same directory at once all succeed, that exactly one of them creates it, and
+    /// that a failed creation names the directory and is retried the next time
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - EnsuredDirs::ensure() errors on a directory another thread is creating
+    /// - EnsuredDirs::ensure() reports the directory as newly ensured more than once
+    /// - EnsuredDirs::ensure() remembers a directory it failed to create
+    #[test]
+    fn ensured_dirs_ensure() {
+        // create inputs
+        let path_buf = PathBuf::from("./ensured_dirs_ensure_test");
+        let dir = path_buf.join("2023/2023-01");
+        let ensured = EnsuredDirs::new();
+
+        // run test, the first attempt fails so the directory must not be remembered
+        let failing = MockFileSystem {
+            create_dir_error: Some(io::ErrorKind::PermissionDenied),
+            ..MockFileSystem::default()
+        };
+        let failed = ensured.ensure(&failing, &dir);
+        let results: Vec<io::Result<Option<bool>>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..16)
+                .map(|_| scope.spawn(|| ensured.ensure(&RealFileSystem, &dir)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        let exists = dir.is_dir();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        let err = failed.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(err.to_string().starts_with(&dir.display().to_string()));
+        let results: Vec<Option<bool>> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(results.iter().filter(|result| result.is_some()).count(), 1);
+        assert!(results.contains(&Some(true)));
+        assert!(exists);
+    }
+}
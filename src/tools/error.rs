@@ -0,0 +1,66 @@
+use std::io;
+use std::path::Path;
+
+/// with_path() returns `err` with the path it concerns in front of its message, such as `./a.jpg: permission denied`,
+/// as errors from the operating system do not say which file they came from. The kind is kept so callers can still
+/// tell errors apart, but the raw OS error is not, so checks such as is_file_in_use() are made before adding the path
+///
+/// # Arguments
+///
+/// `err` the error encountered
+/// `path` a Path that corresponds to the file or directory the failed operation was performed on
+///
+/// # Errors
+///
+/// None
+pub fn with_path(err: io::Error, path: &Path) -> io::Error {
+    io::Error::new(err.kind(), format!("{}: {err}", path.display()))
+}
+
+/// PathContext trait: adds the path an operation was performed on to the error of a failed io::Result, see with_path()
+pub trait PathContext<T> {
+    /// path_context() returns the result unchanged if it succeeded, or its error with `path` added otherwise
+    ///
+    /// # Arguments
+    ///
+    /// `self` the result of the operation
+    /// `path` a Path that corresponds to the file or directory the operation was performed on
+    ///
+    /// # Errors
+    ///
+    /// - `self` is an error
+    fn path_context(self, path: &Path) -> io::Result<T>;
+}
+
+impl<T> PathContext<T> for io::Result<T> {
+    fn path_context(self, path: &Path) -> io::Result<T> {
+        self.map_err(|err| with_path(err, path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies the path is placed in front of the message while the kind of the error is kept
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - the message of the error does not name the path
+    /// - the kind of the error is changed or a successful result is altered
+    #[test]
+    fn path_context_names_path() {
+        let failed: io::Result<()> = Err(io::Error::from(io::ErrorKind::PermissionDenied));
+        let err = failed
+            .path_context(Path::new("./source/a.jpg"))
+            .unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(err.to_string(), "./source/a.jpg: permission denied");
+        assert_eq!(Ok(1).path_context(Path::new("./source/a.jpg")).unwrap(), 1);
+    }
+}
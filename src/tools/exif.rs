@@ -0,0 +1,286 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// number of bytes read from the start of a file when looking for its EXIF data, which JPEG files keep in a segment
+/// of at most 64 KiB near their start and TIFF based raw files in the first directory of tags
+const SCAN_LIMIT: u64 = 256 * 1024;
+
+/// EXIF tag holding the manufacturer of the camera
+const MAKE: u16 = 0x010F;
+
+/// EXIF tag holding the model of the camera
+const MODEL: u16 = 0x0110;
+
+/// EXIF type of tags holding a NUL terminated ASCII string
+const ASCII: u16 = 2;
+
+/// camera_model() returns the camera a photo was taken with as `Make_Model` from the EXIF `Make` and `Model` tags of a
+/// JPEG or TIFF based file, such as `NIKON CORPORATION_NIKON D750`. A model that already starts with the make, such as
+/// `Canon EOS 5D` made by `Canon`, is returned on its own, and either tag is returned alone when the other is missing.
+/// Returns None when the file is not a JPEG or TIFF, carries no EXIF data, or has neither tag
+///
+/// # Arguments
+///
+/// `file` a Path that corresponds to the file
+///
+/// # Errors
+///
+/// - `file` cannot be opened or read
+pub fn camera_model(file: &Path) -> io::Result<Option<String>> {
+    let mut data: Vec<u8> = Vec::new();
+    File::open(file)?.take(SCAN_LIMIT).read_to_end(&mut data)?;
+
+    // JPEG files wrap their EXIF data in a segment, TIFF based files are EXIF data themselves
+    let tiff = if data.starts_with(&[0xFF, 0xD8]) {
+        match exif_segment(&data) {
+            Some(tiff) => tiff,
+            None => return Ok(None),
+        }
+    } else {
+        &data
+    };
+
+    Ok(camera_from_tiff(tiff))
+}
+
+/// exif_segment() returns the TIFF structured contents of the EXIF APP1 segment of the JPEG file in `data`, or None
+/// when the headers of the file end before one is found
+///
+/// # Arguments
+///
+/// `data` the start of a JPEG file
+///
+/// # Errors
+///
+/// None
+fn exif_segment(data: &[u8]) -> Option<&[u8]> {
+    let mut position = 2;
+    while position + 4 <= data.len() {
+        if data[position] != 0xFF {
+            return None;
+        }
+        let marker = data[position + 1];
+        match marker {
+            // padding before a marker and markers without a length
+            0xFF => {
+                position += 1;
+                continue;
+            }
+            0x01 | 0xD0..=0xD7 => {
+                position += 2;
+                continue;
+            }
+            // compressed image data follows the start of scan, no further headers can be read
+            0xD9 | 0xDA => return None,
+            _ => {}
+        }
+
+        let length = usize::from(u16::from_be_bytes([data[position + 2], data[position + 3]]));
+        let segment = data.get(position + 4..position + 2 + length)?;
+        if marker == 0xE1 {
+            if let Some(tiff) = segment.strip_prefix(b"Exif\0\0") {
+                return Some(tiff);
+            }
+        }
+        position += 2 + length;
+    }
+
+    None
+}
+
+/// camera_from_tiff() reads the `Make` and `Model` tags from the first directory of the TIFF structure in `tiff`, see
+/// camera_model()
+///
+/// # Arguments
+///
+/// `tiff` the TIFF structured EXIF data, starting with its byte order mark
+///
+/// # Errors
+///
+/// None
+fn camera_from_tiff(tiff: &[u8]) -> Option<String> {
+    let little_endian = match tiff.get(0..4)? {
+        b"II*\0" => true,
+        b"MM\0*" => false,
+        _ => return None,
+    };
+    let read_u16 = |at: usize| {
+        let bytes = [*tiff.get(at)?, *tiff.get(at + 1)?];
+        Some(if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let read_u32 = |at: usize| {
+        let bytes = [read_u16(at)?, read_u16(at + 2)?];
+        Some(if little_endian {
+            u32::from(bytes[0]) | u32::from(bytes[1]) << 16
+        } else {
+            u32::from(bytes[0]) << 16 | u32::from(bytes[1])
+        })
+    };
+
+    let directory = usize::try_from(read_u32(4)?).ok()?;
+    let mut make: Option<String> = None;
+    let mut model: Option<String> = None;
+    for index in 0..usize::from(read_u16(directory)?) {
+        let entry = directory + 2 + index * 12;
+        let tag = read_u16(entry)?;
+        if (tag != MAKE && tag != MODEL) || read_u16(entry + 2)? != ASCII {
+            continue;
+        }
+
+        // strings of up to four bytes are stored in the entry itself, longer ones at the offset it holds
+        let length = usize::try_from(read_u32(entry + 4)?).ok()?;
+        let start = if length <= 4 {
+            entry + 8
+        } else {
+            usize::try_from(read_u32(entry + 8)?).ok()?
+        };
+        let text = String::from_utf8_lossy(tiff.get(start..start.checked_add(length)?)?)
+            .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+            .to_string();
+        if !text.is_empty() {
+            if tag == MAKE {
+                make = Some(text);
+            } else {
+                model = Some(text);
+            }
+        }
+    }
+
+    match (make, model) {
+        (Some(make), Some(model)) if model.to_lowercase().starts_with(&make.to_lowercase()) => {
+            Some(model)
+        }
+        (Some(make), Some(model)) => Some(format!("{make}_{model}")),
+        (make, model) => make.or(model),
+    }
+}
+
+/// sample_jpeg() returns a minimal JPEG file whose EXIF data holds the given `Make` and `Model` tags, with its TIFF
+/// structure in little or big endian byte order, for tests
+///
+/// # Arguments
+///
+/// `make` the `Make` tag, None to leave it out
+/// `model` the `Model` tag, None to leave it out
+/// `little_endian` whether the TIFF structure uses little endian byte order
+///
+/// # Errors
+///
+/// None
+#[cfg(test)]
+pub fn sample_jpeg(make: Option<&str>, model: Option<&str>, little_endian: bool) -> Vec<u8> {
+    let u16_bytes = |value: u16| {
+        if little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    };
+    let u32_bytes = |value: u32| {
+        if little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    };
+    let tags: Vec<(u16, &str)> = [(MAKE, make), (MODEL, model)]
+        .into_iter()
+        .filter_map(|(tag, value)| value.map(|value| (tag, value)))
+        .collect();
+
+    // header, then a single directory whose strings follow it
+    let mut tiff: Vec<u8> = if little_endian {
+        b"II*\0".to_vec()
+    } else {
+        b"MM\0*".to_vec()
+    };
+    tiff.extend(u32_bytes(8));
+    tiff.extend(u16_bytes(tags.len() as u16));
+    let mut strings: Vec<u8> = Vec::new();
+    let strings_start = 8 + 2 + tags.len() * 12 + 4;
+    for (tag, value) in &tags {
+        let mut value = value.as_bytes().to_vec();
+        value.push(0);
+        tiff.extend(u16_bytes(*tag));
+        tiff.extend(u16_bytes(ASCII));
+        tiff.extend(u32_bytes(value.len() as u32));
+        if value.len() <= 4 {
+            value.resize(4, 0);
+            tiff.extend(value);
+        } else {
+            tiff.extend(u32_bytes((strings_start + strings.len()) as u32));
+            strings.extend(value);
+        }
+    }
+    tiff.extend(u32_bytes(0));
+    tiff.extend(strings);
+
+    let mut jpeg: Vec<u8> = vec![0xFF, 0xD8, 0xFF, 0xE1];
+    jpeg.extend(((tiff.len() + 8) as u16).to_be_bytes());
+    jpeg.extend(b"Exif\0\0");
+    jpeg.extend(tiff);
+    jpeg.extend([0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+    jpeg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+    use std::path::PathBuf;
+
+    /// verifies camera_model() reads the make and model from JPEG files in either byte order and returns None for files
+    /// without them
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - camera_model() does not join the make and model or repeats a make the model already starts with
+    /// - camera_model() does not fall back to a single tag, or returns a camera for a file without EXIF data
+    #[test]
+    fn exif_camera_model() {
+        // create inputs
+        let path_buf = PathBuf::from("./exif_camera_model_test");
+        let files = [
+            (
+                sample_jpeg(Some("NIKON"), Some("D750"), true),
+                Some("NIKON_D750"),
+            ),
+            (
+                sample_jpeg(Some("Canon"), Some("Canon EOS 5D"), false),
+                Some("Canon EOS 5D"),
+            ),
+            (sample_jpeg(None, Some("X100V"), false), Some("X100V")),
+            (sample_jpeg(None, None, true), None),
+            (b"plain text".to_vec(), None),
+        ];
+
+        // create mock directory
+        create_dir_all(&path_buf).unwrap();
+        for (index, (contents, _)) in files.iter().enumerate() {
+            write(path_buf.join(format!("{index}.jpg")), contents).unwrap();
+        }
+
+        // run test
+        let test_result: Vec<Option<String>> = (0..files.len())
+            .map(|index| camera_model(&path_buf.join(format!("{index}.jpg"))).unwrap())
+            .collect();
+        let missing = camera_model(&path_buf.join("missing.jpg"));
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        for (camera, (_, expected)) in test_result.iter().zip(&files) {
+            assert_eq!(camera.as_deref(), *expected);
+        }
+        assert!(missing.is_err());
+    }
+}
@@ -0,0 +1,58 @@
+use std::io;
+
+/// Windows error returned when another process has the file open without sharing it
+#[cfg(windows)]
+const ERROR_SHARING_VIOLATION: i32 = 32;
+
+/// Windows error returned when another process has locked part of the file
+#[cfg(windows)]
+const ERROR_LOCK_VIOLATION: i32 = 33;
+
+/// is_file_in_use() returns whether `err` was caused by another process holding the file open or locked, these are
+/// sharing and lock violations (and the access denied error renaming an open file gives) on Windows, and busy files
+/// elsewhere
+///
+/// # Arguments
+///
+/// `err` the error to classify
+///
+/// # Errors
+///
+/// None
+pub fn is_file_in_use(err: &io::Error) -> bool {
+    #[cfg(windows)]
+    if matches!(
+        err.raw_os_error(),
+        Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)
+    ) || err.kind() == io::ErrorKind::PermissionDenied
+    {
+        return true;
+    }
+
+    matches!(
+        err.kind(),
+        io::ErrorKind::ResourceBusy | io::ErrorKind::ExecutableFileBusy
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies is_file_in_use() recognizes a busy file and nothing unrelated
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - is_file_in_use() does not recognize a busy file or flags an unrelated error
+    #[test]
+    fn file_in_use_kinds() {
+        assert!(is_file_in_use(&io::Error::from(
+            io::ErrorKind::ResourceBusy
+        )));
+        assert!(!is_file_in_use(&io::Error::from(io::ErrorKind::NotFound)));
+    }
+}
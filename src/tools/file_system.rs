@@ -0,0 +1,303 @@
+use std::fs::{self, Metadata};
+use std::io;
+use std::path::{Path, PathBuf};
+
+// declare local code
+use super::free_space;
+use super::long_path::long_path;
+use super::move_file::copy_buffered;
+use super::throttle::Throttle;
+
+/// FileSystem trait: the file system operations performed by tasks that move files, so tests can substitute an
+/// implementation that fails in ways that are hard to provoke on a real file system
+pub trait FileSystem {
+    /// read_dir() returns the paths of every item directly inside `path`, in no particular order
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to the FileSystem
+    /// `path` a Path that corresponds to a directory
+    ///
+    /// # Errors
+    ///
+    /// - `path` cannot be read
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// rename() moves `source` to `target` within a single file system
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to the FileSystem
+    /// `source` a Path that corresponds to the file to be moved
+    /// `target` a Path that corresponds to the desired location of the file
+    ///
+    /// # Errors
+    ///
+    /// - the rename fails, including when the paths live on different file systems
+    fn rename(&self, source: &Path, target: &Path) -> io::Result<()>;
+
+    /// copy() streams the contents of `source` into `target` keeping its timestamps, returns the number of bytes copied,
+    /// see copy_buffered()
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to the FileSystem
+    /// `source` a Path that corresponds to the file to be copied
+    /// `target` a Path that corresponds to the location of the copy
+    /// `throttle` a Throttle limiting the rate data is written at
+    /// `preserve_perms` whether the permissions and ownership of `source` are restored onto `target`
+    ///
+    /// # Errors
+    ///
+    /// - the copy fails
+    fn copy(
+        &self,
+        source: &Path,
+        target: &Path,
+        throttle: &mut Throttle,
+        preserve_perms: bool,
+    ) -> io::Result<u64>;
+
+    /// hard_link() creates `target` as a hard link to `source`, sharing its contents without copying them
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to the FileSystem
+    /// `source` a Path that corresponds to an existing file
+    /// `target` a Path that corresponds to the location of the link
+    ///
+    /// # Errors
+    ///
+    /// - the link cannot be created, including when the paths live on different file systems
+    fn hard_link(&self, source: &Path, target: &Path) -> io::Result<()>;
+
+    /// remove_file() removes the file at `path`
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to the FileSystem
+    /// `path` a Path that corresponds to a file
+    ///
+    /// # Errors
+    ///
+    /// - the file cannot be removed
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+    /// create_dir_all() creates the directory at `path` along with any missing parents
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to the FileSystem
+    /// `path` a Path that corresponds to a directory
+    ///
+    /// # Errors
+    ///
+    /// - a directory cannot be created
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    /// metadata() returns the Metadata of the item at `path`, following symbolic links
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to the FileSystem
+    /// `path` a Path that corresponds to an item
+    ///
+    /// # Errors
+    ///
+    /// - metadata is not retrievable for the item
+    fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+
+    /// same_device() returns whether `a` and `b` live on the same volume, so that renaming one into the other succeeds
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to the FileSystem
+    /// `a` a Path that corresponds to an existing item
+    /// `b` a Path that corresponds to an existing item
+    ///
+    /// # Errors
+    ///
+    /// - metadata is not retrievable for `a` or `b`
+    fn same_device(&self, a: &Path, b: &Path) -> io::Result<bool>;
+}
+
+/// RealFileSystem struct: the FileSystem tasks use outside of tests, every operation is passed on to `std::fs` with
+/// paths that are too long for Windows prefixed, see long_path()
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RealFileSystem;
+
+impl FileSystem for RealFileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::read_dir(path)?
+            .map(|item| item.map(|item| item.path()))
+            .collect()
+    }
+
+    fn rename(&self, source: &Path, target: &Path) -> io::Result<()> {
+        fs::rename(long_path(source), long_path(target))
+    }
+
+    fn copy(
+        &self,
+        source: &Path,
+        target: &Path,
+        throttle: &mut Throttle,
+        preserve_perms: bool,
+    ) -> io::Result<u64> {
+        copy_buffered(
+            &long_path(source),
+            &long_path(target),
+            throttle,
+            preserve_perms,
+        )
+    }
+
+    fn hard_link(&self, source: &Path, target: &Path) -> io::Result<()> {
+        fs::hard_link(long_path(source), long_path(target))
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(long_path(path))
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(long_path(path))
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        fs::metadata(long_path(path))
+    }
+
+    fn same_device(&self, a: &Path, b: &Path) -> io::Result<bool> {
+        free_space::same_device(a, b)
+    }
+}
+
+/// MockFileSystem struct: a FileSystem for tests that fails chosen operations with a given error and passes every
+/// other operation on to RealFileSystem
+#[cfg(test)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MockFileSystem {
+    /// error returned by every rename, None to rename normally
+    pub rename_error: Option<io::ErrorKind>,
+
+    /// number of renames that fail with a timeout before renames succeed, counted down by every failed rename
+    pub timed_out_renames: std::cell::Cell<u32>,
+
+    /// number of renames that succeed before every further rename fails as interrupted, counted down by every
+    /// successful rename, None to never interrupt
+    pub renames_before_error: std::cell::Cell<Option<u32>>,
+
+    /// error returned by every copy, None to copy normally
+    pub copy_error: Option<io::ErrorKind>,
+
+    /// whether every copy alters the last byte it wrote, as a failing disk would, while still reporting success
+    pub corrupt_copies: bool,
+
+    /// error returned by every directory creation, None to create directories normally
+    pub create_dir_error: Option<io::ErrorKind>,
+
+    /// directory whose contents cannot be read, reading it fails with PermissionDenied
+    pub unreadable_dir: Option<&'static str>,
+}
+
+#[cfg(test)]
+impl MockFileSystem {
+    /// fail() returns `error` as an io::Error if set, otherwise performs `operation`
+    ///
+    /// # Arguments
+    ///
+    /// `error` the error to fail with, if any
+    /// `operation` the real operation
+    ///
+    /// # Errors
+    ///
+    /// - `error` is set
+    /// - `operation` fails
+    fn fail<T>(
+        error: Option<io::ErrorKind>,
+        operation: impl FnOnce() -> io::Result<T>,
+    ) -> io::Result<T> {
+        match error {
+            Some(kind) => Err(io::Error::from(kind)),
+            None => operation(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl FileSystem for MockFileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let error = self
+            .unreadable_dir
+            .filter(|dir| path == Path::new(dir))
+            .map(|_| io::ErrorKind::PermissionDenied);
+        Self::fail(error, || RealFileSystem.read_dir(path))
+    }
+
+    fn rename(&self, source: &Path, target: &Path) -> io::Result<()> {
+        let timed_out = self.timed_out_renames.get();
+        if timed_out > 0 {
+            self.timed_out_renames.set(timed_out - 1);
+            return Err(io::Error::from(io::ErrorKind::TimedOut));
+        }
+        match self.renames_before_error.get() {
+            Some(0) => return Err(io::Error::from(io::ErrorKind::Interrupted)),
+            Some(left) => self.renames_before_error.set(Some(left - 1)),
+            None => {}
+        }
+        Self::fail(self.rename_error, || RealFileSystem.rename(source, target))
+    }
+
+    fn copy(
+        &self,
+        source: &Path,
+        target: &Path,
+        throttle: &mut Throttle,
+        preserve_perms: bool,
+    ) -> io::Result<u64> {
+        let copied = Self::fail(self.copy_error, || {
+            RealFileSystem.copy(source, target, throttle, preserve_perms)
+        })?;
+        if self.corrupt_copies {
+            let mut contents = fs::read(target)?;
+            if let Some(last) = contents.last_mut() {
+                *last ^= 0xFF;
+            }
+            fs::write(target, contents)?;
+        }
+
+        Ok(copied)
+    }
+
+    // renames that cross file systems imply links cannot be created either
+    fn hard_link(&self, source: &Path, target: &Path) -> io::Result<()> {
+        let error = self
+            .rename_error
+            .filter(|kind| *kind == io::ErrorKind::CrossesDevices);
+        Self::fail(error, || RealFileSystem.hard_link(source, target))
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        RealFileSystem.remove_file(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        Self::fail(self.create_dir_error, || {
+            RealFileSystem.create_dir_all(path)
+        })
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        RealFileSystem.metadata(path)
+    }
+
+    // renames that cross file systems imply the paths are on different volumes
+    fn same_device(&self, a: &Path, b: &Path) -> io::Result<bool> {
+        if self.rename_error == Some(io::ErrorKind::CrossesDevices) {
+            Ok(false)
+        } else {
+            RealFileSystem.same_device(a, b)
+        }
+    }
+}
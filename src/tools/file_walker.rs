@@ -0,0 +1,785 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::dates::DateSource;
+use super::dir_config::{read_dir_config, DirConfig, DIR_CONFIG_FILE};
+use super::error::with_path;
+use super::file_system::{FileSystem, RealFileSystem};
+use super::glob::glob_match;
+use super::hidden::{is_hidden, Hidden};
+use super::symlinks::{is_loop, SymlinkPolicy};
+
+/// WalkEntry struct: describes a single item found while walking a directory tree
+#[derive(Debug, PartialEq, Eq)]
+pub struct WalkEntry {
+    /// PathBuf to the item, the logical path below the walked root even inside a followed link to a directory, never the
+    /// location the link resolves to, so names and provenance derived from it stay those of the source tree
+    pub path: PathBuf,
+
+    /// number of directories between the walked root and the item, items directly inside the root have a depth of 1
+    pub depth: usize,
+
+    /// whether the item is a directory
+    pub is_dir: bool,
+
+    /// date the item is organized by according to the `.filemanrc` files above it, None to use that of the task
+    pub date: Option<DateSource>,
+}
+
+/// FileWalker struct: recursively visits every item below a root directory in sorted order
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FileWalker {
+    /// deepest level that will be visited, None visits the whole tree
+    max_depth: Option<usize>,
+
+    /// shallowest level files are visited at, directories above it are still visited and descended into
+    min_depth: usize,
+
+    /// names or glob patterns of directories that are skipped entirely along with their contents
+    exclude_dirs: Vec<String>,
+
+    /// how hidden files and directories are treated
+    hidden: Hidden,
+
+    /// how symbolic links are treated
+    symlinks: SymlinkPolicy,
+}
+
+impl FileWalker {
+    /// FileWalker struct initializer, visits the whole tree by default
+    ///
+    /// # Arguments
+    ///
+    /// None
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// limits the walk to items at most `max_depth` levels below the root
+    ///
+    /// # Arguments
+    ///
+    /// `max_depth` deepest level to visit, None visits the whole tree
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// limits the walk to files at least `min_depth` levels below the root, directories are visited at every level so
+    /// deeper files can still be reached and shown in place
+    ///
+    /// # Arguments
+    ///
+    /// `min_depth` shallowest level to visit files at, 0 or 1 visits the files directly inside the root
+    pub fn min_depth(mut self, min_depth: usize) -> Self {
+        self.min_depth = min_depth;
+        self
+    }
+
+    /// returns the shallowest level files are visited at and the deepest level visited, see min_depth() and max_depth()
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to FileWalker
+    pub fn depth_range(&self) -> (usize, Option<usize>) {
+        (self.min_depth, self.max_depth)
+    }
+
+    /// never visits or descends into directories whose name matches `pattern`, may be called repeatedly
+    ///
+    /// # Arguments
+    ///
+    /// `pattern` a directory name or glob pattern such as `node_modules` or `.*`
+    pub fn exclude_dir(mut self, pattern: String) -> Self {
+        self.exclude_dirs.push(pattern);
+        self
+    }
+
+    /// chooses whether hidden files and directories are visited, skipped, or the only files visited
+    ///
+    /// # Arguments
+    ///
+    /// `hidden` how hidden items are treated, see is_hidden()
+    pub fn hidden(mut self, hidden: Hidden) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// chooses whether symbolic links are skipped, followed, or visited as entries of their own
+    ///
+    /// # Arguments
+    ///
+    /// `symlinks` how symbolic links are treated, see SymlinkPolicy
+    pub fn symlinks(mut self, symlinks: SymlinkPolicy) -> Self {
+        self.symlinks = symlinks;
+        self
+    }
+
+    /// returns how symbolic links are treated
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to FileWalker
+    pub fn symlink_policy(&self) -> SymlinkPolicy {
+        self.symlinks
+    }
+
+    /// walk() visits every item below `root` depth first, directories are visited before their contents and the items
+    /// of a directory are visited in order of their paths. The `.filemanrc` of every directory, `root` included, is
+    /// read before it is visited and its settings apply to the whole subtree, see DirConfig, the file itself is never
+    /// visited
+    ///
+    /// # Arguments
+    ///
+    /// `root` a Path that corresponds to a directory
+    /// `visit` a closure called once for every item found
+    ///
+    /// # Errors
+    ///
+    /// -`root` does not correspond to a directory
+    /// -`root` corresponds to a protected object on the file system or maps to a broken link
+    /// - path from item at `root` cannot be obtained
+    /// - read_dir_config() call fails
+    /// - `visit` errors
+    pub fn walk(
+        &self,
+        root: &Path,
+        visit: &mut dyn FnMut(&WalkEntry) -> io::Result<()>,
+    ) -> io::Result<()> {
+        self.walk_in(&RealFileSystem, root, visit)
+    }
+
+    /// walk_in() visits every item below `root` on `fs` in the same order as walk()
+    ///
+    /// # Arguments
+    ///
+    /// `fs` the FileSystem the tree is read from
+    /// `root` a Path that corresponds to a directory
+    /// `visit` a closure called once for every item found
+    ///
+    /// # Errors
+    ///
+    /// - see walk()
+    pub(crate) fn walk_in(
+        &self,
+        fs: &dyn FileSystem,
+        root: &Path,
+        visit: &mut dyn FnMut(&WalkEntry) -> io::Result<()>,
+    ) -> io::Result<()> {
+        self.walk_root(fs, root, visit, None)
+    }
+
+    /// walk_lenient_in() visits every item below `root` on `fs` in the same order as walk(), directories that cannot
+    /// be read, including `root` itself, are recorded in `errors` along with the error and skipped instead of ending
+    /// the walk
+    ///
+    /// # Arguments
+    ///
+    /// `fs` the FileSystem the tree is read from
+    /// `root` a Path that corresponds to a directory
+    /// `visit` a closure called once for every item found
+    /// `errors` where the unreadable directories and their errors are placed
+    ///
+    /// # Errors
+    ///
+    /// - `visit` errors
+    pub(crate) fn walk_lenient_in(
+        &self,
+        fs: &dyn FileSystem,
+        root: &Path,
+        visit: &mut dyn FnMut(&WalkEntry) -> io::Result<()>,
+        errors: &mut Vec<(PathBuf, io::Error)>,
+    ) -> io::Result<()> {
+        self.walk_root(fs, root, visit, Some(errors))
+    }
+
+    /// walk_root() reads the `.filemanrc` of `root` and walks it unless it is skipped
+    ///
+    /// # Arguments
+    ///
+    /// `fs` the FileSystem the tree is read from
+    /// `root` a Path that corresponds to a directory
+    /// `visit` a closure called once for every item found
+    /// `errors` where directories that cannot be read are recorded, None to fail on them
+    ///
+    /// # Errors
+    ///
+    /// - see walk()
+    fn walk_root(
+        &self,
+        fs: &dyn FileSystem,
+        root: &Path,
+        visit: &mut dyn FnMut(&WalkEntry) -> io::Result<()>,
+        mut errors: Option<&mut Vec<(PathBuf, io::Error)>>,
+    ) -> io::Result<()> {
+        let config = match (
+            read_dir_config(root, &DirConfig::default()),
+            errors.as_deref_mut(),
+        ) {
+            (Ok(config), _) => config,
+            (Err(err), Some(errors)) => {
+                errors.push((root.to_path_buf(), err));
+                return Ok(());
+            }
+            (Err(err), None) => return Err(err),
+        };
+        if config.skip == Some(true) {
+            return Ok(());
+        }
+
+        self.walk_dir(fs, root, 1, false, &config, visit, errors)
+    }
+
+    /// walk_dir() recursive portion of walk(), visits all items in `dir` which sits `depth` - 1 levels below the root
+    ///
+    /// # Arguments
+    ///
+    /// `fs` the FileSystem the tree is read from
+    /// `dir` a Path that corresponds to a directory
+    /// `depth` depth assigned to items directly inside `dir`
+    /// `in_hidden` whether `dir` is or lies inside a hidden directory
+    /// `config` the DirConfig in effect inside `dir`
+    /// `visit` a closure called once for every item found
+    /// `errors` where directories that cannot be read are recorded, None to fail on them
+    ///
+    /// # Errors
+    ///
+    /// - see walk()
+    #[allow(clippy::too_many_arguments)]
+    fn walk_dir(
+        &self,
+        fs: &dyn FileSystem,
+        dir: &Path,
+        depth: usize,
+        in_hidden: bool,
+        config: &DirConfig,
+        visit: &mut dyn FnMut(&WalkEntry) -> io::Result<()>,
+        mut errors: Option<&mut Vec<(PathBuf, io::Error)>>,
+    ) -> io::Result<()> {
+        // ensure that dir is a valid directory and read all items in a deterministic order
+        let mut items = match (fs.read_dir(dir), errors.as_deref_mut()) {
+            (Ok(items), _) => items,
+            (Err(err), Some(errors)) => {
+                errors.push((dir.to_path_buf(), err));
+                return Ok(());
+            }
+            (Err(err), None) => return Err(with_path(err, dir)),
+        };
+        items.sort();
+
+        for path in items {
+            // a followed link is visited as what it points to, one that is not is visited as neither file nor directory
+            let link = path.is_symlink();
+            if link && self.symlinks == SymlinkPolicy::Skip {
+                continue;
+            }
+            let follow = !link || self.symlinks == SymlinkPolicy::Follow;
+            let entry = WalkEntry {
+                is_dir: follow && fs.metadata(&path).is_ok_and(|metadata| metadata.is_dir()),
+                path,
+                depth,
+                date: config.date.clone(),
+            };
+
+            // excluded directories are pruned before they are visited so their contents are never read, the
+            // settings of a directory are never visited themselves
+            if (entry.is_dir && self.is_excluded(&entry.path)) || config.excludes(&entry.path) {
+                continue;
+            }
+            if !entry.is_dir
+                && entry
+                    .path
+                    .file_name()
+                    .is_some_and(|name| name == DIR_CONFIG_FILE)
+            {
+                continue;
+            }
+
+            // the settings of a directory decide whether it is visited at all
+            let child = if entry.is_dir {
+                match (read_dir_config(&entry.path, config), errors.as_deref_mut()) {
+                    (Ok(child), _) if child.skip == Some(true) => continue,
+                    (Ok(child), _) => Some(child),
+                    (Err(err), Some(errors)) => {
+                        errors.push((entry.path.clone(), err));
+                        continue;
+                    }
+                    (Err(err), None) => return Err(err),
+                }
+            } else {
+                None
+            };
+
+            // hidden items are only looked for when they are treated differently
+            let hidden = self.hidden != Hidden::Include && (in_hidden || is_hidden(&entry.path));
+            if hidden && self.hidden == Hidden::Skip {
+                continue;
+            }
+
+            // directories are still visited when only hidden or deeper files are wanted as they may contain some
+            if entry.is_dir || ((hidden || self.hidden != Hidden::Only) && depth >= self.min_depth)
+            {
+                visit(&entry)?;
+            }
+
+            // only descend while the next level is still within the depth limit, the contents of a followed link are
+            // read through the link itself so they keep the link's path as their prefix
+            let descend = entry.is_dir && !(link && is_loop(&entry.path, depth));
+            if let Some(child) = child.filter(|_| descend) {
+                if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                    self.walk_dir(
+                        fs,
+                        &entry.path,
+                        depth + 1,
+                        hidden,
+                        &child,
+                        visit,
+                        errors.as_deref_mut(),
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FileWalker {
+    /// is_excluded() returns whether the name of the directory at `path` matches any excluded pattern
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to FileWalker
+    /// `path` a Path that corresponds to a directory
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub(crate) fn is_excluded(&self, path: &Path) -> bool {
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy(),
+            None => return false,
+        };
+
+        self.exclude_dirs
+            .iter()
+            .any(|pattern| glob_match(pattern, &name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::collect_files::collect_files;
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, File};
+
+    /// verifies FileWalker::walk() visits every item of a nested directory in sorted, depth first order
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - FileWalker::walk() misses an item, visits it out of order, or reports the wrong depth
+    #[test]
+    fn file_walker_walk_nested_dir() {
+        // create inputs
+        let path_buf = PathBuf::from("./file_walker_walk_nested_dir_test/");
+        let nested_path_buf = path_buf.join("b/");
+
+        // create mock directory
+        create_dir_all(&nested_path_buf).unwrap();
+        File::create(path_buf.join("a.txt")).unwrap();
+        File::create(nested_path_buf.join("c.txt")).unwrap();
+
+        // run test
+        let mut visited: Vec<(PathBuf, usize, bool)> = Vec::new();
+        FileWalker::new()
+            .walk(&path_buf, &mut |entry| {
+                visited.push((entry.path.clone(), entry.depth, entry.is_dir));
+                Ok(())
+            })
+            .unwrap();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            visited,
+            vec![
+                (path_buf.join("a.txt"), 1, false),
+                (path_buf.join("b"), 1, true),
+                (nested_path_buf.join("c.txt"), 2, false),
+            ]
+        );
+    }
+
+    /// verifies FileWalker::walk() does not descend past `max_depth`
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - FileWalker::walk() visits an item below `max_depth`
+    #[test]
+    fn file_walker_walk_max_depth() {
+        // create inputs
+        let path_buf = PathBuf::from("./file_walker_walk_max_depth_test/");
+        let nested_path_buf = path_buf.join("nested/");
+
+        // create mock directory
+        create_dir_all(&nested_path_buf).unwrap();
+        File::create(path_buf.join("1.txt")).unwrap();
+        File::create(nested_path_buf.join("2.txt")).unwrap();
+
+        // run test
+        let mut visited: Vec<PathBuf> = Vec::new();
+        FileWalker::new()
+            .max_depth(Some(1))
+            .walk(&path_buf, &mut |entry| {
+                visited.push(entry.path.clone());
+                Ok(())
+            })
+            .unwrap();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            visited,
+            vec![path_buf.join("1.txt"), path_buf.join("nested")]
+        );
+    }
+
+    /// verifies FileWalker::walk() skips files above `min_depth` while still visiting and descending into directories
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - FileWalker::walk() visits a file above `min_depth` or misses a directory or a deeper file
+    #[test]
+    fn file_walker_walk_min_depth() {
+        // create inputs
+        let path_buf = PathBuf::from("./file_walker_walk_min_depth_test/");
+        let nested_path_buf = path_buf.join("nested/");
+
+        // create mock directory
+        create_dir_all(&nested_path_buf).unwrap();
+        File::create(path_buf.join("1.txt")).unwrap();
+        File::create(nested_path_buf.join("2.txt")).unwrap();
+
+        // run test
+        let mut visited: Vec<PathBuf> = Vec::new();
+        FileWalker::new()
+            .min_depth(2)
+            .walk(&path_buf, &mut |entry| {
+                visited.push(entry.path.clone());
+                Ok(())
+            })
+            .unwrap();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            visited,
+            vec![path_buf.join("nested"), nested_path_buf.join("2.txt")]
+        );
+    }
+
+    /// verifies FileWalker::walk() never visits files inside excluded directories
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - FileWalker::walk() visits a file inside an excluded directory
+    #[test]
+    fn file_walker_exclude_dir() {
+        // create inputs
+        let path_buf = PathBuf::from("./file_walker_exclude_dir_test/");
+
+        // create mock directory
+        for dir in ["node_modules/pkg", ".git", "src/.cache", "src/keep"] {
+            create_dir_all(path_buf.join(dir)).unwrap();
+        }
+        for file in [
+            "node_modules/pkg/index.js",
+            ".git/HEAD",
+            "src/.cache/blob",
+            "src/keep/main.rs",
+            "README.md",
+        ] {
+            File::create(path_buf.join(file)).unwrap();
+        }
+
+        // run test
+        let mut files: Vec<PathBuf> = Vec::new();
+        let walker = FileWalker::new()
+            .exclude_dir(String::from("node_modules"))
+            .exclude_dir(String::from(".*"));
+        collect_files(&walker, &path_buf, &mut files).unwrap();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                path_buf.join("README.md"),
+                path_buf.join("src/keep/main.rs")
+            ]
+        );
+    }
+
+    /// verifies FileWalker::hidden() skips dotfiles and dot directories, or visits only them
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - FileWalker::walk() visits a hidden file while skipping hidden items
+    /// - FileWalker::walk() visits a regular file or misses one in a hidden directory while visiting only hidden files
+    #[test]
+    fn file_walker_hidden() {
+        // create inputs
+        let path_buf = PathBuf::from("./file_walker_hidden_test/");
+
+        // create mock directory
+        create_dir_all(path_buf.join("photos/.thumbnails")).unwrap();
+        for file in [
+            ".DS_Store",
+            "photos/.DS_Store",
+            "photos/.thumbnails/IMG_1.jpg",
+            "photos/IMG_1.jpg",
+        ] {
+            File::create(path_buf.join(file)).unwrap();
+        }
+
+        // run test
+        let collect = |hidden: Hidden| {
+            let mut files: Vec<PathBuf> = Vec::new();
+            collect_files(&FileWalker::new().hidden(hidden), &path_buf, &mut files).unwrap();
+            files
+        };
+        let test_result = [Hidden::Include, Hidden::Skip, Hidden::Only].map(collect);
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(test_result[0].len(), 4);
+        assert_eq!(test_result[1], [path_buf.join("photos/IMG_1.jpg")]);
+        assert_eq!(
+            test_result[2],
+            [
+                path_buf.join(".DS_Store"),
+                path_buf.join("photos/.DS_Store"),
+                path_buf.join("photos/.thumbnails/IMG_1.jpg"),
+            ]
+        );
+    }
+
+    /// verifies FileWalker::symlinks() skips links, visits them as entries of their own, or follows them without
+    /// walking a link back to the root again
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - FileWalker::walk() visits a link while skipping links
+    /// - FileWalker::walk() treats a link as a directory or descends into it without following links
+    /// - FileWalker::walk() does not descend into a link to a directory or descends into a loop while following links
+    #[cfg(unix)]
+    #[test]
+    fn file_walker_walk_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        // create inputs
+        let path_buf = PathBuf::from("./file_walker_walk_symlinks_test");
+
+        // create mock directory, loop points back at the root
+        create_dir_all(path_buf.join("d")).unwrap();
+        File::create(path_buf.join("a.txt")).unwrap();
+        File::create(path_buf.join("d/b.txt")).unwrap();
+        symlink("d", path_buf.join("link_dir")).unwrap();
+        symlink("a.txt", path_buf.join("link_file")).unwrap();
+        symlink(".", path_buf.join("loop")).unwrap();
+
+        // run test
+        let walk = |symlinks: SymlinkPolicy| {
+            let mut entries: Vec<(PathBuf, bool)> = Vec::new();
+            FileWalker::new()
+                .symlinks(symlinks)
+                .walk(&path_buf, &mut |entry| {
+                    entries.push((entry.path.clone(), entry.is_dir));
+                    Ok(())
+                })
+                .unwrap();
+            entries
+        };
+        let test_result = [
+            SymlinkPolicy::Skip,
+            SymlinkPolicy::NoFollow,
+            SymlinkPolicy::Follow,
+        ]
+        .map(walk);
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        let entry = |path: &str, is_dir: bool| (path_buf.join(path), is_dir);
+        assert_eq!(
+            test_result[0],
+            [
+                entry("a.txt", false),
+                entry("d", true),
+                entry("d/b.txt", false)
+            ]
+        );
+        assert_eq!(
+            test_result[1],
+            [
+                entry("a.txt", false),
+                entry("d", true),
+                entry("d/b.txt", false),
+                entry("link_dir", false),
+                entry("link_file", false),
+                entry("loop", false),
+            ]
+        );
+        assert_eq!(
+            test_result[2],
+            [
+                entry("a.txt", false),
+                entry("d", true),
+                entry("d/b.txt", false),
+                entry("link_dir", true),
+                entry("link_dir/b.txt", false),
+                entry("link_file", false),
+                entry("loop", true),
+            ]
+        );
+    }
+
+    /// verifies the files of a followed link to a directory outside the root are collected under the logical path of
+    /// the link rather than the location it resolves to
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - collect_files() returns a file by its resolved path or misses a file below the link
+    #[cfg(unix)]
+    #[test]
+    fn file_walker_follow_logical_paths() {
+        use std::os::unix::fs::symlink;
+
+        // create inputs, the linked folder lives next to the root rather than inside it
+        let path_buf = PathBuf::from("./file_walker_follow_logical_paths_test");
+        let root = path_buf.join("source");
+        let outside = path_buf.join("elsewhere");
+
+        // create mock directories
+        create_dir_all(outside.join("nested")).unwrap();
+        create_dir_all(&root).unwrap();
+        File::create(outside.join("a.jpg")).unwrap();
+        File::create(outside.join("nested/b.jpg")).unwrap();
+        symlink("../elsewhere", root.join("trip")).unwrap();
+
+        // run test
+        let mut files: Vec<PathBuf> = Vec::new();
+        let walker = FileWalker::new().symlinks(SymlinkPolicy::Follow);
+        collect_files(&walker, &root, &mut files).unwrap();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            files,
+            [root.join("trip/a.jpg"), root.join("trip/nested/b.jpg")]
+        );
+    }
+
+    /// verifies the `.filemanrc` of a directory leaves a subtree with `skip = true` out of the walk, gives the entries
+    /// of a subtree its date source and exclude patterns, and lets a nested `.filemanrc` override them
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - FileWalker::walk() visits a skipped subtree, an excluded file, or a `.filemanrc`
+    /// - FileWalker::walk() does not give an entry the date source in effect above it
+    /// - FileWalker::walk() does not fail on an invalid `.filemanrc`
+    #[test]
+    fn file_walker_dir_config() {
+        use std::fs::write;
+
+        // create inputs
+        let path_buf = PathBuf::from("./file_walker_dir_config_test");
+
+        // create mock directory, scans/raw overrides the date source scans sets
+        for dir in ["drafts/old", "scans/raw"] {
+            create_dir_all(path_buf.join(dir)).unwrap();
+        }
+        for file in [
+            "a.jpg",
+            "drafts/b.jpg",
+            "drafts/old/c.jpg",
+            "scans/d.jpg",
+            "scans/e.tmp",
+            "scans/raw/f.jpg",
+        ] {
+            File::create(path_buf.join(file)).unwrap();
+        }
+        write(path_buf.join("drafts/.filemanrc"), "skip = true\n").unwrap();
+        write(
+            path_buf.join("scans/.filemanrc"),
+            "# scans keep their dates in sidecars\ndate = sidecar\nexclude = *.tmp\n",
+        )
+        .unwrap();
+        write(path_buf.join("scans/raw/.filemanrc"), "date = modified\n").unwrap();
+
+        // run test
+        let mut visited: Vec<(PathBuf, Option<DateSource>)> = Vec::new();
+        FileWalker::new()
+            .walk(&path_buf, &mut |entry| {
+                visited.push((entry.path.clone(), entry.date.clone()));
+                Ok(())
+            })
+            .unwrap();
+        write(path_buf.join("scans/.filemanrc"), "skip = maybe\n").unwrap();
+        let invalid = FileWalker::new().walk(&path_buf, &mut |_| Ok(()));
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        let sidecar = Some(DateSource::Sidecar(None));
+        assert_eq!(
+            visited,
+            [
+                (path_buf.join("a.jpg"), None),
+                (path_buf.join("scans"), None),
+                (path_buf.join("scans/d.jpg"), sidecar.clone()),
+                (path_buf.join("scans/raw"), sidecar),
+                (path_buf.join("scans/raw/f.jpg"), Some(DateSource::Modified)),
+            ]
+        );
+        assert_eq!(invalid.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
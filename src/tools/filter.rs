@@ -0,0 +1,623 @@
+use std::fs::Metadata;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+// declare local code
+use super::glob::glob_match;
+use super::parse_size::parse_size;
+
+/// maximum number of parentheses and `not`s an expression may be nested in, deeper expressions are rejected rather
+/// than risking a stack overflow
+const MAX_DEPTH: usize = 64;
+
+/// error returned for any expression that cannot be parsed
+const ERROR: &str = "'--filter' must be followed by an expression such as \"size > 10MB and ext in [jpg,png] and age < 30d\"";
+
+/// FileInfo struct: the facts about a file a Filter is evaluated against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileInfo {
+    /// name of the file including its extension
+    pub name: String,
+
+    /// lowercased extension of the file, empty for files without one
+    pub ext: String,
+
+    /// size of the file in bytes
+    pub size: u64,
+
+    /// time since the file was last modified, zero for files modified in the future
+    pub age: Duration,
+}
+
+impl FileInfo {
+    /// FileInfo struct initializer from the path and metadata of a file
+    ///
+    /// # Arguments
+    ///
+    /// `path` a Path that corresponds to the file
+    /// `metadata` the Metadata of the file
+    /// `now` the time ages are measured up to
+    ///
+    /// # Errors
+    ///
+    /// - the modification time of the file is not available on this platform
+    pub fn new(path: &Path, metadata: &Metadata, now: SystemTime) -> std::io::Result<Self> {
+        Ok(Self {
+            name: path
+                .file_name()
+                .map_or(String::new(), |name| name.to_string_lossy().into_owned()),
+            ext: path
+                .extension()
+                .map_or(String::new(), |ext| ext.to_string_lossy().to_lowercase()),
+            size: metadata.len(),
+            age: now.duration_since(metadata.modified()?).unwrap_or_default(),
+        })
+    }
+}
+
+/// Field enum: a fact about a file that an expression compares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    /// size in bytes, compared against sizes such as `10MB`
+    Size,
+
+    /// lowercased extension, compared without regard to case or a leading `.`
+    Ext,
+
+    /// file name, compared against glob patterns
+    Name,
+
+    /// time since the last modification, compared against durations such as `30d`
+    Age,
+}
+
+/// Op enum: comparison between a field and a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// `==` or `=`
+    Eq,
+
+    /// `!=`
+    Ne,
+
+    /// `<`
+    Lt,
+
+    /// `<=`
+    Le,
+
+    /// `>`
+    Gt,
+
+    /// `>=`
+    Ge,
+}
+
+impl Op {
+    /// compare() applies the comparison to two ordered values
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Op
+    /// `left` the value of the field
+    /// `right` the value it is compared against
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn compare<T: Ord>(&self, left: T, right: T) -> bool {
+        match self {
+            Self::Eq => left == right,
+            Self::Ne => left != right,
+            Self::Lt => left < right,
+            Self::Le => left <= right,
+            Self::Gt => left > right,
+            Self::Ge => left >= right,
+        }
+    }
+}
+
+/// Filter enum: a parsed `--filter` expression deciding which files are selected, see parse_filter()
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// both expressions hold
+    And(Box<Filter>, Box<Filter>),
+
+    /// either expression holds
+    Or(Box<Filter>, Box<Filter>),
+
+    /// the expression does not hold
+    Not(Box<Filter>),
+
+    /// the size of the file compares to a number of bytes
+    Size(Op, u64),
+
+    /// the age of the file compares to a duration
+    Age(Op, Duration),
+
+    /// the extension of the file is, or is not, one of the lowercased extensions
+    Ext(bool, Vec<String>),
+
+    /// the name of the file matches, or does not match, one of the glob patterns
+    Name(bool, Vec<String>),
+}
+
+impl Filter {
+    /// matches() returns whether `file` is selected by the expression
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Filter
+    /// `file` the FileInfo of the file
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn matches(&self, file: &FileInfo) -> bool {
+        match self {
+            Self::And(left, right) => left.matches(file) && right.matches(file),
+            Self::Or(left, right) => left.matches(file) || right.matches(file),
+            Self::Not(inner) => !inner.matches(file),
+            Self::Size(op, size) => op.compare(file.size, *size),
+            Self::Age(op, age) => op.compare(file.age, *age),
+            Self::Ext(equal, exts) => exts.contains(&file.ext) == *equal,
+            Self::Name(equal, patterns) => {
+                patterns
+                    .iter()
+                    .any(|pattern| glob_match(pattern, &file.name))
+                    == *equal
+            }
+        }
+    }
+}
+
+/// Token enum: a lexical part of an expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// a field, keyword, or value, quoted values may contain spaces and punctuation
+    Word(String),
+
+    /// a comparison operator
+    Op(Op),
+
+    /// `(`
+    Open,
+
+    /// `)`
+    Close,
+
+    /// `[`
+    ListOpen,
+
+    /// `]`
+    ListClose,
+
+    /// `,`
+    Comma,
+}
+
+/// tokenize() splits `text` into Tokens, words run until whitespace or punctuation and may be quoted with `"` or `'`
+///
+/// # Arguments
+///
+/// `text` the expression
+///
+/// # Errors
+///
+/// - a quote is not closed or `!` is not followed by `=`
+fn tokenize(text: &str) -> Result<Vec<Token>, &'static str> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '[' => Token::ListOpen,
+            ']' => Token::ListClose,
+            ',' => Token::Comma,
+            '=' => {
+                // `==` and `=` are the same comparison
+                chars.next_if_eq(&'=');
+                Token::Op(Op::Eq)
+            }
+            '!' if chars.next_if_eq(&'=').is_some() => Token::Op(Op::Ne),
+            '<' if chars.next_if_eq(&'=').is_some() => Token::Op(Op::Le),
+            '>' if chars.next_if_eq(&'=').is_some() => Token::Op(Op::Ge),
+            '<' => Token::Op(Op::Lt),
+            '>' => Token::Op(Op::Gt),
+            '"' | '\'' => {
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(next) => word.push(next),
+                        None => return Err(ERROR),
+                    }
+                }
+                Token::Word(word)
+            }
+            '!' => return Err(ERROR),
+            c => {
+                let mut word = String::from(c);
+                while let Some(next) =
+                    chars.next_if(|next| !next.is_whitespace() && !"()[],=!<>\"'".contains(*next))
+                {
+                    word.push(next);
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// parse_duration() parses a duration such as `90s`, `15m`, `12h`, `30d`, or `2w`
+///
+/// # Arguments
+///
+/// `value` the text to parse
+///
+/// # Errors
+///
+/// None
+fn parse_duration(value: &str) -> Option<Duration> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (digits, unit) = value.split_at(split);
+
+    let multiplier: u64 = match unit.to_ascii_lowercase().as_str() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+
+    Some(Duration::from_secs(
+        digits.parse::<u64>().ok()?.checked_mul(multiplier)?,
+    ))
+}
+
+/// Parser struct: recursive descent parser over the Tokens of an expression, `or` binds loosest, then `and`, then
+/// `not`
+struct Parser {
+    /// the tokens of the expression
+    tokens: Vec<Token>,
+
+    /// index of the next token to read
+    position: usize,
+}
+
+impl Parser {
+    /// next() returns the next token and moves past it
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// keyword() moves past the next token if it is the word `keyword` in any case
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    /// `keyword` the lowercased keyword
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(
+            self.tokens.get(self.position),
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword)
+        );
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    /// or() parses expressions joined by `or`
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    /// `depth` number of parentheses and `not`s the expression is nested in
+    ///
+    /// # Errors
+    ///
+    /// - see and()
+    fn or(&mut self, depth: usize) -> Result<Filter, &'static str> {
+        let mut filter = self.and(depth)?;
+        while self.keyword("or") {
+            filter = Filter::Or(Box::new(filter), Box::new(self.and(depth)?));
+        }
+        Ok(filter)
+    }
+
+    /// and() parses expressions joined by `and`
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    /// `depth` number of parentheses and `not`s the expression is nested in
+    ///
+    /// # Errors
+    ///
+    /// - see unary()
+    fn and(&mut self, depth: usize) -> Result<Filter, &'static str> {
+        let mut filter = self.unary(depth)?;
+        while self.keyword("and") {
+            filter = Filter::And(Box::new(filter), Box::new(self.unary(depth)?));
+        }
+        Ok(filter)
+    }
+
+    /// unary() parses a negation, a parenthesized expression, or a comparison
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    /// `depth` number of parentheses and `not`s the expression is nested in
+    ///
+    /// # Errors
+    ///
+    /// - the expression is nested deeper than MAX_DEPTH
+    /// - a parenthesis is not closed
+    /// - see comparison()
+    fn unary(&mut self, depth: usize) -> Result<Filter, &'static str> {
+        if depth > MAX_DEPTH {
+            return Err("'--filter' expression is nested too deeply");
+        }
+
+        if self.keyword("not") {
+            return Ok(Filter::Not(Box::new(self.unary(depth + 1)?)));
+        }
+        if self.tokens.get(self.position) == Some(&Token::Open) {
+            self.position += 1;
+            let filter = self.or(depth + 1)?;
+            return match self.next() {
+                Some(Token::Close) => Ok(filter),
+                _ => Err(ERROR),
+            };
+        }
+
+        self.comparison()
+    }
+
+    /// comparison() parses a field compared to a value, such as `size > 10MB`, or to a list of values with `in`, such
+    /// as `ext in [jpg,png]`
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    ///
+    /// # Errors
+    ///
+    /// - the field is unknown or the value is not a size, duration, or list as the field requires
+    /// - size or age is compared with `in`, or ext or name with an ordering
+    fn comparison(&mut self) -> Result<Filter, &'static str> {
+        let field = match self.next() {
+            Some(Token::Word(word)) => match word.to_ascii_lowercase().as_str() {
+                "size" => Field::Size,
+                "ext" => Field::Ext,
+                "name" => Field::Name,
+                "age" => Field::Age,
+                _ => return Err("'--filter' fields are size, ext, name, and age"),
+            },
+            _ => return Err(ERROR),
+        };
+
+        // `in` compares against a list of values, anything else against a single one
+        let (op, values) = if self.keyword("in") {
+            if self.next() != Some(Token::ListOpen) {
+                return Err(ERROR);
+            }
+            let mut values: Vec<String> = Vec::new();
+            loop {
+                match self.next() {
+                    Some(Token::Word(word)) => values.push(word),
+                    _ => return Err(ERROR),
+                }
+                match self.next() {
+                    Some(Token::Comma) => continue,
+                    Some(Token::ListClose) => break,
+                    _ => return Err(ERROR),
+                }
+            }
+            (None, values)
+        } else {
+            match (self.next(), self.next()) {
+                (Some(Token::Op(op)), Some(Token::Word(word))) => (Some(op), vec![word]),
+                _ => return Err(ERROR),
+            }
+        };
+
+        match (field, op) {
+            (Field::Size, Some(op)) => match parse_size(&values[0]) {
+                Some(size) => Ok(Filter::Size(op, size)),
+                None => Err("'--filter' compares size to a size such as 10MB"),
+            },
+            (Field::Age, Some(op)) => match parse_duration(&values[0]) {
+                Some(age) => Ok(Filter::Age(op, age)),
+                None => Err("'--filter' compares age to a duration such as 30d, 12h, 15m, or 90s"),
+            },
+            (Field::Ext, None | Some(Op::Eq | Op::Ne)) => Ok(Filter::Ext(
+                op != Some(Op::Ne),
+                values
+                    .iter()
+                    .map(|ext| ext.trim_start_matches('.').to_lowercase())
+                    .collect(),
+            )),
+            (Field::Name, None | Some(Op::Eq | Op::Ne)) => {
+                Ok(Filter::Name(op != Some(Op::Ne), values))
+            }
+            (Field::Size | Field::Age, None) => {
+                Err("'--filter' compares size and age with ==, !=, <, <=, >, or >=")
+            }
+            (Field::Ext | Field::Name, Some(_)) => {
+                Err("'--filter' compares ext and name with ==, !=, or in")
+            }
+        }
+    }
+}
+
+/// parse_filter() parses the value of `--filter`, comparisons of the fields `size`, `ext`, `name`, and `age` joined by
+/// `and`, `or`, `not`, and parentheses, such as `size > 10MB and ext in [jpg,png] and age < 30d`. Sizes take the units
+/// of parse_size(), ages the units `s`, `m`, `h`, `d`, and `w`, extensions are compared without regard to case, and
+/// names are glob patterns
+///
+/// # Arguments
+///
+/// `value` the value following `--filter`
+///
+/// # Errors
+///
+/// - `value` is missing or is not a valid expression
+pub fn parse_filter(value: Option<String>) -> Result<Filter, &'static str> {
+    let mut parser = Parser {
+        tokens: tokenize(&value.ok_or(ERROR)?)?,
+        position: 0,
+    };
+    let filter = parser.or(0)?;
+
+    // everything must be part of the expression
+    if parser.position != parser.tokens.len() {
+        return Err(ERROR);
+    }
+
+    Ok(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies expressions parse with `or` binding looser than `and`, `not` and parentheses applied, and invalid
+    /// expressions rejected
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_filter() does not build the expected Filter for a valid expression
+    /// - parse_filter() accepts an invalid expression
+    #[test]
+    fn filter_parse() {
+        let parse = |value: &str| parse_filter(Some(value.to_string()));
+
+        assert_eq!(parse("size >= 1K"), Ok(Filter::Size(Op::Ge, 1024)));
+        assert_eq!(
+            parse("ext == .JPG or name != 'IMG *' and not age < 2h"),
+            Ok(Filter::Or(
+                Box::new(Filter::Ext(true, vec![String::from("jpg")])),
+                Box::new(Filter::And(
+                    Box::new(Filter::Name(false, vec![String::from("IMG *")])),
+                    Box::new(Filter::Not(Box::new(Filter::Age(
+                        Op::Lt,
+                        Duration::from_secs(7200)
+                    ))))
+                ))
+            ))
+        );
+        assert_eq!(
+            parse("(size<1M OR size>1G) AND ext in [jpg, png]"),
+            Ok(Filter::And(
+                Box::new(Filter::Or(
+                    Box::new(Filter::Size(Op::Lt, 1 << 20)),
+                    Box::new(Filter::Size(Op::Gt, 1 << 30))
+                )),
+                Box::new(Filter::Ext(
+                    true,
+                    vec![String::from("jpg"), String::from("png")]
+                ))
+            ))
+        );
+        for value in [
+            "",
+            "size",
+            "size > ten",
+            "age < 30",
+            "colour == red",
+            "ext < jpg",
+            "size in [1M]",
+            "ext in []",
+            "ext in [jpg",
+            "(size > 1M",
+            "size > 1M extra",
+            "size > 1M and",
+            "name == 'open",
+            "size ! 1M",
+            &format!("{}size > 1M", "not ".repeat(MAX_DEPTH + 2)),
+        ] {
+            assert!(parse(value).is_err(), "{value}");
+        }
+        assert!(parse(&format!("{}size > 1M", "not ".repeat(MAX_DEPTH))).is_ok());
+        assert!(parse_filter(None).is_err());
+    }
+
+    /// verifies expressions are evaluated against sample file facts
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Filter::matches() does not select exactly the files the expression describes
+    #[test]
+    fn filter_matches() {
+        // create inputs
+        let file = |name: &str, ext: &str, size: u64, days: u64| FileInfo {
+            name: name.to_string(),
+            ext: ext.to_string(),
+            size,
+            age: Duration::from_secs(days * 24 * 60 * 60),
+        };
+        let files = [
+            file("IMG_1.JPG", "jpg", 20 << 20, 3),
+            file("IMG_2.png", "png", 1 << 20, 3),
+            file("scan.png", "png", 50 << 20, 90),
+            file("notes", "", 100, 1),
+        ];
+        let selected = |value: &str| {
+            let filter = parse_filter(Some(value.to_string())).unwrap();
+            files
+                .iter()
+                .filter(|file| filter.matches(file))
+                .map(|file| file.name.as_str())
+                .collect::<Vec<&str>>()
+        };
+
+        assert_eq!(
+            selected("size > 10MB and ext in [jpg,png] and age < 30d"),
+            ["IMG_1.JPG"]
+        );
+        assert_eq!(
+            selected("name == IMG_* or age >= 12w"),
+            ["IMG_1.JPG", "IMG_2.png", "scan.png"]
+        );
+        assert_eq!(selected("not ext in [jpg, png]"), ["notes"]);
+        assert_eq!(
+            selected("ext != png and size <= 20M"),
+            ["IMG_1.JPG", "notes"]
+        );
+        assert_eq!(selected("ext == ''"), ["notes"]);
+    }
+}
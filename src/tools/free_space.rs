@@ -0,0 +1,359 @@
+use std::io;
+use std::path::Path;
+
+// declare local code
+use super::file_system::FileSystem;
+
+/// available_space() returns the number of bytes an unprivileged user may still write to the volume holding `path`, or
+/// None on platforms where the free space cannot be queried
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to an existing item on the volume
+///
+/// # Errors
+///
+/// - the free space of the volume cannot be queried, such as when `path` does not exist
+pub fn available_space(path: &Path) -> io::Result<Option<u64>> {
+    platform::available_space(path)
+}
+
+/// available_inodes() returns the number of files and directories an unprivileged user may still create on the volume
+/// holding `path`, or None on platforms where it cannot be queried or file systems that do not limit it
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to an existing item on the volume
+///
+/// # Errors
+///
+/// - the volume cannot be queried, such as when `path` does not exist
+pub fn available_inodes(path: &Path) -> io::Result<Option<u64>> {
+    platform::available_inodes(path)
+}
+
+/// same_device() returns whether `a` and `b` live on the same volume, so that moving one into the other is a rename
+/// rather than a copy
+///
+/// # Arguments
+///
+/// `a` a Path that corresponds to an existing item
+/// `b` a Path that corresponds to an existing item
+///
+/// # Errors
+///
+/// - metadata is not retrievable for `a` or `b`
+pub fn same_device(a: &Path, b: &Path) -> io::Result<bool> {
+    platform::same_device(a, b)
+}
+
+/// copy_estimate() returns the combined size of the files among `files` that would be copied rather than renamed when
+/// moved into `target`, that is those on another volume
+///
+/// # Arguments
+///
+/// `fs` the FileSystem the files are moved on
+/// `files` Paths of the files to be moved along with their sizes in bytes
+/// `target` a Path that corresponds to the directory the files are moved into
+///
+/// # Errors
+///
+/// - FileSystem::same_device() call fails
+pub fn copy_estimate(
+    fs: &dyn FileSystem,
+    files: &[(&Path, u64)],
+    target: &Path,
+) -> io::Result<u64> {
+    let mut total: u64 = 0;
+    for (file, size) in files {
+        if !fs.same_device(file, target)? {
+            total += size;
+        }
+    }
+
+    Ok(total)
+}
+
+#[cfg(any(
+    all(target_os = "linux", target_pointer_width = "64"),
+    target_os = "macos"
+))]
+mod platform {
+    use std::ffi::CString;
+    use std::io;
+    use std::os::raw::{c_char, c_int, c_ulong};
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+    use std::path::Path;
+
+    /// block and inode counts are an unsigned long on 64 bit Linux and an unsigned int on macOS
+    #[cfg(target_os = "linux")]
+    type BlockCount = c_ulong;
+    #[cfg(target_os = "macos")]
+    type BlockCount = u32;
+
+    /// leading fields of `struct statvfs`, which share this layout on every supported platform, followed by room for
+    /// the remaining fields
+    #[repr(C)]
+    struct StatVfs {
+        f_bsize: c_ulong,
+        f_frsize: c_ulong,
+        f_blocks: BlockCount,
+        f_bfree: BlockCount,
+        f_bavail: BlockCount,
+        f_files: BlockCount,
+        f_ffree: BlockCount,
+        f_favail: BlockCount,
+        rest: [u64; 16],
+    }
+
+    extern "C" {
+        fn statvfs(path: *const c_char, buf: *mut StatVfs) -> c_int;
+    }
+
+    /// stat() queries the volume holding `path`
+    ///
+    /// # Arguments
+    ///
+    /// `path` a Path that corresponds to an existing item on the volume
+    ///
+    /// # Errors
+    ///
+    /// - `path` contains a NUL byte
+    /// - statvfs() call fails
+    fn stat(path: &Path) -> io::Result<StatVfs> {
+        let path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        let mut stat = StatVfs {
+            f_bsize: 0,
+            f_frsize: 0,
+            f_blocks: 0,
+            f_bfree: 0,
+            f_bavail: 0,
+            f_files: 0,
+            f_ffree: 0,
+            f_favail: 0,
+            rest: [0; 16],
+        };
+
+        // SAFETY: `path` is a valid NUL terminated string and `stat` is large enough for the whole structure
+        if unsafe { statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(stat)
+    }
+
+    pub fn available_space(path: &Path) -> io::Result<Option<u64>> {
+        let stat = stat(path)?;
+
+        // available blocks are counted in fragments, file systems that do not report a fragment size use the block size
+        let block = if stat.f_frsize == 0 {
+            stat.f_bsize
+        } else {
+            stat.f_frsize
+        };
+
+        // the casts widen the 32 bit block count of macOS and are no-ops on Linux
+        #[allow(clippy::unnecessary_cast)]
+        Ok(Some((stat.f_bavail as u64).saturating_mul(block as u64)))
+    }
+
+    pub fn available_inodes(path: &Path) -> io::Result<Option<u64>> {
+        let stat = stat(path)?;
+
+        // file systems that allocate inodes on demand, such as btrfs, report a total of zero
+        #[allow(clippy::unnecessary_cast)]
+        Ok((stat.f_files != 0).then_some(stat.f_favail as u64))
+    }
+
+    pub fn same_device(a: &Path, b: &Path) -> io::Result<bool> {
+        Ok(a.metadata()?.dev() == b.metadata()?.dev())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::{Component, Path};
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetDiskFreeSpaceExW(
+            directory: *const u16,
+            available: *mut u64,
+            total: *mut u64,
+            free: *mut u64,
+        ) -> i32;
+    }
+
+    pub fn available_space(path: &Path) -> io::Result<Option<u64>> {
+        let path: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+        let (mut available, mut total, mut free) = (0u64, 0u64, 0u64);
+
+        // SAFETY: `path` is NUL terminated and every out pointer refers to a live u64
+        if unsafe { GetDiskFreeSpaceExW(path.as_ptr(), &mut available, &mut total, &mut free) } == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Some(available))
+    }
+
+    // NTFS has no fixed number of inodes
+    pub fn available_inodes(_path: &Path) -> io::Result<Option<u64>> {
+        Ok(None)
+    }
+
+    pub fn same_device(a: &Path, b: &Path) -> io::Result<bool> {
+        // the drive letter or network share of the resolved path identifies the volume
+        let prefix = |path: &Path| -> io::Result<Option<String>> {
+            Ok(path
+                .canonicalize()?
+                .components()
+                .next()
+                .and_then(|component| match component {
+                    Component::Prefix(prefix) => {
+                        Some(prefix.as_os_str().to_string_lossy().to_uppercase())
+                    }
+                    _ => None,
+                }))
+        };
+
+        Ok(prefix(a)? == prefix(b)?)
+    }
+}
+
+#[cfg(not(any(
+    windows,
+    all(target_os = "linux", target_pointer_width = "64"),
+    target_os = "macos"
+)))]
+mod platform {
+    use std::io;
+    use std::path::Path;
+
+    pub fn available_space(_path: &Path) -> io::Result<Option<u64>> {
+        Ok(None)
+    }
+
+    pub fn available_inodes(_path: &Path) -> io::Result<Option<u64>> {
+        Ok(None)
+    }
+
+    #[cfg(unix)]
+    pub fn same_device(a: &Path, b: &Path) -> io::Result<bool> {
+        use std::os::unix::fs::MetadataExt;
+
+        Ok(a.metadata()?.dev() == b.metadata()?.dev())
+    }
+
+    // without a way to tell volumes apart every move is assumed to copy
+    #[cfg(not(unix))]
+    pub fn same_device(_a: &Path, _b: &Path) -> io::Result<bool> {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::file_system::{MockFileSystem, RealFileSystem};
+    use std::fs::{create_dir_all, remove_dir_all, write};
+    use std::path::PathBuf;
+
+    /// verifies copy_estimate() sums the sizes of files on another volume and available_space() reports the target
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - copy_estimate() does not sum the known sizes of files that cross volumes
+    /// - copy_estimate() counts files that are renamed within a volume
+    /// - available_space() does not report the free space of an existing directory or does not fail on a missing one
+    #[test]
+    fn copy_estimate_known_sizes() {
+        // create inputs
+        let path_buf = PathBuf::from("./copy_estimate_known_sizes_test");
+        let target = path_buf.join("target");
+        let cross_device = MockFileSystem {
+            rename_error: Some(io::ErrorKind::CrossesDevices),
+            ..MockFileSystem::default()
+        };
+
+        // create mock directory with files of known sizes
+        create_dir_all(&target).unwrap();
+        let mut files: Vec<(PathBuf, u64)> = Vec::new();
+        for (name, size) in [("a.bin", 1024), ("b.bin", 4096), ("c.bin", 0)] {
+            let file = path_buf.join(name);
+            write(&file, vec![0u8; size]).unwrap();
+            files.push((file, size as u64));
+        }
+        let files: Vec<(&Path, u64)> = files
+            .iter()
+            .map(|(file, size)| (file.as_path(), *size))
+            .collect();
+
+        // run test
+        let copied = copy_estimate(&cross_device, &files, &target);
+        let renamed = copy_estimate(&RealFileSystem, &files, &target);
+        let available = available_space(&target);
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(copied.unwrap(), 5120);
+        assert_eq!(renamed.unwrap(), 0);
+        assert!(available.is_ok());
+        #[cfg(any(
+            windows,
+            target_os = "macos",
+            all(target_os = "linux", target_pointer_width = "64")
+        ))]
+        {
+            assert!(available.unwrap().is_some());
+            assert!(available_space(&path_buf).is_err());
+        }
+    }
+
+    /// verifies available_inodes() reports a plausible number of free inodes on Unix
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - available_inodes() fails on an existing directory or does not fail on a missing one
+    /// - available_inodes() reports fewer free inodes than are in use by a freshly created file, or more than exist
+    #[cfg(unix)]
+    #[test]
+    fn available_inodes_plausible() {
+        // create inputs
+        let path_buf = PathBuf::from("./available_inodes_plausible_test");
+
+        // create mock directory
+        create_dir_all(&path_buf).unwrap();
+        write(path_buf.join("a.txt"), "a").unwrap();
+
+        // run test
+        let available = available_inodes(&path_buf);
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        // a volume that just had a file created on it has inodes to spare unless it does not count them
+        if let Some(available) = available.unwrap() {
+            assert!(available > 0);
+            assert!(available < u64::MAX);
+        }
+        #[cfg(any(
+            target_os = "macos",
+            all(target_os = "linux", target_pointer_width = "64")
+        ))]
+        assert!(available_inodes(&path_buf).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use std::io;
-use std::path::PathBuf;
+use std::path::Path;
 
 /// get_num_files() returns an owned usize corresponding to the number of files in a flat directory (will not recursively search subdirectories)
 ///
@@ -12,7 +12,7 @@ use std::path::PathBuf;
 /// -`path_buf` does not correspond to a directory
 /// -`path_buf` corresponds to a protected object on the file system or maps to a broken link
 /// - path from item at `path_buf` cannot be obtained
-pub fn get_num_files(path_buf: &PathBuf) -> io::Result<usize> {
+pub fn get_num_files(path_buf: &Path) -> io::Result<usize> {
     // owned usize value to be returned, counts number of files in path_buf
     let mut count: usize = 0;
 
@@ -34,6 +34,7 @@ pub fn get_num_files(path_buf: &PathBuf) -> io::Result<usize> {
 mod tests {
     use super::*;
     use std::fs::{create_dir, create_dir_all, remove_dir_all, remove_file, File};
+    use std::path::PathBuf;
 
     /// verifies get_num_files() returns the correct number of files in a flat directory
     ///
@@ -0,0 +1,86 @@
+/// glob_match() returns whether `text` matches the glob `pattern` in its entirety, `*` matches any run of characters
+/// (including none), `?` matches exactly one character, and every other character matches itself so a pattern
+/// without wildcards is a plain name comparison
+///
+/// # Arguments
+///
+/// `pattern` the glob pattern to match against
+/// `text` the text to match
+///
+/// # Errors
+///
+/// None
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    // positions within pattern and text, along with the most recent `*` and the text position it was tried at
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                // first let the star match nothing, backtracking extends it one character at a time
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    // any remaining pattern must be stars matching nothing
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies glob_match() matches plain names exactly
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - glob_match() does not compare a pattern without wildcards as a plain name
+    #[test]
+    fn glob_match_plain_names() {
+        assert!(glob_match("node_modules", "node_modules"));
+        assert!(!glob_match("node_modules", "node_modules2"));
+        assert!(!glob_match(".git", "git"));
+    }
+
+    /// verifies glob_match() handles `*` and `?` wildcards
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - glob_match() does not match wildcards as expected
+    #[test]
+    fn glob_match_wildcards() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*.cache", "thumbs.cache"));
+        assert!(glob_match("build-*", "build-"));
+        assert!(glob_match("a*b*c", "aXXbYYbZc"));
+        assert!(glob_match("?x?", "axb"));
+        assert!(!glob_match("?x?", "xb"));
+        assert!(!glob_match("*.cache", "thumbs.cache.old"));
+    }
+}
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+/// Windows file attribute marking an item as hidden
+#[cfg(windows)]
+const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+
+/// Hidden enum: how a walk treats hidden items, see is_hidden()
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Hidden {
+    /// hidden items are visited like any other
+    #[default]
+    Include,
+
+    /// hidden files are not visited and hidden directories are skipped along with their contents
+    Skip,
+
+    /// only hidden files and files inside hidden directories are visited, directories are still walked to find them
+    Only,
+}
+
+/// is_hidden() returns whether the item at `path` is hidden, that is its name starts with `.` or, on Windows, it has the
+/// hidden attribute set
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to an item
+///
+/// # Errors
+///
+/// None
+pub fn is_hidden(path: &Path) -> bool {
+    // paths ending in `..` have no file name and are never hidden
+    let dotfile = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+
+    #[cfg(windows)]
+    if !dotfile {
+        use std::os::windows::fs::MetadataExt;
+
+        // the attribute of a symbolic link itself decides, as shown by Explorer
+        return path
+            .symlink_metadata()
+            .is_ok_and(|metadata| metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0);
+    }
+
+    dotfile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies is_hidden() recognizes dotfiles and dot directories by name only
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - is_hidden() does not recognize a dotfile or treats a regular name as hidden
+    #[test]
+    fn is_hidden_dotfiles() {
+        assert!(is_hidden(Path::new("photos/.DS_Store")));
+        assert!(is_hidden(Path::new(".thumbnails")));
+        assert!(!is_hidden(Path::new(".thumbnails/IMG_1.jpg")));
+        assert!(!is_hidden(Path::new("photos/a.b")));
+        assert!(!is_hidden(Path::new("..")));
+    }
+}
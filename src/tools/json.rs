@@ -0,0 +1,586 @@
+use std::fmt;
+
+/// Json enum: a JSON value that is written compactly on a single line by its Display implementation, which suits
+/// line based output that other tools read one record at a time, and can be read back by Json::parse()
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    /// `null`
+    Null,
+
+    /// `true` or `false`
+    Bool(bool),
+
+    /// a number, non-finite values are written as `null` as JSON cannot represent them
+    Number(f64),
+
+    /// a string
+    String(String),
+
+    /// an array of values
+    Array(Vec<Json>),
+
+    /// an object whose keys are written in the given order
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// object() builds a Json::Object from key value pairs
+    ///
+    /// # Arguments
+    ///
+    /// `pairs` the keys and values of the object, in output order
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn object<const N: usize>(pairs: [(&str, Json); N]) -> Self {
+        Self::Object(
+            pairs
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    /// get() returns the value of `key` if self is an object containing it
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Json
+    /// `key` the key to look up
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Self::Object(pairs) => pairs
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// parse() reads a single JSON value from `text`, surrounding whitespace is allowed but nothing else may follow
+    ///
+    /// # Arguments
+    ///
+    /// `text` the JSON document
+    ///
+    /// # Errors
+    ///
+    /// - `text` is not a single valid JSON value
+    pub fn parse(text: &str) -> Result<Self, &'static str> {
+        let mut parser = Parser {
+            text: text.as_bytes(),
+            position: 0,
+        };
+        let value = parser.value(0)?;
+        parser.skip_whitespace();
+
+        if parser.position == parser.text.len() {
+            Ok(value)
+        } else {
+            Err("unexpected characters after JSON value")
+        }
+    }
+}
+
+/// deepest nesting of arrays and objects Json::parse() accepts, so malicious input cannot overflow the stack
+const MAX_DEPTH: usize = 128;
+
+/// Parser struct: recursive descent parser over the bytes of a JSON document
+struct Parser<'a> {
+    /// the document being parsed
+    text: &'a [u8],
+
+    /// index of the next byte to read
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// value() parses the value starting at the next non whitespace byte
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    /// `depth` number of arrays and objects the value is nested in
+    ///
+    /// # Errors
+    ///
+    /// - the input is not a valid JSON value or is nested too deeply
+    fn value(&mut self, depth: usize) -> Result<Json, &'static str> {
+        if depth > MAX_DEPTH {
+            return Err("JSON is nested too deeply");
+        }
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'"') => Ok(Json::String(self.string()?)),
+            Some(b'[') => {
+                self.position += 1;
+                let mut values: Vec<Json> = Vec::new();
+                if self.consume(b']') {
+                    return Ok(Json::Array(values));
+                }
+                loop {
+                    values.push(self.value(depth + 1)?);
+                    if self.consume(b']') {
+                        return Ok(Json::Array(values));
+                    }
+                    if !self.consume(b',') {
+                        return Err("expected ',' or ']' in JSON array");
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.position += 1;
+                let mut pairs: Vec<(String, Json)> = Vec::new();
+                if self.consume(b'}') {
+                    return Ok(Json::Object(pairs));
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() != Some(b'"') {
+                        return Err("expected a string key in JSON object");
+                    }
+                    let key = self.string()?;
+                    if !self.consume(b':') {
+                        return Err("expected ':' after key in JSON object");
+                    }
+                    pairs.push((key, self.value(depth + 1)?));
+                    if self.consume(b'}') {
+                        return Ok(Json::Object(pairs));
+                    }
+                    if !self.consume(b',') {
+                        return Err("expected ',' or '}' in JSON object");
+                    }
+                }
+            }
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err("unexpected character in JSON"),
+            None => Err("unexpected end of JSON"),
+        }
+    }
+
+    /// literal() parses `word`, one of `null`, `true`, or `false`, returning `value`
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    /// `word` the literal expected at the current position
+    /// `value` the Json the literal stands for
+    ///
+    /// # Errors
+    ///
+    /// - the input does not continue with `word`
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json, &'static str> {
+        if self.text[self.position..].starts_with(word.as_bytes()) {
+            self.position += word.len();
+            Ok(value)
+        } else {
+            Err("unexpected character in JSON")
+        }
+    }
+
+    /// number() parses a number, the digits are validated against the JSON grammar before being converted
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    ///
+    /// # Errors
+    ///
+    /// - the input is not a valid JSON number
+    fn number(&mut self) -> Result<Json, &'static str> {
+        let start = self.position;
+        let digits = |parser: &mut Self| {
+            let from = parser.position;
+            while parser.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+                parser.position += 1;
+            }
+            parser.position > from
+        };
+
+        self.consume_raw(b'-');
+        // leading zeros are not allowed
+        if !self.consume_raw(b'0') && !digits(self) {
+            return Err("invalid number in JSON");
+        }
+        if self.consume_raw(b'.') && !digits(self) {
+            return Err("invalid number in JSON");
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if !self.consume_raw(b'+') {
+                self.consume_raw(b'-');
+            }
+            if !digits(self) {
+                return Err("invalid number in JSON");
+            }
+        }
+
+        // the bytes are ascii digits and signs so they are valid utf-8
+        std::str::from_utf8(&self.text[start..self.position])
+            .ok()
+            .and_then(|number| number.parse::<f64>().ok())
+            .map(Json::Number)
+            .ok_or("invalid number in JSON")
+    }
+
+    /// string() parses a quoted string starting at the current position, decoding escapes
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    ///
+    /// # Errors
+    ///
+    /// - the string is unterminated, contains a raw control character, or has an invalid escape
+    fn string(&mut self) -> Result<String, &'static str> {
+        // skip the opening quote
+        self.position += 1;
+        let mut bytes: Vec<u8> = Vec::new();
+
+        loop {
+            let byte = self.next().ok_or("unterminated string in JSON")?;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = match self.next().ok_or("unterminated string in JSON")? {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return Err("invalid escape in JSON string"),
+                    };
+                    let mut buffer = [0u8; 4];
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut buffer).as_bytes());
+                }
+                byte if byte < 0x20 => return Err("control character in JSON string"),
+                byte => bytes.push(byte),
+            }
+        }
+
+        String::from_utf8(bytes).map_err(|_| "invalid utf-8 in JSON string")
+    }
+
+    /// unicode_escape() decodes the hex digits of a `\u` escape, combining surrogate pairs, the `\u` has already been
+    /// read
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    ///
+    /// # Errors
+    ///
+    /// - the escape is not four hex digits or is an unpaired surrogate
+    fn unicode_escape(&mut self) -> Result<char, &'static str> {
+        let high = self.hex4()?;
+        let code = match high {
+            0xD800..=0xDBFF => {
+                if !self.text[self.position..].starts_with(b"\\u") {
+                    return Err("unpaired surrogate in JSON string");
+                }
+                self.position += 2;
+                let low = self.hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err("unpaired surrogate in JSON string");
+                }
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            code => code,
+        };
+
+        char::from_u32(code).ok_or("unpaired surrogate in JSON string")
+    }
+
+    /// hex4() reads four hex digits
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    ///
+    /// # Errors
+    ///
+    /// - the next four bytes are not hex digits
+    fn hex4(&mut self) -> Result<u32, &'static str> {
+        let digits = self
+            .text
+            .get(self.position..self.position + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .filter(|digits| digits.bytes().all(|byte| byte.is_ascii_hexdigit()))
+            .ok_or("invalid unicode escape in JSON string")?;
+        self.position += 4;
+
+        // four hex digits always fit in a u32
+        Ok(u32::from_str_radix(digits, 16).unwrap_or_default())
+    }
+
+    /// skip_whitespace() advances past spaces, tabs, and line breaks
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    /// consume() skips whitespace then advances past `byte` if it is next, returning whether it was
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    /// `byte` the byte expected
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn consume(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        self.consume_raw(byte)
+    }
+
+    /// consume_raw() advances past `byte` if it is next, returning whether it was
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    /// `byte` the byte expected
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn consume_raw(&mut self, byte: u8) -> bool {
+        let matched = self.peek() == Some(byte);
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    /// peek() returns the next byte without advancing
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Parser
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.position).copied()
+    }
+
+    /// next() returns the next byte and advances past it
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Parser
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.position += 1;
+        Some(byte)
+    }
+}
+
+/// From trait implementations for the common value types
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<u64> for Json {
+    fn from(value: u64) -> Self {
+        Self::Number(value as f64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Self::Number(value as f64)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+/// Display trait implementation for Json, writes the value as compact JSON
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Number(value) if value.is_finite() => write!(f, "{value}"),
+            Self::Number(_) => write!(f, "null"),
+            Self::String(value) => write_string(f, value),
+            Self::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, "]")
+            }
+            Self::Object(pairs) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in pairs.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// write_string() writes `value` as a quoted JSON string, escaping quotes, backslashes, and control characters
+///
+/// # Arguments
+///
+/// `f` the Formatter to write to
+/// `value` the string to write
+///
+/// # Errors
+///
+/// - writing to `f` fails
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies Json values are written as compact JSON with strings escaped
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Json is not written as valid compact JSON
+    #[test]
+    fn json_display() {
+        // create inputs
+        let value = Json::object([
+            ("path", Json::from("dir/\"a\"\\b\n\u{1}")),
+            ("size", Json::from(10u64)),
+            (
+                "tags",
+                Json::Array(vec![Json::Bool(true), Json::Bool(false)]),
+            ),
+            ("ratio", Json::Number(0.5)),
+            ("bad", Json::Number(f64::NAN)),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            r#"{"path":"dir/\"a\"\\b\n\u0001","size":10,"tags":[true,false],"ratio":0.5,"bad":null}"#
+        );
+    }
+
+    /// verifies Json::parse() reads back displayed values, decodes escapes, and rejects invalid documents
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Json::parse() does not read a valid document
+    /// - Json::parse() accepts an invalid document
+    #[test]
+    fn json_parse() {
+        // create inputs
+        let value = Json::object([
+            ("path", Json::from("dir/\"a\"\\b\n\u{1}")),
+            ("size", Json::from(10u64)),
+            ("tags", Json::Array(vec![Json::Bool(true), Json::Null])),
+            ("ratio", Json::Number(-0.5e-3)),
+        ]);
+        let parsed = Json::parse(
+            r#" {"date": "2023-01-15", "n": [1, 2.5e2, {}], "s": "\u00e9\ud83d\ude00\/"} "#,
+        );
+
+        assert_eq!(Json::parse(&value.to_string()), Ok(value));
+        assert_eq!(
+            parsed.as_ref().unwrap().get("date"),
+            Some(&Json::from("2023-01-15"))
+        );
+        assert_eq!(
+            parsed.as_ref().unwrap().get("n"),
+            Some(&Json::Array(vec![
+                Json::Number(1.0),
+                Json::Number(250.0),
+                Json::Object(Vec::new())
+            ]))
+        );
+        assert_eq!(
+            parsed.unwrap().get("s"),
+            Some(&Json::from("\u{e9}\u{1F600}/"))
+        );
+        for text in [
+            "",
+            "{",
+            "[1,]",
+            "01",
+            "1.",
+            "\"\\x\"",
+            "\"\\ud800\"",
+            "{1: 2}",
+            "true false",
+            "nul",
+        ] {
+            assert!(Json::parse(text).is_err(), "{text}");
+        }
+        assert!(Json::parse(&"[".repeat(MAX_DEPTH + 2)).is_err());
+    }
+}
@@ -0,0 +1,444 @@
+use std::collections::HashSet;
+use std::fs::{read_to_string, File, OpenOptions};
+use std::io::{self, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// Logger struct: appends one timestamped, tab separated line per operation to an optional log file, paths are escaped
+/// with escape_field() so every operation stays on one line, when no log file is configured every call is a no-op
+#[derive(Debug, Default)]
+pub struct Logger {
+    /// log file opened in append mode, lines are flushed as soon as they are written so that a task failing partway
+    /// through still leaves a complete log behind
+    file: Option<LineWriter<File>>,
+
+    /// path of the log file, kept so a resumed run can read back the moves of the run it resumes
+    path: Option<PathBuf>,
+}
+
+impl Logger {
+    /// Logger struct initializer for a logger that discards every operation
+    ///
+    /// # Arguments
+    ///
+    /// None
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Logger struct initializer that appends to the file at `path`, creating it if it does not exist
+    ///
+    /// # Arguments
+    ///
+    /// `path` a Path that corresponds to the log file
+    ///
+    /// # Errors
+    ///
+    /// - the file at `path` cannot be created or opened for appending
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self {
+            file: Some(LineWriter::new(file)),
+            path: Some(path.to_path_buf()),
+        })
+    }
+
+    /// Logger struct initializer from command line arguments, removes `--log-file <path>` from `args` if present and
+    /// opens the log file it names, otherwise returns a disabled logger
+    ///
+    /// # Arguments
+    ///
+    /// `args` a mutable vector of Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - `--log-file` is not followed by a path
+    /// - the log file cannot be opened
+    pub fn from_args(args: &mut Vec<String>) -> Result<Self, &'static str> {
+        let index = match args.iter().position(|arg| arg == "--log-file") {
+            Some(index) => index,
+            None => return Ok(Self::disabled()),
+        };
+
+        // ensures a log file path is provided
+        if index + 1 >= args.len() {
+            return Err("'--log-file' must be followed by a path");
+        }
+
+        let path = args.remove(index + 1);
+        args.remove(index);
+
+        Self::open(Path::new(&path))
+            .map_err(|_| "'--log-file' path could not be opened for appending")
+    }
+
+    /// completed_moves() reads back the log file and returns the source of every logged move, link, or copy whose
+    /// destination still exists, those whose destination is missing did not complete and are left out so they are redone
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Logger
+    ///
+    /// # Errors
+    ///
+    /// - no log file is configured
+    /// - the log file cannot be read
+    pub fn completed_moves(&self) -> io::Result<HashSet<PathBuf>> {
+        let path = self.path.as_ref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "'--resume' requires '--log-file' naming the log of the run to resume",
+            )
+        })?;
+
+        let mut moved: HashSet<PathBuf> = HashSet::new();
+        for line in read_to_string(path)?.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if let [_, "move" | "link" | "copy", source, destination] = fields[..] {
+                // a field that cannot be turned back into a path on this platform is redone rather than guessed
+                if let (Some(source), Some(destination)) =
+                    (unescape_field(source), unescape_field(destination))
+                {
+                    if destination.exists() {
+                        moved.insert(source);
+                    }
+                }
+            }
+        }
+
+        Ok(moved)
+    }
+
+    /// log() records a single operation in the log file as `<timestamp>\t<action>\t<source>\t<destination>`, the paths
+    /// escaped with escape_field()
+    ///
+    /// # Arguments
+    ///
+    /// `action` short name of the operation, such as `move`, `skip`, or `fail`
+    /// `source` a Path that corresponds to the file operated on
+    /// `destination` an optional Path that corresponds to where the file was (or would have been) placed
+    ///
+    /// # Errors
+    ///
+    /// - writing to the log file fails
+    pub fn log(
+        &mut self,
+        action: &str,
+        source: &Path,
+        destination: Option<&Path>,
+    ) -> io::Result<()> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+
+        // timestamps are recorded in UTC so logs from different machines line up
+        let timestamp = OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .map_err(|err| io::Error::other(err.to_string()))?;
+
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}",
+            timestamp,
+            action,
+            escape_field(source),
+            destination.map_or(String::new(), escape_field)
+        )
+    }
+
+    /// sync() writes out any buffered line and, when `fsync` is set, waits until the log file is committed to disk so a
+    /// crash right after cannot lose the operations logged so far
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Logger
+    /// `fsync` whether to sync the log file to disk
+    ///
+    /// # Errors
+    ///
+    /// - flushing or syncing the log file fails
+    pub fn sync(&mut self, fsync: bool) -> io::Result<()> {
+        let Some(file) = &mut self.file else {
+            return Ok(());
+        };
+        file.flush()?;
+        if fsync {
+            file.get_ref().sync_data()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// escape_field() writes `path` as a log field, replacing `%`, tabs, line breaks, and bytes that are not valid unicode
+/// with `%` followed by two hexadecimal digits, so a name holding a tab or newline cannot split a log line and a name
+/// that is not valid unicode is read back exactly. `%` is used rather than `\` as Windows paths are full of the latter
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to the file logged
+///
+/// # Errors
+///
+/// None
+fn escape_field(path: &Path) -> String {
+    let mut field = String::new();
+    for chunk in path.as_os_str().as_encoded_bytes().utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '%' | '\t' | '\n' | '\r' => field.push_str(&format!("%{:02X}", c as u32)),
+                c => field.push(c),
+            }
+        }
+        for byte in chunk.invalid() {
+            field.push_str(&format!("%{byte:02X}"));
+        }
+    }
+
+    field
+}
+
+/// unescape_field() reads back a path written by escape_field(), a `%` not followed by two hexadecimal digits is kept as
+/// is. Returns None when the path is not valid unicode on a platform whose paths must be
+///
+/// # Arguments
+///
+/// `field` a source or destination field of a log line
+///
+/// # Errors
+///
+/// None
+fn unescape_field(field: &str) -> Option<PathBuf> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(field.len());
+    let mut rest = field.as_bytes();
+    while let Some((&byte, after)) = rest.split_first() {
+        let escaped = after
+            .get(..2)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(escaped) if byte == b'%' => {
+                bytes.push(escaped);
+                rest = &after[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = after;
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+
+        Some(PathBuf::from(std::ffi::OsString::from_vec(bytes)))
+    }
+    #[cfg(not(unix))]
+    {
+        String::from_utf8(bytes).ok().map(PathBuf::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{read_to_string, remove_file};
+
+    /// verifies Logger::log() appends one line per operation and keeps lines from previous loggers
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - Logger::log() does not write one tab separated line per operation
+    /// - Logger::open() truncates an existing log file
+    #[test]
+    fn logger_log_appends() {
+        // create inputs
+        let path = Path::new("./logger_log_appends_test.log");
+
+        // run test
+        let mut logger = Logger::open(path).unwrap();
+        logger
+            .log("move", Path::new("a.jpg"), Some(Path::new("b.jpg")))
+            .unwrap();
+        drop(logger);
+
+        let mut logger = Logger::open(path).unwrap();
+        logger.log("skip", Path::new("c.jpg"), None).unwrap();
+        drop(logger);
+
+        let test_result = read_to_string(path).unwrap();
+
+        // clean up temporary files
+        remove_file(path).unwrap();
+
+        let lines: Vec<Vec<&str>> = test_result
+            .lines()
+            .map(|line| line.split('\t').collect())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0][1..], ["move", "a.jpg", "b.jpg"]);
+        assert_eq!(lines[1][1..], ["skip", "c.jpg", ""]);
+    }
+
+    /// verifies Logger::completed_moves() returns the sources of logged moves and links whose destination exists
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - Logger::completed_moves() returns a move whose destination is missing, a skip, or misses a completed move or link
+    /// - Logger::completed_moves() does not error without a log file
+    #[test]
+    fn logger_completed_moves() {
+        // create inputs
+        let path = Path::new("./logger_completed_moves_test.log");
+        let destination = Path::new("./logger_completed_moves_test.txt");
+        File::create(destination).unwrap();
+
+        // run test
+        let mut logger = Logger::open(path).unwrap();
+        logger
+            .log("move", Path::new("a.jpg"), Some(destination))
+            .unwrap();
+        logger
+            .log("move", Path::new("b.jpg"), Some(Path::new("missing.jpg")))
+            .unwrap();
+        logger.log("skip", Path::new("c.jpg"), None).unwrap();
+        logger
+            .log("link", Path::new("d.jpg"), Some(destination))
+            .unwrap();
+        let test_result = logger.completed_moves();
+        drop(logger);
+
+        // clean up temporary files
+        remove_file(path).unwrap();
+        remove_file(destination).unwrap();
+
+        assert_eq!(
+            test_result.unwrap(),
+            HashSet::from([PathBuf::from("a.jpg"), PathBuf::from("d.jpg")])
+        );
+        assert!(Logger::disabled().completed_moves().is_err());
+    }
+
+    /// verifies Logger::log() keeps a path holding a tab, a newline, or a `%` on one line and Logger::completed_moves()
+    /// reads it back exactly, along with a Windows style path and a `%` written by an older version
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - Logger::log() splits an operation over several lines or fields
+    /// - Logger::completed_moves() does not return the exact source of a logged move
+    #[test]
+    fn logger_escapes_fields() {
+        // create inputs, the destination itself holds a newline so it is only found when read back exactly
+        let path = Path::new("./logger_escapes_fields_test.log");
+        let source = Path::new("./day\t1\nholiday 100%.jpg");
+        let destination = PathBuf::from(if cfg!(windows) {
+            "./logger_escapes_fields_test 50%.txt"
+        } else {
+            "./logger_escapes_fields_test\n50%.txt"
+        });
+        File::create(&destination).unwrap();
+
+        // run test
+        let mut logger = Logger::open(path).unwrap();
+        logger.log("move", source, Some(&destination)).unwrap();
+        let test_result = logger.completed_moves();
+        drop(logger);
+        let text = read_to_string(path).unwrap();
+
+        // clean up temporary files
+        remove_file(path).unwrap();
+        remove_file(&destination).unwrap();
+
+        assert_eq!(text.lines().count(), 1);
+        assert_eq!(text.trim_end().split('\t').count(), 4);
+        assert!(text.contains("day%091%0Aholiday 100%25.jpg"));
+        assert_eq!(test_result.unwrap(), HashSet::from([source.to_path_buf()]));
+        assert_eq!(
+            unescape_field(r"C:\Photos\100%.jpg"),
+            Some(PathBuf::from(r"C:\Photos\100%.jpg"))
+        );
+        assert_eq!(
+            escape_field(Path::new(r"C:\Photos\a.jpg")),
+            r"C:\Photos\a.jpg"
+        );
+    }
+
+    /// verifies escape_field() and unescape_field() round trip a file name that is not valid unicode
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - unescape_field() does not return the exact bytes of the name
+    #[cfg(unix)]
+    #[test]
+    fn logger_escapes_non_unicode() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"bad\xff.jpg"));
+
+        assert_eq!(escape_field(path), "bad%FF.jpg");
+        assert_eq!(
+            unescape_field(&escape_field(path)),
+            Some(path.to_path_buf())
+        );
+    }
+
+    /// verifies Logger::from_args() removes `--log-file <path>` from the arguments
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - Logger::from_args() leaves the option in `args` or removes unrelated arguments
+    /// - Logger::from_args() does not error when no path is provided
+    #[test]
+    fn logger_from_args() {
+        // create inputs
+        let path = "./logger_from_args_test.log";
+        let mut args = vec![
+            String::from("foo"),
+            String::from("organize"),
+            String::from("--log-file"),
+            String::from(path),
+            String::from("./src"),
+        ];
+
+        // run test
+        let logger = Logger::from_args(&mut args).unwrap();
+        let enabled = logger.file.is_some();
+
+        // clean up temporary files
+        drop(logger);
+        remove_file(path).unwrap();
+
+        assert!(enabled);
+        assert_eq!(args, ["foo", "organize", "./src"]);
+        assert!(Logger::from_args(&mut vec![String::from("--log-file")]).is_err());
+        assert!(Logger::from_args(&mut vec![String::from("foo")])
+            .unwrap()
+            .file
+            .is_none());
+    }
+}
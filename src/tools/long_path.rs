@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+use std::path::Path;
+
+/// length from which a path risks exceeding MAX_PATH on Windows, which is 260 characters but 248 for directories
+/// since room must be left for an 8.3 file name inside them
+#[cfg(windows)]
+const LIMIT: usize = 248;
+
+/// long_path() returns the path to hand to the operating system for `path`. On Windows a path that risks exceeding
+/// MAX_PATH is made absolute, which also resolves `.`, `..`, and `/` separators, and given the `\\?\` prefix (or
+/// `\\?\UNC\` for network shares) so deeply nested targets can still be created and renamed into. Only file system
+/// calls receive the result, the path shown to users and recorded in reports is always the original. Other paths, and
+/// every path on other platforms, are returned as they are
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to an item about to be operated on
+///
+/// # Errors
+///
+/// None
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    #[cfg(windows)]
+    {
+        use std::path::PathBuf;
+
+        if path.as_os_str().len() >= LIMIT {
+            let absolute = match (path.to_str(), std::path::absolute(path)) {
+                (Some(text), _) if text.starts_with(r"\\?\") => return Cow::Borrowed(path),
+                (_, Ok(absolute)) => absolute,
+                (_, Err(_)) => return Cow::Borrowed(path),
+            };
+            if let Some(text) = absolute.to_str() {
+                return Cow::Owned(PathBuf::from(match text.strip_prefix(r"\\") {
+                    Some(share) => format!(r"\\?\UNC\{share}"),
+                    None => format!(r"\\?\{text}"),
+                }));
+            }
+        }
+    }
+
+    Cow::Borrowed(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies long_path() leaves short paths untouched on every platform
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - long_path() changes a path far below MAX_PATH
+    #[test]
+    fn long_path_short() {
+        let path = Path::new("./target/2023/2023-01/2023-01_0.jpg");
+
+        assert!(matches!(long_path(path), Cow::Borrowed(borrowed) if borrowed == path));
+    }
+
+    /// verifies a target nested more than MAX_PATH characters deep can be created and moved into on Windows, and that
+    /// the prefixed path is only handed to the file system
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - long_path() does not prefix a path exceeding MAX_PATH
+    /// - FileSystem::create_dir_all() or FileSystem::rename() fail on the long path
+    #[cfg(windows)]
+    #[test]
+    fn long_path_windows() {
+        use crate::tools::file_system::{FileSystem, RealFileSystem};
+        use std::fs::{create_dir_all, remove_dir_all, write};
+        use std::path::PathBuf;
+
+        // create inputs, nine folders of forty characters each exceed MAX_PATH
+        let path_buf = PathBuf::from("./long_path_windows_test");
+        let source = path_buf.join("a.txt");
+        let mut dir = path_buf.clone();
+        for index in 0..9 {
+            dir.push(format!("{index}{}", "x".repeat(39)));
+        }
+        let target = dir.join("2023-01_0.txt");
+
+        // create mock directory
+        create_dir_all(&path_buf).unwrap();
+        write(&source, "contents").unwrap();
+
+        // run test
+        let prefixed = long_path(&target).into_owned();
+        let created = RealFileSystem.create_dir_all(&dir);
+        let renamed = RealFileSystem.rename(&source, &target);
+        let moved = RealFileSystem.metadata(&target).is_ok();
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert!(target.as_os_str().len() > 260);
+        assert!(prefixed.to_string_lossy().starts_with(r"\\?\"));
+        assert!(!target.to_string_lossy().starts_with(r"\\?\"));
+        assert!(created.is_ok());
+        assert!(renamed.is_ok());
+        assert!(moved);
+    }
+}
@@ -1,3 +1,51 @@
 // expose tools
+pub mod args;
+#[cfg(feature = "blake3")]
+pub mod blake3;
+pub mod cancel;
+pub mod checksums;
 pub mod collect_files;
+pub mod compound_ext;
+pub mod confirm;
+pub mod copy_permissions;
+pub mod count_named_files;
+pub mod crc32;
+pub mod dates;
+pub mod deflate;
+pub mod digest;
+pub mod dir_config;
+pub mod ensured_dirs;
+pub mod error;
+pub mod exif;
+pub mod file_in_use;
+pub mod file_system;
+pub mod file_walker;
+pub mod filter;
+pub mod free_space;
 pub mod get_num_files;
+pub mod glob;
+pub mod hidden;
+pub mod json;
+pub mod logger;
+pub mod long_path;
+pub mod modes;
+pub mod move_file;
+pub mod name_date;
+#[cfg(unix)]
+pub mod owner;
+pub mod parse_size;
+pub mod path_list;
+pub mod paths;
+pub mod progress;
+pub mod quarantine;
+pub mod regex;
+pub mod retry;
+pub mod sanitize_filename;
+pub mod sha256;
+pub mod shard;
+pub mod sidecar;
+pub mod symlinks;
+pub mod threads;
+pub mod throttle;
+pub mod timezone;
+pub mod unique_path;
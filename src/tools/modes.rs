@@ -0,0 +1,76 @@
+use std::io;
+use std::path::Path;
+
+/// parse_mode() parses an octal permission mode such as `775` or `0664`, as given to `chmod`
+///
+/// # Arguments
+///
+/// `value` the value following the option
+///
+/// # Errors
+///
+/// None, returns None when `value` is missing, is not octal, or sets bits beyond the permission, setuid, setgid, and
+/// sticky bits
+pub fn parse_mode(value: Option<String>) -> Option<u32> {
+    u32::from_str_radix(&value?, 8)
+        .ok()
+        .filter(|mode| *mode <= 0o7777)
+}
+
+/// set_mode() sets the Unix permission bits of the item at `path` to `mode`, replacing those it was created with under
+/// the umask of the process
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to a file or directory
+/// `mode` the permission bits to apply
+///
+/// # Errors
+///
+/// - set_permissions() call fails
+/// - the platform has no Unix permission bits
+pub fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::fs::{set_permissions, Permissions};
+        use std::os::unix::fs::PermissionsExt;
+
+        set_permissions(path, Permissions::from_mode(mode))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (path, mode);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "permission modes are only supported on Unix",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies parse_mode() reads octal modes with or without a leading zero and rejects anything else
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_mode() misreads an octal mode or accepts a decimal digit, a missing value, or too many bits
+    #[test]
+    fn parse_mode_octal() {
+        let parse = |value: &str| parse_mode(Some(String::from(value)));
+
+        assert_eq!(parse("775"), Some(0o775));
+        assert_eq!(parse("0664"), Some(0o664));
+        assert_eq!(parse("2775"), Some(0o2775));
+        assert_eq!(parse("778"), None);
+        assert_eq!(parse("17777"), None);
+        assert_eq!(parse("rwx"), None);
+        assert_eq!(parse_mode(None), None);
+    }
+}
@@ -0,0 +1,605 @@
+use std::fs::{File, FileTimes, Metadata};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+// declare local code
+use super::copy_permissions::copy_permissions;
+use super::file_system::FileSystem;
+use super::sha256::hash_file;
+use super::throttle::Throttle;
+
+/// size of the buffer used by copy_buffered() when streaming file contents
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// move_file() moves a file from `source` to `target`, falling back to a buffered copy followed by removal of `source`
+/// whenever a rename is not possible because the two paths live on different file systems, an existing `target` is
+/// never replaced
+///
+/// # Arguments
+///
+/// `fs` the FileSystem the file is moved on
+/// `source` a Path that corresponds to the file to be moved
+/// `target` a Path that corresponds to the desired location of the file
+/// `throttle` a Throttle limiting the rate of the copy fallback, renames are not limited as they move no data
+/// `preserve_perms` whether the copy fallback restores the permissions and ownership of `source`, renames always keep
+/// them
+///
+/// # Errors
+///
+/// - refuse_existing() call fails
+/// - FileSystem::rename() call fails for any reason other than `source` and `target` being on different file systems
+/// - FileSystem::copy() call fails
+/// - FileSystem::remove_file() call fails
+pub fn move_file(
+    fs: &dyn FileSystem,
+    source: &Path,
+    target: &Path,
+    throttle: &mut Throttle,
+    preserve_perms: bool,
+) -> io::Result<()> {
+    // a rename silently replaces whatever is at `target`, so it is checked first
+    refuse_existing(target)?;
+
+    match fs.rename(source, target) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+            // rename cannot cross file systems so copy the contents over and remove the original
+            fs.copy(source, target, throttle, preserve_perms)?;
+            fs.remove_file(source)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// link_file() places a hard link to `source` at `target`, leaving `source` in place, and falls back to a buffered copy
+/// whenever the two paths live on different file systems, where a link is not possible. Returns whether `target` was
+/// linked rather than copied
+///
+/// # Arguments
+///
+/// `fs` the FileSystem the file is linked on
+/// `source` a Path that corresponds to the file to be linked
+/// `target` a Path that corresponds to the location of the link
+/// `throttle` a Throttle limiting the rate of the copy fallback, links are not limited as they write no data
+/// `preserve_perms` whether the copy fallback gets the permissions and ownership of `source`, links always share them
+///
+/// # Errors
+///
+/// - FileSystem::hard_link() call fails for any reason other than `source` and `target` being on different file
+///   systems
+/// - FileSystem::copy() call fails
+pub fn link_file(
+    fs: &dyn FileSystem,
+    source: &Path,
+    target: &Path,
+    throttle: &mut Throttle,
+    preserve_perms: bool,
+) -> io::Result<bool> {
+    match fs.hard_link(source, target) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+            fs.copy(source, target, throttle, preserve_perms)?;
+            Ok(false)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// safe_move_file() moves a file from `source` to `target` by always copying it, even within one file system, and
+/// removing `source` only once the SHA-256 digest of the copy matches that of the original, so a file is never lost
+/// to a copy that went wrong unnoticed. When the digests differ both files are left in place, an existing `target` is
+/// never replaced
+///
+/// # Arguments
+///
+/// `fs` the FileSystem the file is moved on
+/// `source` a Path that corresponds to the file to be moved
+/// `target` a Path that corresponds to the desired location of the file
+/// `throttle` a Throttle limiting the rate of the copy
+/// `preserve_perms` whether the copy gets the permissions and ownership of `source`
+///
+/// # Errors
+///
+/// - refuse_existing() call fails
+/// - FileSystem::copy() call fails
+/// - hash_file() call fails for `source` or `target`
+/// - the digest of `target` differs from that of `source`, an InvalidData error
+/// - FileSystem::remove_file() call fails
+pub fn safe_move_file(
+    fs: &dyn FileSystem,
+    source: &Path,
+    target: &Path,
+    throttle: &mut Throttle,
+    preserve_perms: bool,
+) -> io::Result<()> {
+    refuse_existing(target)?;
+
+    fs.copy(source, target, throttle, preserve_perms)?;
+    if hash_file(target)? != hash_file(source)? {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "copy at {} does not match the original, both were kept",
+                target.display()
+            ),
+        ));
+    }
+
+    fs.remove_file(source)
+}
+
+/// refuse_existing() fails when anything, including a broken symbolic link, already exists at `target`, so a move never
+/// replaces a file
+///
+/// # Arguments
+///
+/// `target` a Path that corresponds to the desired location of a file
+///
+/// # Errors
+///
+/// - `target` already exists, an AlreadyExists error
+fn refuse_existing(target: &Path) -> io::Result<()> {
+    if target.symlink_metadata().is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists and was not replaced", target.display()),
+        ));
+    }
+
+    Ok(())
+}
+
+/// copy_buffered() streams the contents of `source` into `target` and restores the timestamps of `source` onto `target`
+/// so that files copied across file systems keep the dates they are organized by, optionally along with its permissions
+/// and ownership, returns the number of bytes copied, `target` must not exist yet so no file is truncated
+///
+/// # Arguments
+///
+/// `source` a Path that corresponds to the file to be copied
+/// `target` a Path that corresponds to the location of the copy
+/// `throttle` a Throttle limiting the rate data is written at
+/// `preserve_perms` whether the permissions and ownership of `source` are restored onto `target`
+///
+/// # Errors
+///
+/// - `source` cannot be opened or read
+/// - `target` already exists, cannot be created, or cannot be written to
+/// - restore_times() call fails
+/// - copy_permissions() call fails
+pub fn copy_buffered(
+    source: &Path,
+    target: &Path,
+    throttle: &mut Throttle,
+    preserve_perms: bool,
+) -> io::Result<u64> {
+    // metadata is read before copying so that the access time is not altered by the copy itself
+    let metadata = source.metadata()?;
+
+    let mut reader = BufReader::with_capacity(BUFFER_SIZE, File::open(source)?);
+    let mut writer = BufWriter::with_capacity(BUFFER_SIZE, File::create_new(target)?);
+
+    // owned buffer and byte counter used while streaming the file
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut copied: u64 = 0;
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+
+        writer.write_all(&buffer[..read])?;
+        copied += read as u64;
+        throttle.consume(read as u64);
+    }
+
+    // ensure all data reaches the file before timestamps are set, otherwise later writes would update them
+    let file = writer.into_inner().map_err(|err| err.into_error())?;
+    restore_times(&metadata, &file)?;
+
+    // permissions are restored last so a read only source cannot leave the copy unwritable before its times are set
+    if preserve_perms {
+        copy_permissions(&metadata, &file)?;
+    }
+
+    Ok(copied)
+}
+
+/// restore_times() applies the modified, accessed, and where supported created timestamps found in `metadata` onto `file`
+///
+/// # Arguments
+///
+/// `metadata` Metadata of the file whose timestamps should be restored
+/// `file` an open, writable File to apply the timestamps to
+///
+/// # Errors
+///
+/// - modification or access date is not retrievable from `metadata`
+/// - File::set_times() call fails
+fn restore_times(metadata: &Metadata, file: &File) -> io::Result<()> {
+    let times = FileTimes::new()
+        .set_modified(metadata.modified()?)
+        .set_accessed(metadata.accessed()?);
+
+    // creation dates can only be set on platforms that expose them
+    #[cfg(windows)]
+    let times = match metadata.created() {
+        Ok(created) => std::os::windows::fs::FileTimesExt::set_created(times, created),
+        Err(_) => times,
+    };
+    #[cfg(target_os = "macos")]
+    let times = match metadata.created() {
+        Ok(created) => std::os::macos::fs::FileTimesExt::set_created(times, created),
+        Err(_) => times,
+    };
+
+    file.set_times(times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::file_system::{MockFileSystem, RealFileSystem};
+    use std::fs::{create_dir, read, remove_dir_all, write};
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant, SystemTime};
+
+    /// verifies move_file() moves a file to its target and removes the source
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - move_file() does not place the file at `target`
+    /// - move_file() leaves the file at `source`
+    #[test]
+    fn move_file_same_fs() {
+        // create inputs
+        let path_buf = PathBuf::from("./move_file_same_fs_test/");
+        let source = path_buf.join("source.txt");
+        let target = path_buf.join("target.txt");
+
+        // create mock directory
+        create_dir(&path_buf).unwrap();
+        write(&source, b"move_file").unwrap();
+
+        // run test
+        move_file(
+            &RealFileSystem,
+            &source,
+            &target,
+            &mut Throttle::default(),
+            false,
+        )
+        .unwrap();
+        let moved = (source.exists(), read(&target).unwrap());
+
+        // clean up mock directory
+        remove_dir_all(path_buf).unwrap();
+
+        assert_eq!(moved, (false, b"move_file".to_vec()));
+    }
+
+    /// verifies move_file() and safe_move_file() refuse to replace an existing target, leaving both files untouched
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - move_file() or safe_move_file() replaces or truncates the file at `target`
+    /// - move_file() or safe_move_file() removes the file at `source`
+    #[test]
+    fn move_file_refuses_existing_target() {
+        // create inputs
+        let path_buf = PathBuf::from("./move_file_refuses_existing_target_test/");
+        let source = path_buf.join("source.txt");
+        let target = path_buf.join("target.txt");
+
+        // create mock directory
+        create_dir(&path_buf).unwrap();
+        write(&source, b"source").unwrap();
+        write(&target, b"target").unwrap();
+
+        // run test
+        let moved = move_file(
+            &RealFileSystem,
+            &source,
+            &target,
+            &mut Throttle::default(),
+            false,
+        );
+        let safe_moved = safe_move_file(
+            &RealFileSystem,
+            &source,
+            &target,
+            &mut Throttle::default(),
+            false,
+        );
+        let kept = (read(&source).unwrap(), read(&target).unwrap());
+
+        // clean up mock directory
+        remove_dir_all(path_buf).unwrap();
+
+        assert_eq!(moved.unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(safe_moved.unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(kept, (b"source".to_vec(), b"target".to_vec()));
+    }
+
+    /// verifies link_file() links a file into place leaving the source where it is, and copies it when the paths live
+    /// on different file systems
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - link_file() removes the file at `source` or does not report whether it linked or copied
+    /// - link_file() does not place the contents of `source` at `target`
+    #[test]
+    fn link_file_or_copy() {
+        // create inputs
+        let path_buf = PathBuf::from("./link_file_or_copy_test/");
+        let source = path_buf.join("source.txt");
+        let cross_device = MockFileSystem {
+            rename_error: Some(io::ErrorKind::CrossesDevices),
+            ..MockFileSystem::default()
+        };
+
+        // create mock directory
+        create_dir(&path_buf).unwrap();
+        write(&source, b"link_file").unwrap();
+
+        // run test
+        let link = |fs: &dyn FileSystem, name: &str| {
+            let target = path_buf.join(name);
+            let linked = link_file(fs, &source, &target, &mut Throttle::default(), false);
+            (linked.unwrap(), read(&target).unwrap())
+        };
+        let linked = link(&RealFileSystem, "linked.txt");
+        let copied = link(&cross_device, "copied.txt");
+        let kept = source.exists();
+
+        // clean up mock directory
+        remove_dir_all(path_buf).unwrap();
+
+        assert_eq!(linked, (true, b"link_file".to_vec()));
+        assert_eq!(copied, (false, b"link_file".to_vec()));
+        assert!(kept);
+    }
+
+    /// verifies move_file() falls back to copying and removing the source when a rename crosses file systems, and
+    /// passes every other rename error on without copying
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - move_file() does not copy the file to `target` or leaves the file at `source` after a cross device rename
+    /// - move_file() copies the file after a rename fails for another reason
+    #[test]
+    fn move_file_cross_device_fallback() {
+        // create inputs
+        let path_buf = PathBuf::from("./move_file_cross_device_fallback_test/");
+        let source = path_buf.join("source.txt");
+        let target = path_buf.join("target.txt");
+        let cross_device = MockFileSystem {
+            rename_error: Some(io::ErrorKind::CrossesDevices),
+            ..MockFileSystem::default()
+        };
+        let denied = MockFileSystem {
+            rename_error: Some(io::ErrorKind::PermissionDenied),
+            ..MockFileSystem::default()
+        };
+
+        // create mock directory
+        create_dir(&path_buf).unwrap();
+        write(&source, b"move_file").unwrap();
+
+        // run test
+        let copied = move_file(
+            &cross_device,
+            &source,
+            &target,
+            &mut Throttle::default(),
+            false,
+        );
+        let moved = (source.exists(), read(&target).unwrap());
+        write(&source, b"move_file").unwrap();
+        let failed = move_file(
+            &denied,
+            &source,
+            &path_buf.join("other.txt"),
+            &mut Throttle::default(),
+            false,
+        );
+        let kept = (source.exists(), path_buf.join("other.txt").exists());
+
+        // clean up mock directory
+        remove_dir_all(path_buf).unwrap();
+
+        assert!(copied.is_ok());
+        assert_eq!(moved, (false, b"move_file".to_vec()));
+        assert_eq!(failed.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(kept, (true, false));
+    }
+
+    /// verifies safe_move_file() copies rather than renames, removes the source only once the copy matches it, and
+    /// keeps both files when it does not
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - safe_move_file() renames the file or leaves it at `source` after a verified copy
+    /// - safe_move_file() removes `source` or the copy after a copy that does not match
+    #[test]
+    fn safe_move_file_verified() {
+        // create inputs, renames fail so only a copy can succeed
+        let path_buf = PathBuf::from("./safe_move_file_verified_test/");
+        let source = path_buf.join("source.txt");
+        let target = path_buf.join("target.txt");
+        let no_rename = MockFileSystem {
+            rename_error: Some(io::ErrorKind::PermissionDenied),
+            ..MockFileSystem::default()
+        };
+        let corrupting = MockFileSystem {
+            corrupt_copies: true,
+            ..no_rename.clone()
+        };
+
+        // create mock directory
+        create_dir(&path_buf).unwrap();
+        write(&source, b"irreplaceable").unwrap();
+
+        // run test
+        let verified = safe_move_file(
+            &no_rename,
+            &source,
+            &target,
+            &mut Throttle::default(),
+            false,
+        );
+        let moved = (source.exists(), read(&target).unwrap());
+        write(&source, b"irreplaceable").unwrap();
+        let mismatched = safe_move_file(
+            &corrupting,
+            &source,
+            &path_buf.join("other.txt"),
+            &mut Throttle::default(),
+            false,
+        );
+        let kept = (read(&source).unwrap(), path_buf.join("other.txt").exists());
+
+        // clean up mock directory
+        remove_dir_all(path_buf).unwrap();
+
+        assert!(verified.is_ok());
+        assert_eq!(moved, (false, b"irreplaceable".to_vec()));
+        assert_eq!(mismatched.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(kept, (b"irreplaceable".to_vec(), true));
+    }
+
+    /// verifies copy_buffered() copies the full contents of a file
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - copy_buffered() does not copy every byte of a file larger than its buffer
+    #[test]
+    fn copy_buffered_contents() {
+        // create inputs
+        let path_buf = PathBuf::from("./copy_buffered_contents_test/");
+        let source = path_buf.join("source.bin");
+        let target = path_buf.join("target.bin");
+        let contents: Vec<u8> = (0..BUFFER_SIZE * 3 + 7).map(|i| (i % 251) as u8).collect();
+
+        // create mock directory
+        create_dir(&path_buf).unwrap();
+        write(&source, &contents).unwrap();
+
+        // run test
+        let copied = copy_buffered(&source, &target, &mut Throttle::default(), false).unwrap();
+        let test_result = read(&target).unwrap();
+
+        // clean up mock directory
+        remove_dir_all(path_buf).unwrap();
+
+        assert_eq!(copied, contents.len() as u64);
+        assert_eq!(test_result, contents);
+    }
+
+    /// verifies copy_buffered() restores the modification date of the source onto the copy
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - modification date of the copy differs from the source by more than a second
+    #[test]
+    fn copy_buffered_preserves_mtime() {
+        // create inputs
+        let path_buf = PathBuf::from("./copy_buffered_preserves_mtime_test/");
+        let source = path_buf.join("source.txt");
+        let target = path_buf.join("target.txt");
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+
+        // create mock directory with a source file dated well in the past
+        create_dir(&path_buf).unwrap();
+        write(&source, b"copy_buffered").unwrap();
+        File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        // run test
+        copy_buffered(&source, &target, &mut Throttle::default(), false).unwrap();
+        let source_mtime = source.metadata().unwrap().modified().unwrap();
+        let target_mtime = target.metadata().unwrap().modified().unwrap();
+
+        // clean up mock directory
+        remove_dir_all(path_buf).unwrap();
+
+        let difference = match target_mtime.duration_since(source_mtime) {
+            Ok(difference) => difference,
+            Err(err) => err.duration(),
+        };
+
+        assert!(difference < Duration::from_secs(1));
+    }
+
+    /// verifies copy_buffered() under a throttle takes at least as long as the rate limit allows
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - copy_buffered() copies faster than the throttle permits
+    #[test]
+    fn copy_buffered_throttled() {
+        // create inputs, 256 KiB at 1 MiB per second takes at least a quarter of a second
+        let path_buf = PathBuf::from("./copy_buffered_throttled_test/");
+        let source = path_buf.join("source.bin");
+        let target = path_buf.join("target.bin");
+
+        // create mock directory
+        create_dir(&path_buf).unwrap();
+        write(&source, vec![7u8; 256 * 1024]).unwrap();
+
+        // run test
+        let started = Instant::now();
+        let copied = copy_buffered(
+            &source,
+            &target,
+            &mut Throttle::new(Some(1024 * 1024)),
+            false,
+        )
+        .unwrap();
+        let elapsed = started.elapsed();
+
+        // clean up mock directory
+        remove_dir_all(path_buf).unwrap();
+
+        assert_eq!(copied, 256 * 1024);
+        assert!(elapsed >= Duration::from_millis(250));
+    }
+}
@@ -0,0 +1,197 @@
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+use time::{Date, Month, OffsetDateTime, Time};
+
+// declare local code
+use super::regex::Regex;
+
+/// patterns tried in order when no pattern is given, each capturing the year, month, and day followed by the hour,
+/// minute, and second when the name carries them, the most specific first so a time is not dropped
+const BUILT_IN_PATTERNS: &[&str] = &[
+    // `2021-07-15 12.34.56`, `2021-07-15_12-34-56`, `Screenshot 2021-07-15 at 12.34.56`
+    r"(\d{4})-(\d{2})-(\d{2})[ _T.-](?:at )?(\d{2})[.:-](\d{2})[.:-](\d{2})",
+    // `IMG_20210715_123456`, `PXL_20210715_123456789`, `VID-20210715-123456`
+    r"(\d{4})(\d{2})(\d{2})[_-](\d{2})(\d{2})(\d{2})",
+    // `2021-07-15_scan`, `2021_07_15`, `2021.07.15`
+    r"(\d{4})[-_.](\d{2})[-_.](\d{2})",
+    // `IMG-20210715-WA0001`, `DSC20210715`, never part of a longer number
+    r"(?:^|\D)(\d{4})(\d{2})(\d{2})(?:\D|$)",
+];
+
+/// years a built-in pattern accepts, so a number that only looks like a date, such as a frame counter, is not taken
+/// for one
+const BUILT_IN_YEARS: std::ops::RangeInclusive<i32> = 1900..=2099;
+
+/// built-in patterns, compiled the first time they are needed
+static BUILT_IN: OnceLock<Vec<Regex>> = OnceLock::new();
+
+/// parse_name_pattern() compiles the pattern following `--date name:`, whose first three capture groups hold the year,
+/// month, and day and optional next three the hour, minute, and second, such as `^scan_(\d{4})(\d{2})(\d{2})`
+///
+/// # Arguments
+///
+/// `pattern` the regular expression
+///
+/// # Errors
+///
+/// - Regex::new() call fails
+/// - `pattern` has fewer than three capture groups
+pub fn parse_name_pattern(pattern: &str) -> Result<Regex, &'static str> {
+    let regex = Regex::new(pattern)?;
+    if regex.groups() < 3 {
+        return Err("'--date name:PATTERN' requires capture groups for the year, month, and day");
+    }
+
+    Ok(regex)
+}
+
+/// name_date() returns the date embedded in the file name of `path`, read with `pattern` or else with the first of the
+/// built-in patterns that gives a valid date. Dates are taken as UTC and a name without a time is dated at midnight.
+/// Returns None when the name holds no valid date
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to the file
+/// `pattern` the pattern given with `--date name:PATTERN`, None for the built-in patterns
+///
+/// # Errors
+///
+/// - Regex::captures() call fails
+pub fn name_date(path: &Path, pattern: Option<&Regex>) -> io::Result<Option<OffsetDateTime>> {
+    let Some(name) = path.file_name().map(|name| name.to_string_lossy()) else {
+        return Ok(None);
+    };
+
+    match pattern {
+        Some(pattern) => Ok(pattern
+            .captures(&name)?
+            .and_then(|captures| date_from_captures(&captures))),
+        None => {
+            let patterns = BUILT_IN.get_or_init(|| {
+                BUILT_IN_PATTERNS
+                    .iter()
+                    .map(|pattern| Regex::new(pattern).expect("built-in pattern is valid"))
+                    .collect()
+            });
+            for pattern in patterns {
+                let date = pattern
+                    .captures(&name)?
+                    .and_then(|captures| date_from_captures(&captures));
+                if let Some(date) = date.filter(|date| BUILT_IN_YEARS.contains(&date.year())) {
+                    return Ok(Some(date));
+                }
+            }
+
+            Ok(None)
+        }
+    }
+}
+
+/// date_from_captures() builds the date held by the capture groups of a match, year, month, day, and the optional
+/// hour, minute, and second, the whole match being the first item
+///
+/// # Arguments
+///
+/// `captures` the result of Regex::captures()
+///
+/// # Errors
+///
+/// None
+fn date_from_captures(captures: &[Option<String>]) -> Option<OffsetDateTime> {
+    let number = |index: usize| -> Option<u32> { captures.get(index)?.as_ref()?.parse().ok() };
+
+    let date = Date::from_calendar_date(
+        i32::try_from(number(1)?).ok()?,
+        Month::try_from(u8::try_from(number(2)?).ok()?).ok()?,
+        u8::try_from(number(3)?).ok()?,
+    )
+    .ok()?;
+
+    // a time is only used when all of it was captured
+    let time = match (number(4), number(5), number(6)) {
+        (Some(hour), Some(minute), Some(second)) => Time::from_hms(
+            u8::try_from(hour).ok()?,
+            u8::try_from(minute).ok()?,
+            u8::try_from(second).ok()?,
+        )
+        .ok()?,
+        _ => Time::MIDNIGHT,
+    };
+
+    Some(date.with_time(time).assume_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    /// verifies name_date() reads the dates of common camera, phone, scanner, and screenshot names with the built-in
+    /// patterns and with a given pattern, and returns None for names without a valid date
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - name_date() does not read the date and time embedded in a real-world file name
+    /// - name_date() reads a date from a name holding an impossible date or only a long number
+    /// - parse_name_pattern() accepts a pattern without enough capture groups
+    #[test]
+    fn name_date_real_world_names() {
+        for (name, expected) in [
+            ("2021-07-15_scan.pdf", datetime!(2021-07-15 00:00 UTC)),
+            (
+                "IMG_20210715_123456.jpg",
+                datetime!(2021-07-15 12:34:56 UTC),
+            ),
+            (
+                "PXL_20210715_123456789.jpg",
+                datetime!(2021-07-15 12:34:56 UTC),
+            ),
+            (
+                "VID-20210715-083000.mp4",
+                datetime!(2021-07-15 08:30:00 UTC),
+            ),
+            ("IMG-20210715-WA0001.jpg", datetime!(2021-07-15 00:00 UTC)),
+            (
+                "Screenshot 2021-07-15 at 12.34.56.png",
+                datetime!(2021-07-15 12:34:56 UTC),
+            ),
+            (
+                "2021-07-15 12.34.56.jpg",
+                datetime!(2021-07-15 12:34:56 UTC),
+            ),
+            ("invoice_2021_07_15.pdf", datetime!(2021-07-15 00:00 UTC)),
+        ] {
+            assert_eq!(
+                name_date(Path::new(name), None).unwrap(),
+                Some(expected),
+                "{name}"
+            );
+        }
+
+        for name in [
+            "holiday.jpg",
+            "2021-13-40_scan.pdf",
+            "IMG_1234567890123.jpg",
+            "DSC_0001.jpg",
+        ] {
+            assert_eq!(name_date(Path::new(name), None).unwrap(), None, "{name}");
+        }
+
+        let pattern = parse_name_pattern(r"^scan(\d{4})\.(\d{2})\.(\d{2})").unwrap();
+        assert_eq!(
+            name_date(Path::new("dir/scan2021.07.15.pdf"), Some(&pattern)).unwrap(),
+            Some(datetime!(2021-07-15 00:00 UTC))
+        );
+        assert_eq!(
+            name_date(Path::new("dir/2021-07-15_scan.pdf"), Some(&pattern)).unwrap(),
+            None
+        );
+        assert!(parse_name_pattern(r"(\d{4})-(\d{2})").is_err());
+        assert!(parse_name_pattern(r"(\d{4}").is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::sync::OnceLock;
+
+/// user database read by owner_name(), only local accounts listed there can be resolved
+const PASSWD: &str = "/etc/passwd";
+
+/// owner_name() returns the name of the user with id `uid`, or the id itself when no such user is listed in
+/// `/etc/passwd`, the file is read once and kept for the rest of the run
+///
+/// # Arguments
+///
+/// `uid` the user id, as given by `MetadataExt::uid()`
+///
+/// # Errors
+///
+/// None
+pub fn owner_name(uid: u32) -> String {
+    static USERS: OnceLock<HashMap<u32, String>> = OnceLock::new();
+
+    USERS
+        .get_or_init(|| {
+            read_to_string(PASSWD)
+                .map(|text| parse_passwd(&text))
+                .unwrap_or_default()
+        })
+        .get(&uid)
+        .cloned()
+        .unwrap_or_else(|| uid.to_string())
+}
+
+/// parse_passwd() maps the user id of every entry in a passwd file to its name, the first entry wins when an id is
+/// listed more than once and malformed lines are ignored
+///
+/// # Arguments
+///
+/// `text` the contents of a passwd file, one `name:password:uid:gid:...` entry per line
+///
+/// # Errors
+///
+/// None
+fn parse_passwd(text: &str) -> HashMap<u32, String> {
+    let mut users: HashMap<u32, String> = HashMap::new();
+
+    for line in text.lines() {
+        let mut fields = line.split(':');
+        let (Some(name), Some(_), Some(uid)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        if let Ok(uid) = uid.parse::<u32>() {
+            if !name.is_empty() && !name.starts_with('#') {
+                users.entry(uid).or_insert_with(|| name.to_string());
+            }
+        }
+    }
+
+    users
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies parse_passwd() reads names and ids, and owner_name() falls back to the id of an unknown user
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_passwd() misreads an entry or keeps a malformed line
+    /// - owner_name() does not fall back to the numeric id
+    #[test]
+    fn owner_name_lookup() {
+        // create inputs
+        let users = parse_passwd("root:x:0:0:root:/root:/bin/sh\n# comment\nbroken\nsam:x:1000:1000::/home/sam:/bin/sh\nalias:x:1000:1000\n");
+
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[&0], "root");
+        assert_eq!(users[&1000], "sam");
+        assert_eq!(owner_name(4_000_000_000), "4000000000");
+    }
+}
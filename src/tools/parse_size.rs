@@ -0,0 +1,130 @@
+/// parse_size() parses a byte count such as `4096`, `512K`, `10MiB`, or `1g`, suffixes are case insensitive binary
+/// multiples (K = 1024) and may be followed by `B` or `iB`, returns None if `value` is not a valid size or overflows
+///
+/// # Arguments
+///
+/// `value` the String to parse
+///
+/// # Errors
+///
+/// None
+pub fn parse_size(value: &str) -> Option<u64> {
+    // split the digits from the unit
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (digits, unit) = value.split_at(split);
+    if digits.is_empty() {
+        return None;
+    }
+
+    let multiplier: u64 = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        "t" | "tb" | "tib" => 1 << 40,
+        _ => return None,
+    };
+
+    digits.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// format_size() formats `bytes` in the largest binary unit that divides it exactly, such as `1M` for 1048576 or
+/// `1536` for 1536, so the result reads back through parse_size() unchanged
+///
+/// # Arguments
+///
+/// `bytes` the byte count to format
+///
+/// # Errors
+///
+/// None
+pub fn format_size(bytes: u64) -> String {
+    for (suffix, multiplier) in [
+        ("T", 1u64 << 40),
+        ("G", 1 << 30),
+        ("M", 1 << 20),
+        ("K", 1 << 10),
+    ] {
+        if bytes != 0 && bytes.is_multiple_of(multiplier) {
+            return format!("{}{}", bytes / multiplier, suffix);
+        }
+    }
+
+    bytes.to_string()
+}
+
+/// format_size_human() formats `bytes` for reading rather than parsing, in the largest binary unit it reaches with one
+/// decimal place, such as `26 B`, `1.5 KiB`, or `2.0 GiB`
+///
+/// # Arguments
+///
+/// `bytes` the byte count to format
+///
+/// # Errors
+///
+/// None
+pub fn format_size_human(bytes: u64) -> String {
+    for (suffix, multiplier) in [
+        ("TiB", 1u64 << 40),
+        ("GiB", 1 << 30),
+        ("MiB", 1 << 20),
+        ("KiB", 1 << 10),
+    ] {
+        if bytes >= multiplier {
+            return format!("{:.1} {suffix}", bytes as f64 / multiplier as f64);
+        }
+    }
+
+    format!("{bytes} B")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies parse_size() accepts plain and suffixed sizes and rejects everything else
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - parse_size() does not apply binary multiples
+    /// - parse_size() accepts a malformed or overflowing size
+    /// - format_size() does not pick the largest exact unit or does not read back through parse_size()
+    /// - format_size_human() does not pick the largest unit reached
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(parse_size("4096"), Some(4096));
+        assert_eq!(parse_size("512K"), Some(512 * 1024));
+        assert_eq!(parse_size("10MiB"), Some(10 * 1024 * 1024));
+        assert_eq!(parse_size("1g"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_size("0b"), Some(0));
+
+        for value in ["", "K", "1.5M", "-1", "10 M", "10X", "99999999999T"] {
+            assert_eq!(parse_size(value), None);
+        }
+
+        for (bytes, formatted) in [
+            (0, "0"),
+            (1536, "1536"),
+            (100 << 20, "100M"),
+            (1 << 30, "1G"),
+        ] {
+            assert_eq!(format_size(bytes), formatted);
+            assert_eq!(parse_size(formatted), Some(bytes));
+        }
+
+        for (bytes, formatted) in [
+            (26, "26 B"),
+            (1536, "1.5 KiB"),
+            (100 << 20, "100.0 MiB"),
+            (2 << 30, "2.0 GiB"),
+        ] {
+            assert_eq!(format_size_human(bytes), formatted);
+        }
+    }
+}
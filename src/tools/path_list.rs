@@ -0,0 +1,122 @@
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// read_paths() reads a list of paths from `input`, one per line or, when `null` is set, each terminated by a NUL byte
+/// as written by `find -print0` so that names containing newlines survive. Empty entries are ignored and a trailing
+/// `\r` is removed from lines written on Windows
+///
+/// # Arguments
+///
+/// `input` where the list is read from, such as stdin
+/// `null` whether paths are separated by NUL bytes rather than newlines
+///
+/// # Errors
+///
+/// - `input` cannot be read
+/// - a path is not valid UTF-8 on platforms other than Unix
+pub fn read_paths(input: &mut dyn BufRead, null: bool) -> io::Result<Vec<PathBuf>> {
+    let delimiter = if null { b'\0' } else { b'\n' };
+
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for entry in input.split(delimiter) {
+        let mut entry = entry?;
+        if !null && entry.last() == Some(&b'\r') {
+            entry.pop();
+        }
+        if !entry.is_empty() {
+            paths.push(path_from_bytes(entry)?);
+        }
+    }
+
+    Ok(paths)
+}
+
+/// write_paths() writes `paths` to `output`, each followed by a newline or, when `null` is set, by a NUL byte so the
+/// list can be read back by read_paths() or `xargs -0` whatever characters the names contain
+///
+/// # Arguments
+///
+/// `output` where the list is written to, such as stdout
+/// `paths` the paths to write in order
+/// `null` whether paths are terminated by NUL bytes rather than newlines
+///
+/// # Errors
+///
+/// - `output` cannot be written to
+pub fn write_paths(output: &mut dyn Write, paths: &[PathBuf], null: bool) -> io::Result<()> {
+    let delimiter = if null { b'\0' } else { b'\n' };
+    for path in paths {
+        output.write_all(&path_to_bytes(path))?;
+        output.write_all(&[delimiter])?;
+    }
+
+    output.flush()
+}
+
+/// path_from_bytes() turns the raw bytes of a listed path into a PathBuf, any bytes are a valid path on Unix
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> io::Result<PathBuf> {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+
+    Ok(PathBuf::from(OsString::from_vec(bytes)))
+}
+
+/// path_from_bytes() turns the raw bytes of a listed path into a PathBuf, which must be UTF-8 outside of Unix
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> io::Result<PathBuf> {
+    String::from_utf8(bytes)
+        .map(PathBuf::from)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "listed path is not valid UTF-8"))
+}
+
+/// path_to_bytes() returns the raw bytes of `path` on Unix
+#[cfg(unix)]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+
+    path.as_os_str().as_bytes().to_vec()
+}
+
+/// path_to_bytes() returns `path` as UTF-8 outside of Unix, characters that cannot be represented are replaced
+#[cfg(not(unix))]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().as_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies a name containing a newline survives a NUL delimited round trip and that newline delimited lists
+    /// ignore empty lines and Windows line endings
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - read_paths() does not return the paths written by write_paths()
+    /// - read_paths() splits a NUL delimited name at its newline
+    #[test]
+    fn path_list_round_trip() {
+        let paths = [
+            PathBuf::from("./source/first\nsecond.jpg"),
+            PathBuf::from("./source/plain.jpg"),
+        ];
+
+        let mut output: Vec<u8> = Vec::new();
+        write_paths(&mut output, &paths, true).unwrap();
+        assert_eq!(
+            output,
+            b"./source/first\nsecond.jpg\0./source/plain.jpg\0".to_vec()
+        );
+        assert_eq!(read_paths(&mut output.as_slice(), true).unwrap(), paths);
+        assert_eq!(read_paths(&mut output.as_slice(), false).unwrap().len(), 2);
+        assert_eq!(
+            read_paths(&mut "a.jpg\r\n\nb.jpg".as_bytes(), false).unwrap(),
+            [PathBuf::from("a.jpg"), PathBuf::from("b.jpg")]
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::env;
+
+/// expand_paths() expands `~` and environment variables in every one of `paths`, see expand_path()
+///
+/// # Arguments
+///
+/// `paths` the path arguments of a task
+///
+/// # Errors
+///
+/// - expand_path() call fails for any of `paths`
+pub fn expand_paths(paths: Vec<String>) -> Result<Vec<String>, &'static str> {
+    paths.iter().map(|path| expand_path(path)).collect()
+}
+
+/// expand_path() expands a leading `~` to the home directory and every `$VAR` or `${VAR}` to the value of the
+/// environment variable VAR, so paths such as `~/Pictures` or `$ARCHIVE/dst` work when a task is not started from a
+/// shell that expands them itself. A `$` that is not followed by a variable name is kept as is
+///
+/// # Arguments
+///
+/// `path` a path argument of a task
+///
+/// # Errors
+///
+/// - expand_with() call fails
+pub fn expand_path(path: &str) -> Result<String, &'static str> {
+    expand_with(path, |name| env::var(name).ok())
+}
+
+/// expand_with() body of expand_path() reading variables through `lookup`, so tests do not depend on the environment
+/// they are run in. The home directory is the `HOME` variable, or `USERPROFILE` on Windows
+///
+/// # Arguments
+///
+/// `path` a path argument of a task
+/// `lookup` returns the value of the environment variable of the given name, None when it is not defined
+///
+/// # Errors
+///
+/// - `path` starts with `~` but the home directory is not known
+/// - a variable `path` refers to is not defined, or its value is not valid unicode
+/// - a `${` is not closed by a `}`
+pub fn expand_with(
+    path: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, &'static str> {
+    let mut expanded = String::new();
+
+    // only a `~` standing for the home directory of the current user is expanded, `~user` is kept as is
+    let mut rest = path;
+    if let Some(after) = path.strip_prefix('~') {
+        if after.is_empty()
+            || after.starts_with('/')
+            || after.starts_with(std::path::MAIN_SEPARATOR)
+        {
+            let home = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+            expanded.push_str(
+                &lookup(home).ok_or("'~' cannot be expanded as the home directory is not known")?,
+            );
+            rest = after;
+        }
+    }
+
+    while let Some(index) = rest.find('$') {
+        expanded.push_str(&rest[..index]);
+        let after = &rest[index + 1..];
+
+        let (name, remainder) = if let Some(braced) = after.strip_prefix('{') {
+            let end = braced
+                .find('}')
+                .ok_or("'${' in a path argument must be closed by '}'")?;
+            (&braced[..end], &braced[end + 1..])
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        };
+
+        // a name cannot start with a digit, so `$5` or a lone `$` is literal text
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            expanded.push('$');
+            rest = after;
+            continue;
+        }
+        expanded.push_str(&lookup(name).ok_or(
+            "a path argument refers to an environment variable that is not defined or not valid unicode",
+        )?);
+        rest = remainder;
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies expand_with() expands `~` and defined variables in both forms, leaves text that only looks similar
+    /// alone, and rejects undefined variables
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - expand_with() does not expand `~`, `$VAR`, or `${VAR}`
+    /// - expand_with() expands `~user`, a lone `$`, or a `~` past the start
+    /// - expand_with() replaces an undefined variable rather than failing
+    #[test]
+    fn expand_with_home_and_variables() {
+        let home = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+        let lookup = |name: &str| match name {
+            "ARCHIVE" => Some(String::from("/mnt/archive")),
+            "YEAR" => Some(String::from("2023")),
+            name if name == home => Some(String::from("/home/user")),
+            _ => None,
+        };
+        let expand = |path: &str| expand_with(path, lookup);
+
+        assert_eq!(expand("~"), Ok(String::from("/home/user")));
+        assert_eq!(
+            expand("~/Pictures"),
+            Ok(String::from("/home/user/Pictures"))
+        );
+        assert_eq!(expand("$ARCHIVE/dst"), Ok(String::from("/mnt/archive/dst")));
+        assert_eq!(
+            expand("${ARCHIVE}_old/$YEAR-${YEAR}"),
+            Ok(String::from("/mnt/archive_old/2023-2023"))
+        );
+        for literal in ["~user/a", "./a~/b", "./cost$", "./$5", "./a $ b"] {
+            assert_eq!(expand(literal), Ok(String::from(literal)));
+        }
+
+        for invalid in ["$MISSING/dst", "${MISSING}", "${ARCHIVE"] {
+            assert!(expand(invalid).is_err(), "{invalid}");
+        }
+        assert!(expand_with("~/Pictures", |_| None).is_err());
+        assert_eq!(
+            expand_paths(vec![String::from("./a"), String::from("./b")]),
+            Ok(vec![String::from("./a"), String::from("./b")])
+        );
+    }
+}
@@ -0,0 +1,699 @@
+use std::collections::VecDeque;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// declare local code
+use super::json::Json;
+
+/// span of the most recent transfers the rate is averaged over, long enough to smooth out single large files while
+/// still following changes in speed such as a switch from renames to copies
+const WINDOW: Duration = Duration::from_secs(5);
+
+/// minimum time between two redraws of the progress line so fast runs do not flood the terminal
+const DRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// number of bytes in a mebibyte, the unit the progress line is shown in
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// ProgressFormat enum: how progress is reported while files are processed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressFormat {
+    /// a single self-updating line on stderr, only drawn when stderr is a terminal
+    #[default]
+    Human,
+
+    /// one JSON object per processed file, such as `{"index":3,"total":40,"path":"./a.jpg","action":"move"}`, flushed
+    /// as soon as it is written so a program reading the stream sees every file as it happens
+    Jsonl,
+}
+
+/// ProgressUpdate struct: state of a run after a file has been processed, passed to the `on_file` callback of
+/// OrganizeTask::run_with_progress() so library users can render their own progress
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressUpdate {
+    /// number of files processed so far, whether or not they were moved
+    pub files_done: usize,
+
+    /// number of files the run will process
+    pub files_total: usize,
+
+    /// combined size in bytes of the files processed so far
+    pub bytes_done: u64,
+
+    /// combined size in bytes of every file the run will process
+    pub bytes_total: u64,
+
+    /// average rate in bytes per second over the last few seconds, None until enough time has passed to measure it
+    pub rate: Option<f64>,
+
+    /// estimated time until every file is processed at the current rate, None while the rate is unknown or zero
+    pub eta: Option<Duration>,
+}
+
+/// OnFile type: callback run with every processed file and the ProgressUpdate of the run after it, in a parallel run
+/// it is instead run by the reporter whenever the totals have grown, with an empty path as the files are processed out
+/// of order, see Progress::run_parallel()
+pub type OnFile<'a> = &'a mut dyn FnMut(&Path, &ProgressUpdate);
+
+/// ProgressCounter struct: files and bytes processed so far by the worker threads of a parallel run, which add to it
+/// without locking while a single reporter reads it, see Progress::run_parallel()
+#[derive(Debug, Default)]
+pub struct ProgressCounter {
+    /// number of files processed so far
+    files: AtomicUsize,
+
+    /// combined size in bytes of the files processed so far
+    bytes: AtomicU64,
+}
+
+impl ProgressCounter {
+    /// add() records that a file of `bytes` bytes was processed, safe to call from any number of threads at once
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to ProgressCounter
+    /// `bytes` size of the file in bytes
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn add(&self, bytes: u64) {
+        // the bytes are published before the file so a reader never counts a file without its bytes
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.files.fetch_add(1, Ordering::Release);
+    }
+
+    /// totals() returns the number of files and bytes processed so far, the bytes of a file still being added may
+    /// already be included but never the other way around, so the rate never runs ahead of the files
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to ProgressCounter
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn totals(&self) -> (usize, u64) {
+        let files = self.files.load(Ordering::Acquire);
+        let bytes = self.bytes.load(Ordering::Relaxed);
+
+        (files, bytes)
+    }
+}
+
+/// Progress struct: tracks the files and bytes processed by a run, estimates the remaining time from the rate over a
+/// sliding window, and optionally draws a single self-updating progress line on stderr
+pub struct Progress<'a> {
+    /// number of files the run will process
+    files_total: usize,
+
+    /// combined size in bytes of every file the run will process
+    bytes_total: u64,
+
+    /// number of files processed so far
+    files_done: usize,
+
+    /// combined size in bytes of the files processed so far
+    bytes_done: u64,
+
+    /// time the run started, every sample is measured from it
+    started: Instant,
+
+    /// time since `started` and value of `bytes_done` at each processed file within the last `WINDOW`, along with the
+    /// newest sample older than it so the window always spans its full length
+    samples: VecDeque<(Duration, u64)>,
+
+    /// time since `started` the progress line was last drawn, None if it has not been drawn yet
+    drawn: Option<Duration>,
+
+    /// whether the progress line is drawn on stderr
+    display: bool,
+
+    /// callback run after every processed file
+    on_file: Option<OnFile<'a>>,
+
+    /// stream every processed file is written to as a JSON line, see ProgressFormat::Jsonl
+    jsonl: Option<Box<dyn Write + 'a>>,
+}
+
+impl<'a> Progress<'a> {
+    /// Progress struct initializer, the totals are set once they are known with start()
+    ///
+    /// # Arguments
+    ///
+    /// `display` whether the progress line is drawn, it is only ever drawn when stderr is a terminal
+    /// `on_file` an optional callback run after every processed file
+    pub fn new(display: bool, on_file: Option<OnFile<'a>>) -> Self {
+        Self {
+            files_total: 0,
+            bytes_total: 0,
+            files_done: 0,
+            bytes_done: 0,
+            started: Instant::now(),
+            samples: VecDeque::from([(Duration::ZERO, 0)]),
+            drawn: None,
+            display: display && io::stderr().is_terminal(),
+            on_file,
+            jsonl: None,
+        }
+    }
+
+    /// with_jsonl() writes a JSON line to `stream` for every processed file, see ProgressFormat::Jsonl, in place of the
+    /// progress line
+    ///
+    /// # Arguments
+    ///
+    /// `self` the Progress to report through `stream`
+    /// `stream` where the JSON lines are written
+    pub fn with_jsonl(mut self, stream: Box<dyn Write + 'a>) -> Self {
+        self.display = false;
+        self.jsonl = Some(stream);
+        self
+    }
+
+    /// Progress struct initializer for a run that reports nothing
+    ///
+    /// # Arguments
+    ///
+    /// None
+    pub fn hidden() -> Self {
+        Self::new(false, None)
+    }
+
+    /// start() sets the number of files and bytes the run will process and restarts the clock the rate is measured
+    /// against, so time spent collecting files does not lower the rate
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Progress
+    /// `files_total` number of files the run will process
+    /// `bytes_total` combined size in bytes of those files
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn start(&mut self, files_total: usize, bytes_total: u64) {
+        self.files_total = files_total;
+        self.bytes_total = bytes_total;
+        self.started = Instant::now();
+    }
+
+    /// advance() records that `file` of `bytes` bytes was processed, runs the callback, writes its JSON line, and
+    /// redraws the progress line if it was last drawn long enough ago
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Progress
+    /// `file` a Path that corresponds to the processed file
+    /// `bytes` size of the file in bytes
+    /// `action` what was done with the file, such as `move`, `link`, `skip`, or `fail`
+    ///
+    /// # Errors
+    ///
+    /// - the JSON line cannot be written to or flushed from its stream
+    pub fn advance(&mut self, file: &Path, bytes: u64, action: &str) -> io::Result<()> {
+        let elapsed = self.started.elapsed();
+        self.record(elapsed, bytes);
+        let update = self.update(elapsed);
+
+        if let Some(on_file) = &mut self.on_file {
+            on_file(file, &update);
+        }
+
+        if let Some(stream) = &mut self.jsonl {
+            let line = Json::object([
+                ("index", Json::from(update.files_done)),
+                ("total", Json::from(update.files_total)),
+                ("path", Json::from(file.display().to_string())),
+                ("action", Json::from(action)),
+            ]);
+            writeln!(stream, "{line}")?;
+            stream.flush()?;
+        }
+
+        self.draw(elapsed, &update);
+
+        Ok(())
+    }
+
+    /// run_parallel() runs `work`, whose worker threads add every processed file to `counter`, on a thread of its own
+    /// while the calling thread alone reports progress, reading `counter`, running the callback, and redrawing the
+    /// progress line at a fixed interval so the rate and ETA do not jitter with the order workers finish in. Once
+    /// `work` returns the progress is brought up to the final totals of `counter`, which are exact as every worker has
+    /// finished
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Progress
+    /// `counter` the ProgressCounter the workers of `work` add to
+    /// `work` the parallel work to report on
+    ///
+    /// # Errors
+    ///
+    /// None, whatever `work` returns is passed on
+    pub fn run_parallel<T: Send>(
+        &mut self,
+        counter: &ProgressCounter,
+        work: impl FnOnce() -> T + Send,
+    ) -> T {
+        let (done, finished) = channel::<()>();
+        let result = thread::scope(|scope| {
+            let worker = scope.spawn(move || {
+                let result = work();
+                // the reporter stops waiting as soon as the work is done, dropping `done` signals it even on a panic
+                drop(done);
+                result
+            });
+            while let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(DRAW_INTERVAL) {
+                self.observe(counter);
+            }
+            worker.join().unwrap()
+        });
+        self.observe(counter);
+
+        result
+    }
+
+    /// observe() brings the files and bytes processed up to the totals of `counter`, runs the callback if they have
+    /// grown since they were last observed, and redraws the progress line if it was last drawn long enough ago, see
+    /// run_parallel()
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Progress
+    /// `counter` the ProgressCounter of a parallel run
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn observe(&mut self, counter: &ProgressCounter) -> ProgressUpdate {
+        let elapsed = self.started.elapsed();
+        let totals = counter.totals();
+        let grown = totals != (self.files_done, self.bytes_done);
+        (self.files_done, self.bytes_done) = totals;
+        self.sample(elapsed);
+        let update = self.update(elapsed);
+
+        if let Some(on_file) = self.on_file.as_mut().filter(|_| grown) {
+            on_file(Path::new(""), &update);
+        }
+
+        self.draw(elapsed, &update);
+
+        update
+    }
+
+    /// draw() redraws the progress line with `update` if it is displayed and was last drawn long enough ago, or once
+    /// every file is processed
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Progress
+    /// `elapsed` time since the start of the run
+    /// `update` the ProgressUpdate to draw
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn draw(&mut self, elapsed: Duration, update: &ProgressUpdate) {
+        let due = self
+            .drawn
+            .is_none_or(|drawn| elapsed.saturating_sub(drawn) >= DRAW_INTERVAL);
+        if self.display && (due || update.files_done == update.files_total) {
+            eprint!("\r{}", render_line(update));
+            self.drawn = Some(elapsed);
+        }
+    }
+
+    /// finish() ends the progress line so the summary printed after the run starts on a line of its own
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Progress
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn finish(&mut self) {
+        if self.drawn.take().is_some() {
+            eprintln!();
+        }
+    }
+
+    /// record() adds a processed file of `bytes` bytes at `elapsed` since the start and drops samples that have left
+    /// the window
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Progress
+    /// `elapsed` time since the start of the run
+    /// `bytes` size of the file in bytes
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn record(&mut self, elapsed: Duration, bytes: u64) {
+        self.files_done += 1;
+        self.bytes_done += bytes;
+        self.sample(elapsed);
+    }
+
+    /// sample() adds the bytes processed so far at `elapsed` since the start to the window and drops samples that have
+    /// left it
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Progress
+    /// `elapsed` time since the start of the run
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn sample(&mut self, elapsed: Duration) {
+        self.samples.push_back((elapsed, self.bytes_done));
+
+        // the oldest sample is kept as long as the one after it is still inside the window
+        while self
+            .samples
+            .get(1)
+            .is_some_and(|&(time, _)| elapsed.saturating_sub(time) >= WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// update() computes the ProgressUpdate of the run at `elapsed` since the start
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Progress
+    /// `elapsed` time since the start of the run
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn update(&self, elapsed: Duration) -> ProgressUpdate {
+        let remaining = self.bytes_total.saturating_sub(self.bytes_done);
+
+        // rate over the window, measured from its oldest sample
+        let rate = self.samples.front().and_then(|&(time, bytes)| {
+            let span = elapsed.saturating_sub(time).as_secs_f64();
+            if span > 0.0 {
+                Some((self.bytes_done - bytes) as f64 / span)
+            } else {
+                None
+            }
+        });
+
+        let eta = if remaining == 0 {
+            Some(Duration::ZERO)
+        } else {
+            rate.filter(|&rate| rate > 0.0)
+                .map(|rate| Duration::from_secs_f64(remaining as f64 / rate))
+        };
+
+        ProgressUpdate {
+            files_done: self.files_done,
+            files_total: self.files_total,
+            bytes_done: self.bytes_done,
+            bytes_total: self.bytes_total,
+            rate,
+            eta,
+        }
+    }
+}
+
+/// parse_progress_format() parses the value of `--progress-format`, either `human` or `jsonl`
+///
+/// # Arguments
+///
+/// `value` the value following `--progress-format`
+///
+/// # Errors
+///
+/// - `value` is neither `human` nor `jsonl`
+pub fn parse_progress_format(value: Option<String>) -> Result<ProgressFormat, &'static str> {
+    match value.as_deref() {
+        Some("human") => Ok(ProgressFormat::Human),
+        Some("jsonl") => Ok(ProgressFormat::Jsonl),
+        _ => Err("'--progress-format' must be followed by 'human' or 'jsonl'"),
+    }
+}
+
+/// open_progress_stream() returns the stream JSON progress lines are written to, stderr unless a file descriptor
+/// inherited from the parent process is given
+///
+/// # Arguments
+///
+/// `fd` an optional file descriptor to write to instead of stderr
+///
+/// # Errors
+///
+/// - `fd` is not an open file descriptor that can be written to
+/// - `fd` is given on a platform other than Unix
+pub fn open_progress_stream(fd: Option<u32>) -> io::Result<Box<dyn Write>> {
+    match fd {
+        None => Ok(Box::new(io::stderr())),
+        // the descriptor is reopened through /dev/fd so closing the stream leaves the original open
+        #[cfg(unix)]
+        Some(fd) => Ok(Box::new(
+            std::fs::OpenOptions::new()
+                .write(true)
+                .open(format!("/dev/fd/{fd}"))?,
+        )),
+        #[cfg(not(unix))]
+        Some(_) => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "'--progress-fd' is only supported on Unix",
+        )),
+    }
+}
+
+/// render_line() formats `update` as the progress line, such as `12/40 files, 96.0/320.0 MiB, 8.0 MiB/s, ETA 0:28`
+///
+/// # Arguments
+///
+/// `update` the ProgressUpdate to format
+///
+/// # Errors
+///
+/// None
+fn render_line(update: &ProgressUpdate) -> String {
+    let rate = update
+        .rate
+        .map_or(String::from("-"), |rate| format!("{:.1}", rate / MIB));
+    let eta = update.eta.map_or(String::from("-"), |eta| {
+        let secs = eta.as_secs();
+        if secs >= 3600 {
+            format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+        } else {
+            format!("{}:{:02}", secs / 60, secs % 60)
+        }
+    });
+
+    format!(
+        "{}/{} files, {:.1}/{:.1} MiB, {} MiB/s, ETA {}",
+        update.files_done,
+        update.files_total,
+        update.bytes_done as f64 / MIB,
+        update.bytes_total as f64 / MIB,
+        rate,
+        eta
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies the rate is averaged over the sliding window and the ETA follows from it, using synthetic timings
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - the rate is not the bytes processed within the window divided by its span
+    /// - the ETA is not the remaining bytes divided by the rate
+    /// - render_line() does not format the update as expected
+    #[test]
+    fn progress_rate_and_eta() {
+        // create inputs, 100 MiB in total
+        let mut progress = Progress::hidden();
+        progress.start(10, 100 * 1024 * 1024);
+        let mib = 1024 * 1024;
+
+        // nothing is measurable at the start
+        let initial = progress.update(Duration::ZERO);
+
+        // 1 MiB per second over the first four seconds
+        for second in 1..=4 {
+            progress.record(Duration::from_secs(second), mib);
+        }
+        let steady = progress.update(Duration::from_secs(4));
+
+        // then 10 MiB per second, the early samples leave the window
+        for second in 5..=10 {
+            progress.record(Duration::from_secs(second), 10 * mib);
+        }
+        let faster = progress.update(Duration::from_secs(10));
+
+        assert_eq!((initial.rate, initial.eta), (None, None));
+        assert_eq!(steady.rate, Some(mib as f64));
+        assert_eq!(steady.eta, Some(Duration::from_secs(96)));
+        assert_eq!(progress.samples.len(), 6);
+        assert_eq!(faster.rate, Some(10.0 * mib as f64));
+        assert_eq!(faster.eta, Some(Duration::from_secs_f64(3.6)));
+        assert_eq!(
+            render_line(&faster),
+            "10/10 files, 64.0/100.0 MiB, 10.0 MiB/s, ETA 0:03"
+        );
+    }
+    /// verifies every processed file is written as a JSON line that parses back with monotonic indices, the total, its
+    /// path, and its action
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Progress::advance() does not write one valid JSON line per file
+    /// - the indices of the JSON lines do not count up from one
+    /// - parse_progress_format() does not parse a valid format or accepts an invalid one
+    #[test]
+    fn progress_jsonl_lines() {
+        // create inputs
+        let files = ["./a.jpg", "./b \"quoted\".jpg", "./c.jpg"];
+        let actions = ["move", "skip", "fail"];
+        let mut stream: Vec<u8> = Vec::new();
+
+        // run test
+        let mut progress = Progress::hidden().with_jsonl(Box::new(&mut stream));
+        progress.start(files.len(), 30);
+        let test_result: io::Result<Vec<()>> = files
+            .iter()
+            .zip(actions)
+            .map(|(file, action)| progress.advance(Path::new(file), 10, action))
+            .collect();
+        drop(progress);
+        let output = String::from_utf8(stream).unwrap();
+        let lines: Vec<Json> = output
+            .lines()
+            .map(|line| Json::parse(line).unwrap())
+            .collect();
+
+        assert!(test_result.is_ok());
+        assert!(output.ends_with('\n'));
+        assert_eq!(lines.len(), files.len());
+        for (index, line) in lines.iter().enumerate() {
+            assert_eq!(line.get("index"), Some(&Json::from(index + 1)));
+            assert_eq!(line.get("total"), Some(&Json::from(files.len())));
+            assert_eq!(line.get("path"), Some(&Json::from(files[index])));
+            assert_eq!(line.get("action"), Some(&Json::from(actions[index])));
+        }
+        assert_eq!(
+            parse_progress_format(Some(String::from("jsonl"))),
+            Ok(ProgressFormat::Jsonl)
+        );
+        assert!(parse_progress_format(Some(String::from("json"))).is_err());
+    }
+
+    /// verifies the totals of a run whose files are counted from several threads at once are exact once it finishes
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Progress::run_parallel() does not pass on the result of the work
+    /// - the files or bytes reported after Progress::run_parallel() differ from those the workers processed
+    #[test]
+    fn progress_run_parallel_totals() {
+        // create inputs, 8 workers processing 1000 files of 3 bytes each
+        let (workers, files) = (8, 1000);
+        let counter = ProgressCounter::default();
+        let mut progress = Progress::hidden();
+        progress.start(workers * files, 3 * (workers * files) as u64);
+
+        // run test
+        let test_result = progress.run_parallel(&counter, || {
+            thread::scope(|scope| {
+                for _ in 0..workers {
+                    scope.spawn(|| {
+                        for _ in 0..files {
+                            counter.add(3);
+                        }
+                    });
+                }
+            });
+            "done"
+        });
+        let update = progress.update(progress.started.elapsed());
+
+        assert_eq!(test_result, "done");
+        assert_eq!(update.files_done, workers * files);
+        assert_eq!(update.bytes_done, 3 * (workers * files) as u64);
+        assert_eq!(update.eta, Some(Duration::ZERO));
+        assert_eq!(
+            counter.totals(),
+            (workers * files, 3 * (workers * files) as u64)
+        );
+    }
+
+    /// verifies the callback of a parallel run is run from the reporter with totals that only ever grow, ending at the
+    /// exact totals of the workers
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Progress::run_parallel() does not run the callback
+    /// - the callback sees the files or bytes processed go down
+    /// - the last update the callback sees differs from the totals the workers processed
+    #[test]
+    fn progress_run_parallel_callback() {
+        // create inputs, 4 workers processing 6 files of 5 bytes each slowly enough to span several draw intervals
+        let (workers, files) = (4, 6);
+        let counter = ProgressCounter::default();
+        let mut updates: Vec<(usize, u64)> = Vec::new();
+        let mut on_file = |path: &Path, update: &ProgressUpdate| {
+            assert_eq!(path, Path::new(""));
+            updates.push((update.files_done, update.bytes_done));
+        };
+        let mut progress = Progress::new(false, Some(&mut on_file));
+        progress.start(workers * files, 5 * (workers * files) as u64);
+
+        // run test
+        progress.run_parallel(&counter, || {
+            thread::scope(|scope| {
+                for _ in 0..workers {
+                    scope.spawn(|| {
+                        for _ in 0..files {
+                            thread::sleep(DRAW_INTERVAL / 4);
+                            counter.add(5);
+                        }
+                    });
+                }
+            });
+        });
+        drop(progress);
+
+        assert!(updates.len() >= 2);
+        assert!(updates
+            .windows(2)
+            .all(|pair| pair[0].0 <= pair[1].0 && pair[0].1 <= pair[1].1));
+        assert_eq!(
+            updates.last(),
+            Some(&(workers * files, 5 * (workers * files) as u64))
+        );
+    }
+}
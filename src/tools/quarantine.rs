@@ -0,0 +1,158 @@
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::file_system::FileSystem;
+use super::move_file::move_file;
+use super::throttle::Throttle;
+use super::unique_path::unique_path_by;
+
+/// extension appended to the name of a quarantined file to name the note describing why it failed
+const ERROR_NOTE_EXTENSION: &str = "error";
+
+/// quarantine() moves `file`, which failed with `err`, into `dir` next to a `<name>.error` note naming where it came
+/// from and the error, and returns where it was moved to. Files are placed directly inside `dir` under their own name,
+/// given a `_1`, `_2`, ... suffix when either the name or its note is taken, so every failure of a run ends up in one
+/// flat folder without replacing an earlier quarantined file
+///
+/// # Arguments
+///
+/// `fs` the FileSystem the file is moved on
+/// `dir` a Path that corresponds to the quarantine directory, created if missing
+/// `file` a Path that corresponds to the file that failed
+/// `err` the error the file failed with
+///
+/// # Errors
+///
+/// - FileSystem::create_dir_all() call fails
+/// - move_file() call fails, the file is left where it was
+/// - the note cannot be written, the file stays quarantined
+pub fn quarantine(
+    fs: &dyn FileSystem,
+    dir: &Path,
+    file: &Path,
+    err: &io::Error,
+) -> io::Result<PathBuf> {
+    fs.create_dir_all(dir)?;
+    let name = file.file_name().unwrap_or_default().to_string_lossy();
+    // a quarantined `foo.error` would otherwise be replaced by the note of a later `foo`
+    let destination = unique_path_by(dir, &name, |path| {
+        !path.exists() && !Path::new(&note_path(path)).exists()
+    });
+
+    // quarantining is a last resort after the run gave up on the file, so it is never throttled
+    move_file(fs, file, &destination, &mut Throttle::new(None), false)?;
+
+    File::create_new(note_path(&destination))?
+        .write_all(format!("source: {}\nerror: {err}\n", file.display()).as_bytes())?;
+
+    Ok(destination)
+}
+
+/// note_path() returns the path of the note describing why the file quarantined at `destination` failed
+///
+/// # Arguments
+///
+/// `destination` a Path that corresponds to the quarantined file
+///
+/// # Errors
+///
+/// None
+fn note_path(destination: &Path) -> OsString {
+    let mut note = destination.as_os_str().to_os_string();
+    note.push(".");
+    note.push(ERROR_NOTE_EXTENSION);
+    note
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::file_system::RealFileSystem;
+    use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+
+    /// verifies quarantine() moves a file into the quarantine directory next to a note naming its origin and error,
+    /// without overwriting an earlier file of the same name
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - quarantine() leaves the file in place, overwrites an earlier quarantined file, or writes no note
+    #[test]
+    fn quarantine_moves_with_note() {
+        // create inputs
+        let path_buf = PathBuf::from("./quarantine_moves_with_note_test");
+        let dir = path_buf.join("quarantine");
+        let err = io::Error::new(io::ErrorKind::InvalidData, "no readable date");
+
+        // create mock directories, two files sharing a name
+        create_dir_all(path_buf.join("a")).unwrap();
+        create_dir_all(path_buf.join("b")).unwrap();
+        write(path_buf.join("a/scan.jpg"), "a").unwrap();
+        write(path_buf.join("b/scan.jpg"), "b").unwrap();
+
+        // run test
+        let first = quarantine(&RealFileSystem, &dir, &path_buf.join("a/scan.jpg"), &err);
+        let second = quarantine(&RealFileSystem, &dir, &path_buf.join("b/scan.jpg"), &err);
+        let note = read_to_string(dir.join("scan_1.jpg.error"));
+        let contents = read_to_string(dir.join("scan.jpg"));
+        let left = path_buf.join("a/scan.jpg").exists() || path_buf.join("b/scan.jpg").exists();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(first.unwrap(), dir.join("scan.jpg"));
+        assert_eq!(second.unwrap(), dir.join("scan_1.jpg"));
+        assert_eq!(
+            note.unwrap(),
+            format!(
+                "source: {}\nerror: no readable date\n",
+                path_buf.join("b/scan.jpg").display()
+            )
+        );
+        assert_eq!(contents.unwrap(), "a");
+        assert!(!left);
+    }
+
+    /// verifies quarantine() does not replace an earlier quarantined file that is named like the note of a later one
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - quarantine() writes the note of `foo` over a quarantined `foo.error`
+    #[test]
+    fn quarantine_note_collision() {
+        // create inputs
+        let path_buf = PathBuf::from("./quarantine_note_collision_test");
+        let dir = path_buf.join("quarantine");
+        let err = io::Error::new(io::ErrorKind::InvalidData, "no readable date");
+
+        // create mock directories, `foo.error` is quarantined before `foo`
+        create_dir_all(&path_buf).unwrap();
+        write(path_buf.join("foo.error"), "earlier").unwrap();
+        write(path_buf.join("foo"), "later").unwrap();
+
+        // run test
+        let first = quarantine(&RealFileSystem, &dir, &path_buf.join("foo.error"), &err);
+        let second = quarantine(&RealFileSystem, &dir, &path_buf.join("foo"), &err);
+        let earlier = read_to_string(dir.join("foo.error"));
+        let later = read_to_string(dir.join("foo_1"));
+        let note = read_to_string(dir.join("foo_1.error"));
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(first.unwrap(), dir.join("foo.error"));
+        assert_eq!(second.unwrap(), dir.join("foo_1"));
+        assert_eq!(earlier.unwrap(), "earlier");
+        assert_eq!(later.unwrap(), "later");
+        assert!(note.unwrap().starts_with("source: "));
+    }
+}
@@ -0,0 +1,632 @@
+use std::cell::Cell;
+use std::io;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// number of steps a single Regex::captures() call may take before it gives up, far more than any sensible pattern
+/// needs on a file name but reached quickly by patterns such as `(a+)+b` whose backtracking grows exponentially
+const MAX_STEPS: usize = 1_000_000;
+
+/// Regex struct: a compiled regular expression supporting literals, `.`, character classes such as `[A-Z0-9_]` and
+/// `[^.]`, the classes `\d`, `\w`, `\s` and their negations, the anchors `^` and `$`, groups that capture `(...)` or
+/// not `(?:...)`, alternation with `|`, and the quantifiers `*`, `+`, `?`, `{n}`, `{n,}`, and `{n,m}`, each of which
+/// may be followed by `?` to match as little as possible. Matching backtracks, which is plenty for file names, and is
+/// limited to MAX_STEPS so a pattern that backtracks exponentially fails instead of running for ever
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regex {
+    /// the pattern as it was given
+    pattern: String,
+
+    /// the whole pattern, a group of its alternatives that does not capture
+    root: Node,
+
+    /// number of capture groups
+    groups: usize,
+}
+
+/// Node enum: a single element of a compiled pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    /// a character matching itself
+    Char(char),
+
+    /// `.`, any character
+    Any,
+
+    /// a character class, matching any character one of its items matches, or none of them when negated
+    Class(Vec<ClassItem>, bool),
+
+    /// `^`, the start of the text
+    Start,
+
+    /// `$`, the end of the text
+    End,
+
+    /// a group of alternatives, capturing into the given group when it has an index
+    Group(Option<usize>, Vec<Vec<Node>>),
+
+    /// a node repeated between a minimum and an optional maximum number of times, as often as possible when greedy
+    Repeat(Box<Node>, usize, Option<usize>, bool),
+}
+
+/// ClassItem enum: a part of a character class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClassItem {
+    /// an inclusive range of characters, a single character is a range starting and ending with it
+    Range(char, char),
+
+    /// `\d`, or `\D` when negated
+    Digit(bool),
+
+    /// `\w`, or `\W` when negated
+    Word(bool),
+
+    /// `\s`, or `\S` when negated
+    Space(bool),
+}
+
+impl ClassItem {
+    /// matches() returns whether `c` belongs to this ClassItem
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to ClassItem
+    /// `c` the character to test
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn matches(&self, c: char) -> bool {
+        match *self {
+            Self::Range(from, to) => from <= c && c <= to,
+            Self::Digit(negated) => c.is_ascii_digit() != negated,
+            Self::Word(negated) => (c.is_alphanumeric() || c == '_') != negated,
+            Self::Space(negated) => c.is_whitespace() != negated,
+        }
+    }
+}
+
+/// capture spans by group, group 0 being the whole match, as character positions
+type Spans = Vec<Option<(usize, usize)>>;
+
+impl Regex {
+    /// Regex struct initializer, compiles `pattern`
+    ///
+    /// # Arguments
+    ///
+    /// `pattern` the regular expression
+    ///
+    /// # Errors
+    ///
+    /// - `pattern` has an unbalanced parenthesis or bracket, a quantifier with nothing to repeat, a malformed or
+    ///   reversed repetition count or class range, or an unknown escape
+    pub fn new(pattern: &str) -> Result<Self, &'static str> {
+        let mut chars = pattern.chars().peekable();
+        let mut groups = 0;
+        let alternatives = parse_alternatives(&mut chars, &mut groups)?;
+        if chars.next().is_some() {
+            return Err("regular expression has an unmatched ')'");
+        }
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+            root: Node::Group(None, alternatives),
+            groups,
+        })
+    }
+
+    /// groups() returns the number of capture groups of the pattern
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Regex
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn groups(&self) -> usize {
+        self.groups
+    }
+
+    /// captures() returns the leftmost match of the pattern in `text`, the whole match first followed by the text of
+    /// every capture group in order, None for a group that did not take part in the match. Returns None when the
+    /// pattern matches nowhere in `text`
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Regex
+    /// `text` the text to search
+    ///
+    /// # Errors
+    ///
+    /// - matching takes more than MAX_STEPS steps
+    pub fn captures(&self, text: &str) -> io::Result<Option<Vec<Option<String>>>> {
+        let chars: Vec<char> = text.chars().collect();
+        let steps = Cell::new(MAX_STEPS);
+
+        for start in 0..=chars.len() {
+            let mut spans: Spans = vec![None; self.groups + 1];
+            let mut end = None;
+            let matched = match_nodes(
+                std::slice::from_ref(&self.root),
+                &chars,
+                start,
+                &mut spans,
+                &steps,
+                &mut |at, _| {
+                    end = Some(at);
+                    true
+                },
+            );
+            if steps.get() == 0 {
+                return Err(io::Error::other(format!(
+                    "regular expression '{}' takes too long to match '{text}', simplify nested quantifiers such as '(a+)+'",
+                    self.pattern
+                )));
+            }
+            if matched {
+                spans[0] = end.map(|end| (start, end));
+                return Ok(Some(
+                    spans
+                        .iter()
+                        .map(|span| span.map(|(from, to)| chars[from..to].iter().collect()))
+                        .collect(),
+                ));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// parse_alternatives() parses `|` separated sequences until the end of the pattern or a `)`, which is left unread
+///
+/// # Arguments
+///
+/// `chars` the remaining characters of the pattern
+/// `groups` the number of capture groups opened so far
+///
+/// # Errors
+///
+/// - see Regex::new()
+fn parse_alternatives(
+    chars: &mut Peekable<Chars>,
+    groups: &mut usize,
+) -> Result<Vec<Vec<Node>>, &'static str> {
+    let mut alternatives: Vec<Vec<Node>> = vec![Vec::new()];
+
+    while let Some(&c) = chars.peek() {
+        if c == ')' {
+            break;
+        }
+        chars.next();
+        let node = match c {
+            '|' => {
+                alternatives.push(Vec::new());
+                continue;
+            }
+            '*' | '+' | '?' | '{' => {
+                let sequence = alternatives
+                    .last_mut()
+                    .expect("one alternative always exists");
+                let repeated = match sequence.pop() {
+                    Some(Node::Start | Node::End | Node::Repeat(..)) | None => {
+                        return Err("regular expression has a quantifier with nothing to repeat")
+                    }
+                    Some(node) => node,
+                };
+                let (min, max) = match c {
+                    '*' => (0, None),
+                    '+' => (1, None),
+                    '?' => (0, Some(1)),
+                    _ => parse_count(chars)?,
+                };
+                let greedy = chars.next_if_eq(&'?').is_none();
+                Node::Repeat(Box::new(repeated), min, max, greedy)
+            }
+            '(' => {
+                let index = if chars.next_if_eq(&'?').is_some() {
+                    if chars.next() != Some(':') {
+                        return Err("regular expression groups may only start with '(' or '(?:'");
+                    }
+                    None
+                } else {
+                    *groups += 1;
+                    Some(*groups)
+                };
+                let inner = parse_alternatives(chars, groups)?;
+                if chars.next() != Some(')') {
+                    return Err("regular expression has an unclosed '('");
+                }
+                Node::Group(index, inner)
+            }
+            '[' => parse_class(chars)?,
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '\\' => match parse_escape(chars)? {
+                ClassItem::Range(c, _) => Node::Char(c),
+                item => Node::Class(vec![item], false),
+            },
+            c => Node::Char(c),
+        };
+        alternatives
+            .last_mut()
+            .expect("one alternative always exists")
+            .push(node);
+    }
+
+    Ok(alternatives)
+}
+
+/// parse_count() parses the repetition count following a `{`, `n}`, `n,}`, or `n,m}`
+///
+/// # Arguments
+///
+/// `chars` the remaining characters of the pattern
+///
+/// # Errors
+///
+/// - the count is malformed or its maximum is below its minimum
+fn parse_count(chars: &mut Peekable<Chars>) -> Result<(usize, Option<usize>), &'static str> {
+    const ERROR: &str =
+        "regular expression has a malformed repetition count, use {n}, {n,}, or {n,m}";
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            Some('}') => break,
+            Some(c) => text.push(c),
+            None => return Err(ERROR),
+        }
+    }
+
+    let number = |value: &str| value.parse::<usize>().map_err(|_| ERROR);
+    let (min, max) = match text.split_once(',') {
+        None => {
+            let count = number(&text)?;
+            (count, Some(count))
+        }
+        Some((min, "")) => (number(min)?, None),
+        Some((min, max)) => (number(min)?, Some(number(max)?)),
+    };
+    if max.is_some_and(|max| max < min) {
+        return Err(ERROR);
+    }
+
+    Ok((min, max))
+}
+
+/// parse_class() parses a character class following a `[` up to and including its `]`, a leading `^` negates it and a
+/// `]` right after the opening bracket or a `-` at either end is taken literally
+///
+/// # Arguments
+///
+/// `chars` the remaining characters of the pattern
+///
+/// # Errors
+///
+/// - the class is not closed, holds a reversed range, or an unknown escape
+fn parse_class(chars: &mut Peekable<Chars>) -> Result<Node, &'static str> {
+    let negated = chars.next_if_eq(&'^').is_some();
+    let mut items: Vec<ClassItem> = Vec::new();
+
+    let mut first = true;
+    loop {
+        let item = match chars.next() {
+            None => return Err("regular expression has an unclosed '['"),
+            Some(']') if !first => break,
+            Some('\\') => parse_escape(chars)?,
+            Some(c) => ClassItem::Range(c, c),
+        };
+        first = false;
+
+        // a `-` between two characters forms a range, anywhere else it is itself
+        let from = match item {
+            ClassItem::Range(from, _) if chars.peek() == Some(&'-') => from,
+            item => {
+                items.push(item);
+                continue;
+            }
+        };
+        chars.next();
+        let to = match chars.next() {
+            Some(']') | None => {
+                items.push(ClassItem::Range(from, from));
+                items.push(ClassItem::Range('-', '-'));
+                break;
+            }
+            Some('\\') => match parse_escape(chars)? {
+                ClassItem::Range(to, _) => to,
+                _ => return Err("regular expression class ranges must end with a character"),
+            },
+            Some(to) => to,
+        };
+        if to < from {
+            return Err("regular expression class has a range whose end comes before its start");
+        }
+        items.push(ClassItem::Range(from, to));
+    }
+
+    Ok(Node::Class(items, negated))
+}
+
+/// parse_escape() parses the character following a `\`, a class such as `\d`, a control character such as `\t`, or any
+/// character that is not a letter or digit taken literally
+///
+/// # Arguments
+///
+/// `chars` the remaining characters of the pattern
+///
+/// # Errors
+///
+/// - the pattern ends after the `\` or the escape is not known
+fn parse_escape(chars: &mut Peekable<Chars>) -> Result<ClassItem, &'static str> {
+    let literal = |c: char| ClassItem::Range(c, c);
+    Ok(match chars.next() {
+        Some('d') => ClassItem::Digit(false),
+        Some('D') => ClassItem::Digit(true),
+        Some('w') => ClassItem::Word(false),
+        Some('W') => ClassItem::Word(true),
+        Some('s') => ClassItem::Space(false),
+        Some('S') => ClassItem::Space(true),
+        Some('t') => literal('\t'),
+        Some('n') => literal('\n'),
+        Some(c) if !c.is_alphanumeric() => literal(c),
+        Some(_) => return Err("regular expression has an unknown escape"),
+        None => return Err("regular expression ends with a lone '\\'"),
+    })
+}
+
+/// match_nodes() tries to match `nodes` against `text` from position `at`, calling `next` with the position the match
+/// ended at and backtracking into earlier choices for as long as `next` returns false
+///
+/// # Arguments
+///
+/// `nodes` the sequence of nodes to match
+/// `text` the characters of the text
+/// `at` the position to match from
+/// `spans` the capture spans recorded so far, restored whenever a choice is undone
+/// `steps` the number of steps left, once none are left every call fails at once so the match unwinds
+/// `next` the rest of the match, given the end position of `nodes`
+///
+/// # Errors
+///
+/// None
+fn match_nodes(
+    nodes: &[Node],
+    text: &[char],
+    at: usize,
+    spans: &mut Spans,
+    steps: &Cell<usize>,
+    next: &mut dyn FnMut(usize, &mut Spans) -> bool,
+) -> bool {
+    if steps.get() == 0 {
+        return false;
+    }
+    steps.set(steps.get() - 1);
+
+    let Some((node, rest)) = nodes.split_first() else {
+        return next(at, spans);
+    };
+
+    match node {
+        Node::Char(_) | Node::Any | Node::Class(..) => {
+            let matched = text.get(at).is_some_and(|&c| match node {
+                Node::Char(expected) => c == *expected,
+                Node::Class(items, negated) => items.iter().any(|item| item.matches(c)) != *negated,
+                _ => true,
+            });
+            matched && match_nodes(rest, text, at + 1, spans, steps, next)
+        }
+        Node::Start => at == 0 && match_nodes(rest, text, at, spans, steps, next),
+        Node::End => at == text.len() && match_nodes(rest, text, at, spans, steps, next),
+        Node::Group(index, alternatives) => alternatives.iter().any(|alternative| {
+            match_nodes(alternative, text, at, spans, steps, &mut |end, spans| {
+                let previous = index.map(|index| spans[index].replace((at, end)));
+                if match_nodes(rest, text, end, spans, steps, next) {
+                    return true;
+                }
+                if let (Some(index), Some(previous)) = (index, previous) {
+                    spans[*index] = previous;
+                }
+                false
+            })
+        }),
+        Node::Repeat(repeated, min, max, greedy) => match_repeat(
+            repeated,
+            (*min, *max, *greedy),
+            0,
+            rest,
+            text,
+            at,
+            spans,
+            steps,
+            next,
+        ),
+    }
+}
+
+/// match_repeat() matches further repetitions of `repeated` after `count` of them ended at `at`, followed by `rest`,
+/// trying more repetitions before fewer when greedy and the other way around otherwise. A repetition that matches
+/// nothing once the minimum is reached is not tried again, so patterns such as `(a*)*` end
+///
+/// # Arguments
+///
+/// `repeated` the repeated node
+/// `bounds` the minimum and maximum number of repetitions and whether to match as many as possible
+/// `count` the number of repetitions matched so far
+/// `rest` the nodes following the repetition
+/// `text` the characters of the text
+/// `at` the position the previous repetition ended at
+/// `spans` the capture spans recorded so far
+/// `steps` the number of steps left, see match_nodes()
+/// `next` the rest of the match, see match_nodes()
+///
+/// # Errors
+///
+/// None
+#[allow(clippy::too_many_arguments)]
+fn match_repeat(
+    repeated: &Node,
+    bounds: (usize, Option<usize>, bool),
+    count: usize,
+    rest: &[Node],
+    text: &[char],
+    at: usize,
+    spans: &mut Spans,
+    steps: &Cell<usize>,
+    next: &mut dyn FnMut(usize, &mut Spans) -> bool,
+) -> bool {
+    let (min, max, greedy) = bounds;
+    let can_repeat = max.is_none_or(|max| count < max);
+
+    for repeat in if greedy { [true, false] } else { [false, true] } {
+        let matched = if repeat {
+            can_repeat
+                && match_nodes(
+                    std::slice::from_ref(repeated),
+                    text,
+                    at,
+                    spans,
+                    steps,
+                    &mut |end, spans| {
+                        (end != at || count < min)
+                            && match_repeat(
+                                repeated,
+                                bounds,
+                                count + 1,
+                                rest,
+                                text,
+                                end,
+                                spans,
+                                steps,
+                                next,
+                            )
+                    },
+                )
+        } else {
+            count >= min && match_nodes(rest, text, at, spans, steps, next)
+        };
+        if matched {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies Regex::captures() finds the leftmost match with its capture groups across the supported syntax
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Regex::captures() misses a match, returns the wrong text for a group, or matches where it should not
+    #[test]
+    fn regex_captures() {
+        let captures = |pattern: &str, text: &str| {
+            Regex::new(pattern)
+                .unwrap()
+                .captures(text)
+                .unwrap()
+                .map(|groups| {
+                    groups
+                        .into_iter()
+                        .map(|group| group.unwrap_or_else(|| String::from("-")))
+                        .collect::<Vec<String>>()
+                })
+        };
+
+        assert_eq!(
+            captures(r"(PROJ\d+)_", "PROJ1234_whatever.pdf"),
+            Some(vec![String::from("PROJ1234_"), String::from("PROJ1234")])
+        );
+        assert_eq!(captures(r"^PROJ\d+", "old_PROJ1234.pdf"), None);
+        assert_eq!(
+            captures(r"(\d{4})-(\d{2})", "scan 2023-01-15.jpg").unwrap()[1..],
+            ["2023", "01"]
+        );
+        assert_eq!(
+            captures(r"^(.*?)_(.*)$", "a_b_c").unwrap()[1..],
+            ["a", "b_c"]
+        );
+        assert_eq!(
+            captures(r"^(.*)_(.*)$", "a_b_c").unwrap()[1..],
+            ["a_b", "c"]
+        );
+        assert_eq!(
+            captures(r"(?:IMG|DSC)_([A-Z]+)?(\d+)", "DSC_0042.jpg").unwrap()[1..],
+            ["-", "0042"]
+        );
+        assert_eq!(
+            captures(r"([^.]+)\.[a-z]{2,3}$", "report.final.pdf").unwrap()[1],
+            "final"
+        );
+        assert_eq!(captures(r"[A-Z-]+", "ab-CD-x").unwrap()[0], "-CD-");
+        assert_eq!(captures(r"(a*)*b", "aaab").unwrap()[0], "aaab");
+        assert_eq!(captures(r"\s\w", "no_spaces"), None);
+        assert_eq!(captures(r"x{2,}", "axxxb").unwrap()[0], "xxx");
+        assert_eq!(Regex::new(r"(a)(?:b)(c)").unwrap().groups(), 2);
+    }
+
+    /// verifies Regex::captures() gives up with an error on a pattern whose backtracking grows exponentially rather than
+    /// running for ever, while the same pattern still matches where it can
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Regex::captures() does not fail on `(a+)+b` against a long run of `a` without a `b`
+    /// - Regex::captures() fails where the pattern matches quickly
+    #[test]
+    fn regex_captures_step_limit() {
+        let regex = Regex::new(r"(a+)+b").unwrap();
+        let name = format!("{}.jpg", "a".repeat(40));
+
+        let started = std::time::Instant::now();
+        assert!(regex.captures(&name).is_err());
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        assert_eq!(
+            regex.captures("aaab.jpg").unwrap().unwrap()[0].as_deref(),
+            Some("aaab")
+        );
+    }
+
+    /// verifies Regex::new() rejects malformed patterns
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Regex::new() accepts a malformed pattern
+    #[test]
+    fn regex_new_invalid() {
+        for pattern in [
+            "(abc",
+            "abc)",
+            "[abc",
+            "*a",
+            "a**",
+            "^*",
+            "a{2",
+            "a{3,1}",
+            "a{x}",
+            r"\q",
+            "a\\",
+            "[z-a]",
+            "(?<name>a)",
+        ] {
+            assert!(Regex::new(pattern).is_err(), "{pattern}");
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::io;
+use std::thread::sleep;
+use std::time::Duration;
+
+// declare local code
+use super::file_in_use::is_file_in_use;
+
+/// delay before the first retry when none is given with `--retry-delay`
+const DEFAULT_DELAY: Duration = Duration::from_millis(50);
+
+/// Retry struct: how often and how patiently an operation that failed with a transient error is attempted again, see
+/// is_transient()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retry {
+    /// maximum number of times an operation is attempted again after failing
+    pub retries: u32,
+
+    /// delay before the first retry, doubled before every further retry
+    pub delay: Duration,
+}
+
+impl Default for Retry {
+    /// default Retry never retries, with a first delay of 50 ms once retries are enabled
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            delay: DEFAULT_DELAY,
+        }
+    }
+}
+
+impl Retry {
+    /// run() calls `operation` until it succeeds, fails with an error that is not is_transient(), or has been retried
+    /// &self.retries times, waiting with an exponential backoff starting at &self.delay between attempts so a busy file
+    /// can be released or a flaky network mount can recover, every retry is added to `retried`
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Retry
+    /// `retried` the number of retries performed so far by the run
+    /// `operation` the operation to attempt
+    ///
+    /// # Errors
+    ///
+    /// - the last error returned by `operation`
+    pub fn run<T>(
+        &self,
+        retried: &mut usize,
+        mut operation: impl FnMut() -> io::Result<T>,
+    ) -> io::Result<T> {
+        let mut delay = self.delay;
+
+        for _ in 0..self.retries {
+            match operation() {
+                Err(err) if is_transient(&err) => {
+                    sleep(delay);
+                    delay = delay.saturating_mul(2);
+                    *retried += 1;
+                }
+                result => return result,
+            }
+        }
+
+        operation()
+    }
+}
+
+/// is_transient() returns whether `err` may well not happen again when the operation is repeated, which is the case
+/// for files in use by another process (see is_file_in_use()) and for timeouts, interruptions, and operations that
+/// would block, as given by network mounts that are briefly unavailable. Permanent errors such as a missing file or
+/// denied permission are not retried
+///
+/// # Arguments
+///
+/// `err` the error to classify
+///
+/// # Errors
+///
+/// None
+pub fn is_transient(err: &io::Error) -> bool {
+    is_file_in_use(err)
+        || matches!(
+            err.kind(),
+            io::ErrorKind::TimedOut | io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock
+        )
+}
+
+/// parse_retry_delay() parses the value of `--retry-delay`, the delay before the first retry in milliseconds
+///
+/// # Arguments
+///
+/// `value` the value following `--retry-delay`
+///
+/// # Errors
+///
+/// - `value` is missing or not a non-negative integer
+pub fn parse_retry_delay(value: Option<String>) -> Result<Duration, &'static str> {
+    match value.map(|value| value.parse::<u64>()) {
+        Some(Ok(millis)) => Ok(Duration::from_millis(millis)),
+        _ => Err("'--retry-delay' must be followed by a non-negative number of milliseconds"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies Retry::run() retries only transient errors, gives up after the given number of retries, and counts
+    /// every retry
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Retry::run() does not retry a busy file or a timeout, or retries too often
+    /// - Retry::run() retries a permanent error
+    /// - Retry::run() does not count its retries
+    #[test]
+    fn retry_run_attempts() {
+        // operation that fails with `kind` `failures` times before succeeding
+        let attempts = |retries: u32, failures: u32, kind: io::ErrorKind| {
+            let retry = Retry {
+                retries,
+                delay: Duration::from_millis(1),
+            };
+            let mut calls = 0;
+            let mut retried = 0;
+            let result = retry.run(&mut retried, || {
+                calls += 1;
+                if calls <= failures {
+                    Err(io::Error::from(kind))
+                } else {
+                    Ok(())
+                }
+            });
+            (result.is_ok(), calls, retried)
+        };
+
+        assert_eq!(attempts(2, 2, io::ErrorKind::ResourceBusy), (true, 3, 2));
+        assert_eq!(attempts(5, 2, io::ErrorKind::TimedOut), (true, 3, 2));
+        assert_eq!(attempts(1, 2, io::ErrorKind::Interrupted), (false, 2, 1));
+        assert_eq!(attempts(0, 1, io::ErrorKind::WouldBlock), (false, 1, 0));
+        assert_eq!(attempts(3, 1, io::ErrorKind::NotFound), (false, 1, 0));
+        assert_eq!(
+            attempts(3, 1, io::ErrorKind::PermissionDenied).1,
+            if cfg!(windows) { 2 } else { 1 }
+        );
+        assert_eq!(
+            parse_retry_delay(Some(String::from("250"))),
+            Ok(Duration::from_millis(250))
+        );
+        assert!(parse_retry_delay(Some(String::from("-1"))).is_err());
+        assert!(parse_retry_delay(None).is_err());
+    }
+}
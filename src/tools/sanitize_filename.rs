@@ -0,0 +1,133 @@
+/// characters that are not allowed in file names on at least one supported platform
+const ILLEGAL_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// names reserved by Windows regardless of extension or case
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// is_illegal_char() returns whether `c` cannot appear in a file name on at least one supported platform
+///
+/// # Arguments
+///
+/// `c` the character to check
+///
+/// # Errors
+///
+/// None
+pub fn is_illegal_char(c: char) -> bool {
+    ILLEGAL_CHARS.contains(&c) || c.is_control()
+}
+
+/// sanitize_filename() returns a copy of `name` that is safe to use as a file name on all supported platforms by
+/// replacing illegal characters with `replacement`, trimming surrounding whitespace and trailing dots, and suffixing
+/// Windows reserved names such as `CON` or `NUL` with `replacement`
+///
+/// # Arguments
+///
+/// `name` the file name or file name component to sanitize
+/// `replacement` the character used in place of illegal characters, should itself be a legal character
+///
+/// # Errors
+///
+/// None
+pub fn sanitize_filename(name: &str, replacement: char) -> String {
+    // swap out every character that cannot appear in a file name
+    let replaced: String = name
+        .chars()
+        .map(|c| if is_illegal_char(c) { replacement } else { c })
+        .collect();
+
+    // Windows silently drops trailing dots and spaces which would change the name on disk
+    let mut sanitized = replaced.trim().trim_end_matches(['.', ' ']).to_string();
+
+    // reserved names are matched on the portion before the first dot, ignoring case
+    let stem = sanitized.split('.').next().unwrap_or("");
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        sanitized.insert(stem.len(), replacement);
+    }
+
+    // an empty name is never valid
+    if sanitized.is_empty() {
+        sanitized.push(replacement);
+    }
+
+    sanitized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies sanitize_filename() leaves names that are already safe untouched
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - sanitize_filename() modifies a safe file name
+    #[test]
+    fn sanitize_filename_safe_name() {
+        assert_eq!(sanitize_filename("2023-01_0.jpg", '_'), "2023-01_0.jpg");
+    }
+
+    /// verifies sanitize_filename() replaces illegal characters with the provided replacement character
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - sanitize_filename() does not replace every illegal character
+    /// - sanitize_filename() does not use the provided replacement character
+    #[test]
+    fn sanitize_filename_illegal_chars() {
+        assert_eq!(sanitize_filename("a:b?c*d", '_'), "a_b_c_d");
+        assert_eq!(sanitize_filename("a<b>c|d\"e", '-'), "a-b-c-d-e");
+        assert_eq!(sanitize_filename("a/b\\c\td", '_'), "a_b_c_d");
+    }
+
+    /// verifies sanitize_filename() trims surrounding whitespace and trailing dots
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - sanitize_filename() leaves surrounding whitespace or trailing dots in place
+    /// - sanitize_filename() returns an empty name
+    #[test]
+    fn sanitize_filename_trims() {
+        assert_eq!(sanitize_filename("  name. . ", '_'), "name");
+        assert_eq!(sanitize_filename(" ... ", '_'), "_");
+        assert_eq!(sanitize_filename("", '_'), "_");
+    }
+
+    /// verifies sanitize_filename() alters Windows reserved names regardless of case or extension
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - sanitize_filename() returns a reserved name such as `CON` or `NUL`
+    /// - sanitize_filename() alters names that only contain a reserved name
+    #[test]
+    fn sanitize_filename_reserved_names() {
+        assert_eq!(sanitize_filename("CON", '_'), "CON_");
+        assert_eq!(sanitize_filename("nul", '_'), "nul_");
+        assert_eq!(sanitize_filename("NUL.txt", '_'), "NUL_.txt");
+        assert_eq!(sanitize_filename("com1.tar.gz", '-'), "com1-.tar.gz");
+        assert_eq!(sanitize_filename("CONSOLE", '_'), "CONSOLE");
+        assert_eq!(sanitize_filename("my.CON", '_'), "my.CON");
+    }
+}
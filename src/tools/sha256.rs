@@ -0,0 +1,247 @@
+use std::io;
+use std::path::Path;
+
+use super::digest::{digest_file, DigestAlgorithm};
+
+/// initial hash value, the first 32 bits of the fractional parts of the square roots of the first 8 primes
+const INITIAL: [u32; 8] = [
+    0x6A09_E667,
+    0xBB67_AE85,
+    0x3C6E_F372,
+    0xA54F_F53A,
+    0x510E_527F,
+    0x9B05_688C,
+    0x1F83_D9AB,
+    0x5BE0_CD19,
+];
+
+/// round constants, the first 32 bits of the fractional parts of the cube roots of the first 64 primes
+#[rustfmt::skip]
+const K: [u32; 64] = [
+    0x428A_2F98, 0x7137_4491, 0xB5C0_FBCF, 0xE9B5_DBA5, 0x3956_C25B, 0x59F1_11F1,
+    0x923F_82A4, 0xAB1C_5ED5, 0xD807_AA98, 0x1283_5B01, 0x2431_85BE, 0x550C_7DC3,
+    0x72BE_5D74, 0x80DE_B1FE, 0x9BDC_06A7, 0xC19B_F174, 0xE49B_69C1, 0xEFBE_4786,
+    0x0FC1_9DC6, 0x240C_A1CC, 0x2DE9_2C6F, 0x4A74_84AA, 0x5CB0_A9DC, 0x76F9_88DA,
+    0x983E_5152, 0xA831_C66D, 0xB003_27C8, 0xBF59_7FC7, 0xC6E0_0BF3, 0xD5A7_9147,
+    0x06CA_6351, 0x1429_2967, 0x27B7_0A85, 0x2E1B_2138, 0x4D2C_6DFC, 0x5338_0D13,
+    0x650A_7354, 0x766A_0ABB, 0x81C2_C92E, 0x9272_2C85, 0xA2BF_E8A1, 0xA81A_664B,
+    0xC24B_8B70, 0xC76C_51A3, 0xD192_E819, 0xD699_0624, 0xF40E_3585, 0x106A_A070,
+    0x19A4_C116, 0x1E37_6C08, 0x2748_774C, 0x34B0_BCB5, 0x391C_0CB3, 0x4ED8_AA4A,
+    0x5B9C_CA4F, 0x682E_6FF3, 0x748F_82EE, 0x78A5_636F, 0x84C8_7814, 0x8CC7_0208,
+    0x90BE_FFFA, 0xA450_6CEB, 0xBEF9_A3F7, 0xC671_78F2,
+];
+
+/// Sha256 struct: running SHA-256 digest that can be fed data in chunks
+#[derive(Debug, Clone)]
+pub struct Sha256 {
+    /// intermediate hash value
+    state: [u32; 8],
+
+    /// data that does not yet fill a 64 byte block
+    block: [u8; 64],
+
+    /// number of valid bytes in `block`
+    block_len: usize,
+
+    /// total number of bytes fed so far
+    length: u64,
+}
+
+impl Default for Sha256 {
+    /// default Sha256 is the digest of no data
+    fn default() -> Self {
+        Self {
+            state: INITIAL,
+            block: [0; 64],
+            block_len: 0,
+            length: 0,
+        }
+    }
+}
+
+impl Sha256 {
+    /// Sha256 struct initializer for the digest of no data
+    ///
+    /// # Arguments
+    ///
+    /// None
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// update() feeds `data` into the digest
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Sha256
+    /// `data` the next chunk of data
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+
+        while !data.is_empty() {
+            let taken = data.len().min(64 - self.block_len);
+            self.block[self.block_len..self.block_len + taken].copy_from_slice(&data[..taken]);
+            self.block_len += taken;
+            data = &data[taken..];
+
+            if self.block_len == 64 {
+                let block = self.block;
+                self.compress(&block);
+                self.block_len = 0;
+            }
+        }
+    }
+
+    /// finish() pads the data fed so far and returns its digest
+    ///
+    /// # Arguments
+    ///
+    /// `self` the Sha256 to finish
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn finish(mut self) -> [u8; 32] {
+        let bits = self.length.wrapping_mul(8);
+
+        // a single 1 bit, zeros up to 56 bytes into a block, then the length in bits
+        self.update(&[0x80]);
+        while self.block_len != 56 {
+            self.update(&[0]);
+        }
+        self.update(&bits.to_be_bytes());
+
+        let mut digest = [0u8; 32];
+        for (chunk, word) in digest.chunks_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+
+        digest
+    }
+
+    /// compress() mixes a 64 byte block into the intermediate hash value
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Sha256
+    /// `block` the block to mix in
+    ///
+    /// # Errors
+    ///
+    /// None
+    fn compress(&mut self, block: &[u8; 64]) {
+        // message schedule
+        let mut w = [0u32; 64];
+        for (word, chunk) in w.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_be_bytes(chunk.try_into().unwrap()); // chunks of a 64 byte block are 4 bytes long
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let temp1 = h
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(majority);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+}
+
+/// hash_file() returns the SHA-256 digest of the contents of the file at `path`, reading it in fixed size chunks so
+/// memory use does not depend on the size of the file
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to the file
+///
+/// # Errors
+///
+/// - digest_file() call fails
+pub fn hash_file(path: &Path) -> io::Result<[u8; 32]> {
+    digest_file(path, DigestAlgorithm::Sha256, false)
+}
+
+/// to_hex() formats `digest` as lowercase hexadecimal
+///
+/// # Arguments
+///
+/// `digest` the digest to format
+///
+/// # Errors
+///
+/// None
+pub fn to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies Sha256 produces the standard test vectors and is independent of how data is chunked
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Sha256 does not produce the digests given in FIPS 180-2
+    /// - Sha256 depends on chunk boundaries
+    #[test]
+    fn sha256_test_vectors() {
+        // run test
+        let digest = |data: &[u8], chunk: usize| {
+            let mut sha256 = Sha256::new();
+            for part in data.chunks(chunk) {
+                sha256.update(part);
+            }
+            to_hex(&sha256.finish())
+        };
+        let long = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+
+        assert_eq!(
+            to_hex(&Sha256::new().finish()),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            digest(b"abc", 1),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            digest(long, 7),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+        assert_eq!(digest(long, 7), digest(long, 64));
+    }
+}
@@ -0,0 +1,184 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::digest::{hash_file, DigestAlgorithm};
+use super::error::PathContext;
+use super::sha256::{to_hex, Sha256};
+
+/// largest number of hexadecimal digits a single shard folder is named with, giving a fan-out of 65536
+const MAX_WIDTH: usize = 4;
+
+/// ShardKey enum: what the SHA-256 digest that names the shard folders of a file is taken of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShardKey {
+    /// the file name, so the folder is known without reading the file
+    Name,
+
+    /// the contents, so files sharing a name are still spread out
+    Content,
+}
+
+/// Shard struct: places files into nested folders named after the leading hexadecimal digits of a digest, such as
+/// `ab/cd/` for `depth` 2 and `width` 2, so no folder of a huge flat output holds more than a manageable number of
+/// entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    /// what the digest is taken of
+    pub key: ShardKey,
+
+    /// number of nested shard folders
+    pub depth: usize,
+
+    /// number of hexadecimal digits naming each shard folder, every folder holds up to 16^width subfolders
+    pub width: usize,
+}
+
+impl Shard {
+    /// Shard struct initializer for two levels of 256 folders each
+    ///
+    /// # Arguments
+    ///
+    /// `key` what the digest is taken of
+    pub fn new(key: ShardKey) -> Self {
+        Self {
+            key,
+            depth: 2,
+            width: 2,
+        }
+    }
+
+    /// validate() checks that &self.depth and &self.width describe a layout the digest has enough digits for
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Shard
+    ///
+    /// # Errors
+    ///
+    /// - &self.depth or &self.width is zero
+    /// - &self.width is larger than MAX_WIDTH
+    /// - &self.depth times &self.width is larger than the 64 digits of a digest
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.depth == 0 || self.width == 0 || self.width > MAX_WIDTH {
+            return Err("'--shard-depth' must be at least 1 and '--shard-width' between 1 and 4");
+        }
+        // a depth large enough to overflow the product cannot be named by the digest either
+        if self
+            .depth
+            .checked_mul(self.width)
+            .is_none_or(|digits| digits > 64)
+        {
+            return Err("'--shard-depth' times '--shard-width' must not exceed 64 digits");
+        }
+
+        Ok(())
+    }
+
+    /// dirs() returns the shard folders `file` is placed in, relative to the folder it would be placed in otherwise
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Shard
+    /// `file` a Path that corresponds to the file
+    ///
+    /// # Errors
+    ///
+    /// - hash_file() call fails when &self.key is ShardKey::Content
+    pub fn dirs(&self, file: &Path) -> io::Result<PathBuf> {
+        let hex = match self.key {
+            ShardKey::Name => {
+                let mut sha256 = Sha256::new();
+                sha256.update(file.file_name().unwrap_or_default().as_encoded_bytes());
+                to_hex(&sha256.finish())
+            }
+            ShardKey::Content => {
+                hash_file(file, DigestAlgorithm::Sha256, false).path_context(file)?
+            }
+        };
+
+        Ok((0..self.depth)
+            .map(|level| &hex[level * self.width..(level + 1) * self.width])
+            .collect())
+    }
+}
+
+/// parse_shard_key() parses the value of `--name-from-hash-prefix`, either `name` or `content`
+///
+/// # Arguments
+///
+/// `value` the value following `--name-from-hash-prefix`
+///
+/// # Errors
+///
+/// - `value` is neither `name` nor `content`
+pub fn parse_shard_key(value: Option<String>) -> Result<ShardKey, &'static str> {
+    match value.as_deref() {
+        Some("name") => Ok(ShardKey::Name),
+        Some("content") => Ok(ShardKey::Content),
+        _ => Err("'--name-from-hash-prefix' must be followed by 'name' or 'content'"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies Shard::dirs() names its folders after the leading digits of the digest of the file name and spreads
+    /// many names roughly evenly over them
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Shard::dirs() does not take its folders from the leading digits of the digest
+    /// - Shard::dirs() puts far more or far fewer names than the average into a folder
+    /// - Shard::validate() accepts a layout the digest cannot name
+    #[test]
+    fn shard_dirs_even() {
+        // the SHA-256 digest of `abc` starts with ba7816bf
+        let shard = Shard::new(ShardKey::Name);
+        assert_eq!(
+            shard.dirs(Path::new("./source/abc")).unwrap(),
+            PathBuf::from("ba/78")
+        );
+        let deep = Shard {
+            depth: 4,
+            width: 1,
+            ..shard
+        };
+        assert_eq!(
+            deep.dirs(Path::new("abc")).unwrap(),
+            PathBuf::from("b/a/7/8")
+        );
+
+        // 16 folders of 4096 names each, every one within a quarter of the average
+        let single = Shard {
+            depth: 1,
+            width: 1,
+            ..shard
+        };
+        let mut counts = [0usize; 16];
+        for index in 0..16 * 4096 {
+            let dir = single.dirs(Path::new(&format!("IMG_{index}.jpg"))).unwrap();
+            let digit = dir.to_str().unwrap();
+            counts[usize::from_str_radix(digit, 16).unwrap()] += 1;
+        }
+        for count in counts {
+            assert!((3072..=5120).contains(&count), "{counts:?}");
+        }
+
+        assert!(shard.validate().is_ok());
+        for (depth, width) in [(0, 2), (2, 0), (1, 5), (17, 4), (usize::MAX / 4 + 1, 4)] {
+            assert!(Shard {
+                depth,
+                width,
+                ..shard
+            }
+            .validate()
+            .is_err());
+        }
+        assert!(parse_shard_key(Some(String::from("path"))).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+use std::fs::read_to_string;
+use std::io;
+use std::path::{Path, PathBuf};
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime, PrimitiveDateTime};
+
+// declare local code
+use super::json::Json;
+
+/// field read from `.json` sidecars when none is configured
+const DEFAULT_JSON_FIELD: &str = "date";
+
+/// field read from `.xmp` sidecars when none is configured, the `xmp:CreateDate` property
+const DEFAULT_XMP_FIELD: &str = "CreateDate";
+
+/// sidecar_date() returns the date recorded in the sidecar of `path`, a sibling `.json` or `.xmp` file named either
+/// after the whole file name (`scan001.jpg.json`) or its stem (`scan001.json`), returns None if there is no sidecar
+///
+/// JSON sidecars are read from `field` (default `date`), which may name a nested value with dots such as
+/// `photoTakenTime.timestamp`. XMP sidecars are read from the property `field` (default `CreateDate`), written either as
+/// an attribute or as an element with any namespace prefix
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to the file
+/// `field` the field holding the date, None for the default of each format
+///
+/// # Errors
+///
+/// - the sidecar cannot be read
+/// - the sidecar is not valid JSON
+/// - the sidecar does not contain `field` or its value is not a recognized date
+pub fn sidecar_date(path: &Path, field: Option<&str>) -> io::Result<Option<OffsetDateTime>> {
+    let sidecar = match find_sidecar(path) {
+        Some(sidecar) => sidecar,
+        None => return Ok(None),
+    };
+    let text = read_to_string(&sidecar)?;
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    let value = if is_xmp(&sidecar) {
+        xmp_field(&text, field.unwrap_or(DEFAULT_XMP_FIELD))
+    } else {
+        let json =
+            Json::parse(&text).map_err(|err| invalid(format!("{}: {err}", sidecar.display())))?;
+        json_field(&json, field.unwrap_or(DEFAULT_JSON_FIELD))
+    };
+
+    let value =
+        value.ok_or_else(|| invalid(format!("{}: date field not found", sidecar.display())))?;
+    match parse_date(&value) {
+        Some(date) => Ok(Some(date)),
+        None => Err(invalid(format!(
+            "{}: '{value}' is not a recognized date",
+            sidecar.display()
+        ))),
+    }
+}
+
+/// find_sidecar() returns the first existing sidecar of `path`, full file name before stem and `.json` before `.xmp`
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to the file
+///
+/// # Errors
+///
+/// None
+fn find_sidecar(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    let stem = path.file_stem()?;
+
+    ["json", "xmp"]
+        .iter()
+        .flat_map(|extension| {
+            let mut full = name.to_os_string();
+            full.push(format!(".{extension}"));
+            [
+                path.with_file_name(full),
+                path.with_file_name(stem).with_extension(extension),
+            ]
+        })
+        // a sidecar is never its own sidecar
+        .find(|candidate| candidate != path && candidate.is_file())
+}
+
+/// is_xmp() returns whether `path` has an `.xmp` extension
+///
+/// # Arguments
+///
+/// `path` a Path that corresponds to the file
+///
+/// # Errors
+///
+/// None
+fn is_xmp(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("xmp"))
+}
+
+/// json_field() returns the string or number at the dotted `field` of `json`
+///
+/// # Arguments
+///
+/// `json` the parsed sidecar
+/// `field` the dot separated keys leading to the date
+///
+/// # Errors
+///
+/// None
+fn json_field(json: &Json, field: &str) -> Option<String> {
+    let mut value = json;
+    for key in field.split('.') {
+        value = value.get(key)?;
+    }
+
+    match value {
+        Json::String(value) => Some(value.clone()),
+        Json::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// xmp_field() returns the value of the property `field` in an XMP packet, written as `prefix:field="value"` or
+/// `<prefix:field>value</prefix:field>`
+///
+/// # Arguments
+///
+/// `text` the contents of the sidecar
+/// `field` the local name of the property
+///
+/// # Errors
+///
+/// None
+fn xmp_field(text: &str, field: &str) -> Option<String> {
+    let mut rest = text;
+
+    while let Some(index) = rest.find(field) {
+        let before = &rest[..index];
+        let after = &rest[index + field.len()..];
+        rest = after;
+
+        // the name must stand on its own, preceded by a namespace colon or the start of a tag
+        if !(before.ends_with(':')
+            || before.ends_with('<')
+            || before.ends_with(char::is_whitespace))
+        {
+            continue;
+        }
+
+        let attribute = after.trim_start();
+        if let Some(value) = attribute.strip_prefix('=') {
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let value = &value[1..];
+            return value.find(quote).map(|end| value[..end].trim().to_string());
+        }
+        if let Some(value) = after.strip_prefix('>') {
+            return value.find('<').map(|end| value[..end].trim().to_string());
+        }
+    }
+
+    None
+}
+
+/// parse_date() parses a sidecar date, accepting RFC 3339, `YYYY-MM-DD HH:MM:SS` or `YYYY-MM-DDTHH:MM:SS`, the EXIF
+/// style `YYYY:MM:DD HH:MM:SS`, a plain `YYYY-MM-DD`, or a Unix timestamp in seconds, dates without an offset are
+/// taken to be UTC
+///
+/// # Arguments
+///
+/// `value` the text of the date field
+///
+/// # Errors
+///
+/// None
+fn parse_date(value: &str) -> Option<OffsetDateTime> {
+    let value = value.trim();
+
+    if let Ok(date) = OffsetDateTime::parse(value, &Rfc3339) {
+        return Some(date);
+    }
+    if let Ok(timestamp) = value.parse::<i64>() {
+        return OffsetDateTime::from_unix_timestamp(timestamp).ok();
+    }
+
+    // drop any fractional seconds, no sidecar needs finer than second resolution to pick a folder
+    let value = value.split('.').next().unwrap_or(value);
+    let formats = [
+        format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"),
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+        format_description!("[year]:[month]:[day] [hour]:[minute]:[second]"),
+    ];
+    for format in formats {
+        if let Ok(date) = PrimitiveDateTime::parse(value, format) {
+            return Some(date.assume_utc());
+        }
+    }
+
+    Date::parse(value, format_description!("[year]-[month]-[day]"))
+        .ok()
+        .map(|date| date.midnight().assume_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+    use time::macros::datetime;
+
+    /// verifies sidecar_date() reads the date of a scan from its `.json` and `.xmp` sidecars and returns None without one
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - sidecar_date() does not find a sidecar named after the stem or the full file name
+    /// - sidecar_date() does not read the default or a configured field
+    /// - sidecar_date() does not error on a sidecar without a usable date
+    #[test]
+    fn sidecar_date_fixture_pair() {
+        // create inputs
+        let path_buf = PathBuf::from("./sidecar_date_fixture_pair_test");
+
+        // create mock directories, each scan is paired with a sidecar holding its true date
+        create_dir_all(&path_buf).unwrap();
+        for (name, contents) in [
+            ("scan001.jpg", ""),
+            (
+                "scan001.json",
+                r#"{"date": "2003-07-04T10:30:00Z", "meta": {"taken": 1000000000}}"#,
+            ),
+            ("scan002.jpg", ""),
+            (
+                "scan002.jpg.xmp",
+                r#"<rdf:Description xmp:CreateDate="2005:12:25 08:00:00"/>"#,
+            ),
+            ("scan003.jpg", ""),
+            (
+                "scan003.xmp",
+                "<x:xmpmeta><exif:DateTimeOriginal>2007-03-01</exif:DateTimeOriginal></x:xmpmeta>",
+            ),
+            ("scan004.jpg", ""),
+            ("scan004.json", r#"{"date": "yesterday"}"#),
+            ("scan005.jpg", ""),
+        ] {
+            write(path_buf.join(name), contents).unwrap();
+        }
+
+        // run test
+        let date = |name: &str, field: Option<&str>| sidecar_date(&path_buf.join(name), field);
+        let test_result = [
+            date("scan001.jpg", None).unwrap(),
+            date("scan001.jpg", Some("meta.taken")).unwrap(),
+            date("scan002.jpg", None).unwrap(),
+            date("scan003.jpg", Some("DateTimeOriginal")).unwrap(),
+            date("scan005.jpg", None).unwrap(),
+        ];
+        let invalid = [
+            date("scan004.jpg", None).is_err(),
+            date("scan001.jpg", Some("missing")).is_err(),
+            date("scan003.jpg", None).is_err(),
+        ];
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            test_result,
+            [
+                Some(datetime!(2003-07-04 10:30 UTC)),
+                Some(datetime!(2001-09-09 01:46:40 UTC)),
+                Some(datetime!(2005-12-25 08:00 UTC)),
+                Some(datetime!(2007-03-01 00:00 UTC)),
+                None,
+            ]
+        );
+        assert_eq!(invalid, [true; 3]);
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::Path;
+
+/// SymlinkPolicy enum: how a walk treats symbolic links, shared by every task that walks a tree so `--follow` and
+/// `--no-follow` mean the same everywhere
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// links are not visited at all, neither the link nor what it points to
+    #[default]
+    Skip,
+
+    /// links are visited as the item they point to and links to directories are descended into, a link back to a
+    /// directory the walk is already inside is not descended again so loops end
+    Follow,
+
+    /// links are visited as entries of their own, a link to a directory is never descended into
+    NoFollow,
+}
+
+/// is_loop() returns whether the directory the link at `link` points to is `link`'s own parent or one of its
+/// ancestors up to `depth` levels above it, so following the link would walk the same directories again
+///
+/// # Arguments
+///
+/// `link` a Path that corresponds to a symbolic link to a directory
+/// `depth` number of directories between the walked root and `link`, the root itself is the last ancestor checked
+///
+/// # Errors
+///
+/// None, a link that cannot be resolved is treated as a loop so it is not descended into
+pub fn is_loop(link: &Path, depth: usize) -> bool {
+    let target = match link.canonicalize() {
+        Ok(target) => target,
+        Err(_) => return true,
+    };
+
+    link.ancestors()
+        .skip(1)
+        .take(depth)
+        .filter_map(|ancestor| ancestor.canonicalize().ok())
+        .any(|ancestor| ancestor == target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies a link to an ancestor is a loop and a link to a sibling is not
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - is_loop() does not detect a link to an ancestor or flags a link to a sibling
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_is_loop() {
+        use std::fs::{create_dir_all, remove_dir_all};
+        use std::os::unix::fs::symlink;
+        use std::path::PathBuf;
+
+        // create inputs
+        let path_buf = PathBuf::from("./symlinks_is_loop_test");
+
+        // create mock directories, a/up points back at the root and a/across at its sibling b
+        create_dir_all(path_buf.join("a")).unwrap();
+        create_dir_all(path_buf.join("b")).unwrap();
+        symlink("..", path_buf.join("a/up")).unwrap();
+        symlink("../b", path_buf.join("a/across")).unwrap();
+
+        // run test
+        let up = is_loop(&path_buf.join("a/up"), 2);
+        let across = is_loop(&path_buf.join("a/across"), 2);
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert!(up);
+        assert!(!across);
+    }
+}
@@ -0,0 +1,89 @@
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::available_parallelism;
+
+/// number of worker threads given with `--threads`, zero until it is set so threads() falls back to the parallelism of
+/// the machine
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// threads_from_args() removes `--threads N` from `args` if present and makes N the number of worker threads used by
+/// every task for the rest of the run
+///
+/// # Arguments
+///
+/// `args` a mutable vector of Strings to be used as arguments
+///
+/// # Errors
+///
+/// - `--threads` is not followed by a positive integer
+pub fn threads_from_args(args: &mut Vec<String>) -> Result<(), &'static str> {
+    let index = match args.iter().position(|arg| arg == "--threads") {
+        Some(index) => index,
+        None => return Ok(()),
+    };
+
+    let threads = match args
+        .get(index + 1)
+        .map(|value| value.parse::<NonZeroUsize>())
+    {
+        Some(Ok(threads)) => threads,
+        _ => return Err("'--threads' must be followed by a positive integer"),
+    };
+    args.drain(index..index + 2);
+    THREADS.store(threads.get(), Ordering::Relaxed);
+
+    Ok(())
+}
+
+/// threads() returns the number of worker threads tasks may use, the value of `--threads` if given and otherwise the
+/// available parallelism of the machine, or one when it cannot be determined
+///
+/// # Arguments
+///
+/// None
+///
+/// # Errors
+///
+/// None
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => available_parallelism().map_or(1, NonZeroUsize::get),
+        threads => threads,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies threads_from_args() removes `--threads N` and rejects values that are not positive integers
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - threads_from_args() leaves the option in `args`, removes unrelated arguments, or does not set threads()
+    /// - threads_from_args() accepts a missing, zero, or non-numeric count
+    #[test]
+    fn threads_from_args_removes_option() {
+        // args builder
+        let args = |values: &[&str]| values.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        let mut without = args(&["foo", "dedupe", "./src"]);
+        assert!(threads_from_args(&mut without).is_ok());
+        assert_eq!(without, args(&["foo", "dedupe", "./src"]));
+        assert!(threads() >= 1);
+
+        let mut with = args(&["foo", "dedupe", "--threads", "2", "./src"]);
+        assert!(threads_from_args(&mut with).is_ok());
+        assert_eq!(with, args(&["foo", "dedupe", "./src"]));
+        assert_eq!(threads(), 2);
+
+        for value in ["0", "-1", "many"] {
+            assert!(threads_from_args(&mut args(&["foo", "dedupe", "--threads", value])).is_err());
+        }
+        assert!(threads_from_args(&mut args(&["foo", "dedupe", "--threads"])).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+// declare local code
+use super::parse_size::parse_size;
+
+/// Throttle struct: limits the average rate at which bytes are copied by sleeping whenever the bytes transferred so far
+/// run ahead of the limit. The rate is averaged from the first transfer, so the total time of a run is accurate to
+/// within a few milliseconds while individual bursts of up to one buffer may briefly exceed the limit. Nothing ties the
+/// unit to bytes, so a Throttle consuming one unit per file limits the number of files per second just as well
+#[derive(Debug, Default)]
+pub struct Throttle {
+    /// maximum average rate in bytes per second, None for no limit
+    rate: Option<u64>,
+
+    /// time of the first transfer
+    start: Option<Instant>,
+
+    /// number of bytes transferred since `start`
+    transferred: u64,
+}
+
+impl Throttle {
+    /// Throttle struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// `rate` maximum average rate in bytes per second, None for no limit
+    pub fn new(rate: Option<u64>) -> Self {
+        Self {
+            rate,
+            ..Self::default()
+        }
+    }
+
+    /// consume() records that `bytes` were transferred and sleeps until doing so no longer exceeds the rate limit
+    ///
+    /// # Arguments
+    ///
+    /// `&mut self` mutable reference to Throttle
+    /// `bytes` number of bytes just transferred
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn consume(&mut self, bytes: u64) {
+        let rate = match self.rate {
+            Some(rate) if rate > 0 => rate,
+            _ => return,
+        };
+
+        let start = *self.start.get_or_insert_with(Instant::now);
+        self.transferred += bytes;
+
+        // earliest time at which the bytes transferred so far are within the limit
+        let due = Duration::from_secs_f64(self.transferred as f64 / rate as f64);
+        if let Some(wait) = due.checked_sub(start.elapsed()) {
+            sleep(wait);
+        }
+    }
+}
+
+/// parse_throttle() parses the value passed to a `--throttle` option, a positive number of bytes per second that may
+/// use the suffixes accepted by parse_size()
+///
+/// # Arguments
+///
+/// `value` the String following the option, if any
+///
+/// # Errors
+///
+/// - `value` is missing, is not a valid size, or is zero
+pub fn parse_throttle(value: Option<String>) -> Result<u64, &'static str> {
+    match value.as_deref().and_then(parse_size) {
+        Some(rate) if rate > 0 => Ok(rate),
+        _ => Err("'--throttle' must be followed by a positive number of bytes per second such as 512K or 10M"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// verifies Throttle::consume() sleeps long enough to keep the average rate under the limit, and an unlimited
+    /// Throttle does not sleep
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - Throttle::consume() allows transfers faster than the limit
+    /// - a Throttle without a rate sleeps
+    #[test]
+    fn throttle_consume_rate() {
+        // run test, 100 KiB at 400 KiB per second takes at least a quarter of a second
+        let started = Instant::now();
+        let mut throttle = Throttle::new(Some(400 * 1024));
+        for _ in 0..10 {
+            throttle.consume(10 * 1024);
+        }
+        let limited = started.elapsed();
+
+        let started = Instant::now();
+        let mut throttle = Throttle::default();
+        throttle.consume(u64::MAX);
+        let unlimited = started.elapsed();
+
+        assert!(limited >= Duration::from_millis(250));
+        assert!(unlimited < Duration::from_millis(100));
+    }
+}
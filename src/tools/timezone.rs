@@ -0,0 +1,252 @@
+use time::macros::format_description;
+use time::{OffsetDateTime, UtcOffset};
+
+/// TimeZone enum: the time zone file dates are converted to before they are turned into folders and labels
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TimeZone {
+    /// Coordinated Universal Time, the same on every machine so runs are reproducible
+    #[default]
+    Utc,
+
+    /// the local time zone of the machine at the date being converted, so daylight saving time is taken into account,
+    /// falls back to UTC on platforms where it cannot be determined
+    Local,
+
+    /// a fixed offset from UTC
+    Fixed(UtcOffset),
+}
+
+impl TimeZone {
+    /// convert() returns `date` as the same instant expressed in this time zone
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to TimeZone
+    /// `date` the date to convert
+    ///
+    /// # Errors
+    ///
+    /// None
+    pub fn convert(&self, date: OffsetDateTime) -> OffsetDateTime {
+        let offset = match self {
+            Self::Utc => UtcOffset::UTC,
+            Self::Local => platform::local_offset(date).unwrap_or(UtcOffset::UTC),
+            Self::Fixed(offset) => *offset,
+        };
+
+        date.to_offset(offset)
+    }
+}
+
+/// parse_timezone() parses the value passed to a `--timezone` option
+///
+/// # Arguments
+///
+/// `value` the String following the option, if any
+///
+/// # Errors
+///
+/// - `value` is missing or is not `utc`, `local`, or an offset such as `+02:00` or `-05:30`
+pub fn parse_timezone(value: Option<String>) -> Result<TimeZone, &'static str> {
+    const ERROR: &str =
+        "'--timezone' must be followed by 'utc', 'local', or an offset such as +02:00";
+
+    match value.as_deref() {
+        Some("utc") => Ok(TimeZone::Utc),
+        Some("local") => Ok(TimeZone::Local),
+        Some(value) => UtcOffset::parse(
+            value,
+            format_description!("[offset_hour sign:mandatory]:[offset_minute]"),
+        )
+        .map(TimeZone::Fixed)
+        .map_err(|_| ERROR),
+        None => Err(ERROR),
+    }
+}
+
+#[cfg(any(
+    all(target_os = "linux", target_pointer_width = "64"),
+    target_os = "macos"
+))]
+mod platform {
+    use std::os::raw::{c_char, c_int, c_long};
+    use time::{OffsetDateTime, UtcOffset};
+
+    /// `struct tm` as filled in by localtime_r(), the offset field is an extension shared by glibc, musl, and macOS
+    #[repr(C)]
+    struct Tm {
+        tm_sec: c_int,
+        tm_min: c_int,
+        tm_hour: c_int,
+        tm_mday: c_int,
+        tm_mon: c_int,
+        tm_year: c_int,
+        tm_wday: c_int,
+        tm_yday: c_int,
+        tm_isdst: c_int,
+        tm_gmtoff: c_long,
+        tm_zone: *const c_char,
+    }
+
+    extern "C" {
+        fn localtime_r(time: *const i64, result: *mut Tm) -> *mut Tm;
+    }
+
+    pub fn local_offset(date: OffsetDateTime) -> Option<UtcOffset> {
+        let time = date.unix_timestamp();
+        let mut tm = Tm {
+            tm_sec: 0,
+            tm_min: 0,
+            tm_hour: 0,
+            tm_mday: 0,
+            tm_mon: 0,
+            tm_year: 0,
+            tm_wday: 0,
+            tm_yday: 0,
+            tm_isdst: 0,
+            tm_gmtoff: 0,
+            tm_zone: std::ptr::null(),
+        };
+
+        // SAFETY: both pointers refer to live values of the types localtime_r() expects, `time_t` is 64 bits on every
+        // platform this module is built for. The time zone database is read from the environment, which this program
+        // never modifies
+        if unsafe { localtime_r(&time, &mut tm) }.is_null() {
+            return None;
+        }
+
+        UtcOffset::from_whole_seconds(i32::try_from(tm.tm_gmtoff).ok()?).ok()
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::ffi::c_void;
+    use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+    /// `SYSTEMTIME`, a calendar date and time of day
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemTime {
+        year: u16,
+        month: u16,
+        day_of_week: u16,
+        day: u16,
+        hour: u16,
+        minute: u16,
+        second: u16,
+        milliseconds: u16,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn SystemTimeToTzSpecificLocalTime(
+            zone: *const c_void,
+            universal: *const SystemTime,
+            local: *mut SystemTime,
+        ) -> i32;
+    }
+
+    pub fn local_offset(date: OffsetDateTime) -> Option<UtcOffset> {
+        let date = date.to_offset(UtcOffset::UTC);
+        let universal = SystemTime {
+            year: u16::try_from(date.year()).ok()?,
+            month: u8::from(date.month()).into(),
+            day: date.day().into(),
+            hour: date.hour().into(),
+            minute: date.minute().into(),
+            second: date.second().into(),
+            ..SystemTime::default()
+        };
+        let mut local = SystemTime::default();
+
+        // SAFETY: a null zone selects the current time zone and both other pointers refer to live SYSTEMTIMEs
+        if unsafe { SystemTimeToTzSpecificLocalTime(std::ptr::null(), &universal, &mut local) } == 0
+        {
+            return None;
+        }
+
+        // the offset is the difference between the local wall clock and the universal one
+        let local = PrimitiveDateTime::new(
+            Date::from_calendar_date(
+                local.year.into(),
+                Month::try_from(u8::try_from(local.month).ok()?).ok()?,
+                u8::try_from(local.day).ok()?,
+            )
+            .ok()?,
+            Time::from_hms(
+                u8::try_from(local.hour).ok()?,
+                u8::try_from(local.minute).ok()?,
+                u8::try_from(local.second).ok()?,
+            )
+            .ok()?,
+        );
+        let universal =
+            PrimitiveDateTime::new(date.date(), date.time().replace_millisecond(0).ok()?);
+        UtcOffset::from_whole_seconds(i32::try_from((local - universal).whole_seconds()).ok()?).ok()
+    }
+}
+
+#[cfg(not(any(
+    windows,
+    all(target_os = "linux", target_pointer_width = "64"),
+    target_os = "macos"
+)))]
+mod platform {
+    use time::{OffsetDateTime, UtcOffset};
+
+    pub fn local_offset(_date: OffsetDateTime) -> Option<UtcOffset> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::dates::date_folder;
+    use time::macros::{datetime, offset};
+
+    /// verifies a date shortly before midnight lands in another month's folder depending on the time zone
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - TimeZone::convert() does not move the date across midnight for an offset ahead of or behind UTC
+    /// - TimeZone::convert() changes the instant or does not convert dates carrying another offset to UTC
+    /// - parse_timezone() does not parse a valid time zone or accepts an invalid one
+    #[test]
+    fn timezone_midnight_boundary() {
+        // create inputs, half an hour before midnight at the end of January in UTC
+        let date = datetime!(2023-01-31 23:30 UTC);
+        let parse = |value: &str| parse_timezone(Some(value.to_string()));
+
+        assert_eq!(parse("utc"), Ok(TimeZone::Utc));
+        assert_eq!(parse("local"), Ok(TimeZone::Local));
+        assert_eq!(parse("+02:00"), Ok(TimeZone::Fixed(offset!(+2))));
+        assert_eq!(parse("-05:30"), Ok(TimeZone::Fixed(offset!(-5:30))));
+        for value in ["02:00", "+2", "+26:00", "pst", ""] {
+            assert!(parse(value).is_err());
+        }
+        assert!(parse_timezone(None).is_err());
+
+        let folder = |zone: TimeZone| date_folder(zone.convert(date));
+        assert_eq!(folder(TimeZone::Utc), date_folder(date));
+        assert_eq!(folder(TimeZone::Utc).to_str(), Some("2023/2023-01"));
+        assert_eq!(
+            folder(TimeZone::Fixed(offset!(+2))).to_str(),
+            Some("2023/2023-02")
+        );
+        assert_eq!(
+            folder(TimeZone::Fixed(offset!(-5))).to_str(),
+            Some("2023/2023-01")
+        );
+        assert_eq!(
+            date_folder(TimeZone::Utc.convert(datetime!(2023-02-01 01:30 +02:00))).to_str(),
+            Some("2023/2023-01")
+        );
+        assert_eq!(TimeZone::Local.convert(date), date);
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::{Path, PathBuf};
+
+/// unique_path() returns `dir` joined with `name`, or if an item already exists there the first free variant of
+/// `name` with `_1`, `_2`, ... appended to its stem so that existing files are never overwritten
+///
+/// # Arguments
+///
+/// `dir` a Path that corresponds to the directory the item will be placed in
+/// `name` the desired file name of the item
+///
+/// # Errors
+///
+/// None
+pub fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    unique_path_by(dir, name, |path| !path.exists())
+}
+
+/// unique_path_by() returns `dir` joined with `name`, or the first variant of `name` with `_1`, `_2`, ... appended to
+/// its stem, for which `free` holds, so callers placing more than one item per name can check every one of them
+///
+/// # Arguments
+///
+/// `dir` a Path that corresponds to the directory the item will be placed in
+/// `name` the desired file name of the item
+/// `free` whether a candidate path may be used
+///
+/// # Errors
+///
+/// None
+pub fn unique_path_by(dir: &Path, name: &str, free: impl Fn(&Path) -> bool) -> PathBuf {
+    let path = dir.join(name);
+    if free(&path) {
+        return path;
+    }
+
+    // split the extension off so the suffix is placed in front of it, dotfiles keep their leading dot in the stem
+    let (stem, extension) = match name.rfind('.') {
+        Some(index) if index > 0 => (&name[..index], &name[index..]),
+        _ => (name, ""),
+    };
+
+    // try increasing suffixes until a free path is found
+    (1..)
+        .map(|suffix| dir.join(format!("{stem}_{suffix}{extension}")))
+        .find(|path| free(path))
+        .unwrap() // the range is unbounded so a free path is always found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir, remove_dir_all, File};
+
+    /// verifies unique_path() returns the desired path when it is free and a suffixed path otherwise
+    ///
+    /// # Arguments
+    ///
+    /// none
+    ///
+    /// # Errors
+    ///
+    /// - unique_path() returns a path that already exists
+    /// - unique_path() suffixes a path that is free
+    #[test]
+    fn unique_path_collisions() {
+        // create inputs
+        let path_buf = PathBuf::from("./unique_path_collisions_test/");
+
+        // create mock directory
+        create_dir(&path_buf).unwrap();
+        File::create(path_buf.join("a.txt")).unwrap();
+        File::create(path_buf.join("a_1.txt")).unwrap();
+        File::create(path_buf.join(".hidden")).unwrap();
+
+        // run test
+        let test_result = [
+            unique_path(&path_buf, "a.txt"),
+            unique_path(&path_buf, "b.txt"),
+            unique_path(&path_buf, ".hidden"),
+        ];
+
+        // clean up mock directory
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(
+            test_result,
+            [
+                path_buf.join("a_2.txt"),
+                path_buf.join("b.txt"),
+                path_buf.join(".hidden_1"),
+            ]
+        );
+    }
+}
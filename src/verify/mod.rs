@@ -0,0 +1,317 @@
+// declare cargo crates
+use std::fs::read_to_string;
+use std::io;
+use std::path::PathBuf;
+
+// declare local code
+use super::tools::args::separate_args;
+use super::tools::checksums::parse_checksums;
+use super::tools::error::PathContext;
+use super::tools::paths::expand_paths;
+use super::tools::sha256::hash_file;
+use super::{Logger, RunTask, TaskReport};
+
+/// verify options that are followed by a value, see separate_args()
+pub(crate) const VALUE_OPTIONS: &[&str] = &[];
+
+/// verify options that are not followed by a value, listed along with VALUE_OPTIONS by the capabilities task
+pub(crate) const FLAG_OPTIONS: &[&str] = &[];
+
+/// VerifyTask struct: PathBufs correspond to a checksum manifest and the directory its paths are relative to
+#[derive(Debug, PartialEq, Eq)]
+pub struct VerifyTask {
+    /// PathBuf to the checksum manifest written with `--checksum-manifest` or `sha256sum`
+    manifest: PathBuf,
+
+    /// PathBuf to the directory the paths of &self.manifest are relative to
+    dir: PathBuf,
+}
+
+/// RunTask trait implementation for Verify variant of Config enum
+impl RunTask for VerifyTask {
+    /// run_task() driver function for Verify variant of Config, hashes every file listed in &self.manifest again and
+    /// compares it against the listed digest, files that changed or are missing are listed as failed in the returned
+    /// TaskReport while every intact file is counted as processed, the summary calls them verified and mismatched
+    ///
+    /// # Arguments
+    ///
+    /// `&self` reference to Config enum
+    /// `logger` a Logger that records every verified and failed file
+    ///
+    /// # Errors
+    ///
+    /// - &self.manifest cannot be read
+    /// - parse_checksums() call fails
+    /// - Logger::log() call fails
+    fn run_task(&self, logger: &mut Logger) -> Result<TaskReport, io::Error> {
+        let text = read_to_string(&self.manifest).path_context(&self.manifest)?;
+        let checksums = parse_checksums(&text).path_context(&self.manifest)?;
+
+        let mut report = TaskReport::worded("verified", "mismatched");
+        for (digest, path) in checksums {
+            let file = self.dir.join(path);
+            let problem = match hash_file(&file) {
+                Ok(actual) if actual == digest => None,
+                Ok(_) => Some(String::from("checksum mismatch")),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Some(String::from("missing")),
+                Err(err) => Some(err.to_string()),
+            };
+            match problem {
+                None => {
+                    logger.log("verify", &file, None)?;
+                    report.moved += 1;
+                }
+                Some(problem) => {
+                    logger.log("fail", &file, None)?;
+                    report.failed.push((file, problem));
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+impl VerifyTask {
+    /// VerifyTask struct initializer
+    ///
+    /// # Arguments
+    ///
+    /// `args` - an iterator containing Strings to be used as arguments
+    ///
+    /// # Errors
+    ///
+    /// - a path refers to an undefined environment variable, see expand_path()
+    /// - `./manifest` path not provided
+    /// - `./manifest` does not correspond to a valid file
+    /// - `./dir` does not correspond to a valid directory
+    /// - more than two paths are provided
+    /// - an unknown option is provided
+    pub fn new(args: impl Iterator<Item = String>) -> Result<Self, &'static str> {
+        // separate options from the paths so they may be given in any position
+        let (paths, options) = separate_args(args, VALUE_OPTIONS);
+        let paths = expand_paths(paths)?;
+        let mut paths = paths.into_iter();
+
+        // ensures manifest path is provided and corresponds to a valid file
+        let manifest = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => return Err("no 'manifest' path provided"),
+        };
+        if !manifest.is_file() {
+            return Err("'manifest' path does not correspond to a valid file");
+        }
+
+        // paths are relative to the directory holding the manifest unless another is given
+        let dir = match paths.next() {
+            Some(arg) => PathBuf::from(arg),
+            None => manifest
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .map_or_else(|| PathBuf::from("."), PathBuf::from),
+        };
+        if !dir.is_dir() {
+            return Err("'dir' path does not correspond to a valid directory");
+        }
+
+        // errors if extra paths are provided
+        if paths.next().is_some() {
+            return Err("verify accepts a 'manifest' path and at most one 'dir' path");
+        }
+
+        // parse options
+        if !options.is_empty() {
+            return Err("provided option did not match any verify options");
+        }
+
+        Ok(Self { manifest, dir })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::organize::OrganizeTask;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+
+    /// verifies VerifyTask::new() takes the paths of the manifest relative to its own directory unless another is given
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - VerifyTask::new() does not default to the directory of the manifest
+    /// - VerifyTask::new() does not error on a missing manifest, a missing directory, or an unknown option
+    #[test]
+    fn verify_task_new() {
+        // args iterator builder
+        let args = |values: &[&str]| {
+            values
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+
+        assert_eq!(
+            VerifyTask::new(args(&["./src/lib.rs"])),
+            Ok(VerifyTask {
+                manifest: PathBuf::from("./src/lib.rs"),
+                dir: PathBuf::from("./src"),
+            })
+        );
+        assert_eq!(
+            VerifyTask::new(args(&["Cargo.toml", "./src"])),
+            Ok(VerifyTask {
+                manifest: PathBuf::from("Cargo.toml"),
+                dir: PathBuf::from("./src"),
+            })
+        );
+        assert_eq!(
+            VerifyTask::new(args(&["Cargo.toml"])).unwrap().dir,
+            PathBuf::from(".")
+        );
+        assert!(VerifyTask::new(args(&[])).is_err());
+        assert!(VerifyTask::new(args(&["./src"])).is_err());
+        assert!(VerifyTask::new(args(&["Cargo.toml", "./missing"])).is_err());
+        assert!(VerifyTask::new(args(&["Cargo.toml", "--quick"])).is_err());
+    }
+
+    /// verifies a checksum manifest written by organize passes verification, and that corrupting or removing an
+    /// organized file makes it fail
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() does not list every organized file in the manifest
+    /// - VerifyTask::run_task() fails an intact file or passes a corrupted or missing one
+    #[test]
+    fn verify_task_round_trip() {
+        // create inputs
+        let path_buf = PathBuf::from("./verify_task_round_trip_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let manifest = target.join("SHA256SUMS");
+
+        // create mock directories
+        create_dir_all(source.join("nested")).unwrap();
+        create_dir_all(&target).unwrap();
+        write(source.join("a.txt"), "first").unwrap();
+        write(source.join("nested/b.txt"), "second").unwrap();
+
+        // run test, organize with a manifest, verify it, then corrupt one organized file and remove the other
+        let organized = OrganizeTask::new(
+            [
+                source.display().to_string(),
+                target.display().to_string(),
+                String::from("--checksum-manifest"),
+                manifest.display().to_string(),
+            ]
+            .into_iter(),
+        )
+        .unwrap()
+        .run_task(&mut Logger::disabled())
+        .unwrap();
+        let verify = || {
+            VerifyTask::new([manifest.display().to_string()].into_iter())
+                .unwrap()
+                .run_task(&mut Logger::disabled())
+                .unwrap()
+        };
+        let passed = verify();
+        let lines = read_to_string(&manifest).unwrap();
+        write(&organized.moves[0].1, "rotted").unwrap();
+        std::fs::remove_file(&organized.moves[1].1).unwrap();
+        let failed = verify();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!(organized.moved, 2);
+        assert_eq!(lines.lines().count(), 2);
+        for (_, destination) in &organized.moves {
+            let relative = destination.strip_prefix(&target).unwrap();
+            assert!(lines.contains(&format!("  {}\n", relative.display())));
+        }
+        assert_eq!(passed.moved, 2);
+        assert!(passed.failed.is_empty());
+        assert_eq!(failed.moved, 0);
+        assert!(failed
+            .to_string()
+            .starts_with("0 verified, 0 skipped, 2 mismatched,"));
+        assert_eq!(
+            failed.failed,
+            [
+                (
+                    organized.moves[0].1.clone(),
+                    String::from("checksum mismatch")
+                ),
+                (organized.moves[1].1.clone(), String::from("missing")),
+            ]
+        );
+    }
+
+    /// verifies a checksum manifest kept across organize runs that each place more files still lists the files placed
+    /// by the earlier runs, so verify keeps covering every organized file
+    ///
+    /// # Arguments
+    ///
+    /// None
+    ///
+    /// # Errors
+    ///
+    /// - OrganizeTask::run_task() drops the files of an earlier run from the manifest
+    /// - VerifyTask::run_task() does not check the files of every run
+    #[test]
+    fn verify_task_incremental_manifest() {
+        // create inputs
+        let path_buf = PathBuf::from("./verify_task_incremental_manifest_test");
+        let source = path_buf.join("source");
+        let target = path_buf.join("target");
+        let manifest = path_buf.join("SHA256SUMS");
+
+        // create mock directories
+        create_dir_all(&source).unwrap();
+        create_dir_all(&target).unwrap();
+
+        // run test, organize one file, then another with the same manifest, and verify both
+        let organize = || {
+            OrganizeTask::new(
+                [
+                    source.display().to_string(),
+                    target.display().to_string(),
+                    String::from("--checksum-manifest"),
+                    manifest.display().to_string(),
+                ]
+                .into_iter(),
+            )
+            .unwrap()
+            .run_task(&mut Logger::disabled())
+            .unwrap()
+        };
+        write(source.join("a.txt"), "first").unwrap();
+        let first = organize();
+        write(source.join("b.txt"), "second").unwrap();
+        let second = organize();
+        let lines = read_to_string(&manifest).unwrap();
+        let verified = VerifyTask::new(
+            [manifest.display().to_string(), target.display().to_string()].into_iter(),
+        )
+        .unwrap()
+        .run_task(&mut Logger::disabled())
+        .unwrap();
+
+        // clean up mock directories
+        remove_dir_all(&path_buf).unwrap();
+
+        assert_eq!((first.moved, second.moved), (1, 1));
+        assert_eq!(lines.lines().count(), 2);
+        assert_eq!(verified.moved, 2);
+        assert!(verified.failed.is_empty());
+    }
+}
@@ -0,0 +1,28 @@
+//! Directory walking and counting utilities used by every task, exposed so that crates depending on fileman_rs can
+//! collect files the same way the command line does.
+//!
+//! ```no_run
+//! use fileman_rs::walk::{collect_files, FileWalker, SymlinkPolicy};
+//! use std::path::{Path, PathBuf};
+//!
+//! let walker = FileWalker::new()
+//!     .exclude_dir(String::from(".git"))
+//!     .symlinks(SymlinkPolicy::Follow);
+//! let mut files: Vec<PathBuf> = Vec::new();
+//! collect_files(&walker, Path::new("./photos"), &mut files)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! `collect_files()` stops at the first directory it cannot read, `collect_files_lenient()` instead carries on and
+//! returns the unreadable directories alongside the files it found.
+//!
+//! `FileWalker::walk()` hands every visited item to a callback as a `WalkEntry`, whose `date` is the `DateSource` a
+//! `.filemanrc` above it chose, if any.
+
+// expose the walking and counting tools, every other tool stays internal to the command line
+pub use super::tools::collect_files::{collect_files, collect_files_lenient, CollectOutcome};
+pub use super::tools::dates::DateSource;
+pub use super::tools::file_walker::{FileWalker, WalkEntry};
+pub use super::tools::get_num_files::get_num_files;
+pub use super::tools::hidden::{is_hidden, Hidden};
+pub use super::tools::symlinks::SymlinkPolicy;